@@ -0,0 +1,63 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The benchmarking runtime api primitives.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+use client::decl_runtime_apis;
+use parity_codec::{Encode, Decode};
+use rstd::vec::Vec;
+
+/// A parameter the cost of a benchmarked call depends on, e.g. the number of existing
+/// nominators, together with the range of values it is benchmarked with.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BenchmarkComponent {
+	/// The name of the component.
+	pub name: Vec<u8>,
+	/// The lowest value of the component.
+	pub low: u32,
+	/// The highest value of the component.
+	pub high: u32,
+}
+
+/// Description of a benchmark provided by the runtime.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BenchmarkMetadata {
+	/// The module the benchmarked call belongs to.
+	pub module: Vec<u8>,
+	/// The name of the benchmark.
+	pub name: Vec<u8>,
+	/// The components of the benchmark, in the order their values are passed to `run_benchmark`.
+	pub components: Vec<BenchmarkComponent>,
+}
+
+decl_runtime_apis! {
+	/// The benchmarking api.
+	pub trait Benchmark {
+		/// The benchmarks the runtime provides.
+		fn benchmarks() -> Vec<BenchmarkMetadata>;
+
+		/// Set up the storage of the benchmark `name` of `module` for the given component values,
+		/// dispatch the benchmarked call and return the time the dispatch took in nanoseconds.
+		///
+		/// The state changes of a benchmark are not meant to be committed.
+		fn run_benchmark(module: Vec<u8>, name: Vec<u8>, components: Vec<u32>) -> Result<u64, Vec<u8>>;
+	}
+}
@@ -0,0 +1,238 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Analysis and output of runtime benchmarks.
+
+use std::io::{self, Write};
+use serde_json::json;
+use service::chain_ops::BenchmarkResults;
+
+/// The linear model of the duration of a benchmark as a function of one of its components, with
+/// the other components at their highest value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+	/// The name of the component.
+	pub component: String,
+	/// The duration in nanoseconds when the component is zero.
+	pub base: f64,
+	/// The additional duration in nanoseconds per unit of the component.
+	pub slope: f64,
+	/// The coefficient of determination of the model.
+	pub r_squared: f64,
+}
+
+/// The results of a benchmark run with one executor.
+pub struct Report {
+	/// The executor the benchmark was run with.
+	pub execution: &'static str,
+	/// The measurements.
+	pub results: BenchmarkResults,
+	/// The regressions against each component.
+	pub regressions: Vec<Regression>,
+}
+
+impl Report {
+	/// Analyse the results of a benchmark.
+	pub fn new(execution: &'static str, results: BenchmarkResults) -> Self {
+		let regressions = regressions(&results);
+		Report { execution, results, regressions }
+	}
+}
+
+/// Least-squares fit of `y = base + slope * x` to `points`, returning `(base, slope, r_squared)`.
+///
+/// Returns `None` if there are less than two distinct values of `x`.
+pub fn linear_regression(points: &[(f64, f64)]) -> Option<(f64, f64, f64)> {
+	let n = points.len() as f64;
+	let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+	let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+	let ss_xx = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+	if points.is_empty() || ss_xx == 0.0 {
+		return None;
+	}
+	let ss_xy = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum::<f64>();
+	let ss_yy = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum::<f64>();
+
+	let slope = ss_xy / ss_xx;
+	let base = mean_y - slope * mean_x;
+	let r_squared = if ss_yy == 0.0 { 1.0 } else { ss_xy * ss_xy / (ss_xx * ss_yy) };
+
+	Some((base, slope, r_squared))
+}
+
+/// The regressions of the duration of a benchmark against each of its components, using the
+/// samples where the other components are at their highest value.
+pub fn regressions(results: &BenchmarkResults) -> Vec<Regression> {
+	let components = &results.metadata.components;
+
+	components.iter().enumerate().filter_map(|(index, component)| {
+		let points = results.samples.iter()
+			.filter(|(values, _)| values.iter().zip(components).enumerate()
+				.all(|(i, (v, c))| i == index || *v == c.high)
+			)
+			.map(|(values, duration)| (values[index] as f64, *duration as f64))
+			.collect::<Vec<_>>();
+
+		linear_regression(&points).map(|(base, slope, r_squared)| Regression {
+			component: String::from_utf8_lossy(&component.name).into_owned(),
+			base,
+			slope,
+			r_squared,
+		})
+	}).collect()
+}
+
+/// Write the samples of the reports as CSV, followed by a second table with the regressions.
+pub fn write_csv<W: Write>(output: &mut W, reports: &[Report]) -> io::Result<()> {
+	for (index, report) in reports.iter().enumerate() {
+		let metadata = &report.results.metadata;
+		if index == 0 {
+			write!(output, "execution,module,benchmark")?;
+			for component in &metadata.components {
+				write!(output, ",{}", String::from_utf8_lossy(&component.name))?;
+			}
+			writeln!(output, ",time_ns")?;
+		}
+
+		for (values, duration) in &report.results.samples {
+			write!(
+				output,
+				"{},{},{}",
+				report.execution,
+				String::from_utf8_lossy(&metadata.module),
+				String::from_utf8_lossy(&metadata.name),
+			)?;
+			for value in values {
+				write!(output, ",{}", value)?;
+			}
+			writeln!(output, ",{}", duration)?;
+		}
+	}
+
+	writeln!(output)?;
+	writeln!(output, "execution,component,base_ns,slope_ns,r_squared")?;
+	for report in reports {
+		for regression in &report.regressions {
+			writeln!(
+				output,
+				"{},{},{:.2},{:.2},{:.4}",
+				report.execution,
+				regression.component,
+				regression.base,
+				regression.slope,
+				regression.r_squared,
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Write the reports as a JSON array.
+pub fn write_json<W: Write>(output: &mut W, reports: &[Report]) -> io::Result<()> {
+	let reports = reports.iter().map(|report| {
+		let metadata = &report.results.metadata;
+		json!({
+			"execution": report.execution,
+			"module": String::from_utf8_lossy(&metadata.module),
+			"benchmark": String::from_utf8_lossy(&metadata.name),
+			"components": metadata.components.iter().map(|c| json!({
+				"name": String::from_utf8_lossy(&c.name),
+				"low": c.low,
+				"high": c.high,
+			})).collect::<Vec<_>>(),
+			"samples": report.results.samples.iter().map(|(values, duration)| json!({
+				"components": values,
+				"time_ns": duration,
+			})).collect::<Vec<_>>(),
+			"regressions": report.regressions.iter().map(|r| json!({
+				"component": r.component,
+				"base_ns": r.base,
+				"slope_ns": r.slope,
+				"r_squared": r.r_squared,
+			})).collect::<Vec<_>>(),
+		})
+	}).collect::<Vec<_>>();
+
+	serde_json::to_writer_pretty(&mut *output, &reports)?;
+	writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use service::chain_ops::BenchmarkResults;
+	use benchmarking_primitives::{BenchmarkMetadata, BenchmarkComponent};
+
+	fn results() -> BenchmarkResults {
+		let component = |name: &[u8], low, high| BenchmarkComponent { name: name.to_vec(), low, high };
+		BenchmarkResults {
+			metadata: BenchmarkMetadata {
+				module: b"balances".to_vec(),
+				name: b"transfer".to_vec(),
+				components: vec![component(b"a", 0, 10), component(b"b", 1, 3)],
+			},
+			samples: vec![
+				(vec![0, 3], 130),
+				(vec![5, 3], 180),
+				(vec![10, 3], 230),
+				(vec![10, 1], 210),
+				(vec![10, 2], 220),
+				(vec![10, 3], 230),
+			],
+		}
+	}
+
+	#[test]
+	fn linear_regression_fits_a_line() {
+		let (base, slope, r_squared) = linear_regression(&[(1.0, 3.0), (2.0, 5.0), (3.0, 7.0)]).unwrap();
+		assert!((base - 1.0).abs() < 1e-9);
+		assert!((slope - 2.0).abs() < 1e-9);
+		assert!((r_squared - 1.0).abs() < 1e-9);
+
+		assert_eq!(linear_regression(&[]), None);
+		assert_eq!(linear_regression(&[(1.0, 3.0), (1.0, 5.0)]), None);
+	}
+
+	#[test]
+	fn regressions_use_the_samples_of_each_component() {
+		let regressions = regressions(&results());
+		assert_eq!(regressions.len(), 2);
+
+		assert_eq!(regressions[0].component, "a");
+		assert!((regressions[0].base - 130.0).abs() < 1e-9);
+		assert!((regressions[0].slope - 10.0).abs() < 1e-9);
+
+		assert_eq!(regressions[1].component, "b");
+		assert!((regressions[1].base - 200.0).abs() < 1e-9);
+		assert!((regressions[1].slope - 10.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn csv_output_lists_samples_and_regressions() {
+		let mut output = Vec::new();
+		write_csv(&mut output, &[Report::new("wasm", results())]).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		let lines = output.lines().collect::<Vec<_>>();
+
+		assert_eq!(lines[0], "execution,module,benchmark,a,b,time_ns");
+		assert_eq!(lines[1], "wasm,balances,transfer,0,3,130");
+		assert_eq!(lines[7], "");
+		assert_eq!(lines[8], "execution,component,base_ns,slope_ns,r_squared");
+		assert_eq!(lines[9], "wasm,a,130.00,10.00,1.0000");
+	}
+}
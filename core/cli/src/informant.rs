@@ -24,9 +24,9 @@ use service::{Service, Components};
 use tokio::runtime::TaskExecutor;
 use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
 use network::{SyncState, SyncProvider};
-use client::{backend::Backend, BlockchainEvents};
+use client::{backend::{Backend, CacheStats}, BlockchainEvents};
 use substrate_telemetry::{telemetry, SUBSTRATE_INFO};
-use log::{info, warn};
+use log::{info, debug, warn};
 
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{Header, As};
@@ -76,6 +76,21 @@ pub fn start<C>(service: &Service<C>, exit: ::exit_future::Exit, handle: TaskExe
 				TransferRateFormat(bandwidth_upload),
 			);
 
+			let state_cache = client.state_cache_stats();
+			if let Some(ref cache) = state_cache {
+				debug!(
+					target: "substrate",
+					"State cache: values {}, child values {}, hashes {}, nodes {}",
+					CacheStatsFormat(cache.values),
+					CacheStatsFormat(cache.child_values),
+					CacheStatsFormat(cache.hashes),
+					CacheStatsFormat(cache.nodes),
+				);
+			}
+			let used_state_cache_size = state_cache.map_or(0, |cache|
+				cache.values.used_bytes + cache.child_values.used_bytes + cache.hashes.used_bytes + cache.nodes.used_bytes
+			);
+
 			// get cpu usage and memory usage of this process
 			let (cpu_usage, memory) = if sys.refresh_process(self_pid) {
 				let proc = sys.get_process(self_pid).expect("Above refresh_process succeeds, this should be Some(), qed");
@@ -99,6 +114,7 @@ pub fn start<C>(service: &Service<C>, exit: ::exit_future::Exit, handle: TaskExe
 				"finalized_hash" => ?info.chain.finalized_hash,
 				"bandwidth_download" => bandwidth_download,
 				"bandwidth_upload" => bandwidth_upload,
+				"used_state_cache_size" => used_state_cache_size,
 			);
 		} else {
 			warn!("Error getting best block information");
@@ -191,3 +207,18 @@ impl fmt::Display for TransferRateFormat {
 		write!(f, "{:.1}MiB/s", self.0 as f64 / (1024.0 * 1024.0))
 	}
 }
+
+/// Shows the hit ratio, size and evictions of a cache.
+struct CacheStatsFormat(CacheStats);
+impl fmt::Display for CacheStatsFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{:.1}% hits, {:.1}/{:.1} MiB, {} evictions",
+			self.0.hit_ratio() * 100.0,
+			self.0.used_bytes as f64 / (1024.0 * 1024.0),
+			self.0.max_bytes as f64 / (1024.0 * 1024.0),
+			self.0.evictions,
+		)
+	}
+}
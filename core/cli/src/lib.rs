@@ -22,18 +22,19 @@
 #[macro_use]
 mod traits;
 mod params;
+mod benchmark;
 pub mod error;
 pub mod informant;
 
-use client::ExecutionStrategies;
+use client::{ExecutionStrategies, ExecutionStrategy};
 use runtime_primitives::traits::As;
 use service::{
 	ServiceFactory, FactoryFullConfiguration, RuntimeGenesis,
-	FactoryGenesis, PruningMode, ChainSpec,
+	FactoryGenesis, PruningMode, DatabaseEngine, ChainSpec,
 };
 use network::{
 	self, multiaddr::Protocol,
-	config::{NetworkConfiguration, NonReservedPeerMode, NodeKeyConfig},
+	config::{NetworkConfiguration, NonReservedPeerMode, NodeKeyConfig, MIN_BLOCKS_PRUNING},
 	build_multiaddr,
 };
 use primitives::H256;
@@ -49,11 +50,12 @@ use structopt::{StructOpt, clap::AppSettings};
 #[doc(hidden)]
 pub use structopt::clap::App;
 use params::{
-	RunCmd, PurgeChainCmd, RevertCmd, ImportBlocksCmd, ExportBlocksCmd, BuildSpecCmd,
-	NetworkConfigurationParams, SharedParams, MergeParameters, TransactionPoolParams,
+	RunCmd, PurgeChainCmd, RevertCmd, ImportBlocksCmd, ExportBlocksCmd, ExportStateCmd, ImportStateCmd,
+	MigrateDbCmd, DbStatsCmd, BuildSpecCmd, BenchmarkCmd,
+	TryRuntimeCmd, BenchmarkExecution, BenchmarkFormat, NetworkConfigurationParams, SharedParams, MergeParameters, TransactionPoolParams,
 	NodeKeyParams, NodeKeyType
 };
-pub use params::{NoCustom, CoreParams};
+pub use params::{NoCustom, CoreParams, SharedParams};
 pub use traits::{GetLogFilter, AugmentClap};
 use app_dirs::{AppInfo, AppDataType};
 use error_chain::bail;
@@ -122,7 +124,8 @@ fn generate_node_name() -> String {
 	result
 }
 
-fn load_spec<F, G>(cli: &SharedParams, factory: F) -> error::Result<ChainSpec<G>>
+/// Load the chain spec named by `--chain`, either one known to `factory` or a json file.
+pub fn load_spec<F, G>(cli: &SharedParams, factory: F) -> error::Result<ChainSpec<G>>
 	where G: RuntimeGenesis, F: FnOnce(&str) -> Result<Option<ChainSpec<G>>, String>,
 {
 	let chain_key = get_chain_key(cli);
@@ -236,10 +239,22 @@ where
 			export_blocks::<F, _, _>(params, spec_factory, exit, version).map(|_| None),
 		params::CoreParams::ImportBlocks(params) =>
 			import_blocks::<F, _, _>(params, spec_factory, exit, version).map(|_| None),
+		params::CoreParams::ExportState(params) =>
+			export_state::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::ImportState(params) =>
+			import_state::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::MigrateDb(params) =>
+			migrate_db::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::DbStats(params) =>
+			db_stats::<F, _>(params, spec_factory, version).map(|_| None),
 		params::CoreParams::PurgeChain(params) =>
 			purge_chain::<F, _>(params, spec_factory, version).map(|_| None),
 		params::CoreParams::Revert(params) =>
 			revert_chain::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::Benchmark(params) =>
+			run_benchmark::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::TryRuntime(params) =>
+			try_runtime::<F, _>(params, spec_factory, version).map(|_| None),
 		params::CoreParams::Custom(params) => Ok(Some(params)),
 	}
 }
@@ -399,15 +414,46 @@ where
 		.to_string_lossy()
 		.into();
 
+	config.database = cli.shared_params.database.into();
 	config.database_path =
-		db_path(&base_path, config.chain_spec.id()).to_string_lossy().into();
+		db_path(&base_path, config.chain_spec.id(), config.database).to_string_lossy().into();
 	config.database_cache_size = cli.database_cache_size;
+	let mib = |size: u32| size as usize * 1024 * 1024;
+	let default_cache = config.state_cache;
+	config.state_cache = service::StateCacheSizes {
+		values: cli.state_cache_values.map_or(default_cache.values, mib),
+		child_values: cli.state_cache_child_values.map_or(default_cache.child_values, mib),
+		hashes: cli.state_cache_hashes.map_or(default_cache.hashes, mib),
+		nodes: cli.state_cache_nodes.map_or(default_cache.nodes, mib),
+	};
 	config.pruning = match cli.pruning {
 		Some(ref s) if s == "archive" => PruningMode::ArchiveAll,
 		None => PruningMode::default(),
-		Some(s) => PruningMode::keep_blocks(
-			s.parse().map_err(|_| input_err("Invalid pruning mode specified"))?
-		),
+		Some(s) => {
+			let invalid = |_| input_err("Invalid pruning mode specified");
+			match s.find(':') {
+				Some(i) => {
+					let interval = s[i + 1..].parse().map_err(invalid)?;
+					if interval == 0 {
+						return Err(input_err("The checkpoint interval of the pruning mode must not be 0"));
+					}
+					PruningMode::keep_blocks_and_checkpoints(s[..i].parse().map_err(invalid)?, interval)
+				},
+				None => PruningMode::keep_blocks(s.parse().map_err(invalid)?),
+			}
+		},
+	};
+
+	config.blocks_pruning = match cli.blocks_pruning {
+		Some(ref s) if s == "archive" => service::BlocksPruning::KeepAll,
+		None => service::BlocksPruning::default(),
+		Some(s) => {
+			let keep = s.parse().map_err(|_| input_err("Invalid blocks pruning mode specified"))?;
+			if keep < MIN_BLOCKS_PRUNING {
+				return Err(input_err(format!("Blocks pruning must keep at least {} blocks", MIN_BLOCKS_PRUNING)));
+			}
+			service::BlocksPruning::KeepFinalized(keep)
+		},
 	};
 
 	let role =
@@ -435,7 +481,10 @@ where
 		(params::OffchainWorkerEnabled::WhenValidating, _) => false,
 	};
 
-	config.roles = role;
+	config.roles = match config.blocks_pruning {
+		service::BlocksPruning::KeepFinalized(_) if !cli.light => role | service::Roles::BLOCKS_PRUNED,
+		_ => role,
+	};
 	config.disable_grandpa = cli.no_grandpa;
 
 	let client_id = config.client_id();
@@ -474,6 +523,12 @@ where
 		parse_address(&format!("{}:{}", ws_interface, 9944), cli.ws_port)?
 	);
 
+	let prometheus_interface: &str = if cli.prometheus_external { "0.0.0.0" } else { "127.0.0.1" };
+	config.prometheus_endpoint = match cli.prometheus_port {
+		Some(port) => Some(parse_address(&format!("{}:{}", prometheus_interface, 9615), Some(port))?),
+		None => None,
+	};
+
 	// Override telemetry
 	if cli.no_telemetry {
 		config.telemetry_endpoints = None;
@@ -482,6 +537,7 @@ where
 	}
 
 	config.force_authoring = cli.force_authoring;
+	config.sealing = cli.sealing.into();
 
 	Ok(config)
 }
@@ -549,7 +605,16 @@ where
 {
 	info!("Building chain spec");
 	let raw_output = cli.raw;
-	let mut spec = load_spec(&cli.shared_params, spec_factory)?;
+	let mut spec = match cli.from_state {
+		Some(block) => {
+			let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+			let mut spec = config.chain_spec.clone();
+			let storage = service::chain_ops::state_as_genesis::<F>(config, Some(As::sa(block)), cli.drop_child_tries)?;
+			spec.set_raw_genesis(storage);
+			spec
+		},
+		None => load_spec(&cli.shared_params, spec_factory)?,
+	};
 	with_default_boot_node::<F>(&mut spec, cli, version)?;
 	let json = service::chain_ops::build_spec::<FactoryGenesis<F>>(spec, raw_output)?;
 
@@ -569,7 +634,8 @@ where
 	let base_path = base_path(cli, version);
 
 	let mut config = service::Configuration::default_with_spec(spec.clone());
-	config.database_path = db_path(&base_path, spec.id()).to_string_lossy().into();
+	config.database = cli.database.into();
+	config.database_path = db_path(&base_path, spec.id(), config.database).to_string_lossy().into();
 
 	Ok(config)
 }
@@ -623,6 +689,85 @@ where
 	service::chain_ops::import_blocks::<F, _, _>(config, exit.into_exit(), file).map_err(Into::into)
 }
 
+fn export_state<F, S>(
+	cli: ExportStateCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	info!("DB path: {}", config.database_path);
+	let file: Box<Write> = match cli.output {
+		Some(filename) => Box::new(File::create(filename)?),
+		None => Box::new(stdout()),
+	};
+
+	service::chain_ops::export_state::<F, _>(config, file, cli.block.map(As::sa)).map_err(Into::into)
+}
+
+fn import_state<F, S>(
+	cli: ImportStateCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	let file: Box<Read> = match cli.input {
+		Some(filename) => Box::new(File::open(filename)?),
+		None => Box::new(stdin()),
+	};
+
+	service::chain_ops::import_state::<F, _>(config, file).map_err(Into::into)
+}
+
+fn migrate_db<F, S>(
+	cli: MigrateDbCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+	let to_database = cli.to_database.into();
+	let to_path = match cli.to_path {
+		Some(path) => path,
+		None => db_path(&base_path(&cli.shared_params, version), config.chain_spec.id(), to_database),
+	};
+
+	service::chain_ops::migrate_database::<F>(&config, to_database, &to_path.to_string_lossy())
+		.map_err(Into::into)
+}
+
+fn db_stats<F, S>(
+	cli: DbStatsCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	info!("DB path: {}", config.database_path);
+	println!("{:<16}{:>12}{:>16}{:>16}", "column", "keys", "key bytes", "value bytes");
+	for stats in service::chain_ops::database_stats::<F>(&config)? {
+		println!("{:<16}{:>12}{:>16}{:>16}", stats.name, stats.keys, stats.key_bytes, stats.value_bytes);
+	}
+
+	Ok(())
+}
+
 fn revert_chain<F, S>(
 	cli: RevertCmd,
 	spec_factory: S,
@@ -637,6 +782,72 @@ where
 	Ok(service::chain_ops::revert_chain::<F>(config, As::sa(blocks))?)
 }
 
+fn run_benchmark<F, S>(
+	cli: BenchmarkCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	let executions = match cli.execution {
+		BenchmarkExecution::Native => vec![("native", ExecutionStrategy::NativeWhenPossible)],
+		BenchmarkExecution::Wasm => vec![("wasm", ExecutionStrategy::AlwaysWasm)],
+		BenchmarkExecution::Both => vec![
+			("native", ExecutionStrategy::NativeWhenPossible),
+			("wasm", ExecutionStrategy::AlwaysWasm),
+		],
+	};
+
+	let mut reports = Vec::new();
+	for (execution, strategy) in executions {
+		let results = service::chain_ops::benchmark::<F>(
+			&config, &cli.module, &cli.benchmark, cli.steps, cli.repeat, strategy,
+		)?;
+		let report = benchmark::Report::new(execution, results);
+		for regression in &report.regressions {
+			info!(
+				"{} {}::{}: {:.0} + {:.2} * {} ns (r^2 = {:.3})",
+				execution, cli.module, cli.benchmark,
+				regression.base, regression.slope, regression.component, regression.r_squared,
+			);
+		}
+		reports.push(report);
+	}
+
+	let mut output: Box<Write> = match cli.output {
+		Some(filename) => Box::new(File::create(filename)?),
+		None => Box::new(stdout()),
+	};
+
+	match cli.format {
+		BenchmarkFormat::Csv => benchmark::write_csv(&mut output, &reports)?,
+		BenchmarkFormat::Json => benchmark::write_json(&mut output, &reports)?,
+	}
+
+	Ok(())
+}
+
+fn try_runtime<F, S>(
+	cli: TryRuntimeCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	let mut code = Vec::new();
+	File::open(&cli.wasm)?.read_to_end(&mut code)?;
+
+	service::chain_ops::try_runtime::<F>(config, code, cli.at.map(As::sa), As::sa(cli.blocks)).map_err(Into::into)
+}
+
 fn purge_chain<F, S>(
 	cli: PurgeChainCmd,
 	spec_factory: S,
@@ -647,6 +858,10 @@ where
 	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
 {
 	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+	if config.database == DatabaseEngine::Memory {
+		println!("An in-memory database has nothing to purge.");
+		return Ok(());
+	}
 	let db_path = config.database_path;
 
 	if cli.yes == false {
@@ -694,11 +909,14 @@ fn keystore_path(base_path: &Path, chain_id: &str) -> PathBuf {
 	path
 }
 
-fn db_path(base_path: &Path, chain_id: &str) -> PathBuf {
+fn db_path(base_path: &Path, chain_id: &str, database: DatabaseEngine) -> PathBuf {
 	let mut path = base_path.to_owned();
 	path.push("chains");
 	path.push(chain_id);
-	path.push("db");
+	path.push(match database {
+		DatabaseEngine::Sled => "sled",
+		DatabaseEngine::RocksDb | DatabaseEngine::Memory => "db",
+	});
 	path
 }
 
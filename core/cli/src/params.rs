@@ -19,6 +19,7 @@ use crate::traits::{AugmentClap, GetLogFilter};
 use std::path::PathBuf;
 use structopt::{StructOpt, clap::{arg_enum, _clap_count_exprs, App, AppSettings, SubCommand, Arg}};
 use client;
+use service;
 
 /// Auxialary macro to implement `GetLogFilter` for all types that have the `shared_params` field.
 macro_rules! impl_get_log_filter {
@@ -55,6 +56,26 @@ impl Into<client::ExecutionStrategy> for ExecutionStrategy {
 	}
 }
 
+arg_enum! {
+	/// Which engine stores the database
+	#[derive(Debug, Clone, Copy)]
+	pub enum Database {
+		RocksDb,
+		Sled,
+		Memory,
+	}
+}
+
+impl Into<service::DatabaseEngine> for Database {
+	fn into(self) -> service::DatabaseEngine {
+		match self {
+			Database::RocksDb => service::DatabaseEngine::RocksDb,
+			Database::Sled => service::DatabaseEngine::Sled,
+			Database::Memory => service::DatabaseEngine::Memory,
+		}
+	}
+}
+
 arg_enum! {
 	/// How to execute blocks
 	#[derive(Debug, Clone)]
@@ -65,6 +86,45 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// Which executors to run a benchmark with
+	#[derive(Debug, Clone)]
+	pub enum BenchmarkExecution {
+		Native,
+		Wasm,
+		Both,
+	}
+}
+
+arg_enum! {
+	/// How to output the results of a benchmark
+	#[derive(Debug, Clone)]
+	pub enum BenchmarkFormat {
+		Csv,
+		Json,
+	}
+}
+
+arg_enum! {
+	/// How blocks are sealed
+	#[derive(Debug, Clone)]
+	pub enum Sealing {
+		Consensus,
+		Instant,
+		Manual,
+	}
+}
+
+impl Into<service::Sealing> for Sealing {
+	fn into(self) -> service::Sealing {
+		match self {
+			Sealing::Consensus => service::Sealing::Consensus,
+			Sealing::Instant => service::Sealing::Instant,
+			Sealing::Manual => service::Sealing::Manual,
+		}
+	}
+}
+
 /// Shared parameters used by all `CoreParams`.
 #[derive(Debug, StructOpt, Clone)]
 pub struct SharedParams {
@@ -83,6 +143,18 @@ pub struct SharedParams {
 	/// Sets a custom logging filter
 	#[structopt(short = "l", long = "log", value_name = "LOG_PATTERN")]
 	pub log: Option<String>,
+
+	/// Select the engine of the database. The in-memory database is lost when the node stops.
+	#[structopt(
+		long = "database",
+		value_name = "DB",
+		raw(
+			possible_values = "&Database::variants()",
+			case_insensitive = "true",
+			default_value = r#""RocksDb""#
+		)
+	)]
+	pub database: Database,
 }
 
 impl GetLogFilter for SharedParams {
@@ -308,6 +380,22 @@ pub struct RunCmd {
 	#[structopt(long = "db-cache", value_name = "MiB")]
 	pub database_cache_size: Option<u32>,
 
+	/// Limit the memory the cache of storage values can use. Default is 16.
+	#[structopt(long = "state-cache-values", value_name = "MiB")]
+	pub state_cache_values: Option<u32>,
+
+	/// Limit the memory the cache of child storage values can use. Default is 4.
+	#[structopt(long = "state-cache-child-values", value_name = "MiB")]
+	pub state_cache_child_values: Option<u32>,
+
+	/// Limit the memory the cache of storage hashes can use. Default is 4.
+	#[structopt(long = "state-cache-hashes", value_name = "MiB")]
+	pub state_cache_hashes: Option<u32>,
+
+	/// Limit the memory the cache of trie nodes can use. Default is 32.
+	#[structopt(long = "state-cache-nodes", value_name = "MiB")]
+	pub state_cache_nodes: Option<u32>,
+
 	/// Listen to all RPC interfaces (default is local)
 	#[structopt(long = "rpc-external")]
 	pub rpc_external: bool,
@@ -324,10 +412,24 @@ pub struct RunCmd {
 	#[structopt(long = "ws-port", value_name = "PORT")]
 	pub ws_port: Option<u16>,
 
+	/// Serve Prometheus metrics on this TCP port (disabled by default)
+	#[structopt(long = "prometheus-port", value_name = "PORT")]
+	pub prometheus_port: Option<u16>,
+
+	/// Listen to all Prometheus interfaces (default is local)
+	#[structopt(long = "prometheus-external")]
+	pub prometheus_external: bool,
+
 	/// Specify the pruning mode, a number of blocks to keep or 'archive'. Default is 256.
+	/// 'N:K' keeps N blocks and the state of every Kth block, e.g. '256:10000'.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
 
+	/// Specify the blocks pruning mode, a number of finalized blocks whose bodies are kept or
+	/// 'archive'. Default is 'archive'. Peers are told not to request older bodies.
+	#[structopt(long = "blocks-pruning", value_name = "BLOCKS_PRUNING_MODE")]
+	pub blocks_pruning: Option<String>,
+
 	/// The human-readable name for this node, as reported to the telemetry server, if enabled
 	#[structopt(long = "name", value_name = "NAME")]
 	pub name: Option<String>,
@@ -378,6 +480,19 @@ pub struct RunCmd {
 	/// Enable authoring even when offline.
 	#[structopt(long = "force-authoring")]
 	pub force_authoring: bool,
+
+	/// How blocks are sealed: by the consensus engine, as soon as a transaction is ready (instant),
+	/// or on request through the `engine_createBlock` RPC (manual). Only meant for development chains.
+	#[structopt(
+		long = "sealing",
+		value_name = "SEALING",
+		raw(
+			possible_values = "&Sealing::variants()",
+			case_insensitive = "true",
+			default_value = r#""Consensus""#
+		)
+	)]
+	pub sealing: Sealing,
 }
 
 /// Stores all required Cli values for a keyring test account.
@@ -475,6 +590,15 @@ pub struct BuildSpecCmd {
 	#[structopt(long = "raw")]
 	pub raw: bool,
 
+	/// Use the state of the given block of the database as genesis, turning the chain into a new one.
+	#[structopt(long = "from-state", value_name = "BLOCK")]
+	pub from_state: Option<u64>,
+
+	/// Leave out the child tries of the state used as genesis, which a raw genesis can't hold,
+	/// rather than refusing the state.
+	#[structopt(long = "drop-child-tries", raw(requires = r#""from_state""#))]
+	pub drop_child_tries: bool,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub shared_params: SharedParams,
@@ -530,6 +654,38 @@ pub struct ImportBlocksCmd {
 
 impl_get_log_filter!(ImportBlocksCmd);
 
+/// The `export-state` command used to export the state at a block.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ExportStateCmd {
+	/// The block whose state is exported. Finalized block by default.
+	#[structopt(value_name = "BLOCK")]
+	pub block: Option<u64>,
+
+	/// Output file name or stdout if unspecified.
+	#[structopt(long = "output", value_name = "PATH", parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(ExportStateCmd);
+
+/// The `import-state` command used to start a database from an exported state.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ImportStateCmd {
+	/// Input file or stdin if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub input: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(ImportStateCmd);
+
 /// The `revert` command used revert the chain to a previos state.
 #[derive(Debug, StructOpt, Clone)]
 pub struct RevertCmd {
@@ -558,6 +714,113 @@ pub struct PurgeChainCmd {
 
 impl_get_log_filter!(PurgeChainCmd);
 
+/// The `migrate-db` command used to copy the database to another engine.
+#[derive(Debug, StructOpt, Clone)]
+pub struct MigrateDbCmd {
+	/// The engine of the new database.
+	#[structopt(
+		long = "to-database",
+		value_name = "DB",
+		raw(possible_values = "&Database::variants()", case_insensitive = "true")
+	)]
+	pub to_database: Database,
+
+	/// Path of the new database. The default path of the engine by default.
+	#[structopt(long = "to-path", value_name = "PATH", parse(from_os_str))]
+	pub to_path: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(MigrateDbCmd);
+
+/// The `db-stats` command used to show the size of the columns of the database.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DbStatsCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(DbStatsCmd);
+
+/// The `benchmark` command used to measure the cost of the dispatchable calls of the runtime.
+#[derive(Debug, StructOpt, Clone)]
+pub struct BenchmarkCmd {
+	/// The module of the benchmark, e.g. `balances`.
+	pub module: String,
+
+	/// The name of the benchmark, e.g. `transfer`.
+	pub benchmark: String,
+
+	/// The number of steps from the lowest to the highest value of each component, which is
+	/// benchmarked with `steps + 1` values.
+	#[structopt(long = "steps", value_name = "COUNT", default_value = "10")]
+	pub steps: u32,
+
+	/// How many times each combination of component values is benchmarked.
+	#[structopt(long = "repeat", value_name = "COUNT", default_value = "20")]
+	pub repeat: u32,
+
+	/// The executors the benchmark is run with.
+	#[structopt(
+		long = "execution",
+		value_name = "EXECUTION",
+		raw(
+			possible_values = "&BenchmarkExecution::variants()",
+			case_insensitive = "true",
+			default_value = r#""Both""#
+		)
+	)]
+	pub execution: BenchmarkExecution,
+
+	/// The format of the results.
+	#[structopt(
+		long = "format",
+		value_name = "FORMAT",
+		raw(
+			possible_values = "&BenchmarkFormat::variants()",
+			case_insensitive = "true",
+			default_value = r#""Csv""#
+		)
+	)]
+	pub format: BenchmarkFormat,
+
+	/// Output file name or stdout if unspecified.
+	#[structopt(long = "output", value_name = "PATH", parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(BenchmarkCmd);
+
+/// The `try-runtime` command used to execute blocks of the chain with a new runtime.
+#[derive(Debug, StructOpt, Clone)]
+pub struct TryRuntimeCmd {
+	/// The wasm file of the new runtime.
+	#[structopt(parse(from_os_str))]
+	pub wasm: PathBuf,
+
+	/// The block whose state the new runtime is tried on. `--blocks` blocks before the best block by default.
+	#[structopt(long = "at", value_name = "BLOCK")]
+	pub at: Option<u64>,
+
+	/// The number of blocks after `--at` to execute.
+	#[structopt(long = "blocks", value_name = "COUNT", default_value = "10")]
+	pub blocks: u64,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(TryRuntimeCmd);
+
 /// All core commands that are provided by default.
 ///
 /// The core commands are split into multiple subcommands and `Run` is the default subcommand. From
@@ -577,12 +840,30 @@ pub enum CoreParams<CC, RP> {
 	/// Import blocks from file.
 	ImportBlocks(ImportBlocksCmd),
 
+	/// Export the state at a block to a file.
+	ExportState(ExportStateCmd),
+
+	/// Start an empty database from a state exported to a file.
+	ImportState(ImportStateCmd),
+
+	/// Copy the database to another engine.
+	MigrateDb(MigrateDbCmd),
+
+	/// Show the size of the columns of the database.
+	DbStats(DbStatsCmd),
+
 	/// Revert chain to the previous state.
 	Revert(RevertCmd),
 
 	/// Remove the whole chain data.
 	PurgeChain(PurgeChainCmd),
 
+	/// Benchmark the dispatchable calls of the runtime.
+	Benchmark(BenchmarkCmd),
+
+	/// Execute blocks of the chain with a new runtime, without importing them.
+	TryRuntime(TryRuntimeCmd),
+
 	/// Further custom subcommands.
 	Custom(CC),
 }
@@ -608,6 +889,22 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 			ImportBlocksCmd::augment_clap(SubCommand::with_name("import-blocks"))
 				.about("Import blocks from file.")
 		)
+		.subcommand(
+			ExportStateCmd::augment_clap(SubCommand::with_name("export-state"))
+				.about("Export the state at a block to a file.")
+		)
+		.subcommand(
+			ImportStateCmd::augment_clap(SubCommand::with_name("import-state"))
+				.about("Start an empty database from a state exported to a file.")
+		)
+		.subcommand(
+			MigrateDbCmd::augment_clap(SubCommand::with_name("migrate-db"))
+				.about("Copy the database to another engine.")
+		)
+		.subcommand(
+			DbStatsCmd::augment_clap(SubCommand::with_name("db-stats"))
+				.about("Show the size of the columns of the database.")
+		)
 		.subcommand(
 			RevertCmd::augment_clap(SubCommand::with_name("revert"))
 				.about("Revert chain to the previous state.")
@@ -616,6 +913,14 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 			PurgeChainCmd::augment_clap(SubCommand::with_name("purge-chain"))
 				.about("Remove the whole chain data.")
 		)
+		.subcommand(
+			BenchmarkCmd::augment_clap(SubCommand::with_name("benchmark"))
+				.about("Benchmark the dispatchable calls of the runtime.")
+		)
+		.subcommand(
+			TryRuntimeCmd::augment_clap(SubCommand::with_name("try-runtime"))
+				.about("Execute blocks of the chain with a new runtime, without importing them.")
+		)
 	}
 
 	fn from_clap(matches: &::structopt::clap::ArgMatches) -> Self {
@@ -626,9 +931,21 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 				CoreParams::ExportBlocks(ExportBlocksCmd::from_clap(matches)),
 			("import-blocks", Some(matches)) =>
 				CoreParams::ImportBlocks(ImportBlocksCmd::from_clap(matches)),
+			("export-state", Some(matches)) =>
+				CoreParams::ExportState(ExportStateCmd::from_clap(matches)),
+			("import-state", Some(matches)) =>
+				CoreParams::ImportState(ImportStateCmd::from_clap(matches)),
+			("migrate-db", Some(matches)) =>
+				CoreParams::MigrateDb(MigrateDbCmd::from_clap(matches)),
+			("db-stats", Some(matches)) =>
+				CoreParams::DbStats(DbStatsCmd::from_clap(matches)),
 			("revert", Some(matches)) => CoreParams::Revert(RevertCmd::from_clap(matches)),
 			("purge-chain", Some(matches)) =>
 				CoreParams::PurgeChain(PurgeChainCmd::from_clap(matches)),
+			("benchmark", Some(matches)) =>
+				CoreParams::Benchmark(BenchmarkCmd::from_clap(matches)),
+			("try-runtime", Some(matches)) =>
+				CoreParams::TryRuntime(TryRuntimeCmd::from_clap(matches)),
 			(_, None) => CoreParams::Run(MergeParameters::from_clap(matches)),
 			_ => CoreParams::Custom(CC::from_clap(matches)),
 		}
@@ -642,8 +959,14 @@ impl<CC, RP> GetLogFilter for CoreParams<CC, RP> where CC: GetLogFilter {
 			CoreParams::BuildSpec(c) => c.get_log_filter(),
 			CoreParams::ExportBlocks(c) => c.get_log_filter(),
 			CoreParams::ImportBlocks(c) => c.get_log_filter(),
+			CoreParams::ExportState(c) => c.get_log_filter(),
+			CoreParams::ImportState(c) => c.get_log_filter(),
+			CoreParams::MigrateDb(c) => c.get_log_filter(),
+			CoreParams::DbStats(c) => c.get_log_filter(),
 			CoreParams::PurgeChain(c) => c.get_log_filter(),
 			CoreParams::Revert(c) => c.get_log_filter(),
+			CoreParams::Benchmark(c) => c.get_log_filter(),
+			CoreParams::TryRuntime(c) => c.get_log_filter(),
 			CoreParams::Custom(c) => c.get_log_filter(),
 		}
 	}
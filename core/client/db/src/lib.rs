@@ -29,12 +29,15 @@ pub mod light;
 mod cache;
 mod storage_cache;
 mod utils;
+mod sled_db;
+mod maintenance;
 
 use std::sync::Arc;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::io;
 
-use client::backend::NewBlockState;
+use client::backend::{NewBlockState, StateCacheStats, StorageCollection, ChildStorageCollection};
 use client::blockchain::HeaderBackend;
 use client::ExecutionStrategies;
 use parity_codec::{Decode, Encode};
@@ -44,6 +47,7 @@ use trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use parking_lot::RwLock;
 use primitives::{H256, Blake2Hasher, ChangesTrieConfiguration, convert_hash};
 use primitives::storage::well_known_keys;
+use primitives::hexdisplay::HexDisplay;
 use runtime_primitives::{generic::BlockId, Justification, StorageOverlay, ChildrenStorageOverlay};
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, As, NumberFor, Zero, Digest, DigestItem, AuthorityIdFor};
 use runtime_primitives::BuildStorage;
@@ -54,28 +58,78 @@ use crate::utils::{Meta, db_err, meta_keys, open_database, read_db, block_id_to_
 use client::leaves::{LeafSet, FinalizationDisplaced};
 use client::children;
 use state_db::StateDb;
-use crate::storage_cache::{CachingState, SharedCache, new_shared_cache};
-use log::{trace, debug, warn};
+use crate::storage_cache::{CachingState, SharedCache, NodeCache, new_shared_cache};
+use log::{trace, debug, info, warn};
+use lazy_static::lazy_static;
+use substrate_prometheus::IntGaugeVec;
 pub use state_db::PruningMode;
+pub use maintenance::{ColumnStats, database_stats, migrate_database};
+pub use storage_cache::StateCacheSizes;
 
 #[cfg(feature = "test-helpers")]
 use client::in_mem::Backend as InMemoryBackend;
 
 const CANONICALIZATION_DELAY: u64 = 4096;
 const MIN_BLOCKS_TO_KEEP_CHANGES_TRIES_FOR: u64 = 32768;
-const STATE_CACHE_SIZE_BYTES: usize = 16 * 1024 * 1024;
+
+lazy_static! {
+	static ref STATE_DB_MEMORY: IntGaugeVec = substrate_prometheus::int_gauge_vec(
+		"state_db_memory_bytes",
+		"Estimated memory used by the overlays of the state database",
+		&["overlay"],
+	);
+}
 
 /// DB-backed patricia trie state, transaction type is an overlay of changes to commit.
 pub type DbState = state_machine::TrieBackend<Arc<state_machine::Storage<Blake2Hasher>>, Blake2Hasher>;
 
+/// The engine storing a database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseEngine {
+	/// RocksDB.
+	RocksDb,
+	/// Sled, an embedded database written in Rust.
+	Sled,
+	/// Kept in memory and lost when closed. Meant for tests.
+	Memory,
+}
+
+impl Default for DatabaseEngine {
+	fn default() -> Self {
+		DatabaseEngine::RocksDb
+	}
+}
+
+/// Which block bodies are kept in the database. Justifications are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocksPruning {
+	/// Keep the bodies of all blocks.
+	KeepAll,
+	/// Delete the bodies of finalized blocks older than the given number of blocks, and of the
+	/// forks displaced by finalization.
+	KeepFinalized(u32),
+}
+
+impl Default for BlocksPruning {
+	fn default() -> Self {
+		BlocksPruning::KeepAll
+	}
+}
+
 /// Database settings.
 pub struct DatabaseSettings {
 	/// Cache size in bytes. If `None` default is used.
 	pub cache_size: Option<usize>,
-	/// Path to the database.
+	/// Path to the database. Unused by the in-memory engine.
 	pub path: PathBuf,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Block bodies pruning mode.
+	pub blocks_pruning: BlocksPruning,
+	/// Sizes of the state caches.
+	pub state_cache: StateCacheSizes,
+	/// Database engine.
+	pub engine: DatabaseEngine,
 }
 
 /// Create an instance of db-backed client.
@@ -95,6 +149,32 @@ pub fn new_client<E, S, Block, RA>(
 	Ok(client::Client::new(backend, executor, genesis_storage, execution_strategies)?)
 }
 
+/// Import the state of a block into an empty database, as the starting point of the chain.
+///
+/// See `Backend::import_state_snapshot`.
+pub fn import_state_snapshot<Block, I>(
+	settings: DatabaseSettings,
+	genesis_hash: Block::Hash,
+	header: Block::Header,
+	entries: I,
+	aux: Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<(), client::error::Error>
+	where
+		Block: BlockT<Hash=H256>,
+		I: IntoIterator<Item=Result<SnapshotEntry, client::error::Error>>,
+{
+	let backend = Backend::<Block>::new(settings, CANONICALIZATION_DELAY)?;
+	backend.import_state_snapshot(genesis_hash, header, entries, aux)
+}
+
+/// An entry of a state snapshot: the child storage key of the entry, if it belongs to a child
+/// trie, its key and its value.
+pub type SnapshotEntry = (Option<Vec<u8>>, Vec<u8>, Vec<u8>);
+
+/// The number of snapshot entries inserted into the state trie before its nodes are written to
+/// the database.
+const SNAPSHOT_CHUNK_SIZE: usize = 64 * 1024;
+
 mod columns {
 	pub const META: Option<u32> = crate::utils::COLUMN_META;
 	pub const STATE: Option<u32> = Some(1);
@@ -131,19 +211,39 @@ pub struct BlockchainDb<Block: BlockT> {
 	db: Arc<KeyValueDB>,
 	meta: Arc<RwLock<Meta<NumberFor<Block>, Block::Hash>>>,
 	leaves: RwLock<LeafSet<Block::Hash, NumberFor<Block>>>,
+	blocks_pruning: BlocksPruning,
 }
 
 impl<Block: BlockT> BlockchainDb<Block> {
-	fn new(db: Arc<KeyValueDB>) -> Result<Self, client::error::Error> {
+	fn new(db: Arc<KeyValueDB>, blocks_pruning: BlocksPruning) -> Result<Self, client::error::Error> {
 		let meta = read_meta::<Block>(&*db, columns::META, columns::HEADER)?;
 		let leaves = LeafSet::read_from_db(&*db, columns::META, meta_keys::LEAF_PREFIX)?;
 		Ok(BlockchainDb {
 			db,
 			leaves: RwLock::new(leaves),
 			meta: Arc::new(RwLock::new(meta)),
+			blocks_pruning,
 		})
 	}
 
+	/// Whether the given block is canonical and old enough for its body to have been pruned.
+	fn is_body_pruned(&self, id: BlockId<Block>) -> Result<bool, client::error::Error> {
+		let keep = match self.blocks_pruning {
+			BlocksPruning::KeepAll => return Ok(false),
+			BlocksPruning::KeepFinalized(keep) => keep,
+		};
+		let header = match utils::read_header::<Block>(&*self.db, columns::KEY_LOOKUP, columns::HEADER, id)? {
+			Some(header) => header,
+			None => return Ok(false),
+		};
+		let number = *header.number();
+		if number.is_zero() || self.hash(number)? != Some(header.hash()) {
+			return Ok(false);
+		}
+		let finalized_number = self.meta.read().finalized_number;
+		Ok(number.as_() + u64::from(keep) <= finalized_number.as_())
+	}
+
 	fn update_meta(
 		&self,
 		hash: Block::Hash,
@@ -225,6 +325,8 @@ impl<Block: BlockT> client::blockchain::Backend<Block> for BlockchainDb<Block> {
 				Some(body) => Ok(Some(body)),
 				None => return Err(client::error::ErrorKind::Backend("Error decoding body".into()).into()),
 			}
+			None if self.is_body_pruned(id)? =>
+				Err(client::error::ErrorKind::BlockBodyPruned(format!("{}", id)).into()),
 			None => Ok(None),
 		}
 	}
@@ -260,7 +362,8 @@ impl<Block: BlockT> client::blockchain::Backend<Block> for BlockchainDb<Block> {
 pub struct BlockImportOperation<Block: BlockT, H: Hasher> {
 	old_state: CachingState<Blake2Hasher, DbState, Block>,
 	db_updates: PrefixedMemoryDB<H>,
-	storage_updates: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	storage_updates: StorageCollection,
+	child_storage_updates: ChildStorageCollection,
 	changes_trie_updates: MemoryDB<H>,
 	pending_block: Option<PendingBlock<Block>>,
 	aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
@@ -355,8 +458,13 @@ where Block: BlockT<Hash=H256>,
 		Ok(())
 	}
 
-	fn update_storage(&mut self, update: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> Result<(), client::error::Error> {
+	fn update_storage(
+		&mut self,
+		update: StorageCollection,
+		child_update: ChildStorageCollection,
+	) -> Result<(), client::error::Error> {
 		self.storage_updates = update;
+		self.child_storage_updates = child_update;
 		Ok(())
 	}
 
@@ -375,12 +483,14 @@ where Block: BlockT<Hash=H256>,
 struct StorageDb<Block: BlockT> {
 	pub db: Arc<KeyValueDB>,
 	pub state_db: StateDb<Block::Hash, Vec<u8>>,
+	pub node_cache: NodeCache,
 }
 
 impl<Block: BlockT> state_machine::Storage<Blake2Hasher> for StorageDb<Block> {
 	fn get(&self, key: &H256, prefix: &[u8]) -> Result<Option<DBValue>, String> {
 		let key = prefixed_key::<Blake2Hasher>(key, prefix);
-		self.state_db.get(&key, self).map(|r| r.map(|v| DBValue::from_slice(&v)))
+		self.node_cache.get_or_fetch(&key, || self.state_db.get(&key, self))
+			.map(|r| r.map(|v| DBValue::from_slice(&v)))
 			.map_err(|e| format!("Database backend error: {:?}", e))
 	}
 }
@@ -539,7 +649,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 	pub fn new(config: DatabaseSettings, canonicalization_delay: u64) -> Result<Self, client::error::Error> {
 		let db = open_database(&config, columns::META, "full")?;
 
-		Backend::from_kvdb(db as Arc<_>, config.pruning, canonicalization_delay)
+		Backend::from_kvdb(db as Arc<_>, config.pruning, config.blocks_pruning, config.state_cache, canonicalization_delay)
 	}
 
 	#[cfg(any(test, feature = "test-helpers"))]
@@ -551,19 +661,28 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 		Backend::from_kvdb(
 			db as Arc<_>,
 			PruningMode::keep_blocks(keep_blocks),
+			BlocksPruning::KeepAll,
+			Default::default(),
 			canonicalization_delay,
 		).expect("failed to create test-db")
 	}
 
-	fn from_kvdb(db: Arc<KeyValueDB>, pruning: PruningMode, canonicalization_delay: u64) -> Result<Self, client::error::Error> {
+	fn from_kvdb(
+		db: Arc<KeyValueDB>,
+		pruning: PruningMode,
+		blocks_pruning: BlocksPruning,
+		state_cache: StateCacheSizes,
+		canonicalization_delay: u64,
+	) -> Result<Self, client::error::Error> {
 		let is_archive_pruning = pruning.is_archive();
-		let blockchain = BlockchainDb::new(db.clone())?;
+		let blockchain = BlockchainDb::new(db.clone(), blocks_pruning)?;
 		let meta = blockchain.meta.clone();
 		let map_e = |e: state_db::Error<io::Error>| ::client::error::Error::from(format!("State database error: {:?}", e));
 		let state_db: StateDb<_, _> = StateDb::new(pruning, &StateMetaDb(&*db)).map_err(map_e)?;
 		let storage_db = StorageDb {
 			db: db.clone(),
 			state_db,
+			node_cache: NodeCache::new(state_cache.nodes),
 		};
 		let changes_tries_storage = DbChangesTrieStorage {
 			db,
@@ -577,7 +696,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			changes_tries_storage,
 			blockchain,
 			canonicalization_delay,
-			shared_cache: new_shared_cache(STATE_CACHE_SIZE_BYTES),
+			shared_cache: new_shared_cache(&state_cache),
 		})
 	}
 
@@ -771,6 +890,153 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 		Ok(())
 	}
 
+	/// Import the state of a block into an empty database.
+	///
+	/// The block becomes the best and finalized block and the chain continues from it, while the
+	/// blocks before it stay unknown. The entries are inserted into the state trie in chunks,
+	/// whose nodes are written to the database as they are built, so the entries never have to fit
+	/// in memory. The nodes superseded by later chunks are deleted once the state is complete, and
+	/// the others are committed through the state database, which tracks them for pruning like the
+	/// nodes of any imported block. Entries of the same child trie should follow each other. The storage root of the
+	/// state must match the state root of the header. `aux` is written to the auxiliary store
+	/// along with the block.
+	pub fn import_state_snapshot<I>(
+		&self,
+		genesis_hash: Block::Hash,
+		header: Block::Header,
+		entries: I,
+		aux: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Result<(), client::error::Error>
+		where I: IntoIterator<Item=Result<SnapshotEntry, client::error::Error>>,
+	{
+		if self.blockchain.meta.read().genesis_hash != Default::default() {
+			return Err(client::error::ErrorKind::Backend(
+				"State snapshots can only be imported into an empty database".into()
+			).into());
+		}
+
+		let mut root = DbGenesisStorage::new().0;
+		let mut nodes = HashMap::new();
+		let mut chunk_child_key = None;
+		let mut chunk = Vec::new();
+		for entry in entries {
+			let (child_key, key, value) = entry?;
+			let is_valid = match child_key {
+				Some(ref child_key) => well_known_keys::is_child_storage_key(child_key),
+				None => !well_known_keys::is_child_storage_key(&key),
+			};
+			if !is_valid {
+				return Err(client::error::ErrorKind::Backend(
+					format!("Invalid state snapshot entry {}", HexDisplay::from(&key))
+				).into());
+			}
+
+			if child_key != chunk_child_key || chunk.len() == SNAPSHOT_CHUNK_SIZE {
+				root = self.import_state_snapshot_chunk(root, chunk_child_key.as_ref(), &mut chunk, &mut nodes)?;
+				chunk_child_key = child_key;
+			}
+			chunk.push((key, Some(value)));
+		}
+		root = self.import_state_snapshot_chunk(root, chunk_child_key.as_ref(), &mut chunk, &mut nodes)?;
+
+		// the written nodes that are not part of the state are deleted, and the others are
+		// committed again through the state database along with the block.
+		let mut changeset = state_db::ChangeSet::default();
+		let mut transaction = DBTransaction::new();
+		let mismatch = root != *header.state_root();
+		for (key, (value, rc)) in nodes {
+			match value {
+				Some(value) if rc > 0 && !mismatch => changeset.inserted.push((key, value.to_vec())),
+				Some(_) => transaction.delete(columns::STATE, &key),
+				None => (),
+			}
+		}
+
+		if mismatch {
+			self.storage.db.write(transaction).map_err(db_err)?;
+			return Err(client::error::ErrorKind::Backend(format!(
+				"State root mismatch: expected {}, computed {}", header.state_root(), root,
+			)).into());
+		}
+
+		let hash = header.hash();
+		let number = *header.number();
+		let parent_hash = *header.parent_hash();
+		let lookup_key = utils::number_and_hash_to_lookup_key(number, hash);
+
+		utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash);
+		utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash);
+		transaction.put(columns::HEADER, &lookup_key, &header.encode());
+		transaction.put(columns::META, meta_keys::BEST_BLOCK, &lookup_key);
+		transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
+		transaction.put(columns::META, meta_keys::GENESIS_HASH, genesis_hash.as_ref());
+		for (key, value) in aux {
+			transaction.put_vec(columns::AUX, &key, value);
+		}
+
+		let map_e = |e: state_db::Error<io::Error>| client::error::Error::from(format!("State database error: {:?}", e));
+		let commit = self.storage.state_db
+			.insert_block(&hash, number.as_(), &parent_hash, changeset)
+			.map_err(map_e)?;
+		apply_state_commit(&mut transaction, commit);
+		let commit = self.storage.state_db.canonicalize_block(&hash).map_err(map_e)?;
+		apply_state_commit(&mut transaction, commit);
+
+		{
+			let mut leaves = self.blockchain.leaves.write();
+			leaves.import(hash, number, parent_hash);
+			leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+		}
+
+		self.storage.db.write(transaction).map_err(db_err)?;
+
+		self.blockchain.update_meta(hash, number, true, true);
+		self.blockchain.meta.write().genesis_hash = genesis_hash;
+		info!("Imported state of block #{} ({}), state root {}", number, hash, root);
+
+		Ok(())
+	}
+
+	/// Insert a chunk of snapshot entries into the state trie with the given root, write the new
+	/// nodes to the database and return the new root. The entries of a child trie also update its
+	/// root in the top trie. `nodes` keeps the value of each written node and its reference count
+	/// over all chunks.
+	fn import_state_snapshot_chunk(
+		&self,
+		root: H256,
+		child_key: Option<&Vec<u8>>,
+		chunk: &mut Vec<(Vec<u8>, Option<Vec<u8>>)>,
+		nodes: &mut HashMap<Vec<u8>, (Option<DBValue>, i32)>,
+	) -> Result<H256, client::error::Error> {
+		if chunk.is_empty() {
+			return Ok(root);
+		}
+
+		let state = DbState::new(self.storage.clone(), root);
+		let (root, mut update) = match child_key {
+			Some(child_key) => {
+				let (child_root, _, child_update) = state.child_storage_root(child_key, chunk.drain(..));
+				let (root, mut update) = state.storage_root(::std::iter::once((child_key.clone(), Some(child_root))));
+				update.consolidate(child_update);
+				(root, update)
+			},
+			None => state.storage_root(chunk.drain(..)),
+		};
+
+		let mut transaction = DBTransaction::new();
+		for (key, (val, rc)) in update.drain() {
+			let node = nodes.entry(key.clone()).or_insert((None, 0));
+			node.1 += rc;
+			if rc > 0 {
+				transaction.put(columns::STATE, &key, &val);
+				node.0 = Some(val);
+			}
+		}
+		self.storage.db.write(transaction).map_err(db_err)?;
+
+		Ok(root)
+	}
+
 	fn try_commit_operation(&self, mut operation: BlockImportOperation<Block, Blake2Hasher>)
 		-> Result<(), client::error::Error>
 	{
@@ -926,6 +1192,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 				&enacted,
 				&retracted,
 				operation.storage_updates,
+				operation.child_storage_updates,
 				Some(hash),
 				Some(number),
 				|| is_best,
@@ -940,6 +1207,14 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 	}
 
 
+	// apply the pending changes of the state database and update its memory metrics.
+	fn apply_state_db_pending(&self) {
+		self.storage.state_db.apply_pending();
+		let memory = self.storage.state_db.memory_info();
+		STATE_DB_MEMORY.with_label_values(&["non_canonical"]).set(memory.non_canonical as i64);
+		STATE_DB_MEMORY.with_label_values(&["pruning"]).set(memory.pruning as i64);
+	}
+
 	// write stuff to a transaction after a new block is finalized.
 	// this canonicalizes finalized blocks. Fails if called with a block which
 	// was not a child of the last finalized block.
@@ -970,9 +1245,16 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 				.storage(well_known_keys::CHANGES_TRIE_CONFIG)?
 				.and_then(|v| Decode::decode(&mut &*v));
 			self.changes_tries_storage.prune(changes_trie_config, transaction, f_hash, f_num);
+
+			if let BlocksPruning::KeepFinalized(keep) = self.blockchain.blocks_pruning {
+				self.prune_block_body(transaction, f_num, keep)?;
+			}
 		}
 
 		let new_displaced = self.blockchain.leaves.write().finalize_height(f_num);
+		if let BlocksPruning::KeepFinalized(_) = self.blockchain.blocks_pruning {
+			self.prune_displaced_bodies(transaction, &new_displaced)?;
+		}
 		match displaced {
 			x @ &mut None => *x = Some(new_displaced),
 			&mut Some(ref mut displaced) => displaced.merge(new_displaced),
@@ -980,6 +1262,52 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 
 		Ok(())
 	}
+
+	// delete the body of the canonical block `keep` blocks before a newly finalized block.
+	// justifications are kept, as they are needed to prove finality.
+	fn prune_block_body(
+		&self,
+		transaction: &mut DBTransaction,
+		f_num: NumberFor<Block>,
+		keep: u32,
+	) -> Result<(), client::error::Error> {
+		let f_num = f_num.as_();
+		// the genesis body is always kept.
+		if f_num <= u64::from(keep) {
+			return Ok(());
+		}
+
+		let number: NumberFor<Block> = As::sa(f_num - u64::from(keep));
+		if let Some(hash) = self.blockchain.hash(number)? {
+			trace!(target: "db", "Pruning body of block #{} ({:?})", number, hash);
+			transaction.delete(columns::BODY, &utils::number_and_hash_to_lookup_key(number, hash));
+		}
+
+		Ok(())
+	}
+
+	// delete the bodies of the forks whose leaves were displaced by finalization, down to the
+	// canonical chain.
+	fn prune_displaced_bodies(
+		&self,
+		transaction: &mut DBTransaction,
+		displaced: &FinalizationDisplaced<Block::Hash, NumberFor<Block>>,
+	) -> Result<(), client::error::Error> {
+		for leaf in displaced.leaves() {
+			let mut hash = leaf.clone();
+			while let Some(header) = self.blockchain.header(BlockId::Hash(hash))? {
+				let number = *header.number();
+				if self.blockchain.hash(number)? == Some(hash) {
+					break;
+				}
+				trace!(target: "db", "Pruning body of displaced block #{} ({:?})", number, hash);
+				transaction.delete(columns::BODY, &utils::number_and_hash_to_lookup_key(number, hash));
+				hash = *header.parent_hash();
+			}
+		}
+
+		Ok(())
+	}
 }
 
 fn apply_state_commit(transaction: &mut DBTransaction, commit: state_db::CommitSet<Vec<u8>>) {
@@ -1034,6 +1362,7 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 			old_state,
 			db_updates: PrefixedMemoryDB::default(),
 			storage_updates: Default::default(),
+			child_storage_updates: Default::default(),
 			changes_trie_updates: MemoryDB::default(),
 			aux_ops: Vec::new(),
 			finalized_blocks: Vec::new(),
@@ -1051,7 +1380,7 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 	{
 		match self.try_commit_operation(operation) {
 			Ok(_) => {
-				self.storage.state_db.apply_pending();
+				self.apply_state_db_pending();
 				Ok(())
 			},
 			e @ Err(_) => {
@@ -1082,7 +1411,7 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 			Ok(())
 		};
 		match commit(&mut displaced) {
-			Ok(()) => self.storage.state_db.apply_pending(),
+			Ok(()) => self.apply_state_db_pending(),
 			e @ Err(_) => {
 				self.storage.state_db.revert_pending();
 				if let Some(displaced) = displaced {
@@ -1162,7 +1491,13 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 					let state = DbState::new(self.storage.clone(), root);
 					Ok(CachingState::new(state, self.shared_cache.clone(), Some(hash)))
 				} else {
-					Err(client::error::ErrorKind::UnknownBlock(format!("State already discarded for {:?}", block)).into())
+					let nearest = match self.storage.state_db.nearest_checkpoint(hdr.number().as_()) {
+						Some((number, hash)) => format!(", the nearest checkpoint is #{} ({:?})", number, hash),
+						None => String::new(),
+					};
+					Err(client::error::ErrorKind::UnknownBlock(
+						format!("State already discarded for {:?}{}", block, nearest)
+					).into())
 				}
 			},
 			Ok(None) => Err(client::error::ErrorKind::UnknownBlock(format!("Unknown state for block {:?}", block)).into()),
@@ -1174,10 +1509,17 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 		!self.storage.state_db.is_pruned(hash, number.as_())
 	}
 
+	fn state_cache_stats(&self) -> Option<StateCacheStats> {
+		Some(StateCacheStats {
+			nodes: self.storage.node_cache.stats(),
+			..self.shared_cache.lock().stats()
+		})
+	}
+
 	fn destroy_state(&self, mut state: Self::State) -> Result<(), client::error::Error> {
 		if let Some(hash) = state.parent_hash.clone() {
 			let is_best = || self.blockchain.meta.read().best_hash == hash;
-			state.sync_cache(&[], &[], vec![], None, None, is_best);
+			state.sync_cache(&[], &[], vec![], vec![], None, None, is_best);
 		}
 		Ok(())
 	}
@@ -1298,7 +1640,7 @@ mod tests {
 			db.storage.db.clone()
 		};
 
-		let backend = Backend::<Block>::from_kvdb(backing, PruningMode::keep_blocks(1), 0).unwrap();
+		let backend = Backend::<Block>::from_kvdb(backing, PruningMode::keep_blocks(1), BlocksPruning::KeepAll, Default::default(), 0).unwrap();
 		assert_eq!(backend.blockchain().info().unwrap().best_number, 9);
 		for i in 0..10 {
 			assert!(backend.blockchain().hash(i).unwrap().is_some())
@@ -1922,6 +2264,68 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn prunes_old_finalized_and_displaced_block_bodies() {
+		use client::blockchain::{Backend as BlockChainBackend};
+
+		let backend = Backend::<Block>::from_kvdb(
+			Arc::new(::kvdb_memorydb::create(crate::utils::NUM_COLUMNS)),
+			PruningMode::keep_blocks(10),
+			BlocksPruning::KeepFinalized(2),
+			Default::default(),
+			10,
+		).unwrap();
+
+		let import = |number, parent_hash, extrinsics_root, body: u64, state| {
+			let header = Header {
+				number,
+				parent_hash,
+				state_root: BlakeTwo256::trie_root::<_, &[u8], &[u8]>(Vec::new()),
+				digest: Default::default(),
+				extrinsics_root,
+			};
+			let hash = header.hash();
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(parent_hash)).unwrap();
+			op.set_block_data(header, Some(vec![body.into()]), None, state).unwrap();
+			backend.commit_operation(op).unwrap();
+			hash
+		};
+
+		let mut hashes = Vec::new();
+		let mut parent_hash = Default::default();
+		for number in 0..6 {
+			parent_hash = import(number, parent_hash, Default::default(), number, NewBlockState::Best);
+			hashes.push(parent_hash);
+		}
+		let fork = import(2, hashes[1], H256::from_low_u64_be(1), 20, NewBlockState::Normal);
+		for number in 1..5 {
+			backend.finalize_block(BlockId::Number(number), Some(vec![number as u8])).unwrap();
+		}
+
+		let blockchain = backend.blockchain();
+		assert_eq!(blockchain.body(BlockId::Number(0)).unwrap(), Some(vec![0.into()]));
+		for number in 1..3 {
+			match blockchain.body(BlockId::Number(number)) {
+				Err(client::error::Error(client::error::ErrorKind::BlockBodyPruned(_), _)) => (),
+				body => panic!("Unexpected body of pruned block #{}: {:?}", number, body),
+			}
+		}
+		for number in 3..6 {
+			assert_eq!(blockchain.body(BlockId::Number(number)).unwrap(), Some(vec![number.into()]));
+		}
+		// justifications are kept to prove finality.
+		for number in 1..5 {
+			assert_eq!(blockchain.justification(BlockId::Number(number)).unwrap(), Some(vec![number as u8]));
+		}
+		assert_eq!(blockchain.body(BlockId::Number(6)).unwrap(), None);
+
+		// the body of the displaced fork is deleted, but it is not reported as pruned.
+		assert_eq!(blockchain.body(BlockId::Hash(fork)).unwrap(), None);
+		assert!(blockchain.header(BlockId::Hash(fork)).unwrap().is_some());
+		assert_eq!(blockchain.body(BlockId::Hash(H256::from_low_u64_be(42))).unwrap(), None);
+	}
+
 	#[test]
 	fn test_finalize_non_sequential() {
 		let backend = Backend::<Block>::new_test(10, 10);
@@ -1936,4 +2340,45 @@ mod tests {
 			backend.commit_operation(op).unwrap_err();
 		}
 	}
+
+	#[test]
+	fn state_snapshot_leaves_no_superseded_nodes() {
+		use std::collections::HashSet;
+
+		let child = b":child_storage:default:a".to_vec();
+		let top = (0..20u32).map(|i| (i.encode(), vec![i as u8; 40])).collect::<Vec<_>>();
+		let child_entries = (0..5u32).map(|i| (i.encode(), vec![i as u8; 3])).collect::<Vec<_>>();
+
+		let source = Backend::<Block>::new_test(u32::max_value(), 0);
+		let mut op = source.begin_operation().unwrap();
+		let state_root = op.reset_storage(
+			top.iter().cloned().collect(),
+			::std::iter::once((child.clone(), child_entries.iter().cloned().collect())).collect(),
+		).unwrap();
+		let header = Header {
+			number: 0,
+			parent_hash: Default::default(),
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		op.set_block_data(header.clone(), None, None, NewBlockState::Final).unwrap();
+		source.commit_operation(op).unwrap();
+
+		// the child entries split the entries of the top trie, which is built in two chunks.
+		let entry = |child_key, (key, value): &(Vec<u8>, Vec<u8>)| -> Result<SnapshotEntry, client::error::Error> {
+			Ok((child_key, key.clone(), value.clone()))
+		};
+		let entries = top[..10].iter().map(|e| entry(None, e))
+			.chain(child_entries.iter().map(|e| entry(Some(child.clone()), e)))
+			.chain(top[10..].iter().map(|e| entry(None, e)))
+			.collect::<Vec<_>>();
+		let target = Backend::<Block>::new_test(u32::max_value(), 0);
+		target.import_state_snapshot(Default::default(), header, entries, Vec::new()).unwrap();
+
+		let nodes = |backend: &Backend<Block>| backend.storage.db.iter(columns::STATE)
+			.map(|(key, _)| key.to_vec())
+			.collect::<HashSet<_>>();
+		assert_eq!(nodes(&target), nodes(&source));
+	}
 }
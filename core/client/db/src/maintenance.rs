@@ -0,0 +1,170 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Database statistics and migration between engines.
+
+use kvdb::{KeyValueDB, DBTransaction};
+use log::info;
+
+use client;
+use crate::{DatabaseSettings, DatabaseEngine};
+use crate::utils::{COLUMN_META, NUM_COLUMNS, meta_keys, db_err, open_kvdb};
+
+/// Number of entries copied in a single transaction when migrating a database.
+const MIGRATION_BATCH_SIZE: usize = 4096;
+
+/// Names of the columns of the full database.
+const FULL_COLUMNS: &[&str] = &[
+	"meta", "state", "state_meta", "key_lookup", "header", "body", "justification", "changes_trie", "aux",
+];
+/// Names of the columns of the light database.
+const LIGHT_COLUMNS: &[&str] = &["meta", "key_lookup", "header", "cache", "cht", "aux"];
+
+/// Statistics of a column of the database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ColumnStats {
+	/// Name of the column.
+	pub name: &'static str,
+	/// Number of keys.
+	pub keys: u64,
+	/// Total size of the keys in bytes.
+	pub key_bytes: u64,
+	/// Total size of the values in bytes.
+	pub value_bytes: u64,
+}
+
+/// Compute the statistics of every column of the database.
+pub fn database_stats(settings: &DatabaseSettings) -> client::error::Result<Vec<ColumnStats>> {
+	let db = open_kvdb(settings)?;
+	column_stats(&*db)
+}
+
+/// Copy a database to another engine or path, returning the number of copied entries.
+///
+/// The destination must be empty.
+pub fn migrate_database(from: &DatabaseSettings, to: &DatabaseSettings) -> client::error::Result<u64> {
+	if from.engine == DatabaseEngine::Memory || to.engine == DatabaseEngine::Memory {
+		return Err(client::error::ErrorKind::Backend("Can't migrate from or to an in-memory database".into()).into());
+	}
+	if from.engine == to.engine && from.path == to.path {
+		return Err(client::error::ErrorKind::Backend("Can't migrate a database to itself".into()).into());
+	}
+
+	let source = open_kvdb(from)?;
+	let destination = open_kvdb(to)?;
+	copy_columns(&*source, &*destination)
+}
+
+fn column_stats(db: &KeyValueDB) -> client::error::Result<Vec<ColumnStats>> {
+	let names = match db.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)? {
+		Some(ref db_type) if &**db_type == b"light" => LIGHT_COLUMNS,
+		_ => FULL_COLUMNS,
+	};
+
+	Ok((0..NUM_COLUMNS).map(|col| {
+		let mut stats = ColumnStats {
+			name: names.get(col as usize).cloned().unwrap_or("unused"),
+			..Default::default()
+		};
+		for (key, value) in db.iter(Some(col)) {
+			stats.keys += 1;
+			stats.key_bytes += key.len() as u64;
+			stats.value_bytes += value.len() as u64;
+		}
+		stats
+	}).collect())
+}
+
+fn copy_columns(source: &KeyValueDB, destination: &KeyValueDB) -> client::error::Result<u64> {
+	if source.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)?.is_none() {
+		return Err(client::error::ErrorKind::Backend("The source database is empty".into()).into());
+	}
+	if destination.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)?.is_some() {
+		return Err(client::error::ErrorKind::Backend("The destination database is not empty".into()).into());
+	}
+
+	let mut copied = 0;
+	for col in 0..NUM_COLUMNS {
+		let mut column_copied = 0;
+		let mut transaction = DBTransaction::new();
+		for (key, value) in source.iter(Some(col)) {
+			// the type is written last, marking the destination as complete.
+			if Some(col) == COLUMN_META && &*key == meta_keys::TYPE {
+				continue;
+			}
+
+			transaction.put(Some(col), &key, &value);
+			column_copied += 1;
+			if transaction.ops.len() == MIGRATION_BATCH_SIZE {
+				destination.write(transaction).map_err(db_err)?;
+				transaction = DBTransaction::new();
+			}
+		}
+		destination.write(transaction).map_err(db_err)?;
+
+		info!("Copied {} entries of column {}", column_copied, col);
+		copied += column_copied;
+	}
+
+	let db_type = source.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)?
+		.expect("checked above; qed");
+	let mut transaction = DBTransaction::new();
+	transaction.put(COLUMN_META, meta_keys::TYPE, &db_type);
+	destination.write(transaction).map_err(db_err)?;
+	destination.flush().map_err(db_err)?;
+
+	Ok(copied + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db(db_type: &[u8]) -> kvdb_memorydb::InMemory {
+		let db = kvdb_memorydb::create(NUM_COLUMNS);
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, meta_keys::TYPE, db_type);
+		transaction.put(Some(1), b"key", b"value");
+		transaction.put(Some(1), b"other key", b"other value");
+		transaction.put(Some(5), b"aux", &[]);
+		db.write(transaction).unwrap();
+		db
+	}
+
+	#[test]
+	fn column_stats_work() {
+		let stats = column_stats(&test_db(b"full")).unwrap();
+		assert_eq!(stats.len(), NUM_COLUMNS as usize);
+		assert_eq!(stats[0], ColumnStats { name: "meta", keys: 1, key_bytes: 4, value_bytes: 4 });
+		assert_eq!(stats[1], ColumnStats { name: "state", keys: 2, key_bytes: 12, value_bytes: 16 });
+		assert_eq!(stats[5], ColumnStats { name: "body", keys: 1, key_bytes: 3, value_bytes: 0 });
+
+		let stats = column_stats(&test_db(b"light")).unwrap();
+		assert_eq!(stats[5].name, "aux");
+		assert_eq!(stats[8].name, "unused");
+	}
+
+	#[test]
+	fn copy_columns_copies_every_entry_once() {
+		let source = test_db(b"full");
+		let destination = kvdb_memorydb::create(NUM_COLUMNS);
+
+		assert_eq!(copy_columns(&source, &destination).unwrap(), 4);
+		assert_eq!(column_stats(&destination).unwrap(), column_stats(&source).unwrap());
+		assert!(copy_columns(&source, &destination).is_err());
+		assert!(copy_columns(&kvdb_memorydb::create(NUM_COLUMNS), &destination).is_err());
+	}
+}
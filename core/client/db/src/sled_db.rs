@@ -0,0 +1,110 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `KeyValueDB` stored in a sled database.
+
+use std::io;
+use std::path::Path;
+
+use kvdb::{KeyValueDB, DBTransaction, DBOp, DBValue};
+use log::warn;
+
+/// Size of the column prefix of the keys.
+const COLUMN_PREFIX_LEN: usize = 4;
+
+/// A sled database holding all columns in a single tree, with the keys prefixed by their column,
+/// so that transactions touching several columns are applied atomically.
+pub struct SledDatabase {
+	db: sled::Db,
+}
+
+impl SledDatabase {
+	/// Open the database at `path`, creating it if needed.
+	pub fn open(path: &Path) -> io::Result<Self> {
+		Ok(SledDatabase {
+			db: sled::Db::open(path).map_err(sled_err)?,
+		})
+	}
+}
+
+fn sled_err(err: sled::Error) -> io::Error {
+	io::Error::new(io::ErrorKind::Other, format!("{:?}", err))
+}
+
+/// The key in the tree of `key` of the column `col`.
+fn column_key(col: Option<u32>, key: &[u8]) -> Vec<u8> {
+	// `None` is the default column, which comes first.
+	let column = col.map_or(0, |col| col + 1);
+	let mut column_key = Vec::with_capacity(COLUMN_PREFIX_LEN + key.len());
+	column_key.extend_from_slice(&column.to_be_bytes());
+	column_key.extend_from_slice(key);
+	column_key
+}
+
+impl KeyValueDB for SledDatabase {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>> {
+		self.db.get(column_key(col, key))
+			.map(|value| value.map(|value| DBValue::from_slice(&value)))
+			.map_err(sled_err)
+	}
+
+	fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		self.iter_from_prefix(col, prefix).next().map(|(_, value)| value)
+	}
+
+	fn write_buffered(&self, transaction: DBTransaction) {
+		if let Err(e) = self.write(transaction) {
+			warn!("Error writing to the database: {:?}", e);
+		}
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		let mut batch = sled::Batch::default();
+		for op in transaction.ops {
+			match op {
+				DBOp::Insert { col, key, value } => batch.insert(column_key(col, &key), &*value),
+				DBOp::Delete { col, key } => batch.remove(column_key(col, &key)),
+			}
+		}
+		self.db.apply_batch(batch).map_err(sled_err)
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		self.db.flush().map(|_| ()).map_err(sled_err)
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		self.iter_from_prefix(col, &[])
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: Option<u32>,
+		prefix: &'a [u8],
+	) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		Box::new(self.db.scan_prefix(column_key(col, prefix))
+			.filter_map(|entry| entry.map_err(|e| warn!("Error iterating the database: {:?}", e)).ok())
+			.map(|(key, value)| (
+				key[COLUMN_PREFIX_LEN..].to_vec().into_boxed_slice(),
+				value.to_vec().into_boxed_slice(),
+			))
+		)
+	}
+
+	fn restore(&self, _new_db: &str) -> io::Result<()> {
+		Err(io::Error::new(io::ErrorKind::Other, "Restoring is not supported by the sled database"))
+	}
+}
@@ -16,43 +16,225 @@
 
 //! Global cache state.
 
+use std::borrow::Borrow;
 use std::collections::{VecDeque, HashSet, HashMap};
+use std::hash::Hash as StdHash;
 use std::sync::Arc;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use lru_cache::LruCache;
 use hash_db::Hasher;
 use runtime_primitives::traits::{Block, Header};
 use state_machine::{backend::Backend as StateBackend, TrieBackend};
+use client::backend::{CacheStats, StateCacheStats};
 use log::trace;
 
 const STATE_CACHE_BLOCKS: usize = 12;
 
 type StorageKey = Vec<u8>;
+type ChildStorageKey = (Vec<u8>, Vec<u8>);
 type StorageValue = Vec<u8>;
 
+/// Maximal sizes of the state caches, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateCacheSizes {
+	/// Storage values.
+	pub values: usize,
+	/// Child storage values.
+	pub child_values: usize,
+	/// Storage hashes.
+	pub hashes: usize,
+	/// Trie nodes.
+	pub nodes: usize,
+}
+
+impl Default for StateCacheSizes {
+	fn default() -> Self {
+		StateCacheSizes {
+			values: 16 * 1024 * 1024,
+			child_values: 4 * 1024 * 1024,
+			hashes: 4 * 1024 * 1024,
+			nodes: 32 * 1024 * 1024,
+		}
+	}
+}
+
+/// Estimated memory used by a cache key or value.
+trait EstimateSize {
+	fn estimate_size(&self) -> usize;
+}
+
+impl EstimateSize for Vec<u8> {
+	fn estimate_size(&self) -> usize {
+		self.len()
+	}
+}
+
+impl EstimateSize for ChildStorageKey {
+	fn estimate_size(&self) -> usize {
+		self.0.len() + self.1.len()
+	}
+}
+
+impl<T: AsRef<[u8]>> EstimateSize for Option<T> {
+	fn estimate_size(&self) -> usize {
+		self.as_ref().map_or(0, |value| value.as_ref().len())
+	}
+}
+
+/// LRU cache bounded by the estimated size of its entries. Counts hits, misses and evictions.
+struct LRUMap<K: StdHash + Eq, V> {
+	/// Entries along with their size.
+	map: LruCache<K, (V, usize)>,
+	used_bytes: usize,
+	max_bytes: usize,
+	stats: CacheStats,
+}
+
+impl<K: StdHash + Eq + EstimateSize, V: EstimateSize> LRUMap<K, V> {
+	fn new(max_bytes: usize) -> Self {
+		LRUMap {
+			// entries are evicted according to their size, not their number.
+			map: LruCache::new(usize::max_value()),
+			used_bytes: 0,
+			max_bytes,
+			stats: Default::default(),
+		}
+	}
+
+	/// Look up an entry, counting a hit or a miss.
+	fn get<Q: ?Sized + StdHash + Eq>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q> {
+		match self.map.get_mut(key) {
+			Some(&mut (ref mut value, _)) => {
+				self.stats.hits += 1;
+				Some(value)
+			},
+			None => {
+				self.stats.misses += 1;
+				None
+			},
+		}
+	}
+
+	/// Count a lookup which could not use the cache.
+	fn note_miss(&mut self) {
+		self.stats.misses += 1;
+	}
+
+	/// Insert an entry, evicting the least recently used entries while the cache is too large.
+	fn add(&mut self, key: K, value: V) {
+		self.remove(&key);
+		let size = key.estimate_size() + value.estimate_size();
+		if size > self.max_bytes {
+			return;
+		}
+
+		self.used_bytes += size;
+		self.map.insert(key, (value, size));
+		while self.used_bytes > self.max_bytes {
+			match self.map.remove_lru() {
+				Some((_, (_, size))) => {
+					self.used_bytes -= size;
+					self.stats.evictions += 1;
+				},
+				None => break,
+			}
+		}
+	}
+
+	fn remove<Q: ?Sized + StdHash + Eq>(&mut self, key: &Q) where K: Borrow<Q> {
+		if let Some((_, size)) = self.map.remove(key) {
+			self.used_bytes -= size;
+		}
+	}
+
+	fn clear(&mut self) {
+		self.map.clear();
+		self.used_bytes = 0;
+	}
+
+	fn stats(&self) -> CacheStats {
+		CacheStats {
+			used_bytes: self.used_bytes,
+			max_bytes: self.max_bytes,
+			..self.stats
+		}
+	}
+}
+
 /// Shared canonical state cache.
 pub struct Cache<B: Block, H: Hasher> {
 	/// Storage cache. `None` indicates that key is known to be missing.
-	storage: LruCache<StorageKey, Option<StorageValue>>,
+	storage: LRUMap<StorageKey, Option<StorageValue>>,
+	/// Child storage cache. `None` indicates that key is known to be missing.
+	child_storage: LRUMap<ChildStorageKey, Option<StorageValue>>,
 	/// Storage hashes cache. `None` indicates that key is known to be missing.
-	hashes: LruCache<StorageKey, Option<H::Out>>,
+	hashes: LRUMap<StorageKey, Option<H::Out>>,
 	/// Information on the modifications in recently committed blocks; specifically which keys
 	/// changed in which block. Ordered by block number.
 	modifications: VecDeque<BlockChanges<B::Header>>,
 }
 
+impl<B: Block, H: Hasher> Cache<B, H> {
+	/// Statistics of the caches. Trie nodes are cached separately, see `NodeCache`.
+	pub fn stats(&self) -> StateCacheStats {
+		StateCacheStats {
+			values: self.storage.stats(),
+			child_values: self.child_storage.stats(),
+			hashes: self.hashes.stats(),
+			nodes: Default::default(),
+		}
+	}
+}
+
 pub type SharedCache<B, H> = Arc<Mutex<Cache<B, H>>>;
 
-/// Create new shared cache instance with given max memory usage.
-pub fn new_shared_cache<B: Block, H: Hasher>(shared_cache_size: usize) -> SharedCache<B, H> {
-	let cache_items = shared_cache_size / 100; // Guestimate, potentially inaccurate
+/// Create new shared cache instance with given maximal sizes.
+pub fn new_shared_cache<B: Block, H: Hasher>(sizes: &StateCacheSizes) -> SharedCache<B, H> {
 	Arc::new(Mutex::new(Cache {
-		storage: LruCache::new(cache_items),
-		hashes: LruCache::new(cache_items),
+		storage: LRUMap::new(sizes.values),
+		child_storage: LRUMap::new(sizes.child_values),
+		hashes: LRUMap::new(sizes.hashes),
 		modifications: VecDeque::new(),
 	}))
 }
 
+/// Cache of trie nodes, by prefixed node hash.
+///
+/// Nodes are addressed by their content, so entries never need to be invalidated.
+pub struct NodeCache {
+	nodes: Mutex<LRUMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl NodeCache {
+	/// Create a cache holding at most `max_bytes` of nodes.
+	pub fn new(max_bytes: usize) -> Self {
+		NodeCache {
+			nodes: Mutex::new(LRUMap::new(max_bytes)),
+		}
+	}
+
+	/// Get a node from the cache, or from `fetch` if it isn't cached.
+	pub fn get_or_fetch<E, F>(&self, key: &[u8], fetch: F) -> Result<Option<Vec<u8>>, E> where
+		F: FnOnce() -> Result<Option<Vec<u8>>, E>,
+	{
+		if let Some(node) = self.nodes.lock().get(key) {
+			return Ok(Some(node.clone()));
+		}
+
+		// the lock is not held while reading from the database.
+		let node = fetch()?;
+		if let Some(ref node) = node {
+			self.nodes.lock().add(key.to_vec(), node.clone());
+		}
+		Ok(node)
+	}
+
+	/// Statistics of the cache.
+	pub fn stats(&self) -> CacheStats {
+		self.nodes.lock().stats()
+	}
+}
+
 #[derive(Debug)]
 /// Accumulates a list of storage changed in a block.
 struct BlockChanges<B: Header> {
@@ -64,6 +246,8 @@ struct BlockChanges<B: Header> {
 	parent: B::Hash,
 	/// A set of modified storage keys.
 	storage: HashSet<StorageKey>,
+	/// A set of modified child storage keys.
+	child_storage: HashSet<ChildStorageKey>,
 	/// Block is part of the canonical chain.
 	is_canon: bool,
 }
@@ -72,6 +256,8 @@ struct BlockChanges<B: Header> {
 struct LocalCache<H: Hasher> {
 	/// Storage cache. `None` indicates that key is known to be missing.
 	storage: HashMap<StorageKey, Option<StorageValue>>,
+	/// Child storage cache. `None` indicates that key is known to be missing.
+	child_storage: HashMap<ChildStorageKey, Option<StorageValue>>,
 	/// Storage hashes cache. `None` indicates that key is known to be missing.
 	hashes: HashMap<StorageKey, Option<H::Out>>,
 }
@@ -103,6 +289,7 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 			shared_cache,
 			local_cache: RwLock::new(LocalCache {
 				storage: Default::default(),
+				child_storage: Default::default(),
 				hashes: Default::default(),
 			}),
 			parent_hash: parent_hash,
@@ -120,6 +307,7 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 		enacted: &[B::Hash],
 		retracted: &[B::Hash],
 		changes: Vec<(StorageKey, Option<StorageValue>)>,
+		child_changes: Vec<(StorageKey, Vec<(StorageKey, Option<StorageValue>)>)>,
 		commit_hash: Option<B::Hash>,
 		commit_number: Option<<B::Header as Header>::Number>,
 		is_best: F,
@@ -140,6 +328,11 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 					for a in &m.storage {
 						trace!("Reverting enacted key {:?}", a);
 						cache.storage.remove(a);
+						cache.hashes.remove(a);
+					}
+					for a in &m.child_storage {
+						trace!("Reverting enacted child key {:?}", a);
+						cache.child_storage.remove(a);
 					}
 					false
 				} else {
@@ -156,6 +349,11 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 					for a in &m.storage {
 						trace!("Retracted key {:?}", a);
 						cache.storage.remove(a);
+						cache.hashes.remove(a);
+					}
+					for a in &m.child_storage {
+						trace!("Retracted child key {:?}", a);
+						cache.child_storage.remove(a);
 					}
 					false
 				} else {
@@ -167,6 +365,8 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 			// We don't know anything about the block; clear everything
 			trace!("Wiping cache");
 			cache.storage.clear();
+			cache.child_storage.clear();
+			cache.hashes.clear();
 			cache.modifications.clear();
 		}
 
@@ -176,12 +376,21 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 		if let Some(_) = self.parent_hash {
 			let mut local_cache = self.local_cache.write();
 			if is_best {
-				trace!("Committing {} local, {} hashes, {} modified entries", local_cache.storage.len(), local_cache.hashes.len(), changes.len());
+				trace!(
+					"Committing {} local, {} child, {} hashes, {} modified entries",
+					local_cache.storage.len(),
+					local_cache.child_storage.len(),
+					local_cache.hashes.len(),
+					changes.len(),
+				);
 				for (k, v) in local_cache.storage.drain() {
-					cache.storage.insert(k, v);
+					cache.storage.add(k, v);
+				}
+				for (k, v) in local_cache.child_storage.drain() {
+					cache.child_storage.add(k, v);
 				}
 				for (k, v) in local_cache.hashes.drain() {
-					cache.hashes.insert(k, v);
+					cache.hashes.add(k, v);
 				}
 			}
 		}
@@ -198,12 +407,23 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 				modifications.insert(k.clone());
 				if is_best {
 					cache.hashes.remove(&k);
-					cache.storage.insert(k, v);
+					cache.storage.add(k, v);
+				}
+			}
+			let mut child_modifications = HashSet::new();
+			for (storage_key, changes) in child_changes.into_iter() {
+				for (k, v) in changes.into_iter() {
+					let k = (storage_key.clone(), k);
+					child_modifications.insert(k.clone());
+					if is_best {
+						cache.child_storage.add(k, v);
+					}
 				}
 			}
 			// Save modified storage. These are ordered by the block number.
 			let block_changes = BlockChanges {
 				storage: modifications,
+				child_storage: child_modifications,
 				number: *number,
 				hash: hash.clone(),
 				is_canon: is_best,
@@ -225,7 +445,8 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 	/// Check if the key can be returned from cache by matching current block parent hash against canonical
 	/// state and filtering out entries modified in later blocks.
 	fn is_allowed(
-		key: &[u8],
+		key: Option<&[u8]>,
+		child_key: Option<&ChildStorageKey>,
 		parent_hash: &Option<B::Hash>,
 		modifications:
 		&VecDeque<BlockChanges<B::Header>>
@@ -233,13 +454,13 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 	{
 		let mut parent = match *parent_hash {
 			None => {
-				trace!("Cache lookup skipped for {:?}: no parent hash", key);
+				trace!("Cache lookup skipped for {:?}: no parent hash", key.or(child_key.map(|k| &k.1[..])));
 				return false;
 			}
 			Some(ref parent) => parent,
 		};
 		if modifications.is_empty() {
-			trace!("Cache lookup allowed for {:?}", key);
+			trace!("Cache lookup allowed for {:?}", key.or(child_key.map(|k| &k.1[..])));
 			return true;
 		}
 		// Ignore all storage modified in later blocks
@@ -254,12 +475,17 @@ impl<H: Hasher, S: StateBackend<H>, B: Block> CachingState<H, S, B> {
 				}
 				parent = &m.parent;
 			}
-			if m.storage.contains(key) {
-				trace!("Cache lookup skipped for {:?}: modified in a later block", key);
+			let modified = match (key, child_key) {
+				(Some(key), _) => m.storage.contains(key),
+				(None, Some(child_key)) => m.child_storage.contains(child_key),
+				(None, None) => false,
+			};
+			if modified {
+				trace!("Cache lookup skipped for {:?}: modified in a later block", key.or(child_key.map(|k| &k.1[..])));
 				return false;
 			}
 		}
-		trace!("Cache lookup skipped for {:?}: parent hash is unknown", key);
+		trace!("Cache lookup skipped for {:?}: parent hash is unknown", key.or(child_key.map(|k| &k.1[..])));
 		false
 	}
 }
@@ -276,11 +502,13 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 			return Ok(entry)
 		}
 		let mut cache = self.shared_cache.lock();
-		if Self::is_allowed(key, &self.parent_hash, &cache.modifications) {
-			if let Some(entry) = cache.storage.get_mut(key).map(|a| a.clone()) {
+		if Self::is_allowed(Some(key), None, &self.parent_hash, &cache.modifications) {
+			if let Some(entry) = cache.storage.get(key).map(|a| a.clone()) {
 				trace!("Found in shared cache: {:?}", key);
 				return Ok(entry)
 			}
+		} else {
+			cache.storage.note_miss();
 		}
 		trace!("Cache miss: {:?}", key);
 		let value = self.state.storage(key)?;
@@ -295,11 +523,13 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 			return Ok(entry)
 		}
 		let mut cache = self.shared_cache.lock();
-		if Self::is_allowed(key, &self.parent_hash, &cache.modifications) {
-			if let Some(entry) = cache.hashes.get_mut(key).map(|a| a.clone()) {
+		if Self::is_allowed(Some(key), None, &self.parent_hash, &cache.modifications) {
+			if let Some(entry) = cache.hashes.get(key).map(|a| a.clone()) {
 				trace!("Found hash in shared cache: {:?}", key);
 				return Ok(entry)
 			}
+		} else {
+			cache.hashes.note_miss();
 		}
 		trace!("Cache hash miss: {:?}", key);
 		let hash = self.state.storage_hash(key)?;
@@ -308,7 +538,25 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 	}
 
 	fn child_storage(&self, storage_key: &[u8], key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
-		self.state.child_storage(storage_key, key)
+		let key = (storage_key.to_vec(), key.to_vec());
+		let local_cache = self.local_cache.upgradable_read();
+		if let Some(entry) = local_cache.child_storage.get(&key).cloned() {
+			trace!("Found in local cache: {:?}", key);
+			return Ok(entry)
+		}
+		let mut cache = self.shared_cache.lock();
+		if Self::is_allowed(None, Some(&key), &self.parent_hash, &cache.modifications) {
+			if let Some(entry) = cache.child_storage.get(&key).map(|a| a.clone()) {
+				trace!("Found in shared cache: {:?}", key);
+				return Ok(entry)
+			}
+		} else {
+			cache.child_storage.note_miss();
+		}
+		trace!("Cache miss: {:?}", key);
+		let value = self.state.child_storage(storage_key, &key.1)?;
+		RwLockUpgradableReadGuard::upgrade(local_cache).child_storage.insert(key, value.clone());
+		Ok(value)
 	}
 
 	fn exists_storage(&self, key: &[u8]) -> Result<bool, Self::Error> {
@@ -316,7 +564,7 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 	}
 
 	fn exists_child_storage(&self, storage_key: &[u8], key: &[u8]) -> Result<bool, Self::Error> {
-		self.state.exists_child_storage(storage_key, key)
+		Ok(self.child_storage(storage_key, key)?.is_some())
 	}
 
 	fn for_keys_with_prefix<F: FnMut(&[u8])>(&self, prefix: &[u8], f: F) {
@@ -377,27 +625,27 @@ mod tests {
 		let h3a = H256::random();
 		let h3b = H256::random();
 
-		let shared = new_shared_cache::<Block, Blake2Hasher>(256*1024);
+		let shared = new_shared_cache::<Block, Blake2Hasher>(&Default::default());
 
 		// blocks  [ 3a(c) 2a(c) 2b 1b 1a(c) 0 ]
 		// state   [ 5     5     4  3  2     2 ]
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(root_parent.clone()));
-		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![2]))], Some(h0.clone()), Some(0), || true);
+		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![2]))], vec![], Some(h0.clone()), Some(0), || true);
 
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h0.clone()));
-		s.sync_cache(&[], &[], vec![], Some(h1a.clone()), Some(1), || true);
+		s.sync_cache(&[], &[], vec![], vec![], Some(h1a.clone()), Some(1), || true);
 
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h0.clone()));
-		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![3]))], Some(h1b.clone()), Some(1), || false);
+		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![3]))], vec![], Some(h1b.clone()), Some(1), || false);
 
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h1b.clone()));
-		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![4]))], Some(h2b.clone()), Some(2), || false);
+		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![4]))], vec![], Some(h2b.clone()), Some(2), || false);
 
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h1a.clone()));
-		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![5]))], Some(h2a.clone()), Some(2), || true);
+		s.sync_cache(&[], &[], vec![(key.clone(), Some(vec![5]))], vec![], Some(h2a.clone()), Some(2), || true);
 
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h2a.clone()));
-		s.sync_cache(&[], &[], vec![], Some(h3a.clone()), Some(3), || true);
+		s.sync_cache(&[], &[], vec![], vec![], Some(h3a.clone()), Some(3), || true);
 
 		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h3a.clone()));
 		assert_eq!(s.storage(&key).unwrap().unwrap(), vec![5]);
@@ -414,8 +662,61 @@ mod tests {
 		// reorg to 3b
 		// blocks  [ 3b(c) 3a 2a 2b(c) 1b 1a 0 ]
 		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h2b.clone()));
-		s.sync_cache(&[h1b.clone(), h2b.clone(), h3b.clone()], &[h1a.clone(), h2a.clone(), h3a.clone()], vec![], Some(h3b.clone()), Some(3), || true);
+		s.sync_cache(&[h1b.clone(), h2b.clone(), h3b.clone()], &[h1a.clone(), h2a.clone(), h3a.clone()], vec![], vec![], Some(h3b.clone()), Some(3), || true);
 		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h3a.clone()));
 		assert!(s.storage(&key).unwrap().is_none());
 	}
+
+	#[test]
+	fn lru_map_is_bounded_by_size() {
+		let mut map = LRUMap::<Vec<u8>, Option<Vec<u8>>>::new(10);
+		map.add(vec![1], Some(vec![1; 4]));
+		map.add(vec![2], Some(vec![2; 4]));
+		assert!(map.get(&[1][..]).is_some());
+
+		// evicts the least recently used entry.
+		map.add(vec![3], None);
+		map.add(vec![4], Some(vec![4; 2]));
+		assert!(map.get(&[2][..]).is_none());
+		assert!(map.get(&[1][..]).is_some());
+
+		// too large to be cached.
+		map.add(vec![5], Some(vec![5; 10]));
+		assert!(map.get(&[5][..]).is_none());
+
+		map.remove(&[4][..]);
+		assert_eq!(map.stats(), CacheStats { hits: 2, misses: 2, evictions: 1, used_bytes: 6, max_bytes: 10 });
+	}
+
+	#[test]
+	fn child_storage_is_cached_and_invalidated() {
+		let storage_key = b":child_storage:default:child".to_vec();
+		let key = H256::random()[..].to_vec();
+		let h0 = H256::random();
+		let h1 = H256::random();
+		let h2 = H256::random();
+
+		let shared = new_shared_cache::<Block, Blake2Hasher>(&Default::default());
+
+		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(H256::random()));
+		s.sync_cache(&[], &[], vec![], vec![(storage_key.clone(), vec![(key.clone(), Some(vec![1]))])], Some(h0.clone()), Some(0), || true);
+
+		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h0.clone()));
+		assert_eq!(s.child_storage(&storage_key, &key).unwrap(), Some(vec![1]));
+		assert_eq!(shared.lock().stats().child_values.hits, 1);
+
+		// not canonical: the change is known but not cached.
+		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h0.clone()));
+		s.sync_cache(&[], &[], vec![], vec![(storage_key.clone(), vec![(key.clone(), Some(vec![2]))])], Some(h1.clone()), Some(1), || false);
+
+		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h1.clone()));
+		assert!(s.child_storage(&storage_key, &key).unwrap().is_none());
+		assert_eq!(shared.lock().stats().child_values.misses, 1);
+
+		// reorg to h2 on top of h1 retracts the cached value.
+		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h1.clone()));
+		s.sync_cache(&[h1.clone(), h2.clone()], &[], vec![], vec![], Some(h2.clone()), Some(2), || true);
+		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h2.clone()));
+		assert!(s.child_storage(&storage_key, &key).unwrap().is_none());
+	}
 }
@@ -22,20 +22,28 @@ use std::io;
 
 use kvdb::{KeyValueDB, DBTransaction};
 use kvdb_rocksdb::{Database, DatabaseConfig};
-use log::debug;
+use log::{debug, info};
 
 use client;
-use parity_codec::Decode;
+use parity_codec::{Encode, Decode};
 use trie::DBValue;
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{As, Block as BlockT, Header as HeaderT, Zero};
-use crate::DatabaseSettings;
+use crate::{DatabaseSettings, DatabaseEngine};
+use crate::sled_db::SledDatabase;
 
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
 pub const NUM_COLUMNS: u32 = 9;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: Option<u32> = Some(0);
+/// Version of the layout of the database. Databases of any other version are refused, except
+/// unversioned ones which are migrated when opened.
+///
+/// Version 1 keys the nodes of child tries by the hash of their storage key; databases without a
+/// version predate it. Their child trie nodes are not moved, so the chains using child storage
+/// must be synced again, but other databases need no change.
+pub const DB_VERSION: u32 = 1;
 
 /// Keys of entries in COLUMN_META.
 pub mod meta_keys {
@@ -53,6 +61,8 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Version of the database layout.
+	pub const VERSION: &[u8; 7] = b"version";
 }
 
 /// Database metadata.
@@ -190,29 +200,67 @@ pub fn db_err(err: io::Error) -> client::error::Error {
 	client::error::ErrorKind::Backend(err.description().into()).into()
 }
 
-/// Open RocksDB database.
+/// Open the database of the engine of `config`, without checking its type.
+pub fn open_kvdb(config: &DatabaseSettings) -> client::error::Result<Arc<KeyValueDB>> {
+	let db: Arc<KeyValueDB> = match config.engine {
+		DatabaseEngine::RocksDb => {
+			let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
+			db_config.memory_budget = config.cache_size;
+			let path = config.path.to_str().ok_or_else(|| client::error::ErrorKind::Backend("Invalid database path".into()))?;
+			Arc::new(Database::open(&db_config, &path).map_err(db_err)?)
+		},
+		DatabaseEngine::Sled => Arc::new(SledDatabase::open(&config.path).map_err(db_err)?),
+		DatabaseEngine::Memory => Arc::new(::kvdb_memorydb::create(NUM_COLUMNS)),
+	};
+
+	Ok(db)
+}
+
+/// Open the database and check that it is of type `db_type` and of the current version.
 pub fn open_database(config: &DatabaseSettings, col_meta: Option<u32>, db_type: &str) -> client::error::Result<Arc<KeyValueDB>> {
-	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
-	db_config.memory_budget = config.cache_size;
-	let path = config.path.to_str().ok_or_else(|| client::error::ErrorKind::Backend("Invalid database path".into()))?;
-	let db = Database::open(&db_config, &path).map_err(db_err)?;
+	let db = open_kvdb(config)?;
+	check_database_type(&*db, col_meta, db_type)?;
+	Ok(db)
+}
 
-	// check database type
+/// Check that the database is of type `db_type` and of the current version, initializing both if
+/// the database is new.
+fn check_database_type(db: &KeyValueDB, col_meta: Option<u32>, db_type: &str) -> client::error::Result<()> {
 	match db.get(col_meta, meta_keys::TYPE).map_err(db_err)? {
 		Some(stored_type) => {
 			if db_type.as_bytes() != &*stored_type {
 				return Err(client::error::ErrorKind::Backend(
 					format!("Unexpected database type. Expected: {}", db_type)).into());
 			}
+
+			let version = db.get(col_meta, meta_keys::VERSION).map_err(db_err)?
+				.and_then(|version| u32::decode(&mut &version[..]))
+				.unwrap_or(0);
+			if version == 0 {
+				info!(
+					"Migrating the database to version {}. Child storage written by older versions is not migrated",
+					DB_VERSION,
+				);
+				let mut transaction = DBTransaction::new();
+				transaction.put(col_meta, meta_keys::VERSION, &DB_VERSION.encode());
+				db.write(transaction).map_err(db_err)?;
+			} else if version != DB_VERSION {
+				return Err(client::error::ErrorKind::Backend(format!(
+					"Unsupported database version {}. Expected: {}. The database must be removed and the chain synced again",
+					version,
+					DB_VERSION,
+				)).into());
+			}
 		},
 		None => {
 			let mut transaction = DBTransaction::new();
 			transaction.put(col_meta, meta_keys::TYPE, db_type.as_bytes());
+			transaction.put(col_meta, meta_keys::VERSION, &DB_VERSION.encode());
 			db.write(transaction).map_err(db_err)?;
 		},
 	}
 
-	Ok(Arc::new(db))
+	Ok(())
 }
 
 /// Read database column entry for the given block.
@@ -303,3 +351,27 @@ pub fn read_meta<Block>(db: &KeyValueDB, col_meta: Option<u32>, col_header: Opti
 		genesis_hash,
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unversioned_databases_are_migrated_and_others_refused() {
+		let db = kvdb_memorydb::create(NUM_COLUMNS);
+		check_database_type(&db, COLUMN_META, "full").unwrap();
+		check_database_type(&db, COLUMN_META, "full").unwrap();
+		assert!(check_database_type(&db, COLUMN_META, "light").is_err());
+
+		let mut transaction = DBTransaction::new();
+		transaction.delete(COLUMN_META, meta_keys::VERSION);
+		db.write(transaction).unwrap();
+		check_database_type(&db, COLUMN_META, "full").unwrap();
+		assert_eq!(db.get(COLUMN_META, meta_keys::VERSION).unwrap().map(|v| v.to_vec()), Some(DB_VERSION.encode()));
+
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, meta_keys::VERSION, &(DB_VERSION + 1).encode());
+		db.write(transaction).unwrap();
+		assert!(check_database_type(&db, COLUMN_META, "full").is_err());
+	}
+}
@@ -25,6 +25,50 @@ use state_machine::ChangesTrieStorage as StateChangesTrieStorage;
 use hash_db::Hasher;
 use trie::MemoryDB;
 
+/// Changes to the top level storage, `None` values are deletions.
+pub type StorageCollection = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// Changes to the child storages, by storage key.
+pub type ChildStorageCollection = Vec<(Vec<u8>, StorageCollection)>;
+
+/// Hit, miss and eviction counters of a cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Number of lookups found in the cache.
+	pub hits: u64,
+	/// Number of lookups not found in the cache.
+	pub misses: u64,
+	/// Number of entries evicted to make room for new ones.
+	pub evictions: u64,
+	/// Size of the cached entries in bytes.
+	pub used_bytes: usize,
+	/// Maximal size of the cached entries in bytes.
+	pub max_bytes: usize,
+}
+
+impl CacheStats {
+	/// Share of the lookups found in the cache, between 0 and 1.
+	pub fn hit_ratio(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			lookups => self.hits as f64 / lookups as f64,
+		}
+	}
+}
+
+/// Statistics of the caches of the state.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StateCacheStats {
+	/// Storage values cache.
+	pub values: CacheStats,
+	/// Child storage values cache.
+	pub child_values: CacheStats,
+	/// Storage hashes cache.
+	pub hashes: CacheStats,
+	/// Trie nodes cache.
+	pub nodes: CacheStats,
+}
+
 /// State of a new block.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NewBlockState {
@@ -80,8 +124,8 @@ pub trait BlockImportOperation<Block, H> where
 	fn update_db_storage(&mut self, update: <Self::State as StateBackend<H>>::Transaction) -> error::Result<()>;
 	/// Inject storage data into the database replacing any existing data.
 	fn reset_storage(&mut self, top: StorageOverlay, children: ChildrenStorageOverlay) -> error::Result<H::Out>;
-	/// Set top level storage changes.
-	fn update_storage(&mut self, update: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> error::Result<()>;
+	/// Set top level and child storage changes.
+	fn update_storage(&mut self, update: StorageCollection, child_update: ChildStorageCollection) -> error::Result<()>;
 	/// Inject changes trie data into the database.
 	fn update_changes_trie(&mut self, update: MemoryDB<H>) -> error::Result<()>;
 	/// Insert auxiliary keys. Values are `None` if should be deleted.
@@ -155,6 +199,10 @@ pub trait Backend<Block, H>: AuxStore + Send + Sync where
 	/// Attempts to revert the chain by `n` blocks. Returns the number of blocks that were
 	/// successfully reverted.
 	fn revert(&self, n: NumberFor<Block>) -> error::Result<NumberFor<Block>>;
+	/// Statistics of the state caches, if the backend has any.
+	fn state_cache_stats(&self) -> Option<StateCacheStats> {
+		None
+	}
 
 	/// Insert auxiliary data into key-value store.
 	fn insert_aux<
@@ -41,7 +41,7 @@ use primitives::storage::well_known_keys;
 use parity_codec::{Encode, Decode};
 use state_machine::{
 	DBValue, Backend as StateBackend, CodeExecutor, ChangesTrieAnchorBlockId,
-	ExecutionStrategy, ExecutionManager, prove_read,
+	ExecutionStrategy, ExecutionManager, prove_read, prove_child_read,
 	ChangesTrieRootsStorage, ChangesTrieStorage,
 	key_changes, key_changes_proof, OverlayedChanges, NeverOffchainExt,
 };
@@ -62,6 +62,8 @@ use crate::block_builder::{self, api::BlockBuilder as BlockBuilderAPI};
 use crate::genesis;
 use consensus;
 use substrate_telemetry::{telemetry, SUBSTRATE_INFO};
+use substrate_prometheus::Histogram;
+use lazy_static::lazy_static;
 
 use log::{info, trace, warn};
 use error_chain::bail;
@@ -75,6 +77,13 @@ pub type FinalityNotifications<Block> = mpsc::UnboundedReceiver<FinalityNotifica
 type StorageUpdate<B, Block> = <<<B as backend::Backend<Block, Blake2Hasher>>::BlockImportOperation as BlockImportOperation<Block, Blake2Hasher>>::State as state_machine::Backend<Blake2Hasher>>::Transaction;
 type ChangesUpdate = trie::MemoryDB<Blake2Hasher>;
 
+lazy_static! {
+	static ref BLOCK_IMPORT_TIME: Histogram = substrate_prometheus::histogram(
+		"block_import_time_seconds",
+		"Time taken to execute and import a block",
+	);
+}
+
 /// Execution strategies settings.
 #[derive(Debug, Clone)]
 pub struct ExecutionStrategies {
@@ -279,7 +288,10 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		build_genesis_storage: S,
 		execution_strategies: ExecutionStrategies
 	) -> error::Result<Self> {
-		if backend.blockchain().header(BlockId::Number(Zero::zero()))?.is_none() {
+		// a database started from a state snapshot has no genesis block, but has a best block.
+		let is_empty = backend.blockchain().header(BlockId::Number(Zero::zero()))?.is_none()
+			&& backend.blockchain().info()?.best_hash == Default::default();
+		if is_empty {
 			let (genesis_storage, children_genesis_storage) = build_genesis_storage.build_storage()?;
 			let mut op = backend.begin_operation()?;
 			backend.begin_state_operation(&mut op, BlockId::Hash(Default::default()))?;
@@ -323,6 +335,11 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		&self.backend
 	}
 
+	/// Get the statistics of the state caches of the backend, if any.
+	pub fn state_cache_stats(&self) -> Option<backend::StateCacheStats> {
+		self.backend.state_cache_stats()
+	}
+
 	/// Return storage entry keys in state in a block of given hash with given prefix.
 	pub fn storage_keys(&self, id: &BlockId<Block>, key_prefix: &StorageKey) -> error::Result<Vec<StorageKey>> {
 		let keys = self.state_at(id)?.keys(&key_prefix.0).into_iter().map(StorageKey).collect();
@@ -336,6 +353,34 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			.map(StorageData))
 	}
 
+	/// Given a `BlockId`, a key prefix, and a child storage key, return the matching child storage keys.
+	pub fn child_storage_keys(
+		&self,
+		id: &BlockId<Block>,
+		child_storage_key: &StorageKey,
+		key_prefix: &StorageKey
+	) -> error::Result<Vec<StorageKey>> {
+		let mut keys = Vec::new();
+		self.state_at(id)?.for_keys_in_child_storage(&child_storage_key.0, |key| {
+			if key.starts_with(&key_prefix.0) {
+				keys.push(StorageKey(key.to_vec()));
+			}
+		});
+		Ok(keys)
+	}
+
+	/// Given a `BlockId`, a key and a child storage key, return the value under the key in that block.
+	pub fn child_storage(
+		&self,
+		id: &BlockId<Block>,
+		child_storage_key: &StorageKey,
+		key: &StorageKey
+	) -> error::Result<Option<StorageData>> {
+		Ok(self.state_at(id)?
+			.child_storage(&child_storage_key.0, &key.0).map_err(|e| error::Error::from_state(Box::new(e)))?
+			.map(StorageData))
+	}
+
 	/// Get the code at a given block.
 	pub fn code_at(&self, id: &BlockId<Block>) -> error::Result<Vec<u8>> {
 		Ok(self.storage(id, &StorageKey(well_known_keys::CODE.to_vec()))?
@@ -370,6 +415,20 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 				.map_err(Into::into))
 	}
 
+	/// Reads child storage value at a given block + storage_key + key, returning
+	/// read proof.
+	pub fn read_child_proof(
+		&self,
+		id: &BlockId<Block>,
+		storage_key: &[u8],
+		key: &[u8]
+	) -> error::Result<Vec<Vec<u8>>> {
+		self.state_at(id)
+			.and_then(|state| prove_child_read(state, storage_key, key)
+				.map(|(_, proof)| proof)
+				.map_err(Into::into))
+	}
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
@@ -722,6 +781,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 		*self.importing_block.write() = Some(hash);
 
+		let timer = BLOCK_IMPORT_TIME.start_timer();
 		let result = self.execute_and_import_block(
 			operation,
 			origin,
@@ -734,6 +794,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 		);
+		timer.observe_duration();
 
 		telemetry!(SUBSTRATE_INFO; "block.import";
 			"height" => height,
@@ -816,9 +877,13 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		if let Some(storage_update) = storage_update {
 			operation.op.update_db_storage(storage_update)?;
 		}
-		if let Some(storage_changes) = storage_changes.clone() {
-			operation.op.update_storage(storage_changes)?;
-		}
+		let storage_changes = match storage_changes {
+			Some((storage_changes, child_storage_changes)) => {
+				operation.op.update_storage(storage_changes.clone(), child_storage_changes)?;
+				Some(storage_changes)
+			},
+			None => None,
+		};
 		if let Some(Some(changes_update)) = changes_update {
 			operation.op.update_changes_trie(changes_update)?;
 		}
@@ -846,7 +911,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 	) -> error::Result<(
 		Option<StorageUpdate<B, Block>>,
 		Option<Option<ChangesUpdate>>,
-		Option<Vec<(Vec<u8>, Option<Vec<u8>>)>>,
+		Option<(backend::StorageCollection, backend::ChildStorageCollection)>,
 	)>
 		where
 			E: CallExecutor<Block, Blake2Hasher> + Send + Sync + Clone,
@@ -889,7 +954,12 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				overlay.commit_prospective();
 
-				Ok((Some(storage_update), Some(changes_update), Some(overlay.into_committed().collect())))
+				let (storage_changes, child_storage_changes) = overlay.into_committed_with_children();
+				Ok((
+					Some(storage_update),
+					Some(changes_update),
+					Some((storage_changes.collect(), child_storage_changes.collect())),
+				))
 			},
 			None => Ok((None, None, None))
 		}
@@ -62,6 +62,12 @@ error_chain! {
 			display("Blockchain: {}", e),
 		}
 
+		/// The body of a block has been pruned.
+		BlockBodyPruned(h: String) {
+			description("block body pruned"),
+			display("Body of block {} has been pruned", &*h),
+		}
+
 		/// Invalid authorities set received from the runtime.
 		InvalidAuthoritiesSet {
 			description("authorities set is invalid"),
@@ -522,7 +522,11 @@ where
 		Ok(())
 	}
 
-	fn update_storage(&mut self, _update: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> error::Result<()> {
+	fn update_storage(
+		&mut self,
+		_update: backend::StorageCollection,
+		_child_update: backend::ChildStorageCollection,
+	) -> error::Result<()> {
 		Ok(())
 	}
 
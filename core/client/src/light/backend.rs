@@ -26,11 +26,14 @@ use runtime_primitives::{generic::BlockId, Justification, StorageOverlay, Childr
 use state_machine::{Backend as StateBackend, TrieBackend, backend::InMemory as InMemoryState};
 use runtime_primitives::traits::{Block as BlockT, NumberFor, AuthorityIdFor, Zero, Header};
 use crate::in_mem::{self, check_genesis_storage};
-use crate::backend::{AuxStore, Backend as ClientBackend, BlockImportOperation, RemoteBackend, NewBlockState};
+use crate::backend::{
+	AuxStore, Backend as ClientBackend, BlockImportOperation, RemoteBackend, NewBlockState,
+	StorageCollection, ChildStorageCollection,
+};
 use crate::blockchain::HeaderBackend as BlockchainHeaderBackend;
 use crate::error::{Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult};
 use crate::light::blockchain::{Blockchain, Storage as BlockchainStorage};
-use crate::light::fetcher::{Fetcher, RemoteReadRequest};
+use crate::light::fetcher::{Fetcher, RemoteReadRequest, RemoteReadChildRequest};
 use hash_db::Hasher;
 use trie::MemoryDB;
 use heapsize::HeapSizeOf;
@@ -291,7 +294,7 @@ where
 		Ok(())
 	}
 
-	fn update_storage(&mut self, _update: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> ClientResult<()> {
+	fn update_storage(&mut self, _update: StorageCollection, _child_update: ChildStorageCollection) -> ClientResult<()> {
 		// we're not storing anything locally => ignore changes
 		Ok(())
 	}
@@ -307,6 +310,26 @@ where
 	}
 }
 
+impl<Block, S, F> OnDemandState<Block, S, F>
+where
+	Block: BlockT,
+	S: BlockchainStorage<Block>,
+	F: Fetcher<Block>,
+{
+	/// Get the header of the block the state is at, reading it from the blockchain once.
+	fn header(&self) -> ClientResult<Block::Header> {
+		if let Some(header) = self.cached_header.read().clone() {
+			return Ok(header);
+		}
+
+		let header = self.blockchain.upgrade()
+			.ok_or_else(|| ClientErrorKind::UnknownBlock(format!("{}", self.block)).into())
+			.and_then(|blockchain| blockchain.expect_header(BlockId::Hash(self.block)))?;
+		*self.cached_header.write() = Some(header.clone());
+		Ok(header)
+	}
+}
+
 impl<Block, S, F, H> StateBackend<H> for OnDemandState<Block, S, F>
 where
 	Block: BlockT,
@@ -319,27 +342,26 @@ where
 	type TrieBackendStorage = MemoryDB<H>;
 
 	fn storage(&self, key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
-		let mut header = self.cached_header.read().clone();
-		if header.is_none() {
-			let cached_header = self.blockchain.upgrade()
-				.ok_or_else(|| ClientErrorKind::UnknownBlock(format!("{}", self.block)).into())
-				.and_then(|blockchain| blockchain.expect_header(BlockId::Hash(self.block)))?;
-			header = Some(cached_header.clone());
-			*self.cached_header.write() = Some(cached_header);
-		}
-
 		self.fetcher.upgrade().ok_or(ClientErrorKind::NotAvailableOnLightClient)?
 			.remote_read(RemoteReadRequest {
 				block: self.block,
-				header: header.expect("if block above guarantees that header is_some(); qed"),
+				header: self.header()?,
 				key: key.to_vec(),
 				retry_count: None,
 			})
 			.into_future().wait()
 	}
 
-	fn child_storage(&self, _storage_key: &[u8], _key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
-		Err(ClientErrorKind::NotAvailableOnLightClient.into())
+	fn child_storage(&self, storage_key: &[u8], key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		self.fetcher.upgrade().ok_or(ClientErrorKind::NotAvailableOnLightClient)?
+			.remote_read_child(RemoteReadChildRequest {
+				block: self.block,
+				header: self.header()?,
+				storage_key: storage_key.to_vec(),
+				key: key.to_vec(),
+				retry_count: None,
+			})
+			.into_future().wait()
 	}
 
 	fn for_keys_with_prefix<A: FnMut(&[u8])>(&self, _prefix: &[u8], _action: A) {
@@ -217,20 +217,38 @@ impl SlotDuration {
 		C: client::backend::AuxStore,
 		C: ProvideRuntimeApi,
 		C::Api: AuraApi<B>,
+	{
+		Self::get_or_compute_with(
+			client,
+			b"aura_slot_duration",
+			|client, at| client.runtime_api().slot_duration(at),
+		)
+	}
+
+	/// Either fetch the slot duration stored under the given auxiliary key or
+	/// compute it from the genesis state with the given closure and store it.
+	///
+	/// This lets slot-based consensus engines other than Aura reuse the slot
+	/// infrastructure.
+	pub fn get_or_compute_with<B: Block, C, F>(
+		client: &C,
+		key: &[u8],
+		compute: F,
+	) -> ::client::error::Result<Self> where
+		C: client::backend::AuxStore,
+		F: FnOnce(&C, &BlockId<B>) -> ::client::error::Result<u64>,
 	{
 		use codec::Decode;
-		const SLOT_KEY: &[u8] = b"aura_slot_duration";
 
-		match client.get_aux(SLOT_KEY)? {
+		match client.get_aux(key)? {
 			Some(v) => u64::decode(&mut &v[..])
 				.map(SlotDuration)
 				.ok_or_else(|| ::client::error::ErrorKind::Backend(
-					format!("Aura slot duration kept in invalid format"),
+					format!("Slot duration kept in invalid format"),
 				).into()),
 			None => {
 				use runtime_primitives::traits::Zero;
-				let genesis_slot_duration = client.runtime_api()
-					.slot_duration(&BlockId::number(Zero::zero()))?;
+				let genesis_slot_duration = compute(client, &BlockId::number(Zero::zero()))?;
 
 				info!(
 					"Loaded block-time = {:?} seconds from genesis on first-launch",
@@ -238,7 +256,7 @@ impl SlotDuration {
 				);
 
 				genesis_slot_duration.using_encoded(|s| {
-					client.insert_aux(&[(SLOT_KEY, &s[..])], &[])
+					client.insert_aux(&[(key, &s[..])], &[])
 				})?;
 
 				Ok(SlotDuration(genesis_slot_duration))
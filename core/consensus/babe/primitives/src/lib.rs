@@ -0,0 +1,102 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives for BABE.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::vec::Vec;
+use parity_codec::{Encode, Decode};
+use substrate_client::decl_runtime_apis;
+
+/// The context used when turning a VRF in/out pair into bytes for threshold
+/// comparison.
+pub const BABE_VRF_PREFIX: &[u8] = b"substrate-babe-vrf";
+
+/// The length of a VRF output.
+pub const VRF_OUTPUT_LENGTH: usize = 32;
+
+/// The length of a VRF proof.
+pub const VRF_PROOF_LENGTH: usize = 64;
+
+/// A BABE authority identifier. BABE authorities sign with sr25519, since
+/// the VRF is only available over that curve.
+pub type AuthorityId = substrate_primitives::sr25519::Public;
+
+/// The randomness of an epoch.
+pub type Randomness = [u8; VRF_OUTPUT_LENGTH];
+
+/// The output of a VRF evaluation.
+pub type VrfOutput = [u8; VRF_OUTPUT_LENGTH];
+
+/// The proof of a VRF evaluation.
+pub type VrfProof = [u8; VRF_PROOF_LENGTH];
+
+/// Configuration data used by the BABE consensus engine, as read from the
+/// genesis state.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BabeConfiguration {
+	/// The slot duration in seconds.
+	pub slot_duration: u64,
+	/// The number of slots in an epoch.
+	pub epoch_length: u64,
+	/// The probability of a slot having at least one primary author, expressed
+	/// as the fraction `c.0 / c.1`.
+	pub c: (u64, u64),
+	/// Whether blocks may be authored in secondary (round-robin) slots when no
+	/// primary author has been elected.
+	pub secondary_slots: bool,
+}
+
+/// The data of a BABE epoch: its authorities and randomness.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Epoch {
+	/// The index of the epoch.
+	pub epoch_index: u64,
+	/// The first slot of the epoch.
+	pub start_slot: u64,
+	/// The number of slots in the epoch.
+	pub duration: u64,
+	/// The authorities allowed to author blocks in this epoch.
+	pub authorities: Vec<AuthorityId>,
+	/// The randomness used to seed the VRF of this epoch.
+	pub randomness: Randomness,
+}
+
+impl Epoch {
+	/// The first slot which is not part of this epoch.
+	pub fn end_slot(&self) -> u64 {
+		self.start_slot.saturating_add(self.duration)
+	}
+}
+
+decl_runtime_apis! {
+	/// API necessary for block authorship with BABE.
+	pub trait BabeApi {
+		/// Return the configuration for BABE. Currently, only the value
+		/// provided by this type at genesis will be used.
+		fn startup_data() -> BabeConfiguration;
+
+		/// Return the epoch that the current block is part of.
+		fn epoch() -> Epoch;
+
+		/// Return the epoch that follows the current one. Its randomness and
+		/// authorities are fixed once the current epoch has started.
+		fn next_epoch() -> Epoch;
+	}
+}
@@ -0,0 +1,861 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! BABE (Blind Assignment for Blockchain Extension) consensus in substrate.
+//!
+//! Like Aura, BABE divides time into slots of t seconds each, and slots into
+//! epochs. Unlike Aura, the author of a slot is not publicly known in
+//! advance: every authority evaluates a VRF over the epoch randomness and
+//! the slot number, and may author a block in a *primary* slot if the output
+//! is below a threshold. Several authorities or none may win a slot.
+//!
+//! If enabled in the runtime, slots also have a *secondary* author chosen
+//! round-robin, so the chain keeps progressing when no primary author has
+//! been elected.
+//!
+//! The runtime collects the VRF outputs of primary slot authors into the
+//! randomness of later epochs.
+
+use std::{sync::Arc, time::Duration, thread};
+
+use parity_codec::{Encode, Decode};
+use consensus_common::{BlockImport, Environment, Proposer, ForkChoiceStrategy};
+use consensus_common::import_queue::{Verifier, BasicQueue, SharedBlockImport, SharedJustificationImport};
+use client::block_builder::api::BlockBuilder as BlockBuilderApi;
+use client::runtime_api::ApiExt;
+use consensus_common::{ImportBlock, BlockOrigin};
+use runtime_primitives::{generic, generic::BlockId, Justification};
+use runtime_primitives::traits::{
+	Block, Header, Digest, DigestItemFor, AuthorityIdFor, ProvideRuntimeApi
+};
+use primitives::{Pair, sr25519};
+use inherents::{InherentDataProviders, InherentData, RuntimeString};
+use schnorrkel::vrf::{VRFInOut, VRFOutput, VRFProof};
+use merlin::Transcript;
+
+use futures::{Future, IntoFuture, future};
+use tokio::timer::Timeout;
+use log::{warn, debug, info, trace};
+
+use srml_babe::{
+	BabeInherentData,
+	timestamp::{TimestampInherentData, InherentError as TIError}
+};
+use substrate_telemetry::{telemetry, CONSENSUS_TRACE, CONSENSUS_DEBUG, CONSENSUS_WARN, CONSENSUS_INFO};
+
+use slots::{CheckedHeader, SlotWorker, SlotInfo, SlotCompatible};
+
+pub use slots::SlotDuration;
+pub use babe_primitives::*;
+pub use consensus_common::SyncOracle;
+
+/// The transcript label of BABE VRFs.
+const BABE_TRANSCRIPT_LABEL: &[u8] = b"BABE";
+
+/// A BABE seal. It is placed as the last digest item of a block and
+/// removed before the block is executed.
+#[derive(Clone, Encode, Decode)]
+pub struct BabeSeal {
+	/// The slot number the block was authored in.
+	pub slot_number: u64,
+	/// The index of the author in the authority set of the epoch.
+	pub authority_index: u32,
+	/// The VRF output and proof of the author for primary slots, `None` for
+	/// secondary slots.
+	pub vrf: Option<(VrfOutput, VrfProof)>,
+	/// The signature of the author over the pre-header hash and the fields
+	/// above.
+	pub signature: sr25519::Signature,
+}
+
+impl BabeSeal {
+	/// The payload signed by the author of a block with the given pre-header hash.
+	fn signing_payload<H: Encode>(&self, pre_hash: &H) -> Vec<u8> {
+		(self.slot_number, self.authority_index, &self.vrf, pre_hash).encode()
+	}
+}
+
+/// A digest item which is usable with BABE consensus.
+pub trait CompatibleDigestItem: Sized {
+	/// Construct a digest item which contains a BABE seal.
+	fn babe_seal(seal: BabeSeal) -> Self;
+
+	/// If this item is a BABE seal, return it.
+	fn as_babe_seal(&self) -> Option<BabeSeal>;
+}
+
+impl<Hash, AuthorityId, SealSignature> CompatibleDigestItem for generic::DigestItem<Hash, AuthorityId, SealSignature> {
+	fn babe_seal(seal: BabeSeal) -> Self {
+		generic::DigestItem::Other(seal.encode())
+	}
+
+	fn as_babe_seal(&self) -> Option<BabeSeal> {
+		self.as_other().and_then(|data| BabeSeal::decode(&mut &data[..]))
+	}
+}
+
+fn inherent_to_common_error(err: RuntimeString) -> consensus_common::Error {
+	consensus_common::ErrorKind::InherentData(err.into()).into()
+}
+
+struct BabeSlotCompatible;
+
+impl SlotCompatible for BabeSlotCompatible {
+	fn extract_timestamp_and_slot(
+		data: &InherentData
+	) -> Result<(u64, u64), consensus_common::Error> {
+		data.timestamp_inherent_data()
+			.and_then(|t| data.babe_inherent_data().map(|(slot, _)| (t, slot)))
+			.map_err(inherent_to_common_error)
+	}
+}
+
+/// The BABE configuration of a chain. Create with `get_or_compute`.
+#[derive(Clone, Debug)]
+pub struct Config {
+	slot_duration: SlotDuration,
+	genesis: BabeConfiguration,
+}
+
+impl Config {
+	/// Either fetch the configuration from disk or compute it from the genesis
+	/// state.
+	pub fn get_or_compute<B: Block, C>(client: &C) -> ::client::error::Result<Self> where
+		C: client::backend::AuxStore,
+		C: ProvideRuntimeApi,
+		C::Api: BabeApi<B>,
+	{
+		const CONFIG_KEY: &[u8] = b"babe_configuration";
+
+		let genesis = match client.get_aux(CONFIG_KEY)? {
+			Some(v) => BabeConfiguration::decode(&mut &v[..])
+				.ok_or_else(|| ::client::error::Error::from(::client::error::ErrorKind::Backend(
+					format!("BABE configuration kept in invalid format"),
+				)))?,
+			None => {
+				use runtime_primitives::traits::Zero;
+				let genesis = client.runtime_api()
+					.startup_data(&BlockId::number(Zero::zero()))?;
+
+				info!("Loaded BABE configuration from genesis on first-launch: {:?}", genesis);
+
+				genesis.using_encoded(|s| {
+					client.insert_aux(&[(CONFIG_KEY, &s[..])], &[])
+				})?;
+
+				genesis
+			}
+		};
+
+		let slot_duration = SlotDuration::get_or_compute_with(
+			client,
+			b"babe_slot_duration",
+			|_, _: &BlockId<B>| Ok(genesis.slot_duration),
+		)?;
+
+		Ok(Config { slot_duration, genesis })
+	}
+
+	/// Returns the slot duration.
+	pub fn slot_duration(&self) -> SlotDuration {
+		self.slot_duration
+	}
+
+	/// Returns the genesis BABE configuration.
+	pub fn genesis(&self) -> &BabeConfiguration {
+		&self.genesis
+	}
+}
+
+/// Create the VRF transcript of a slot.
+fn make_transcript(randomness: &[u8], slot_number: u64, epoch_index: u64) -> Transcript {
+	let mut transcript = Transcript::new(BABE_TRANSCRIPT_LABEL);
+	transcript.append_message(b"slot number", &slot_number.to_le_bytes());
+	transcript.append_message(b"current epoch", &epoch_index.to_le_bytes());
+	transcript.append_message(b"chain randomness", randomness);
+	transcript
+}
+
+/// Calculate the primary slot threshold for an authority set of the given
+/// size, such that the probability of a slot having at least one primary
+/// author is `c`.
+fn calculate_primary_threshold(c: (u64, u64), authorities: usize) -> u128 {
+	let c = c.0 as f64 / c.1 as f64;
+	let theta = 1f64 / authorities as f64;
+	let p = 1f64 - (1f64 - c).powf(theta);
+
+	(u128::max_value() as f64 * p) as u128
+}
+
+/// Whether the given VRF in/out pair wins a primary slot.
+fn check_primary_threshold(inout: &VRFInOut, threshold: u128) -> bool {
+	u128::from_le_bytes(inout.make_bytes::<[u8; 16]>(BABE_VRF_PREFIX)) < threshold
+}
+
+/// Get the secondary slot author index for the given slot.
+fn secondary_slot_author(slot_number: u64, authorities: &[AuthorityId]) -> Option<usize> {
+	if authorities.is_empty() { return None }
+
+	let idx = slot_number % (authorities.len() as u64);
+	assert!(idx <= usize::max_value() as u64,
+		"It is impossible to have a vector with length beyond the address space; qed");
+
+	Some(idx as usize)
+}
+
+/// Try to claim the given slot with the given key. Returns the index of the
+/// key in the authority set and, for primary slots, the VRF output and proof.
+fn claim_slot(
+	slot_number: u64,
+	epoch: &Epoch,
+	config: &BabeConfiguration,
+	pair: &sr25519::Pair,
+) -> Option<(u32, Option<(VrfOutput, VrfProof)>)> {
+	let public = pair.public();
+	let authority_index = epoch.authorities.iter().position(|a| a == &public)?;
+
+	let transcript = make_transcript(&epoch.randomness, slot_number, epoch.epoch_index);
+	let threshold = calculate_primary_threshold(config.c, epoch.authorities.len());
+	let keypair: &schnorrkel::Keypair = pair.as_ref();
+	let (inout, proof, _) = keypair.vrf_sign(transcript);
+
+	if check_primary_threshold(&inout, threshold) {
+		Some((authority_index as u32, Some((inout.to_output().to_bytes(), proof.to_bytes()))))
+	} else if config.secondary_slots
+		&& secondary_slot_author(slot_number, &epoch.authorities) == Some(authority_index)
+	{
+		Some((authority_index as u32, None))
+	} else {
+		None
+	}
+}
+
+/// Get the epoch a slot built on top of the given block belongs to.
+///
+/// Epochs without any block are skipped: if the slot lies beyond the next
+/// epoch, the next epoch is moved forward to the boundary before the slot,
+/// as the runtime does.
+fn epoch_for_slot<B: Block, C>(client: &C, at: &BlockId<B>, slot_number: u64) -> Result<Epoch, String> where
+	C: ProvideRuntimeApi,
+	C::Api: BabeApi<B>,
+{
+	let epoch = client.runtime_api().epoch(at)
+		.map_err(|e| format!("Could not fetch epoch at {:?}: {:?}", at, e))?;
+
+	if slot_number < epoch.end_slot() {
+		return Ok(epoch)
+	}
+
+	if epoch.duration == 0 {
+		return Err("BABE epoch duration cannot be zero".into())
+	}
+
+	let mut next = client.runtime_api().next_epoch(at)
+		.map_err(|e| format!("Could not fetch next epoch at {:?}: {:?}", at, e))?;
+	next.start_slot = epoch.start_slot + (slot_number - epoch.start_slot) / epoch.duration * epoch.duration;
+
+	Ok(next)
+}
+
+/// Get the slot for now.
+fn slot_now(slot_duration: u64) -> Option<u64> {
+	slots::duration_now().map(|s| s.as_secs() / slot_duration)
+}
+
+/// Start the babe worker. The returned future should be run in a tokio runtime.
+pub fn start_babe<B, C, E, I, SO, Error, OnExit>(
+	config: Config,
+	local_key: Arc<sr25519::Pair>,
+	client: Arc<C>,
+	block_import: Arc<I>,
+	env: Arc<E>,
+	sync_oracle: SO,
+	on_exit: OnExit,
+	inherent_data_providers: InherentDataProviders,
+	force_authoring: bool,
+) -> Result<impl Future<Item=(), Error=()>, consensus_common::Error> where
+	B: Block,
+	C: ProvideRuntimeApi + client::ChainHead<B>,
+	C::Api: BabeApi<B>,
+	E: Environment<B, Error=Error>,
+	E::Proposer: Proposer<B, Error=Error>,
+	<<E::Proposer as Proposer<B>>::Create as IntoFuture>::Future: Send + 'static,
+	I: BlockImport<B> + Send + Sync + 'static,
+	Error: From<I::Error>,
+	SO: SyncOracle + Send + Sync + Clone,
+	DigestItemFor<B>: CompatibleDigestItem,
+	Error: ::std::error::Error + Send + 'static + From<::consensus_common::Error>,
+	OnExit: Future<Item=(), Error=()>,
+{
+	let worker = BabeWorker {
+		client: client.clone(),
+		block_import,
+		env,
+		local_key,
+		inherent_data_providers: inherent_data_providers.clone(),
+		sync_oracle: sync_oracle.clone(),
+		force_authoring,
+		config: config.genesis.clone(),
+	};
+	slots::start_slot_worker::<_, _, _, _, BabeSlotCompatible, _>(
+		config.slot_duration,
+		client,
+		Arc::new(worker),
+		sync_oracle,
+		on_exit,
+		inherent_data_providers
+	)
+}
+
+struct BabeWorker<C, E, I, SO> {
+	client: Arc<C>,
+	block_import: Arc<I>,
+	env: Arc<E>,
+	local_key: Arc<sr25519::Pair>,
+	sync_oracle: SO,
+	inherent_data_providers: InherentDataProviders,
+	force_authoring: bool,
+	config: BabeConfiguration,
+}
+
+impl<B: Block, C, E, I, Error, SO> SlotWorker<B> for BabeWorker<C, E, I, SO> where
+	C: ProvideRuntimeApi,
+	C::Api: BabeApi<B>,
+	E: Environment<B, Error=Error>,
+	E::Proposer: Proposer<B, Error=Error>,
+	<<E::Proposer as Proposer<B>>::Create as IntoFuture>::Future: Send + 'static,
+	I: BlockImport<B> + Send + Sync + 'static,
+	Error: From<I::Error>,
+	SO: SyncOracle + Send + Clone,
+	DigestItemFor<B>: CompatibleDigestItem,
+	Error: ::std::error::Error + Send + 'static + From<::consensus_common::Error>,
+{
+	type OnSlot = Box<Future<Item=(), Error=consensus_common::Error> + Send>;
+
+	fn on_start(
+		&self,
+		slot_duration: u64
+	) -> Result<(), consensus_common::Error> {
+		register_babe_inherent_data_provider(&self.inherent_data_providers, slot_duration)
+	}
+
+	fn on_slot(
+		&self,
+		chain_head: B::Header,
+		slot_info: SlotInfo,
+	) -> Self::OnSlot {
+		let pair = self.local_key.clone();
+		let block_import = self.block_import.clone();
+		let env = self.env.clone();
+
+		let (timestamp, slot_num, slot_duration) =
+			(slot_info.timestamp, slot_info.number, slot_info.duration);
+
+		let epoch = match epoch_for_slot(&*self.client, &BlockId::Hash(chain_head.hash()), slot_num) {
+			Ok(epoch) => epoch,
+			Err(e) => {
+				warn!("Unable to fetch epoch at block {:?}: {:?}", chain_head.hash(), e);
+				telemetry!(CONSENSUS_WARN; "babe.unable_fetching_epoch";
+					"slot" => ?chain_head.hash(), "err" => ?e
+				);
+				return Box::new(future::ok(()));
+			}
+		};
+
+		if !self.force_authoring && self.sync_oracle.is_offline() && epoch.authorities.len() > 1 {
+			debug!(target: "babe", "Skipping proposal slot. Waiting for the network.");
+			telemetry!(CONSENSUS_DEBUG; "babe.skipping_proposal_slot";
+				"authorities_len" => epoch.authorities.len()
+			);
+			return Box::new(future::ok(()));
+		}
+
+		let (authority_index, vrf) = match claim_slot(slot_num, &epoch, &self.config, &pair) {
+			None => return Box::new(future::ok(())),
+			Some(claim) => claim,
+		};
+
+		debug!(
+			target: "babe", "Starting authorship at slot {}; timestamp = {}; primary = {}",
+			slot_num,
+			timestamp,
+			vrf.is_some(),
+		);
+		telemetry!(CONSENSUS_DEBUG; "babe.starting_authorship";
+			"slot_num" => slot_num, "timestamp" => timestamp, "primary" => vrf.is_some()
+		);
+
+		// we are a slot author. make a block and sign it.
+		let proposer = match env.init(&chain_head, &[]) {
+			Ok(p) => p,
+			Err(e) => {
+				warn!("Unable to author block in slot {:?}: {:?}", slot_num, e);
+				telemetry!(CONSENSUS_WARN; "babe.unable_authoring_block";
+					"slot" => slot_num, "err" => ?e
+				);
+				return Box::new(future::ok(()))
+			}
+		};
+
+		// the runtime needs to know the VRF output to accumulate randomness.
+		let mut inherent_data = slot_info.inherent_data;
+		inherent_data.babe_replace_inherent_data((slot_num, vrf.as_ref().map(|(output, _)| *output)));
+
+		let remaining_duration = slot_info.remaining_duration();
+		// deadline our production to approx. the end of the slot
+		let proposal_work = Timeout::new(
+			proposer.propose(inherent_data, remaining_duration).into_future(),
+			remaining_duration,
+		);
+
+		Box::new(
+			proposal_work
+				.map(move |b| {
+					// minor hack since we don't have access to the timestamp
+					// that is actually set by the proposer.
+					let slot_after_building = slot_now(slot_duration);
+					if slot_after_building != Some(slot_num) {
+						info!(
+							"Discarding proposal for slot {}; block production took too long",
+							slot_num
+						);
+						telemetry!(CONSENSUS_INFO; "babe.discarding_proposal_took_too_long";
+							"slot" => slot_num
+						);
+						return
+					}
+
+					let (header, body) = b.deconstruct();
+					let header_num = header.number().clone();
+					let pre_hash = header.hash();
+					let parent_hash = header.parent_hash().clone();
+
+					// sign the pre-sealed hash of the block and the claim, and
+					// then add it to a digest item.
+					let mut seal = BabeSeal {
+						slot_number: slot_num,
+						authority_index,
+						vrf,
+						signature: Default::default(),
+					};
+					seal.signature = pair.sign(&seal.signing_payload(&pre_hash));
+					let item = <DigestItemFor<B> as CompatibleDigestItem>::babe_seal(seal);
+
+					let import_block: ImportBlock<B> = ImportBlock {
+						origin: BlockOrigin::Own,
+						header,
+						justification: None,
+						post_digests: vec![item],
+						body: Some(body),
+						finalized: false,
+						auxiliary: Vec::new(),
+						fork_choice: ForkChoiceStrategy::LongestChain,
+					};
+
+					info!("Pre-sealed block for proposal at {}. Hash now {:?}, previously {:?}.",
+						  header_num,
+						  import_block.post_header().hash(),
+						  pre_hash
+					);
+					telemetry!(CONSENSUS_INFO; "babe.pre_sealed_block";
+						"header_num" => ?header_num,
+						"hash_now" => ?import_block.post_header().hash(),
+						"hash_previously" => ?pre_hash
+					);
+
+					if let Err(e) = block_import.import_block(import_block, None) {
+						warn!(target: "babe", "Error with block built on {:?}: {:?}",
+							  parent_hash, e);
+						telemetry!(CONSENSUS_WARN; "babe.err_with_block_built_on";
+							"hash" => ?parent_hash, "err" => ?e
+						);
+					}
+				})
+				.map_err(|e| consensus_common::ErrorKind::ClientImport(format!("{:?}", e)).into())
+		)
+	}
+}
+
+/// Check a header has been signed by an authority allowed to author in its
+/// slot. If the slot is too far in the future, an error will be returned.
+/// If it's successful, returns the pre-header, the slot number, and the seal.
+//
+// FIXME #1018 needs misbehavior types
+fn check_header<B: Block>(
+	slot_now: u64,
+	mut header: B::Header,
+	hash: B::Hash,
+	epoch: &Epoch,
+	config: &BabeConfiguration,
+) -> Result<CheckedHeader<B::Header, BabeSeal>, String>
+	where DigestItemFor<B>: CompatibleDigestItem,
+{
+	let digest_item = match header.digest_mut().pop() {
+		Some(x) => x,
+		None => return Err(format!("Header {:?} is unsealed", hash)),
+	};
+	let seal = match digest_item.as_babe_seal() {
+		Some(x) => x,
+		None => return Err(format!("Header {:?} is unsealed", hash)),
+	};
+
+	if seal.slot_number > slot_now {
+		header.digest_mut().push(digest_item);
+		return Ok(CheckedHeader::Deferred(header, seal.slot_number))
+	}
+
+	let author = match epoch.authorities.get(seal.authority_index as usize) {
+		Some(author) => author.clone(),
+		None => return Err(format!("Authority index {} out of bounds", seal.authority_index)),
+	};
+
+	let pre_hash = header.hash();
+	if !sr25519::Pair::verify(&seal.signature, &seal.signing_payload(&pre_hash)[..], &author) {
+		return Err(format!("Bad signature on {:?}", hash))
+	}
+
+	match seal.vrf {
+		Some((ref output, ref proof)) => {
+			let public = schnorrkel::PublicKey::from_bytes(author.as_slice())
+				.map_err(|_| format!("Invalid authority key {:?}", author))?;
+			let output = VRFOutput::from_bytes(&output[..])
+				.map_err(|_| format!("Invalid VRF output on {:?}", hash))?;
+			let proof = VRFProof::from_bytes(&proof[..])
+				.map_err(|_| format!("Invalid VRF proof on {:?}", hash))?;
+
+			let transcript = make_transcript(&epoch.randomness, seal.slot_number, epoch.epoch_index);
+			let (inout, _) = public.vrf_verify(transcript, &output, &proof)
+				.map_err(|_| format!("VRF verification failed on {:?}", hash))?;
+
+			let threshold = calculate_primary_threshold(config.c, epoch.authorities.len());
+			if !check_primary_threshold(&inout, threshold) {
+				return Err(format!("VRF output of {:?} is over the threshold", hash))
+			}
+		}
+		None => {
+			if !config.secondary_slots {
+				return Err(format!("Secondary slots are disabled; {:?} has no VRF proof", hash))
+			}
+
+			let expected = secondary_slot_author(seal.slot_number, &epoch.authorities);
+			if expected != Some(seal.authority_index as usize) {
+				return Err(format!("Invalid secondary slot author on {:?}", hash))
+			}
+		}
+	}
+
+	let slot_number = seal.slot_number;
+	Ok(CheckedHeader::Checked(header, slot_number, seal))
+}
+
+/// A verifier for BABE blocks.
+pub struct BabeVerifier<C> {
+	client: Arc<C>,
+	config: BabeConfiguration,
+	inherent_data_providers: inherents::InherentDataProviders,
+}
+
+impl<C> BabeVerifier<C> {
+	fn check_inherents<B: Block>(
+		&self,
+		block: B,
+		block_id: BlockId<B>,
+		inherent_data: InherentData,
+		timestamp_now: u64,
+	) -> Result<(), String>
+		where C: ProvideRuntimeApi, C::Api: BlockBuilderApi<B>
+	{
+		const MAX_TIMESTAMP_DRIFT_SECS: u64 = 60;
+
+		let inherent_res = self.client.runtime_api().check_inherents(
+			&block_id,
+			block,
+			inherent_data,
+		).map_err(|e| format!("{:?}", e))?;
+
+		if !inherent_res.ok() {
+			inherent_res
+				.into_errors()
+				.try_for_each(|(i, e)| match TIError::try_from(&i, &e) {
+					Some(TIError::ValidAtTimestamp(timestamp)) => {
+						// halt import until timestamp is valid.
+						// reject when too far ahead.
+						if timestamp > timestamp_now + MAX_TIMESTAMP_DRIFT_SECS {
+							return Err("Rejecting block too far in future".into());
+						}
+
+						let diff = timestamp.saturating_sub(timestamp_now);
+						info!(
+							target: "babe",
+							"halting for block {} seconds in the future",
+							diff
+						);
+						telemetry!(CONSENSUS_INFO; "babe.halting_for_future_block";
+							"diff" => ?diff
+						);
+						thread::sleep(Duration::from_secs(diff));
+						Ok(())
+					},
+					Some(TIError::Other(e)) => Err(e.into()),
+					None => Err(self.inherent_data_providers.error_to_string(&i, &e)),
+				})
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<B: Block, C> Verifier<B> for BabeVerifier<C> where
+	C: ProvideRuntimeApi + Send + Sync,
+	C::Api: BlockBuilderApi<B> + BabeApi<B>,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	fn verify(
+		&self,
+		origin: BlockOrigin,
+		header: B::Header,
+		justification: Option<Justification>,
+		mut body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(ImportBlock<B>, Option<Vec<AuthorityIdFor<B>>>), String> {
+		let mut inherent_data = self.inherent_data_providers.create_inherent_data().map_err(String::from)?;
+		let (timestamp_now, slot_now) = BabeSlotCompatible::extract_timestamp_and_slot(&inherent_data)
+			.map_err(|e| format!("Could not extract timestamp and slot: {:?}", e))?;
+		let hash = header.hash();
+		let parent_hash = *header.parent_hash();
+
+		let seal_slot = header.digest().logs().last()
+			.and_then(|item| item.as_babe_seal())
+			.map(|seal| seal.slot_number)
+			.ok_or_else(|| format!("Header {:?} is unsealed", hash))?;
+		let epoch = epoch_for_slot(&*self.client, &BlockId::Hash(parent_hash), seal_slot)?;
+
+		// we add one to allow for some small drift.
+		// FIXME #1019 in the future, alter this queue to allow deferring of headers
+		let checked_header = check_header::<B>(slot_now + 1, header, hash, &epoch, &self.config)?;
+		match checked_header {
+			CheckedHeader::Checked(pre_header, slot_num, seal) => {
+				// if the body is passed through, we need to use the runtime
+				// to check that the internally-set slot and VRF output in the
+				// inherents actually match the seal.
+				if let Some(inner_body) = body.take() {
+					inherent_data.babe_replace_inherent_data(
+						(slot_num, seal.vrf.as_ref().map(|(output, _)| *output))
+					);
+					let block = B::new(pre_header.clone(), inner_body);
+
+					// skip the inherents verification if the runtime API is old.
+					if self.client
+						.runtime_api()
+						.has_api_with::<BlockBuilderApi<B>, _>(&BlockId::Hash(parent_hash), |v| v >= 2)
+						.map_err(|e| format!("{:?}", e))?
+					{
+						self.check_inherents(
+							block.clone(),
+							BlockId::Hash(parent_hash),
+							inherent_data,
+							timestamp_now,
+						)?;
+					}
+
+					let (_, inner_body) = block.deconstruct();
+					body = Some(inner_body);
+				}
+
+				trace!(target: "babe", "Checked {:?}; importing.", pre_header);
+				telemetry!(CONSENSUS_TRACE; "babe.checked_and_importing"; "pre_header" => ?pre_header);
+
+				let import_block = ImportBlock {
+					origin,
+					header: pre_header,
+					post_digests: vec![<DigestItemFor<B>>::babe_seal(seal)],
+					body,
+					finalized: false,
+					justification,
+					auxiliary: Vec::new(),
+					fork_choice: ForkChoiceStrategy::LongestChain,
+				};
+
+				Ok((import_block, None))
+			}
+			CheckedHeader::Deferred(a, b) => {
+				debug!(target: "babe", "Checking {:?} failed; {:?}, {:?}.", hash, a, b);
+				telemetry!(CONSENSUS_DEBUG; "babe.header_too_far_in_future";
+					"hash" => ?hash, "a" => ?a, "b" => ?b
+				);
+				Err(format!("Header {:?} rejected: too far in the future", hash))
+			}
+		}
+	}
+}
+
+/// The BABE import queue type.
+pub type BabeImportQueue<B> = BasicQueue<B>;
+
+/// Register the babe inherent data provider, if not registered already.
+fn register_babe_inherent_data_provider(
+	inherent_data_providers: &InherentDataProviders,
+	slot_duration: u64,
+) -> Result<(), consensus_common::Error> {
+	if !inherent_data_providers.has_provider(&srml_babe::INHERENT_IDENTIFIER) {
+		inherent_data_providers
+			.register_provider(srml_babe::InherentDataProvider::new(slot_duration))
+			.map_err(inherent_to_common_error)
+	} else {
+		Ok(())
+	}
+}
+
+/// Start an import queue for the BABE consensus algorithm.
+pub fn import_queue<B, C>(
+	config: Config,
+	block_import: SharedBlockImport<B>,
+	justification_import: Option<SharedJustificationImport<B>>,
+	client: Arc<C>,
+	inherent_data_providers: InherentDataProviders,
+) -> Result<BabeImportQueue<B>, consensus_common::Error> where
+	B: Block,
+	C: 'static + ProvideRuntimeApi + Send + Sync,
+	C::Api: BlockBuilderApi<B> + BabeApi<B>,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	register_babe_inherent_data_provider(&inherent_data_providers, config.slot_duration.get())?;
+
+	let verifier = Arc::new(
+		BabeVerifier {
+			client: client.clone(),
+			config: config.genesis,
+			inherent_data_providers,
+		}
+	);
+	Ok(BasicQueue::new(verifier, block_import, justification_import))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use keyring::sr25519::Keyring;
+	use runtime_primitives::testing::{Block as TestBlock, Header as TestHeader, ExtrinsicWrapper, Digest as TestDigest};
+	use primitives::H256;
+
+	type TestBlockT = TestBlock<ExtrinsicWrapper<u64>>;
+
+	fn test_epoch(authorities: Vec<AuthorityId>) -> Epoch {
+		Epoch {
+			epoch_index: 1,
+			start_slot: 0,
+			duration: 100,
+			authorities,
+			randomness: [42; 32],
+		}
+	}
+
+	fn test_config(c: (u64, u64), secondary_slots: bool) -> BabeConfiguration {
+		BabeConfiguration {
+			slot_duration: 1,
+			epoch_length: 100,
+			c,
+			secondary_slots,
+		}
+	}
+
+	fn sealed_header(slot_number: u64, pair: &sr25519::Pair, claim: (u32, Option<(VrfOutput, VrfProof)>)) -> TestHeader {
+		let mut header = TestHeader {
+			parent_hash: H256::default(),
+			number: 1,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: TestDigest { logs: vec![] },
+		};
+
+		let mut seal = BabeSeal {
+			slot_number,
+			authority_index: claim.0,
+			vrf: claim.1,
+			signature: Default::default(),
+		};
+		seal.signature = pair.sign(&seal.signing_payload(&header.hash()));
+		header.digest.logs.push(CompatibleDigestItem::babe_seal(seal));
+		header
+	}
+
+	#[test]
+	fn primary_threshold_grows_with_c() {
+		let low = calculate_primary_threshold((1, 4), 10);
+		let high = calculate_primary_threshold((1, 2), 10);
+		assert!(low < high);
+		assert_eq!(calculate_primary_threshold((1, 1), 1), u128::max_value());
+	}
+
+	#[test]
+	fn claimed_primary_slot_is_verified() {
+		let pair = Keyring::Alice.pair();
+		let epoch = test_epoch(vec![Keyring::Alice.into()]);
+		// every slot has a primary author.
+		let config = test_config((1, 1), false);
+
+		let claim = claim_slot(5, &epoch, &config, &pair).expect("Alice wins every slot");
+		assert!(claim.1.is_some());
+
+		let header = sealed_header(5, &pair, claim);
+		let hash = header.hash();
+		match check_header::<TestBlockT>(10, header, hash, &epoch, &config) {
+			Ok(CheckedHeader::Checked(_, slot, _)) => assert_eq!(slot, 5),
+			_ => panic!("header should be valid"),
+		}
+	}
+
+	#[test]
+	fn secondary_slots_are_round_robin() {
+		let pair = Keyring::Bob.pair();
+		let epoch = test_epoch(vec![Keyring::Alice.into(), Keyring::Bob.into()]);
+		// no slot has a primary author.
+		let config = test_config((0, 1), true);
+
+		assert!(claim_slot(4, &epoch, &config, &pair).is_none());
+		let claim = claim_slot(5, &epoch, &config, &pair).expect("Bob is the secondary author of odd slots");
+		assert_eq!(claim.0, 1);
+		assert!(claim.1.is_none());
+
+		let header = sealed_header(5, &pair, claim);
+		let hash = header.hash();
+		assert!(check_header::<TestBlockT>(10, header, hash, &epoch, &config).is_ok());
+
+		// the same claim in a slot belonging to Alice is rejected.
+		let header = sealed_header(6, &pair, (1, None));
+		let hash = header.hash();
+		assert!(check_header::<TestBlockT>(10, header, hash, &epoch, &config).is_err());
+
+		// and secondary claims are rejected when secondary slots are disabled.
+		let config = test_config((0, 1), false);
+		let header = sealed_header(5, &pair, (1, None));
+		let hash = header.hash();
+		assert!(check_header::<TestBlockT>(10, header, hash, &epoch, &config).is_err());
+	}
+
+	#[test]
+	fn headers_from_future_slots_are_deferred() {
+		let pair = Keyring::Alice.pair();
+		let epoch = test_epoch(vec![Keyring::Alice.into()]);
+		let config = test_config((0, 1), true);
+
+		let header = sealed_header(20, &pair, (0, None));
+		let hash = header.hash();
+		match check_header::<TestBlockT>(10, header, hash, &epoch, &config) {
+			Ok(CheckedHeader::Deferred(_, slot)) => assert_eq!(slot, 20),
+			_ => panic!("header should be deferred"),
+		}
+	}
+}
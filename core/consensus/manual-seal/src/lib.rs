@@ -0,0 +1,498 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manual and instant sealing for development chains.
+//!
+//! Blocks are authored without any slot or proof: with manual sealing, a
+//! block is authored whenever an `EngineCommand` is received, usually through
+//! the `engine_createBlock` RPC; with instant sealing, a block is authored as
+//! soon as the transaction pool has a new ready transaction.
+//!
+//! Sealed blocks carry no seal at all, so they can't be verified by anyone
+//! else: the import queue of this crate refuses all blocks but those sealed
+//! locally, and a manually sealed chain is run by a single node.
+//!
+//! Runtimes with a slot-based module, like Aura, must be given timestamps in
+//! distinct slots however quickly blocks are sealed; register a
+//! `SlotTimestampProvider` with the slot duration for them.
+
+use std::{fmt, sync::Arc, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use consensus_common::{
+	BlockImport, BlockOrigin, Environment, ForkChoiceStrategy, ImportBlock, Proposer,
+};
+use consensus_common::import_queue::{
+	Verifier, BasicQueue, SharedBlockImport, SharedJustificationImport,
+};
+use client::{ChainHead, Client, CallExecutor};
+use client::backend::Backend;
+use client::blockchain::HeaderBackend;
+use parity_codec::Decode;
+use primitives::{Blake2Hasher, twox_128, storage::StorageKey};
+use runtime_primitives::{generic::BlockId, Justification};
+use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, AuthorityIdFor};
+use transaction_pool::txpool::{self, Pool as TransactionPool};
+use inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData, RuntimeString};
+use futures::{Future, IntoFuture, Stream, sync::{mpsc, oneshot}};
+use log::{info, warn};
+use parking_lot::Mutex;
+
+pub mod rpc;
+
+/// The time given to the proposer to build a block.
+const MAX_PROPOSAL_DURATION: u64 = 10;
+
+/// Error of the manual seal engine.
+#[derive(Debug)]
+pub enum Error {
+	/// The requested parent block is unknown.
+	UnknownParent(String),
+	/// The proposer failed to build a block.
+	Proposing(String),
+	/// The block failed to import.
+	Import(String),
+	/// Any other error.
+	Other(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::UnknownParent(e) => write!(f, "Unknown parent block: {}", e),
+			Error::Proposing(e) => write!(f, "Block proposing error: {}", e),
+			Error::Import(e) => write!(f, "Block import error: {}", e),
+			Error::Other(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// A command sent to the manual seal engine.
+pub enum EngineCommand<Hash> {
+	/// Author a new block.
+	SealNewBlock {
+		/// Author the block even if the transaction pool has no ready
+		/// transaction.
+		create_empty: bool,
+		/// Finalize the block once imported.
+		finalize: bool,
+		/// The parent of the block. The best block if `None`.
+		parent_hash: Option<Hash>,
+		/// Receives the hash of the new block, or the reason why it couldn't
+		/// be authored.
+		sender: Option<oneshot::Sender<Result<Hash, Error>>>,
+	},
+}
+
+/// Sending half of the engine command channel.
+pub type EngineCommandSender<Hash> = mpsc::UnboundedSender<EngineCommand<Hash>>;
+
+/// Receiving half of the engine command channel.
+pub type EngineCommandStream<Hash> = mpsc::UnboundedReceiver<EngineCommand<Hash>>;
+
+/// Create a new engine command channel.
+pub fn channel<Hash>() -> (EngineCommandSender<Hash>, EngineCommandStream<Hash>) {
+	mpsc::unbounded()
+}
+
+/// A verifier which accepts locally sealed blocks only, as blocks of manually
+/// sealed chains carry no seal that could be checked.
+pub struct ManualSealVerifier;
+
+impl<B: BlockT> Verifier<B> for ManualSealVerifier {
+	fn verify(
+		&self,
+		origin: BlockOrigin,
+		header: B::Header,
+		justification: Option<Justification>,
+		body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(ImportBlock<B>, Option<Vec<AuthorityIdFor<B>>>), String> {
+		if origin != BlockOrigin::Own {
+			return Err(format!(
+				"Refusing block {:?} from {:?}: manually sealed blocks can't be verified",
+				header.hash(),
+				origin,
+			))
+		}
+
+		let import_block = ImportBlock {
+			origin,
+			header,
+			justification,
+			post_digests: Vec::new(),
+			body,
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		Ok((import_block, None))
+	}
+}
+
+/// The manual seal import queue type.
+pub type ManualSealImportQueue<B> = BasicQueue<B>;
+
+/// Start an import queue which accepts manually sealed blocks.
+pub fn import_queue<B: BlockT>(
+	block_import: SharedBlockImport<B>,
+	justification_import: Option<SharedJustificationImport<B>>,
+) -> ManualSealImportQueue<B> {
+	BasicQueue::new(Arc::new(ManualSealVerifier), block_import, justification_import)
+}
+
+/// Provides the timestamp inherent, moving it forward by at least `increment`
+/// seconds from one block to the next.
+///
+/// This keeps blocks sealed in quick succession in distinct slots of
+/// slot-based runtime modules, at the cost of timestamps running ahead of the
+/// wall clock while blocks are sealed faster than one per slot.
+pub struct SlotTimestampProvider {
+	last: Mutex<u64>,
+	increment: u64,
+}
+
+impl SlotTimestampProvider {
+	/// Create a new provider, continuing from the timestamp `last` of the best
+	/// block. Zero starts from the current time.
+	pub fn new(last: u64, increment: u64) -> Self {
+		SlotTimestampProvider {
+			last: Mutex::new(last),
+			increment,
+		}
+	}
+
+	/// Create a new provider, continuing from the timestamp of the best block
+	/// of `client` as stored by the timestamp module.
+	pub fn from_best_block<B, E, Block, RA>(
+		client: &Client<B, E, Block, RA>,
+		increment: u64,
+	) -> client::error::Result<Self> where
+		B: Backend<Block, Blake2Hasher>,
+		E: CallExecutor<Block, Blake2Hasher>,
+		Block: BlockT,
+	{
+		let best_hash = client.info()?.chain.best_hash;
+		let key = StorageKey(twox_128(b"Timestamp Now").to_vec());
+		let last = client.storage(&BlockId::Hash(best_hash), &key)?
+			.and_then(|data| u64::decode(&mut &data.0[..]))
+			.unwrap_or(0);
+
+		Ok(Self::new(last, increment))
+	}
+}
+
+impl ProvideInherentData for SlotTimestampProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&srml_timestamp::INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), RuntimeString> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|_| RuntimeString::from("Current time is before unix epoch"))?
+			.as_secs();
+
+		let mut last = self.last.lock();
+		let timestamp: srml_timestamp::InherentType = if *last == 0 {
+			now
+		} else {
+			now.max(*last + self.increment)
+		};
+		*last = timestamp;
+
+		inherent_data.put_data(srml_timestamp::INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		srml_timestamp::InherentError::try_from(&srml_timestamp::INHERENT_IDENTIFIER, error)
+			.map(|e| format!("{:?}", e))
+	}
+}
+
+/// Register the timestamp inherent data provider, if not registered already.
+fn register_timestamp_inherent_data_provider(
+	inherent_data_providers: &InherentDataProviders,
+) -> Result<(), Error> {
+	if !inherent_data_providers.has_provider(&srml_timestamp::INHERENT_IDENTIFIER) {
+		inherent_data_providers
+			.register_provider(srml_timestamp::InherentDataProvider)
+			.map_err(|e| Error::Other(e.into()))
+	} else {
+		Ok(())
+	}
+}
+
+/// Author a new block according to the given command parameters, and import
+/// it. Returns `None` if the block was skipped because there was nothing to
+/// include.
+fn seal_new_block<B, C, I, E, A>(
+	block_import: &I,
+	client: &C,
+	env: &E,
+	pool: &TransactionPool<A>,
+	inherent_data_providers: &InherentDataProviders,
+	create_empty: bool,
+	finalize: bool,
+	parent_hash: Option<B::Hash>,
+) -> Result<Option<B::Hash>, Error> where
+	B: BlockT,
+	C: HeaderBackend<B> + ChainHead<B>,
+	I: BlockImport<B>,
+	I::Error: fmt::Debug,
+	E: Environment<B>,
+	E::Error: fmt::Debug,
+	A: txpool::ChainApi<Block=B>,
+{
+	if !create_empty && pool.status().ready == 0 {
+		return Ok(None)
+	}
+
+	let parent = match parent_hash {
+		Some(hash) => client.header(BlockId::Hash(hash))
+			.map_err(|e| Error::Other(format!("{:?}", e)))?
+			.ok_or_else(|| Error::UnknownParent(format!("{:?}", hash)))?,
+		None => client.best_block_header()
+			.map_err(|e| Error::Other(format!("{:?}", e)))?,
+	};
+
+	let proposer = env.init(&parent, &[])
+		.map_err(|e| Error::Proposing(format!("{:?}", e)))?;
+	let inherent_data = inherent_data_providers.create_inherent_data()
+		.map_err(|e| Error::Other(String::from(e)))?;
+	let block = proposer.propose(inherent_data, Duration::from_secs(MAX_PROPOSAL_DURATION))
+		.into_future()
+		.wait()
+		.map_err(|e| Error::Proposing(format!("{:?}", e)))?;
+
+	let (header, body) = block.deconstruct();
+	let hash = header.hash();
+	let import_block = ImportBlock {
+		origin: BlockOrigin::Own,
+		header,
+		justification: None,
+		post_digests: Vec::new(),
+		body: Some(body),
+		finalized: finalize,
+		auxiliary: Vec::new(),
+		fork_choice: ForkChoiceStrategy::LongestChain,
+	};
+
+	block_import.import_block(import_block, None)
+		.map_err(|e| Error::Import(format!("{:?}", e)))?;
+
+	info!(target: "manual-seal", "Sealed block {:?} on top of {:?}", hash, parent.hash());
+
+	Ok(Some(hash))
+}
+
+/// Run the manual seal engine: a new block is authored and imported for every
+/// command received on `commands`.
+pub fn run_manual_seal<B, C, I, E, A, S>(
+	block_import: Arc<I>,
+	client: Arc<C>,
+	env: E,
+	pool: Arc<TransactionPool<A>>,
+	commands: S,
+	inherent_data_providers: InherentDataProviders,
+) -> impl Future<Item=(), Error=()> where
+	B: BlockT,
+	C: HeaderBackend<B> + ChainHead<B>,
+	I: BlockImport<B>,
+	I::Error: fmt::Debug,
+	E: Environment<B>,
+	E::Error: fmt::Debug,
+	A: txpool::ChainApi<Block=B>,
+	S: Stream<Item=EngineCommand<B::Hash>, Error=()>,
+{
+	if let Err(e) = register_timestamp_inherent_data_provider(&inherent_data_providers) {
+		warn!(target: "manual-seal", "Registering inherent data provider for timestamp failed: {}", e);
+	}
+
+	commands.for_each(move |command| {
+		match command {
+			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
+				let res = seal_new_block(
+					&*block_import,
+					&*client,
+					&env,
+					&*pool,
+					&inherent_data_providers,
+					create_empty,
+					finalize,
+					parent_hash,
+				);
+
+				match sender {
+					Some(sender) => {
+						let res = res.and_then(|hash| hash.ok_or_else(|| {
+							Error::Other("No block was authored".into())
+						}));
+						let _ = sender.send(res);
+					},
+					None => if let Err(e) = res {
+						warn!(target: "manual-seal", "Failed to seal block: {}", e);
+					},
+				}
+			}
+		}
+
+		Ok(())
+	})
+}
+
+/// Run the instant seal engine: a new block is authored on top of the best
+/// block whenever the transaction pool has a new ready transaction.
+pub fn run_instant_seal<B, C, I, E, A>(
+	block_import: Arc<I>,
+	client: Arc<C>,
+	env: E,
+	pool: Arc<TransactionPool<A>>,
+	inherent_data_providers: InherentDataProviders,
+) -> impl Future<Item=(), Error=()> where
+	B: BlockT,
+	C: HeaderBackend<B> + ChainHead<B>,
+	I: BlockImport<B>,
+	I::Error: fmt::Debug,
+	E: Environment<B>,
+	E::Error: fmt::Debug,
+	A: txpool::ChainApi<Block=B>,
+{
+	let commands = pool.import_notification_stream()
+		.map(|_| EngineCommand::SealNewBlock {
+			create_empty: false,
+			finalize: false,
+			parent_hash: None,
+			sender: None,
+		});
+
+	run_manual_seal(block_import, client, env, pool, commands, inherent_data_providers)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basic_authorship::ProposerFactory;
+	use test_client::{self, runtime::{Extrinsic, Transfer}, AccountKeyring};
+	use parity_codec::Encode;
+
+	fn extrinsic(nonce: u64) -> Extrinsic {
+		let tx = Transfer {
+			amount: Default::default(),
+			nonce,
+			from: AccountKeyring::Alice.into(),
+			to: Default::default(),
+		};
+		let signature = AccountKeyring::from_public(&tx.from).unwrap().sign(&tx.encode()).into();
+		Extrinsic::Transfer(tx, signature)
+	}
+
+	#[test]
+	fn manual_seal_authors_blocks_on_command() {
+		let client = Arc::new(test_client::new());
+		let pool = Arc::new(TransactionPool::new(Default::default(), transaction_pool::ChainApi::new(client.clone())));
+		let env = ProposerFactory {
+			client: client.clone(),
+			transaction_pool: pool.clone(),
+			inherents_pool: Default::default(),
+		};
+		let genesis_hash = client.info().unwrap().chain.best_hash;
+
+		let (sender, stream) = channel();
+		let (first_sender, first_receiver) = oneshot::channel();
+		let (second_sender, second_receiver) = oneshot::channel();
+		sender.unbounded_send(EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(first_sender),
+		}).unwrap();
+		sender.unbounded_send(EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: true,
+			parent_hash: Some(genesis_hash),
+			sender: Some(second_sender),
+		}).unwrap();
+		drop(sender);
+
+		run_manual_seal(client.clone(), client.clone(), env, pool, stream, Default::default())
+			.wait()
+			.unwrap();
+
+		let first = first_receiver.wait().unwrap().unwrap();
+		let second = second_receiver.wait().unwrap().unwrap();
+		assert_ne!(first, second);
+
+		let info = client.info().unwrap().chain;
+		assert_eq!(info.best_hash, first);
+		assert_eq!(info.finalized_hash, second);
+		assert_eq!(*client.header(&BlockId::Hash(second)).unwrap().unwrap().parent_hash(), genesis_hash);
+	}
+
+	#[test]
+	fn slot_timestamps_are_at_least_one_slot_apart() {
+		let provider = SlotTimestampProvider::new(0, 10);
+		let timestamp = || {
+			let mut data = InherentData::new();
+			provider.provide_inherent_data(&mut data).unwrap();
+			data.get_data::<u64>(&srml_timestamp::INHERENT_IDENTIFIER).unwrap().unwrap()
+		};
+
+		let first = timestamp();
+		assert_eq!(timestamp(), first + 10);
+		assert_eq!(timestamp(), first + 20);
+
+		let provider = SlotTimestampProvider::new(first + 1000, 10);
+		let mut data = InherentData::new();
+		provider.provide_inherent_data(&mut data).unwrap();
+		assert_eq!(data.get_data::<u64>(&srml_timestamp::INHERENT_IDENTIFIER).unwrap(), Some(first + 1010));
+	}
+
+	#[test]
+	fn verifier_refuses_blocks_from_the_network() {
+		let client = test_client::new();
+		let header = client.header(&BlockId::number(0)).unwrap().unwrap();
+
+		assert!(ManualSealVerifier.verify(BlockOrigin::NetworkBroadcast, header.clone(), None, None).is_err());
+		assert!(ManualSealVerifier.verify(BlockOrigin::NetworkInitialSync, header.clone(), None, None).is_err());
+		assert!(ManualSealVerifier.verify(BlockOrigin::Own, header, None, None).is_ok());
+	}
+
+	#[test]
+	fn instant_seal_skips_blocks_without_ready_transactions() {
+		let client = Arc::new(test_client::new());
+		let pool = Arc::new(TransactionPool::new(Default::default(), transaction_pool::ChainApi::new(client.clone())));
+		let env = ProposerFactory {
+			client: client.clone(),
+			transaction_pool: pool.clone(),
+			inherents_pool: Default::default(),
+		};
+
+		let hash = seal_new_block(
+			&*client, &*client, &env, &*pool, &Default::default(), false, false, None,
+		).unwrap();
+		assert!(hash.is_none());
+
+		pool.submit_one(&BlockId::number(0), extrinsic(0)).unwrap();
+		let hash = seal_new_block(
+			&*client, &*client, &env, &*pool, &Default::default(), false, false, None,
+		).unwrap().unwrap();
+
+		assert_eq!(client.info().unwrap().chain.best_hash, hash);
+		let block = client.block(&BlockId::Hash(hash)).unwrap().unwrap().block;
+		assert_eq!(block.extrinsics().len(), 1);
+	}
+}
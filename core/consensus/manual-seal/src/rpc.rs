@@ -0,0 +1,99 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface of the manual seal engine.
+
+use futures::{Future, sync::oneshot};
+use jsonrpc_core::{self as rpc, BoxFuture};
+use jsonrpc_derive::rpc;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{EngineCommand, EngineCommandSender, Error};
+
+/// Base code for all manual seal errors.
+const BASE_ERROR: i64 = 6000;
+/// The requested parent block is unknown.
+const UNKNOWN_PARENT: i64 = BASE_ERROR + 1;
+/// The block could not be authored or imported.
+const SEALING_FAILED: i64 = BASE_ERROR + 2;
+/// The manual seal engine is not running.
+const ENGINE_STOPPED: i64 = BASE_ERROR + 3;
+
+impl From<Error> for rpc::Error {
+	fn from(e: Error) -> Self {
+		let code = match e {
+			Error::UnknownParent(_) => UNKNOWN_PARENT,
+			_ => SEALING_FAILED,
+		};
+
+		rpc::Error {
+			code: rpc::ErrorCode::ServerError(code),
+			message: e.to_string(),
+			data: None,
+		}
+	}
+}
+
+fn engine_stopped() -> rpc::Error {
+	rpc::Error {
+		code: rpc::ErrorCode::ServerError(ENGINE_STOPPED),
+		message: "Manual seal engine is not running".into(),
+		data: None,
+	}
+}
+
+/// Manual seal RPC API.
+#[rpc]
+pub trait ManualSealApi<Hash> {
+	/// Author a new block on top of `parent`, or of the best block if `None`,
+	/// and finalize it if `finalize` is set. Returns the hash of the block.
+	#[rpc(name = "engine_createBlock")]
+	fn create_block(&self, finalize: bool, parent: Option<Hash>) -> BoxFuture<Hash>;
+}
+
+/// Manual seal RPC API implementation, forwarding requests to the engine.
+pub struct ManualSeal<Hash> {
+	commands: EngineCommandSender<Hash>,
+}
+
+impl<Hash> ManualSeal<Hash> {
+	/// Create new instance of the manual seal API.
+	pub fn new(commands: EngineCommandSender<Hash>) -> Self {
+		ManualSeal { commands }
+	}
+}
+
+impl<Hash> ManualSealApi<Hash> for ManualSeal<Hash> where
+	Hash: Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+	fn create_block(&self, finalize: bool, parent: Option<Hash>) -> BoxFuture<Hash> {
+		let (sender, receiver) = oneshot::channel();
+		let command = EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize,
+			parent_hash: parent,
+			sender: Some(sender),
+		};
+
+		if self.commands.unbounded_send(command).is_err() {
+			return Box::new(futures::future::err(engine_stopped()))
+		}
+
+		Box::new(receiver
+			.map_err(|_| engine_stopped())
+			.and_then(|res| res.map_err(Into::into)))
+	}
+}
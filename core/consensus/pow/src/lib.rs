@@ -0,0 +1,569 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proof of work consensus for substrate.
+//!
+//! There is no validator set: anyone may author a block by finding a seal
+//! that satisfies the difficulty at its parent. The actual work is defined
+//! by an implementation of `PowAlgorithm`, which is passed to the verifier,
+//! the block import and the mining worker of this crate.
+//!
+//! The best chain is the one with the largest total difficulty. The total
+//! difficulty of every block is kept in the auxiliary store of the client,
+//! under the `POW_AUX_PREFIX` prefix. Algorithms that need to store more
+//! (e.g. the difficulty adjustment state) should use a prefix of their own.
+
+use std::{sync::Arc, thread, time::Duration};
+
+use parity_codec::{Encode, Decode};
+use consensus_common::{
+	BlockImport, BlockOrigin, Environment, ErrorKind, ForkChoiceStrategy, ImportBlock,
+	ImportResult, Proposer, SyncOracle,
+};
+use consensus_common::import_queue::{
+	Verifier, BasicQueue, SharedBlockImport, SharedJustificationImport,
+};
+use client::ChainHead;
+use client::backend::AuxStore;
+use client::block_builder::api::BlockBuilder as BlockBuilderApi;
+use client::runtime_api::ApiExt;
+use runtime_primitives::{generic, generic::BlockId, Justification};
+use runtime_primitives::traits::{
+	Block as BlockT, Header as HeaderT, Digest, DigestItemFor, AuthorityIdFor, ProvideRuntimeApi,
+};
+use primitives::U256;
+use inherents::{InherentDataProviders, InherentData};
+use srml_timestamp::{TimestampInherentData, InherentError as TIError};
+use futures::{Future, IntoFuture};
+use log::{debug, error, info, warn};
+
+mod simple;
+
+pub use simple::SimpleAlgorithm;
+
+/// Auxiliary storage prefix of the PoW engine.
+pub const POW_AUX_PREFIX: [u8; 4] = *b"PoW:";
+
+/// Identifier of PoW seals in digest items.
+pub const POW_ENGINE_ID: [u8; 4] = *b"pow_";
+
+/// Type of difficulty.
+pub type Difficulty = U256;
+
+/// Type of seal. Its content is only meaningful to the `PowAlgorithm`.
+pub type Seal = Vec<u8>;
+
+/// Get the auxiliary storage key used by the PoW engine for the given block.
+fn aux_key<T: AsRef<[u8]>>(hash: &T) -> Vec<u8> {
+	POW_AUX_PREFIX.iter().chain(hash.as_ref()).cloned().collect()
+}
+
+/// Auxiliary data kept for every block imported through `PowBlockImport`.
+#[derive(Clone, Debug, Default, Encode, Decode)]
+pub struct PowAux {
+	/// Difficulty of the block.
+	pub difficulty: Difficulty,
+	/// Total difficulty of the chain up to and including the block.
+	pub total_difficulty: Difficulty,
+}
+
+impl PowAux {
+	/// Read the auxiliary data of the given block. Blocks which were not
+	/// imported through `PowBlockImport`, such as the genesis block, have zero
+	/// difficulty.
+	pub fn read<C: AuxStore, B: BlockT>(client: &C, hash: &B::Hash) -> Result<Self, String> {
+		let key = aux_key(hash);
+
+		match client.get_aux(&key).map_err(|e| format!("{:?}", e))? {
+			Some(bytes) => PowAux::decode(&mut &bytes[..])
+				.ok_or_else(|| format!("PoW auxiliary data of {:?} kept in invalid format", hash)),
+			None => Ok(PowAux::default()),
+		}
+	}
+}
+
+/// Algorithm used for proof of work.
+pub trait PowAlgorithm<B: BlockT> {
+	/// Get the difficulty of a block built on top of the given parent.
+	fn difficulty(&self, parent: &BlockId<B>) -> Result<Difficulty, String>;
+
+	/// Verify that the seal of a block with the given pre-header hash
+	/// satisfies the difficulty.
+	fn verify(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		seal: &Seal,
+		difficulty: Difficulty,
+	) -> Result<bool, String>;
+
+	/// Try `round` times to find a seal satisfying the difficulty for a block
+	/// with the given pre-header hash. Returns `None` if no seal was found.
+	fn mine(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		difficulty: Difficulty,
+		round: u32,
+	) -> Result<Option<Seal>, String>;
+}
+
+/// A digest item which is usable with PoW consensus.
+pub trait CompatibleDigestItem: Sized {
+	/// Construct a digest item which contains a PoW seal.
+	fn pow_seal(seal: Seal) -> Self;
+
+	/// If this item is a PoW seal, return the seal.
+	fn as_pow_seal(&self) -> Option<Seal>;
+}
+
+impl<Hash, AuthorityId, SealSignature> CompatibleDigestItem for generic::DigestItem<Hash, AuthorityId, SealSignature> {
+	fn pow_seal(seal: Seal) -> Self {
+		generic::DigestItem::Other((POW_ENGINE_ID, seal).encode())
+	}
+
+	fn as_pow_seal(&self) -> Option<Seal> {
+		self.as_other()
+			.and_then(|data| <([u8; 4], Seal)>::decode(&mut &data[..]))
+			.and_then(|(id, seal)| if id == POW_ENGINE_ID { Some(seal) } else { None })
+	}
+}
+
+/// A verifier for PoW blocks.
+pub struct PowVerifier<C, Algorithm> {
+	client: Arc<C>,
+	algorithm: Algorithm,
+	inherent_data_providers: InherentDataProviders,
+}
+
+impl<C, Algorithm> PowVerifier<C, Algorithm> {
+	/// Remove the seal from the header and check it satisfies the difficulty.
+	/// Returns the pre-header and the seal digest item.
+	fn check_header<B: BlockT>(
+		&self,
+		mut header: B::Header,
+		parent: BlockId<B>,
+	) -> Result<(B::Header, DigestItemFor<B>), String> where
+		Algorithm: PowAlgorithm<B>,
+		DigestItemFor<B>: CompatibleDigestItem,
+	{
+		let hash = header.hash();
+
+		let (seal, inner_seal) = match header.digest_mut().pop() {
+			Some(seal) => match seal.as_pow_seal() {
+				Some(inner_seal) => (seal, inner_seal),
+				None => return Err(format!("Header {:?} has no PoW seal", hash)),
+			},
+			None => return Err(format!("Header {:?} is unsealed", hash)),
+		};
+
+		let pre_hash = header.hash();
+		let difficulty = self.algorithm.difficulty(&parent)?;
+
+		if !self.algorithm.verify(&parent, &pre_hash, &inner_seal, difficulty)? {
+			return Err(format!("Header {:?} has an invalid PoW seal", hash));
+		}
+
+		Ok((header, seal))
+	}
+
+	fn check_inherents<B: BlockT>(
+		&self,
+		block: B,
+		block_id: BlockId<B>,
+		inherent_data: InherentData,
+		timestamp_now: u64,
+	) -> Result<(), String>
+		where C: ProvideRuntimeApi, C::Api: BlockBuilderApi<B>
+	{
+		const MAX_TIMESTAMP_DRIFT_SECS: u64 = 60;
+
+		let inherent_res = self.client.runtime_api().check_inherents(
+			&block_id,
+			block,
+			inherent_data,
+		).map_err(|e| format!("{:?}", e))?;
+
+		if !inherent_res.ok() {
+			inherent_res
+				.into_errors()
+				.try_for_each(|(i, e)| match TIError::try_from(&i, &e) {
+					Some(TIError::ValidAtTimestamp(timestamp)) => {
+						if timestamp > timestamp_now + MAX_TIMESTAMP_DRIFT_SECS {
+							return Err("Rejecting block too far in future".into());
+						}
+
+						Ok(())
+					},
+					Some(TIError::Other(e)) => Err(e.into()),
+					None => Err(self.inherent_data_providers.error_to_string(&i, &e)),
+				})
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<B: BlockT, C, Algorithm> Verifier<B> for PowVerifier<C, Algorithm> where
+	C: ProvideRuntimeApi + Send + Sync,
+	C::Api: BlockBuilderApi<B>,
+	Algorithm: PowAlgorithm<B> + Send + Sync,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	fn verify(
+		&self,
+		origin: BlockOrigin,
+		header: B::Header,
+		justification: Option<Justification>,
+		mut body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(ImportBlock<B>, Option<Vec<AuthorityIdFor<B>>>), String> {
+		let inherent_data = self.inherent_data_providers.create_inherent_data().map_err(String::from)?;
+		let timestamp_now = inherent_data.timestamp_inherent_data().map_err(String::from)?;
+
+		let parent_hash = *header.parent_hash();
+		let (checked_header, seal) = self.check_header::<B>(header, BlockId::Hash(parent_hash))?;
+
+		if let Some(inner_body) = body.take() {
+			let block = B::new(checked_header.clone(), inner_body);
+
+			// skip the inherents verification if the runtime API is old.
+			if self.client
+				.runtime_api()
+				.has_api_with::<BlockBuilderApi<B>, _>(&BlockId::Hash(parent_hash), |v| v >= 2)
+				.map_err(|e| format!("{:?}", e))?
+			{
+				self.check_inherents(
+					block.clone(),
+					BlockId::Hash(parent_hash),
+					inherent_data,
+					timestamp_now,
+				)?;
+			}
+
+			let (_, inner_body) = block.deconstruct();
+			body = Some(inner_body);
+		}
+
+		let import_block = ImportBlock {
+			origin,
+			header: checked_header,
+			post_digests: vec![seal],
+			body,
+			finalized: false,
+			justification,
+			auxiliary: Vec::new(),
+			// replaced by `PowBlockImport`.
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		Ok((import_block, None))
+	}
+}
+
+/// A block import which keeps track of the total difficulty of blocks and
+/// chooses the chain with the largest total difficulty as the best chain.
+pub struct PowBlockImport<I, C, Algorithm> {
+	inner: Arc<I>,
+	client: Arc<C>,
+	algorithm: Algorithm,
+}
+
+impl<I, C, Algorithm> PowBlockImport<I, C, Algorithm> {
+	/// Create a new PoW block import wrapping the given block import.
+	pub fn new(inner: Arc<I>, client: Arc<C>, algorithm: Algorithm) -> Self {
+		PowBlockImport { inner, client, algorithm }
+	}
+}
+
+impl<B, I, C, Algorithm> BlockImport<B> for PowBlockImport<I, C, Algorithm> where
+	B: BlockT,
+	I: BlockImport<B>,
+	C: AuxStore + ChainHead<B>,
+	Algorithm: PowAlgorithm<B>,
+{
+	type Error = consensus_common::Error;
+
+	fn check_block(
+		&self,
+		hash: B::Hash,
+		parent_hash: B::Hash,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(hash, parent_hash)
+			.map_err(|e| ErrorKind::ClientImport(e.to_string()).into())
+	}
+
+	fn import_block(
+		&self,
+		mut block: ImportBlock<B>,
+		new_authorities: Option<Vec<AuthorityIdFor<B>>>,
+	) -> Result<ImportResult, Self::Error> {
+		let parent_hash = *block.header.parent_hash();
+		let best_hash = self.client.best_block_header()
+			.map_err(|e| ErrorKind::ClientImport(format!("{:?}", e)))?
+			.hash();
+
+		let best_aux = PowAux::read::<_, B>(&*self.client, &best_hash)
+			.map_err(ErrorKind::ClientImport)?;
+		let mut aux = PowAux::read::<_, B>(&*self.client, &parent_hash)
+			.map_err(ErrorKind::ClientImport)?;
+
+		aux.difficulty = self.algorithm.difficulty(&BlockId::Hash(parent_hash))
+			.map_err(ErrorKind::ClientImport)?;
+		aux.total_difficulty = aux.total_difficulty.saturating_add(aux.difficulty);
+
+		let key = aux_key(&block.post_header().hash());
+		block.auxiliary.push((key, Some(aux.encode())));
+		block.fork_choice = ForkChoiceStrategy::Custom(aux.total_difficulty > best_aux.total_difficulty);
+
+		self.inner.import_block(block, new_authorities)
+			.map_err(|e| ErrorKind::ClientImport(e.to_string()).into())
+	}
+}
+
+/// The PoW import queue type.
+pub type PowImportQueue<B> = BasicQueue<B>;
+
+/// Register the timestamp inherent data provider, if not registered already.
+fn register_pow_inherent_data_provider(
+	inherent_data_providers: &InherentDataProviders,
+) -> Result<(), consensus_common::Error> {
+	if !inherent_data_providers.has_provider(&srml_timestamp::INHERENT_IDENTIFIER) {
+		inherent_data_providers
+			.register_provider(srml_timestamp::InherentDataProvider)
+			.map(Into::into)
+			.map_err(|e| ErrorKind::InherentData(e.into()).into())
+	} else {
+		Ok(())
+	}
+}
+
+/// Start an import queue for the PoW consensus algorithm. The given block
+/// import should be a `PowBlockImport`.
+pub fn import_queue<B, C, Algorithm>(
+	block_import: SharedBlockImport<B>,
+	justification_import: Option<SharedJustificationImport<B>>,
+	client: Arc<C>,
+	algorithm: Algorithm,
+	inherent_data_providers: InherentDataProviders,
+) -> Result<PowImportQueue<B>, consensus_common::Error> where
+	B: BlockT,
+	C: 'static + ProvideRuntimeApi + Send + Sync,
+	C::Api: BlockBuilderApi<B>,
+	Algorithm: 'static + PowAlgorithm<B> + Send + Sync,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	register_pow_inherent_data_provider(&inherent_data_providers)?;
+
+	let verifier = Arc::new(
+		PowVerifier {
+			client,
+			algorithm,
+			inherent_data_providers,
+		}
+	);
+	Ok(BasicQueue::new(verifier, block_import, justification_import))
+}
+
+/// Start the mining worker in a separate thread. Mining is CPU-bound, so it
+/// can't be run as a future.
+///
+/// `round` is the number of seals tried before checking whether a new best
+/// block has been imported, in which case mining restarts on top of it.
+/// `build_time` is the time given to the proposer to build a block.
+pub fn start_mine<B, C, I, E, Algorithm, SO>(
+	block_import: Arc<I>,
+	client: Arc<C>,
+	algorithm: Algorithm,
+	env: E,
+	round: u32,
+	sync_oracle: SO,
+	build_time: Duration,
+	inherent_data_providers: InherentDataProviders,
+) where
+	B: BlockT,
+	C: ChainHead<B> + Send + Sync + 'static,
+	I: BlockImport<B> + Send + Sync + 'static,
+	E: Environment<B> + Send + Sync + 'static,
+	E::Error: ::std::fmt::Debug,
+	Algorithm: PowAlgorithm<B> + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + 'static,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	if let Err(e) = register_pow_inherent_data_provider(&inherent_data_providers) {
+		warn!(target: "pow", "Registering inherent data provider for timestamp failed: {:?}", e);
+	}
+
+	thread::spawn(move || {
+		loop {
+			if let Err(e) = mine_loop(
+				&*block_import,
+				&*client,
+				&algorithm,
+				&env,
+				round,
+				&sync_oracle,
+				build_time,
+				&inherent_data_providers,
+			) {
+				error!(target: "pow", "Mining block failed with {:?}. Restarting in 1 second.", e);
+			}
+
+			thread::sleep(Duration::from_secs(1));
+		}
+	});
+}
+
+fn mine_loop<B, C, I, E, Algorithm, SO>(
+	block_import: &I,
+	client: &C,
+	algorithm: &Algorithm,
+	env: &E,
+	round: u32,
+	sync_oracle: &SO,
+	build_time: Duration,
+	inherent_data_providers: &InherentDataProviders,
+) -> Result<(), String> where
+	B: BlockT,
+	C: ChainHead<B>,
+	I: BlockImport<B>,
+	E: Environment<B>,
+	E::Error: ::std::fmt::Debug,
+	Algorithm: PowAlgorithm<B>,
+	SO: SyncOracle,
+	DigestItemFor<B>: CompatibleDigestItem,
+{
+	'outer: loop {
+		if sync_oracle.is_major_syncing() {
+			debug!(target: "pow", "Skipping proposal due to sync.");
+			thread::sleep(Duration::from_secs(1));
+			continue 'outer
+		}
+
+		let best_header = client.best_block_header()
+			.map_err(|e| format!("Fetching best header failed: {:?}", e))?;
+		let best_hash = best_header.hash();
+
+		let proposer = env.init(&best_header, &[])
+			.map_err(|e| format!("Creating proposer failed: {:?}", e))?;
+		let inherent_data = inherent_data_providers.create_inherent_data()
+			.map_err(String::from)?;
+		let block = proposer.propose(inherent_data, build_time)
+			.into_future()
+			.wait()
+			.map_err(|e| format!("Block proposing error: {:?}", e))?;
+
+		let (header, body) = block.deconstruct();
+		let pre_hash = header.hash();
+		let difficulty = algorithm.difficulty(&BlockId::Hash(best_hash))?;
+
+		let seal = loop {
+			if let Some(seal) = algorithm.mine(&BlockId::Hash(best_hash), &pre_hash, difficulty, round)? {
+				break seal
+			}
+
+			let new_best_hash = client.best_block_header()
+				.map_err(|e| format!("Fetching best header failed: {:?}", e))?
+				.hash();
+			if new_best_hash != best_hash {
+				continue 'outer
+			}
+		};
+
+		let import_block = ImportBlock {
+			origin: BlockOrigin::Own,
+			header,
+			justification: None,
+			post_digests: vec![<DigestItemFor<B> as CompatibleDigestItem>::pow_seal(seal)],
+			body: Some(body),
+			finalized: false,
+			auxiliary: Vec::new(),
+			// replaced by `PowBlockImport`.
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		info!(target: "pow", "Mined block on top of {:?}; pre-hash {:?}", best_hash, pre_hash);
+
+		block_import.import_block(import_block, None)
+			.map_err(|e| format!("Error with block built on {:?}: {:?}", best_hash, e))?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::H256;
+	use test_client::{self, runtime::{Block as TestBlock, DigestItem}};
+
+	#[test]
+	fn pow_seal_digest_roundtrip() {
+		let item = DigestItem::pow_seal(vec![1, 2, 3]);
+		assert_eq!(item.as_pow_seal(), Some(vec![1, 2, 3]));
+
+		let other = DigestItem::Other(vec![1, 2, 3]);
+		assert_eq!(other.as_pow_seal(), None);
+	}
+
+	#[test]
+	fn simple_algorithm_mines_verifiable_seals() {
+		let algorithm = SimpleAlgorithm::new(U256::from(1000));
+		let parent = BlockId::<TestBlock>::Number(0);
+		let pre_hash = H256::repeat_byte(7);
+
+		let seal = algorithm.mine(&parent, &pre_hash, U256::from(1000), 100_000)
+			.unwrap()
+			.expect("difficulty is low enough to find a seal");
+
+		assert!(algorithm.verify(&parent, &pre_hash, &seal, U256::from(1000)).unwrap());
+		assert!(!algorithm.verify(&parent, &pre_hash, &seal, U256::max_value()).unwrap());
+		assert!(!algorithm.verify(&parent, &pre_hash, &vec![1, 2, 3], U256::from(1)).unwrap());
+	}
+
+	#[test]
+	fn block_import_tracks_total_difficulty() {
+		let client = Arc::new(test_client::new());
+		let algorithm = SimpleAlgorithm::new(U256::from(2));
+		let block_import = PowBlockImport::new(client.clone(), client.clone(), SimpleAlgorithm::new(U256::from(2)));
+
+		let mut parent_hash = client.info().unwrap().chain.best_hash;
+		for i in 1..3u64 {
+			let block = client.new_block().unwrap().bake().unwrap();
+			let (header, body) = block.deconstruct();
+			let seal = algorithm.mine(&BlockId::Hash(parent_hash), &header.hash(), U256::from(2), 1_000_000)
+				.unwrap()
+				.expect("difficulty is low enough to find a seal");
+
+			let import_block = ImportBlock {
+				origin: BlockOrigin::Own,
+				header,
+				justification: None,
+				post_digests: vec![DigestItem::pow_seal(seal)],
+				body: Some(body),
+				finalized: false,
+				auxiliary: Vec::new(),
+				fork_choice: ForkChoiceStrategy::LongestChain,
+			};
+			let hash = import_block.post_header().hash();
+			block_import.import_block(import_block, None).unwrap();
+
+			let aux = PowAux::read::<_, TestBlock>(&*client, &hash).unwrap();
+			assert_eq!(aux.difficulty, U256::from(2));
+			assert_eq!(aux.total_difficulty, U256::from(2 * i));
+			assert_eq!(client.info().unwrap().chain.best_hash, hash);
+
+			parent_hash = hash;
+		}
+	}
+}
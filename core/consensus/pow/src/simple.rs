@@ -0,0 +1,98 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A simple, fixed-difficulty PoW algorithm, for development and testing.
+//!
+//! The seal is a nonce, and it is valid if the blake2 hash of the pre-header
+//! hash and the nonce, multiplied by the difficulty, does not overflow.
+
+use parity_codec::{Encode, Decode};
+use parking_lot::Mutex;
+use primitives::{H256, U256, blake2_256};
+use runtime_primitives::generic::BlockId;
+use runtime_primitives::traits::Block as BlockT;
+
+use crate::{Difficulty, PowAlgorithm, Seal};
+
+/// Compute the work hash of a pre-header hash and a seal.
+pub(crate) fn compute(pre_hash: &H256, seal: &Seal) -> H256 {
+	H256::from(blake2_256(&(pre_hash, seal).encode()[..]))
+}
+
+/// Check whether the work hash satisfies the difficulty.
+pub(crate) fn check_hash(hash: &H256, difficulty: Difficulty) -> bool {
+	let num = U256::from_big_endian(&hash[..]);
+	let (_, overflowed) = num.overflowing_mul(difficulty);
+
+	!overflowed
+}
+
+/// A PoW algorithm with a fixed difficulty.
+pub struct SimpleAlgorithm {
+	difficulty: Difficulty,
+	nonce: Mutex<u64>,
+}
+
+impl SimpleAlgorithm {
+	/// Create a new algorithm with the given, fixed difficulty.
+	pub fn new(difficulty: Difficulty) -> Self {
+		SimpleAlgorithm {
+			difficulty,
+			nonce: Mutex::new(0),
+		}
+	}
+}
+
+impl<B: BlockT<Hash=H256>> PowAlgorithm<B> for SimpleAlgorithm {
+	fn difficulty(&self, _parent: &BlockId<B>) -> Result<Difficulty, String> {
+		Ok(self.difficulty)
+	}
+
+	fn verify(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &H256,
+		seal: &Seal,
+		difficulty: Difficulty,
+	) -> Result<bool, String> {
+		if u64::decode(&mut &seal[..]).is_none() {
+			return Ok(false)
+		}
+
+		Ok(check_hash(&compute(pre_hash, seal), difficulty))
+	}
+
+	fn mine(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &H256,
+		difficulty: Difficulty,
+		round: u32,
+	) -> Result<Option<Seal>, String> {
+		let mut nonce = self.nonce.lock();
+
+		for _ in 0..round {
+			let seal = nonce.encode();
+			*nonce = nonce.wrapping_add(1);
+
+			if check_hash(&compute(pre_hash, &seal), difficulty) {
+				return Ok(Some(seal))
+			}
+		}
+
+		Ok(None)
+	}
+}
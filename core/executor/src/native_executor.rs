@@ -38,7 +38,8 @@ enum RuntimePreproc {
 	ValidCode(WasmModuleInstanceRef, Option<RuntimeVersion>),
 }
 
-type CacheType = HashMap<[u8; 32], RuntimePreproc>;
+/// Runtimes keyed by the hash of their code and by whether they were prepared for benchmarks.
+type CacheType = HashMap<([u8; 32], bool), RuntimePreproc>;
 
 thread_local! {
 	static RUNTIMES_CACHE: RefCell<CacheType> = RefCell::new(HashMap::new());
@@ -58,7 +59,7 @@ fn fetch_cached_runtime_version<'a, E: Externalities<Blake2Hasher>>(
 		Some(code_hash) => code_hash,
 		None => return Err(ErrorKind::InvalidCode(vec![]).into()),
 	};
-	let maybe_runtime_preproc = cache.borrow_mut().entry(code_hash.into())
+	let maybe_runtime_preproc = cache.borrow_mut().entry((code_hash.into(), wasm_executor.is_benchmarking()))
 		.or_insert_with(|| {
 			let code = match ext.storage(well_known_keys::CODE) {
 				Some(code) => code,
@@ -153,6 +154,15 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			default_heap_pages,
 		}
 	}
+
+	/// Create a new instance whose wasm executor provides the host functions reserved to
+	/// benchmarks. See `WasmExecutor::new_benchmarking`.
+	pub fn new_benchmarking(default_heap_pages: Option<u64>) -> Self {
+		NativeExecutor {
+			fallback: WasmExecutor::new_benchmarking(),
+			..Self::new(default_heap_pages)
+		}
+	}
 }
 
 impl<D: NativeExecutionDispatch> Clone for NativeExecutor<D> {
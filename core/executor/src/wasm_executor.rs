@@ -529,6 +529,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 
 		Ok(())
 	},
+	ext_benchmark_time() -> u64 => {
+		let now = ::std::time::SystemTime::now()
+			.duration_since(::std::time::UNIX_EPOCH)
+			.map_err(|_| UserError("System time is before the unix epoch: wasm"))?;
+		Ok(now.as_secs() * 1_000_000_000 + now.subsec_nanos() as u64)
+	},
 	ext_sandbox_instantiate(
 		dispatch_thunk_idx: usize,
 		wasm_ptr: *const u8,
@@ -644,17 +650,52 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 	=> <'e, E: Externalities<Blake2Hasher> + 'e>
 );
 
+/// Host functions only resolved for runtimes run by a benchmarking executor.
+const BENCHMARKING_FUNCTIONS: &[&str] = &["ext_benchmark_time"];
+
+/// Resolves the host functions of `FunctionExecutor`, leaving out `BENCHMARKING_FUNCTIONS`
+/// unless `benchmarking` is set.
+struct HostFunctions {
+	resolver: &'static ::wasmi::ModuleImportResolver,
+	benchmarking: bool,
+}
+
+impl ::wasmi::ModuleImportResolver for HostFunctions {
+	fn resolve_func(&self, name: &str, signature: &::wasmi::Signature) -> ::std::result::Result<::wasmi::FuncRef, ::wasmi::Error> {
+		if !self.benchmarking && BENCHMARKING_FUNCTIONS.contains(&name) {
+			return Err(::wasmi::Error::Instantiation(
+				format!("Export {} is only available to benchmarks", name),
+			));
+		}
+		self.resolver.resolve_func(name, signature)
+	}
+}
+
 /// Wasm rust executor for contracts.
 ///
 /// Executes the provided code in a sandboxed wasm runtime.
 #[derive(Debug, Clone)]
-pub struct WasmExecutor;
+pub struct WasmExecutor {
+	benchmarking: bool,
+}
 
 impl WasmExecutor {
 
 	/// Create a new instance.
 	pub fn new() -> Self {
-		WasmExecutor
+		WasmExecutor { benchmarking: false }
+	}
+
+	/// Create a new instance that also provides the host functions reserved to benchmarks, such
+	/// as the time of the host. It must never run runtime calls whose result matters for
+	/// consensus.
+	pub fn new_benchmarking() -> Self {
+		WasmExecutor { benchmarking: true }
+	}
+
+	/// Whether this instance provides the host functions reserved to benchmarks.
+	pub fn is_benchmarking(&self) -> bool {
+		self.benchmarking
 	}
 
 	/// Call a given method in the given code.
@@ -799,10 +840,14 @@ impl WasmExecutor {
 		) -> Result<ModuleRef>
 	{
 		// start module instantiation. Don't run 'start' function yet.
+		let host_functions = HostFunctions {
+			resolver: FunctionExecutor::<E>::resolver(),
+			benchmarking: self.benchmarking,
+		};
 		let intermediate_instance = ModuleInstance::new(
 			module,
 			&ImportsBuilder::new()
-			.with_resolver("env", FunctionExecutor::<E>::resolver())
+			.with_resolver("env", &host_functions)
 		)?;
 
 		// extract a reference to a linear memory, optional reference to a table
@@ -999,4 +1044,19 @@ mod tests {
 			ordered_trie_root::<Blake2Hasher, _, _>(vec![b"zero".to_vec(), b"one".to_vec(), b"two".to_vec()].iter()).as_fixed_bytes().encode()
 		);
 	}
+
+	#[test]
+	fn benchmark_time_should_only_be_resolved_for_benchmarks() {
+		use wasmi::ModuleImportResolver;
+
+		let signature = wasmi::Signature::new(&[][..], Some(wasmi::ValueType::I64));
+		let host_functions = |benchmarking| HostFunctions {
+			resolver: FunctionExecutor::<TestExternalities<Blake2Hasher>>::resolver(),
+			benchmarking,
+		};
+		assert!(host_functions(false).resolve_func("ext_benchmark_time", &signature).is_err());
+		assert!(host_functions(true).resolve_func("ext_benchmark_time", &signature).is_ok());
+		assert!(!WasmExecutor::new().is_benchmarking());
+		assert!(WasmExecutor::new_benchmarking().is_benchmarking());
+	}
 }
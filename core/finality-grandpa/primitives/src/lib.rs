@@ -53,6 +53,18 @@ pub mod well_known_keys {
 	pub const AUTHORITY_COUNT: &[u8] = b":grandpa:auth:len";
 }
 
+/// Keys of the auxiliary data GRANDPA keeps in the client database.
+pub mod aux_keys {
+	/// The key of the current authority set. Its encoding starts with the authorities and their
+	/// weights `Vec<(AuthorityId, u64)>`, the set id `u64`, the pending standard changes (a fork
+	/// tree, encoded as its roots followed by the best finalized number) and the pending forced
+	/// changes.
+	pub const AUTHORITY_SET: &[u8] = b"grandpa_voters";
+	/// The key of the authority set `(Vec<(AuthorityId, u64)>, u64)` at the block of an imported
+	/// state snapshot, from which GRANDPA starts instead of the genesis authorities.
+	pub const SNAPSHOT_AUTHORITY_SET: &[u8] = b"grandpa_snapshot_voters";
+}
+
 decl_runtime_apis! {
 	/// APIs for integrating the GRANDPA finality gadget into runtimes.
 	/// This should be implemented on the runtime side.
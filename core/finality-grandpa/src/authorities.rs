@@ -114,6 +114,17 @@ where H: PartialEq,
 		}
 	}
 
+	/// Get the set with given authorities and set id at the block of a state snapshot, without
+	/// any pending changes.
+	pub(crate) fn from_snapshot(authorities: Vec<(AuthorityId, u64)>, set_id: u64) -> Self {
+		AuthoritySet {
+			current_authorities: authorities,
+			set_id,
+			pending_standard_changes: ForkTree::new(),
+			pending_forced_changes: Vec::new(),
+		}
+	}
+
 	/// Get the current set id and a reference to the current authority set.
 	pub(crate) fn current(&self) -> (u64, &[(AuthorityId, u64)]) {
 		(self.set_id, &self.current_authorities[..])
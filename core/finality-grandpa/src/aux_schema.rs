@@ -33,7 +33,7 @@ use substrate_primitives::ed25519::Public as AuthorityId;
 
 const VERSION_KEY: &[u8] = b"grandpa_schema_version";
 const SET_STATE_KEY: &[u8] = b"grandpa_completed_round";
-const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
+const AUTHORITY_SET_KEY: &[u8] = fg_primitives::aux_keys::AUTHORITY_SET;
 const CONSENSUS_CHANGES_KEY: &[u8] = b"grandpa_consensus_changes";
 
 const CURRENT_VERSION: u32 = 1;
@@ -131,6 +131,9 @@ pub(crate) struct PersistentData<H, N> {
 }
 
 /// Load or initialize persistent data from backend.
+///
+/// `genesis_hash` and `genesis_number` are those of the block GRANDPA starts from on first
+/// startup: the genesis block, or the block of an imported state snapshot.
 pub(crate) fn load_persistent<B, H, N, G>(
 	backend: &B,
 	genesis_hash: H,
@@ -195,11 +198,26 @@ pub(crate) fn load_persistent<B, H, N, G>(
 		).into()),
 	}
 
-	// genesis.
-	info!(target: "afg", "Loading GRANDPA authority set \
-		from genesis on what appears to be first startup.");
+	let snapshot_set = load_decode::<_, (Vec<(AuthorityId, u64)>, u64)>(
+		backend,
+		fg_primitives::aux_keys::SNAPSHOT_AUTHORITY_SET,
+	)?;
+	let genesis_set = match snapshot_set {
+		// the chain starts from an imported state snapshot.
+		Some((authorities, set_id)) => {
+			info!(target: "afg", "Loading GRANDPA authority set {} \
+				from the state snapshot on what appears to be first startup.", set_id);
+
+			AuthoritySet::from_snapshot(authorities, set_id)
+		},
+		// genesis.
+		None => {
+			info!(target: "afg", "Loading GRANDPA authority set \
+				from genesis on what appears to be first startup.");
 
-	let genesis_set = AuthoritySet::genesis(genesis_authorities()?);
+			AuthoritySet::genesis(genesis_authorities()?)
+		},
+	};
 	let genesis_state = VoterSetState::Live(0, make_genesis_round());
 	backend.insert_aux(
 		&[
@@ -357,4 +375,41 @@ mod test {
 			VoterSetState::Live(round_number, round_state),
 		);
 	}
+
+	#[test]
+	fn load_persistent_starts_from_snapshot_authority_set() {
+		let client = test_client::new();
+
+		let authorities = vec![(AuthorityId::default(), 100)];
+		let set_id = 3;
+		let snapshot_hash = H256::random();
+		client.insert_aux(
+			&[(fg_primitives::aux_keys::SNAPSHOT_AUTHORITY_SET, (authorities.clone(), set_id).encode().as_slice())],
+			&[],
+		).unwrap();
+
+		let PersistentData { authority_set, set_state, .. } = load_persistent(
+			&client,
+			snapshot_hash,
+			10,
+			|| unreachable!(),
+		).unwrap();
+
+		let expected_set = AuthoritySet::<H256, u64>::from_snapshot(authorities.clone(), set_id);
+		assert_eq!(*authority_set.inner().read(), expected_set);
+		assert_eq!(
+			set_state,
+			VoterSetState::Live(0, RoundState::genesis((snapshot_hash, 10))),
+		);
+
+		// the layout of the stored set, as documented for `fg_primitives::aux_keys::AUTHORITY_SET`.
+		let stored_set = client.get_aux(AUTHORITY_SET_KEY).unwrap().unwrap();
+		let (stored_authorities, stored_set_id, standard_changes, best_finalized, forced_changes):
+			(Vec<(AuthorityId, u64)>, u64, parity_codec::Compact<u32>, Option<u64>, parity_codec::Compact<u32>) =
+			Decode::decode(&mut &stored_set[..]).unwrap();
+		assert_eq!(
+			(stored_authorities, stored_set_id, standard_changes.0, best_finalized, forced_changes.0),
+			(authorities, set_id, 0, None, 0),
+		);
+	}
 }
@@ -24,16 +24,33 @@ use grandpa::VoterSet;
 use grandpa::Message::{Prevote, Precommit};
 use futures::prelude::*;
 use futures::sync::mpsc;
+use lazy_static::lazy_static;
 use log::{debug, trace};
 use parity_codec::{Encode, Decode};
 use substrate_primitives::{ed25519, Pair};
 use substrate_telemetry::{telemetry, CONSENSUS_INFO};
+use substrate_prometheus::IntCounterVec;
 use runtime_primitives::traits::Block as BlockT;
 use tokio::timer::Interval;
 use crate::{Error, Network, Message, SignedMessage, Commit,
 	CompactCommit, GossipMessage, FullCommitMessage, VoteOrPrecommitMessage};
 use ed25519::{Public as AuthorityId, Signature as AuthoritySignature};
 
+lazy_static! {
+	static ref VOTES: IntCounterVec = substrate_prometheus::int_counter_vec(
+		"finality_grandpa_votes_total",
+		"Number of GRANDPA votes cast locally or received from the network",
+		&["kind", "origin"],
+	);
+}
+
+fn vote_kind<Block: BlockT>(message: &Message<Block>) -> &'static str {
+	match message {
+		Prevote(_) => "prevote",
+		Precommit(_) => "precommit",
+	}
+}
+
 fn localized_payload<E: Encode>(round: u64, set_id: u64, message: &E) -> Vec<u8> {
 	(message, round, set_id).encode()
 }
@@ -287,6 +304,7 @@ pub(crate) fn checked_message_stream<Block: BlockT, S>(
 						return Ok(None);
 					}
 
+					VOTES.with_label_values(&[vote_kind::<Block>(&msg.message.message), "remote"]).inc();
 					match &msg.message.message {
 						Prevote(prevote) => {
 							telemetry!(CONSENSUS_INFO; "afg.received_prevote";
@@ -335,6 +353,8 @@ impl<Block: BlockT, N: Network<Block>> Sink for OutgoingMessages<Block, N>
 			let encoded = localized_payload(self.round, self.set_id, &msg);
 			let signature = pair.sign(&encoded[..]);
 
+			VOTES.with_label_values(&[vote_kind::<Block>(&msg), "local"]).inc();
+
 			let target_hash = msg.target().0.clone();
 			let signed = SignedMessage::<Block> {
 				message: msg,
@@ -17,6 +17,7 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use lazy_static::lazy_static;
 use log::{debug, warn, info};
 use parity_codec::Encode;
 use futures::prelude::*;
@@ -35,6 +36,7 @@ use runtime_primitives::traits::{
 };
 use substrate_primitives::{Blake2Hasher, ed25519, H256, Pair};
 use substrate_telemetry::{telemetry, CONSENSUS_INFO};
+use substrate_prometheus::IntGauge;
 
 use crate::{
 	Commit, Config, Error, Network, Precommit, Prevote,
@@ -48,6 +50,13 @@ use crate::until_imported::UntilVoteTargetImported;
 
 use ed25519::Public as AuthorityId;
 
+lazy_static! {
+	static ref ROUND: IntGauge = substrate_prometheus::int_gauge(
+		"finality_grandpa_round",
+		"Round of the GRANDPA voter",
+	);
+}
+
 /// Data about a completed round.
 pub(crate) type CompletedRound<H, N> = (u64, RoundState<H, N>);
 
@@ -227,6 +236,8 @@ impl<B, E, Block: BlockT<Hash=H256>, N, RA> voter::Environment<Block::Hash, Numb
 		&self,
 		round: u64
 	) -> voter::RoundData<Self::Timer, Self::In, Self::Out> {
+		ROUND.set(round as i64);
+
 		let now = Instant::now();
 		let prevote_timer = Delay::new(now + self.config.gossip_duration * 2);
 		let precommit_timer = Delay::new(now + self.config.gossip_duration * 4);
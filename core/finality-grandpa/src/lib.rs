@@ -663,15 +663,20 @@ pub fn block_import<B, E, Block: BlockT<Hash=H256>, RA, PRA>(
 	use runtime_primitives::traits::Zero;
 
 	let chain_info = client.info()?;
-	let genesis_hash = chain_info.chain.genesis_hash;
+	// a chain imported from a state snapshot has no blocks before the snapshot block, where
+	// GRANDPA starts with the authority set carried in the snapshot.
+	let (genesis_hash, genesis_number) = match client.header(&BlockId::number(Zero::zero()))? {
+		Some(_) => (chain_info.chain.genesis_hash, <NumberFor<Block>>::zero()),
+		None => (chain_info.chain.finalized_hash, chain_info.chain.finalized_number),
+	};
 
 	let persistent_data = aux_schema::load_persistent(
 		&**client.backend(),
 		genesis_hash,
-		<NumberFor<Block>>::zero(),
+		genesis_number,
 		|| {
 			let genesis_authorities = api.runtime_api()
-				.grandpa_authorities(&BlockId::number(Zero::zero()))?;
+				.grandpa_authorities(&BlockId::hash(genesis_hash))?;
 			telemetry!(CONSENSUS_DEBUG; "afg.loading_authorities";
 				"authorities_len" => ?genesis_authorities.len()
 			);
@@ -68,9 +68,16 @@ bitflags! {
 		const LIGHT = 0b00000010;
 		/// Act as an authority
 		const AUTHORITY = 0b00000100;
+		/// Full node deleting the bodies of old finalized blocks.
+		/// Advertised since protocol version 3, and ignored when decoding older roles.
+		const BLOCKS_PRUNED = 0b00001000;
 	}
 }
 
+/// Minimal number of finalized blocks whose bodies are kept by nodes with the `BLOCKS_PRUNED` role.
+/// Peers only request older bodies from nodes keeping all of them.
+pub const MIN_BLOCKS_PRUNING: u32 = 256;
+
 impl parity_codec::Encode for Roles {
 	fn encode_to<T: parity_codec::Output>(&self, dest: &mut T) {
 		dest.push_byte(self.bits())
@@ -79,6 +86,7 @@ impl parity_codec::Encode for Roles {
 
 impl parity_codec::Decode for Roles {
 	fn decode<I: parity_codec::Input>(input: &mut I) -> Option<Self> {
-		Self::from_bits(input.read_byte()?)
+		// roles added by later protocol versions are ignored.
+		Some(Self::from_bits_truncate(input.read_byte()?))
 	}
 }
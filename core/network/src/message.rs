@@ -21,7 +21,7 @@ use runtime_primitives::traits::{Block as BlockT, Header as HeaderT};
 use parity_codec::{Encode, Decode, Input, Output};
 pub use self::generic::{
 	BlockAnnounce, RemoteCallRequest, RemoteReadRequest,
-	RemoteHeaderRequest, RemoteHeaderResponse,
+	RemoteReadChildRequest, RemoteHeaderRequest, RemoteHeaderResponse,
 	RemoteChangesRequest, RemoteChangesResponse,
 	FromBlock
 };
@@ -131,10 +131,28 @@ pub mod generic {
 	use network_libp2p::{CustomMessage, CustomMessageId};
 	use runtime_primitives::Justification;
 	use crate::config::Roles;
+	use lazy_static::lazy_static;
+	use substrate_prometheus::IntCounterVec;
 	use super::{
 		BlockAttributes, RemoteCallResponse, RemoteReadResponse,
 		RequestId, Transactions, Direction, ConsensusEngineId,
 	};
+
+	lazy_static! {
+		static ref BANDWIDTH: IntCounterVec = substrate_prometheus::int_counter_vec(
+			"network_bytes_total",
+			"Bytes of protocol messages sent and received",
+			&["protocol", "direction"],
+		);
+	}
+
+	/// Bandwidth labels of the consensus engines known to gossip. The engine id of a message is
+	/// chosen by the peer sending it, so any other engine is accounted as unknown rather than
+	/// given a label of its own.
+	const CONSENSUS_PROTOCOL_NAMES: &[(ConsensusEngineId, &str)] = &[
+		(*b"afg1", "consensus/afg1"),
+	];
+
 	/// Consensus is mostly opaque to us
 	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
 	pub struct ConsensusMessage {
@@ -201,20 +219,46 @@ pub mod generic {
 		RemoteChangesRequest(RemoteChangesRequest<Hash>),
 		/// Remote changes reponse.
 		RemoteChangesResponse(RemoteChangesResponse<Number, Hash>),
+		/// Remote child storage read request.
+		RemoteReadChildRequest(RemoteReadChildRequest<Hash>),
 		/// Chain-specific message
 		#[codec(index = "255")]
 		ChainSpecific(Vec<u8>),
 	}
 
+	impl<Header, Hash, Number, Extrinsic> Message<Header, Hash, Number, Extrinsic> {
+		/// Name of the protocol the message belongs to, used to account for the bandwidth.
+		pub fn protocol_name(&self) -> &'static str {
+			match *self {
+				Message::Status(_) | Message::BlockRequest(_) | Message::BlockResponse(_) |
+				Message::BlockAnnounce(_) => "sync",
+				Message::Transactions(_) => "transactions",
+				Message::Consensus(ref msg) => CONSENSUS_PROTOCOL_NAMES.iter()
+					.find(|(engine_id, _)| *engine_id == msg.engine_id)
+					.map_or("consensus/unknown", |&(_, name)| name),
+				Message::RemoteCallRequest(_) | Message::RemoteCallResponse(_) |
+				Message::RemoteReadRequest(_) | Message::RemoteReadResponse(_) |
+				Message::RemoteHeaderRequest(_) | Message::RemoteHeaderResponse(_) |
+				Message::RemoteChangesRequest(_) | Message::RemoteChangesResponse(_) |
+				Message::RemoteReadChildRequest(_) => "light",
+				Message::ChainSpecific(_) => "chain-specific",
+			}
+		}
+	}
+
 	impl<Header, Hash, Number, Extrinsic> CustomMessage for Message<Header, Hash, Number, Extrinsic>
 		where Self: Decode + Encode
 	{
 		fn into_bytes(self) -> Vec<u8> {
-			self.encode()
+			let bytes = self.encode();
+			BANDWIDTH.with_label_values(&[self.protocol_name(), "out"]).inc_by(bytes.len() as i64);
+			bytes
 		}
 
 		fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
-			Decode::decode(&mut &bytes[..]).ok_or(())
+			let message: Self = Decode::decode(&mut &bytes[..]).ok_or(())?;
+			BANDWIDTH.with_label_values(&[message.protocol_name(), "in"]).inc_by(bytes.len() as i64);
+			Ok(message)
 		}
 
 		fn request_id(&self) -> CustomMessageId {
@@ -233,6 +277,7 @@ pub mod generic {
 				Message::RemoteHeaderResponse(ref resp) => CustomMessageId::Response(resp.id),
 				Message::RemoteChangesRequest(ref req) => CustomMessageId::Request(req.id),
 				Message::RemoteChangesResponse(ref resp) => CustomMessageId::Response(resp.id),
+				Message::RemoteReadChildRequest(ref req) => CustomMessageId::Request(req.id),
 				Message::ChainSpecific(_) => CustomMessageId::OneWay,
 			}
 		}
@@ -314,6 +359,19 @@ pub mod generic {
 		pub key: Vec<u8>,
 	}
 
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Remote storage read child request.
+	pub struct RemoteReadChildRequest<H> {
+		/// Unique request id.
+		pub id: RequestId,
+		/// Block at which to perform call.
+		pub block: H,
+		/// Child Storage key.
+		pub storage_key: Vec<u8>,
+		/// Storage key.
+		pub key: Vec<u8>,
+	}
+
 	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
 	/// Remote header request.
 	pub struct RemoteHeaderRequest<N> {
@@ -27,7 +27,7 @@ use linked_hash_map::Entry;
 use parking_lot::Mutex;
 use client::{error::{Error as ClientError, ErrorKind as ClientErrorKind}};
 use client::light::fetcher::{Fetcher, FetchChecker, RemoteHeaderRequest,
-	RemoteCallRequest, RemoteReadRequest, RemoteChangesRequest, ChangesProof};
+	RemoteCallRequest, RemoteReadRequest, RemoteReadChildRequest, RemoteChangesRequest, ChangesProof};
 use crate::message;
 use network_libp2p::{Severity, PeerId};
 use crate::config::Roles;
@@ -105,6 +105,7 @@ struct Request<Block: BlockT> {
 enum RequestData<Block: BlockT> {
 	RemoteHeader(RemoteHeaderRequest<Block::Header>, OneShotSender<Result<Block::Header, ClientError>>),
 	RemoteRead(RemoteReadRequest<Block::Header>, OneShotSender<Result<Option<Vec<u8>>, ClientError>>),
+	RemoteReadChild(RemoteReadChildRequest<Block::Header>, OneShotSender<Result<Option<Vec<u8>>, ClientError>>),
 	RemoteCall(RemoteCallRequest<Block::Header>, OneShotSender<Result<Vec<u8>, ClientError>>),
 	RemoteChanges(RemoteChangesRequest<Block::Header>, OneShotSender<Result<Vec<(NumberFor<Block>, u32)>, ClientError>>),
 }
@@ -273,6 +274,14 @@ impl<B> OnDemandService<B> for OnDemand<B> where
 				},
 				Err(error) => Accept::CheckFailed(error, RequestData::RemoteRead(request, sender)),
 			},
+			RequestData::RemoteReadChild(request, sender) => match self.checker.check_read_child_proof(&request, response.proof) {
+				Ok(response) => {
+					// we do not bother if receiver has been dropped already
+					let _ = sender.send(Ok(response));
+					Accept::Ok
+				},
+				Err(error) => Accept::CheckFailed(error, RequestData::RemoteReadChild(request, sender)),
+			},
 			data @ _ => Accept::Unexpected(data),
 		})
 	}
@@ -333,6 +342,12 @@ impl<B> Fetcher<B> for OnDemand<B> where
 			RemoteResponse { receiver })
 	}
 
+	fn remote_read_child(&self, request: RemoteReadChildRequest<B::Header>) -> Self::RemoteReadResult {
+		let (sender, receiver) = channel();
+		self.schedule_request(request.retry_count.clone(), RequestData::RemoteReadChild(request, sender),
+			RemoteResponse { receiver })
+	}
+
 	fn remote_call(&self, request: RemoteCallRequest<B::Header>) -> Self::RemoteCallResult {
 		let (sender, receiver) = channel();
 		self.schedule_request(request.retry_count.clone(), RequestData::RemoteCall(request, sender),
@@ -471,6 +486,7 @@ impl<Block: BlockT> Request<Block> {
 		match self.data {
 			RequestData::RemoteHeader(ref data, _) => data.block,
 			RequestData::RemoteRead(ref data, _) => *data.header.number(),
+			RequestData::RemoteReadChild(ref data, _) => *data.header.number(),
 			RequestData::RemoteCall(ref data, _) => *data.header.number(),
 			RequestData::RemoteChanges(ref data, _) => data.max_block.0,
 		}
@@ -489,6 +505,13 @@ impl<Block: BlockT> Request<Block> {
 					block: data.block,
 					key: data.key.clone(),
 				}),
+			RequestData::RemoteReadChild(ref data, _) =>
+				message::generic::Message::RemoteReadChildRequest(message::RemoteReadChildRequest {
+					id: self.id,
+					block: data.block,
+					storage_key: data.storage_key.clone(),
+					key: data.key.clone(),
+				}),
 			RequestData::RemoteCall(ref data, _) =>
 				message::generic::Message::RemoteCallRequest(message::RemoteCallRequest {
 					id: self.id,
@@ -516,6 +539,7 @@ impl<Block: BlockT> RequestData<Block> {
 			RequestData::RemoteHeader(_, sender) => { let _ = sender.send(Err(error)); },
 			RequestData::RemoteCall(_, sender) => { let _ = sender.send(Err(error)); },
 			RequestData::RemoteRead(_, sender) => { let _ = sender.send(Err(error)); },
+			RequestData::RemoteReadChild(_, sender) => { let _ = sender.send(Err(error)); },
 			RequestData::RemoteChanges(_, sender) => { let _ = sender.send(Err(error)); },
 		}
 	}
@@ -529,7 +553,7 @@ pub mod tests {
 	use runtime_primitives::traits::NumberFor;
 	use client::{error::{ErrorKind as ClientErrorKind, Result as ClientResult}};
 	use client::light::fetcher::{Fetcher, FetchChecker, RemoteHeaderRequest,
-		RemoteCallRequest, RemoteReadRequest, RemoteChangesRequest, ChangesProof};
+		RemoteCallRequest, RemoteReadRequest, RemoteReadChildRequest, RemoteChangesRequest, ChangesProof};
 	use crate::config::Roles;
 	use crate::message;
 	use network_libp2p::{PeerId, Severity};
@@ -560,6 +584,13 @@ pub mod tests {
 			}
 		}
 
+		fn check_read_child_proof(&self, _: &RemoteReadChildRequest<Header>, _: Vec<Vec<u8>>) -> ClientResult<Option<Vec<u8>>> {
+			match self.ok {
+				true => Ok(Some(vec![42])),
+				false => Err(ClientErrorKind::Backend("Test error".into()).into()),
+			}
+		}
+
 		fn check_execution_proof(&self, _: &RemoteCallRequest<Header>, _: Vec<Vec<u8>>) -> ClientResult<Vec<u8>> {
 			match self.ok {
 				true => Ok(vec![42]),
@@ -845,6 +876,33 @@ pub mod tests {
 		thread.join().unwrap();
 	}
 
+	#[test]
+	fn receives_remote_read_child_response() {
+		let (_x, on_demand) = dummy(true);
+		let (network_sender, _network_port) = network_channel();
+		let peer0 = PeerId::random();
+		on_demand.set_network_sender(network_sender.clone());
+		on_demand.on_connect(peer0.clone(), Roles::FULL, 1000);
+
+		let response = on_demand.remote_read_child(RemoteReadChildRequest {
+			header: dummy_header(),
+			block: Default::default(),
+			storage_key: b":child_storage:sub".to_vec(),
+			key: b":key".to_vec(),
+			retry_count: None,
+		});
+		let thread = ::std::thread::spawn(move || {
+			let result = response.wait().unwrap();
+			assert_eq!(result, Some(vec![42]));
+		});
+
+		on_demand.on_remote_read_response(peer0.clone(), message::RemoteReadResponse {
+			id: 0,
+			proof: vec![vec![2]],
+		});
+		thread.join().unwrap();
+	}
+
 	#[test]
 	fn receives_remote_header_response() {
 		let (_x, on_demand) = dummy(true);
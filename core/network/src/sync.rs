@@ -30,7 +30,7 @@ use runtime_primitives::Justification;
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, As, NumberFor, Zero, CheckedSub};
 use runtime_primitives::generic::BlockId;
 use crate::message::{self, generic::Message as GenericMessage};
-use crate::config::Roles;
+use crate::config::{Roles, MIN_BLOCKS_PRUNING};
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -55,6 +55,8 @@ struct PeerSync<B: BlockT> {
 	pub best_number: NumberFor<B>,
 	pub state: PeerSyncState<B>,
 	pub recently_announced: VecDeque<B::Hash>,
+	/// Whether the peer deletes the bodies of old finalized blocks.
+	pub blocks_pruned: bool,
 }
 
 #[derive(Debug)]
@@ -490,6 +492,7 @@ impl<B: BlockT> ChainSync<B> {
 						best_number: info.best_number,
 						state: PeerSyncState::Available,
 						recently_announced: Default::default(),
+						blocks_pruned: info.roles.contains(Roles::BLOCKS_PRUNED),
 					});
 				}
 				(Ok(BlockStatus::Unknown), _) => {
@@ -503,6 +506,7 @@ impl<B: BlockT> ChainSync<B> {
 							best_number: info.best_number,
 							state: PeerSyncState::AncestorSearch(common_best, AncestorSearchState::ExponentialBackoff(As::sa(1))),
 							recently_announced: Default::default(),
+							blocks_pruned: info.roles.contains(Roles::BLOCKS_PRUNED),
 						});
 						Self::request_ancestry(protocol, who, common_best)
 					} else {
@@ -514,6 +518,7 @@ impl<B: BlockT> ChainSync<B> {
 							best_number: info.best_number,
 							state: PeerSyncState::Available,
 							recently_announced: Default::default(),
+							blocks_pruned: info.roles.contains(Roles::BLOCKS_PRUNED),
 						});
 						self.download_new(protocol, who)
 					}
@@ -526,6 +531,7 @@ impl<B: BlockT> ChainSync<B> {
 						best_number: info.best_number,
 						state: PeerSyncState::Available,
 						recently_announced: Default::default(),
+						blocks_pruned: info.roles.contains(Roles::BLOCKS_PRUNED),
 					});
 				}
 			}
@@ -1023,7 +1029,14 @@ impl<B: BlockT> ChainSync<B> {
 			match peer.state {
 				PeerSyncState::Available => {
 					trace!(target: "sync", "Considering new block download from {}, common block is {}, best is {:?}", who, peer.common_number, peer.best_number);
-					if let Some(range) = self.blocks.needed_blocks(who.clone(), MAX_BLOCKS_TO_REQUEST, peer.best_number, peer.common_number) {
+					// peers pruning block bodies are only known to have the most recent ones.
+					let common_number = if peer.blocks_pruned {
+						let kept_from = peer.best_number.as_().saturating_sub(u64::from(MIN_BLOCKS_PRUNING));
+						max(peer.common_number, As::sa(kept_from))
+					} else {
+						peer.common_number
+					};
+					if let Some(range) = self.blocks.needed_blocks(who.clone(), MAX_BLOCKS_TO_REQUEST, peer.best_number, common_number) {
 						trace!(target: "sync", "Requesting blocks from {}, ({} to {})", who, range.start, range.end);
 						let request = message::generic::BlockRequest {
 							id: 0,
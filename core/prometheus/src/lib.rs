@@ -0,0 +1,131 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics.
+//!
+//! Metrics are created with the helpers of this crate, which register them in a global registry,
+//! and may be updated anywhere in the Substrate codebase. They are served in the Prometheus text
+//! format on `/metrics` by the HTTP server started with `init_prometheus`. Updating a metric is
+//! cheap, so it is done whether the server is running or not.
+
+use std::net::SocketAddr;
+use futures::Future;
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::service_fn_ok;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use prometheus::{Encoder, Registry, TextEncoder, core::Collector};
+
+pub use prometheus::{Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts};
+
+/// Prefix of the names of all metrics.
+const NAMESPACE: &str = "substrate";
+
+lazy_static! {
+	static ref REGISTRY: Registry = Registry::new_custom(Some(NAMESPACE.into()), None)
+		.expect("the namespace is a valid metric name; qed");
+}
+
+/// Register a metric in the global registry, returning it.
+///
+/// A metric which can't be registered, e.g. because its name is already used, is still returned
+/// but not served.
+pub fn register<T: Collector + Clone + 'static>(metric: T) -> T {
+	if let Err(e) = REGISTRY.register(Box::new(metric.clone())) {
+		warn!("Failed to register a Prometheus metric: {}", e);
+	}
+	metric
+}
+
+/// Create and register a gauge.
+pub fn int_gauge(name: &str, help: &str) -> IntGauge {
+	register(IntGauge::new(name, help).expect("name and help are not empty; qed"))
+}
+
+/// Create and register a gauge with labels.
+pub fn int_gauge_vec(name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+	register(IntGaugeVec::new(Opts::new(name, help), labels).expect("name and help are not empty; qed"))
+}
+
+/// Create and register a counter.
+pub fn int_counter(name: &str, help: &str) -> IntCounter {
+	register(IntCounter::new(name, help).expect("name and help are not empty; qed"))
+}
+
+/// Create and register a counter with labels.
+pub fn int_counter_vec(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+	register(IntCounterVec::new(Opts::new(name, help), labels).expect("name and help are not empty; qed"))
+}
+
+/// Create and register a histogram with the default buckets, suited for durations in seconds.
+pub fn histogram(name: &str, help: &str) -> Histogram {
+	register(Histogram::with_opts(HistogramOpts::new(name, help)).expect("name and help are not empty; qed"))
+}
+
+/// Encode all registered metrics in the Prometheus text format.
+pub fn gather() -> Vec<u8> {
+	let mut buffer = Vec::new();
+	if let Err(e) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+		warn!("Failed to encode Prometheus metrics: {}", e);
+	}
+	buffer
+}
+
+/// Start an HTTP server serving the metrics on `/metrics` at the given address.
+///
+/// The returned future must be spawned on a tokio runtime.
+pub fn init_prometheus(addr: SocketAddr) -> Result<impl Future<Item=(), Error=()>, hyper::Error> {
+	let server = Server::try_bind(&addr)?.serve(|| service_fn_ok(serve_request));
+	info!("Prometheus metrics served on http://{}/metrics", server.local_addr());
+	Ok(server.map_err(|e| warn!("Prometheus server error: {}", e)))
+}
+
+fn serve_request(request: Request<Body>) -> Response<Body> {
+	let response = if request.method() == Method::GET && request.uri().path() == "/metrics" {
+		Response::builder()
+			.status(StatusCode::OK)
+			.header(CONTENT_TYPE, TextEncoder::new().format_type())
+			.body(Body::from(gather()))
+	} else {
+		Response::builder()
+			.status(StatusCode::NOT_FOUND)
+			.body(Body::from("Not found."))
+	};
+	response.expect("status and headers are valid; qed")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn serves_registered_metrics() {
+		let gauge = int_gauge("test_gauge", "A test gauge");
+		gauge.set(42);
+		let counter = int_counter_vec("test_counter", "A test counter", &["kind"]);
+		counter.with_label_values(&["a"]).inc_by(3);
+
+		let metrics = String::from_utf8(gather()).unwrap();
+		assert!(metrics.contains("substrate_test_gauge 42"));
+		assert!(metrics.contains("substrate_test_counter{kind=\"a\"} 3"));
+
+		let request = Request::get("/metrics").body(Body::empty()).unwrap();
+		assert_eq!(serve_request(request).status(), StatusCode::OK);
+		let request = Request::get("/other").body(Body::empty()).unwrap();
+		assert_eq!(serve_request(request).status(), StatusCode::NOT_FOUND);
+	}
+}
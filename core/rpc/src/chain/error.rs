@@ -32,10 +32,20 @@ error_chain! {
 	}
 }
 
+/// Base code for all chain errors.
+const BASE_ERROR: i64 = 3000;
+/// The body of the requested block has been pruned.
+const BLOCK_BODY_PRUNED: i64 = BASE_ERROR + 1;
+
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
 		match e {
 			Error(ErrorKind::Unimplemented, _) => errors::unimplemented(),
+			Error(ErrorKind::Client(client::error::ErrorKind::BlockBodyPruned(hash)), _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BLOCK_BODY_PRUNED),
+				message: "Block body has been pruned".into(),
+				data: Some(hash.into()),
+			},
 			e => errors::internal(e),
 		}
 	}
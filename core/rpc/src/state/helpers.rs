@@ -0,0 +1,73 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate state API helpers.
+
+use client::backend;
+use serde_derive::Serialize;
+
+/// Statistics of a cache returned by the RPC
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+	/// Number of lookups found in the cache
+	pub hits: u64,
+	/// Number of lookups not found in the cache
+	pub misses: u64,
+	/// Number of entries evicted to make room for new ones
+	pub evictions: u64,
+	/// Size of the cached entries in bytes
+	pub used_bytes: usize,
+	/// Maximal size of the cached entries in bytes
+	pub max_bytes: usize,
+}
+
+impl From<backend::CacheStats> for CacheStats {
+	fn from(stats: backend::CacheStats) -> Self {
+		CacheStats {
+			hits: stats.hits,
+			misses: stats.misses,
+			evictions: stats.evictions,
+			used_bytes: stats.used_bytes,
+			max_bytes: stats.max_bytes,
+		}
+	}
+}
+
+/// Statistics of the state caches returned by the RPC
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateCacheStats {
+	/// Storage values cache
+	pub values: CacheStats,
+	/// Child storage values cache
+	pub child_values: CacheStats,
+	/// Storage hashes cache
+	pub hashes: CacheStats,
+	/// Trie nodes cache
+	pub nodes: CacheStats,
+}
+
+impl From<backend::StateCacheStats> for StateCacheStats {
+	fn from(stats: backend::StateCacheStats) -> Self {
+		StateCacheStats {
+			values: stats.values.into(),
+			child_values: stats.child_values.into(),
+			hashes: stats.hashes.into(),
+			nodes: stats.nodes.into(),
+		}
+	}
+}
@@ -35,6 +35,42 @@ fn should_return_storage() {
 	)
 }
 
+#[test]
+fn should_return_child_storage() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let client = State::new(client, Subscriptions::new(core.executor()));
+	let child_key = StorageKey(b":child_storage:default:test".to_vec());
+
+	assert_matches!(
+		client.child_storage(child_key.clone(), StorageKey(vec![10]), Some(genesis_hash).into()),
+		Ok(None)
+	);
+	assert_matches!(
+		client.child_storage_size(child_key.clone(), StorageKey(vec![10]), Some(genesis_hash).into()),
+		Ok(None)
+	);
+	assert_matches!(
+		client.child_storage_keys(child_key, StorageKey(vec![]), Some(genesis_hash).into()),
+		Ok(ref keys) if keys.is_empty()
+	);
+}
+
+#[test]
+fn should_return_cache_stats() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let client = State::new(client, Subscriptions::new(core.executor()));
+
+	client.storage(StorageKey(vec![10]), Some(genesis_hash).into()).unwrap();
+	assert_matches!(
+		client.cache_stats(),
+		Ok(Some(ref stats)) if stats.values.max_bytes > 0 && stats.nodes.max_bytes > 0
+	);
+}
+
 #[test]
 fn should_call_contract() {
 	let core = ::tokio::runtime::Runtime::new().unwrap();
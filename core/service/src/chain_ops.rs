@@ -16,21 +16,27 @@
 
 //! Chain utilities.
 
-use std::{self, io::{Read, Write}};
+use std::{self, io::{Read, Write}, time::Instant};
 use futures::Future;
 use log::{info, warn};
 
 use runtime_primitives::generic::{SignedBlock, BlockId};
-use runtime_primitives::traits::{As, Block, Header, NumberFor};
+use runtime_primitives::traits::{As, Block, Header, Hash, NumberFor};
 use consensus_common::import_queue::{ImportQueue, IncomingBlock, Link};
 use network::message;
 
 use consensus_common::BlockOrigin;
+use client::{CallExecutor, ExecutionStrategy, backend::AuxStore, runtime_api::RuntimeVersion};
+use primitives::{Blake2Hasher, H256, NeverNativeValue, storage::well_known_keys};
+use runtime_primitives::{ApplyResult, BuildStorage, StorageOverlay};
+use state_machine::{Backend as StateBackend, NeverOffchainExt, OverlayedChanges};
+use benchmarking_primitives::{Benchmark, BenchmarkMetadata};
 use crate::components::{self, Components, ServiceFactory, FactoryFullConfiguration, FactoryBlockNumber, RuntimeGenesis};
 use crate::new_client;
-use parity_codec::{Decode, Encode};
+use parity_codec::{Compact, Decode, Encode};
 use crate::error;
 use crate::chain_spec::ChainSpec;
+use crate::config::DatabaseEngine;
 
 /// Export a range of blocks to a binary stream.
 pub fn export_blocks<F, E, W>(
@@ -209,9 +215,772 @@ pub fn revert_chain<F>(
 	Ok(())
 }
 
+/// Version of the format of state snapshots written by `export_state`.
+const STATE_SNAPSHOT_VERSION: u32 = 2;
+
+/// A GRANDPA authority set: the authorities with their weights, and the set id.
+type GrandpaAuthoritySet = (Vec<(primitives::ed25519::Public, u64)>, u64);
+
+/// Export the state at a given block to a binary stream, defaulting to the finalized block.
+///
+/// The snapshot starts with a header holding the genesis hash, the block hash, the state root,
+/// the header of the block and the GRANDPA authority set at the block, if the chain uses GRANDPA.
+/// It is followed by one `Some((child_storage_key, key, value))` entry per storage entry, with
+/// the entries of a child trie following each other under its child storage key, and ends with
+/// `None`.
+///
+/// GRANDPA only knows its authority set at the finalized block, so a chain using GRANDPA can
+/// only export the state of the finalized block, and not while an authority set change is
+/// pending.
+pub fn export_state<F, W>(
+	config: FactoryFullConfiguration<F>,
+	mut output: W,
+	block: Option<FactoryBlockNumber<F>>,
+) -> error::Result<()>
+	where F: ServiceFactory, W: Write,
+{
+	let client = new_client::<F>(&config)?;
+	let info = client.info()?.chain;
+	let block = block.unwrap_or(info.finalized_number);
+	let id = BlockId::number(block);
+	let header = client.header(&id)?.ok_or_else(|| format!("Unknown block #{}", block))?;
+	let hash = header.hash();
+	let state_root = *header.state_root();
+	let state = client.state_at(&id)?;
+
+	let grandpa_set = match client.get_aux(fg_primitives::aux_keys::AUTHORITY_SET)? {
+		Some(encoded_set) => {
+			if hash != info.finalized_hash {
+				return Err(format!(
+					"The GRANDPA authority set is only known at the finalized block #{}",
+					info.finalized_number,
+				).into());
+			}
+			Some(grandpa_authority_set::<FactoryBlockNumber<F>>(&encoded_set)?)
+		},
+		None => None,
+	};
+
+	info!("Exporting state of block #{} ({}), state root {}", block, hash, state_root);
+	output.write_all(&(STATE_SNAPSHOT_VERSION, info.genesis_hash, hash, state_root, header, grandpa_set).encode())?;
+	let count = write_state_entries(&state, &mut output)?;
+
+	info!("Exported {} entries", count);
+	Ok(())
+}
+
+/// Read the current GRANDPA authority set from its encoding in the auxiliary store, failing if
+/// an authority set change is pending.
+fn grandpa_authority_set<N: Decode>(mut encoded_set: &[u8]) -> error::Result<GrandpaAuthoritySet> {
+	let decode_err = "Error reading the GRANDPA authority set";
+	let (authorities, set_id, standard_changes): (Vec<(primitives::ed25519::Public, u64)>, u64, Compact<u32>) =
+		Decode::decode(&mut encoded_set).ok_or(decode_err)?;
+	let pending = standard_changes.0 != 0 || {
+		let (_, forced_changes): (Option<N>, Compact<u32>) =
+			Decode::decode(&mut encoded_set).ok_or(decode_err)?;
+		forced_changes.0 != 0
+	};
+	if pending {
+		return Err("A GRANDPA authority set change is pending; export the state once it is enacted".into());
+	}
+
+	Ok((authorities, set_id))
+}
+
+/// Write one snapshot entry per storage entry of `state`, followed by the final `None`, and
+/// return the number of entries.
+fn write_state_entries<S, W>(state: &S, output: &mut W) -> error::Result<u64>
+	where S: StateBackend<Blake2Hasher>, W: Write,
+{
+	let state_err = |e| format!("Error reading state: {:?}", e);
+
+	let mut keys = Vec::new();
+	state.for_keys_with_prefix(&[], |key| keys.push(key.to_vec()));
+
+	let mut count: u64 = 0;
+	for key in keys {
+		if well_known_keys::is_child_storage_key(&key) {
+			// the root of a child trie is recomputed from its entries on import.
+			let mut child_keys = Vec::new();
+			state.for_keys_in_child_storage(&key, |child_key| child_keys.push(child_key.to_vec()));
+			for child_key in child_keys {
+				if let Some(value) = state.child_storage(&key, &child_key).map_err(state_err)? {
+					output.write_all(&Some((Some(key.clone()), child_key, value)).encode())?;
+					count += 1;
+				}
+			}
+		} else if let Some(value) = state.storage(&key).map_err(state_err)? {
+			output.write_all(&Some((None::<Vec<u8>>, key, value)).encode())?;
+			count += 1;
+		}
+	}
+	output.write_all(&None::<client_db::SnapshotEntry>.encode())?;
+
+	Ok(count)
+}
+
+/// The entries of a state snapshot, decoded one at a time from its stream.
+struct SnapshotEntries<R> {
+	input: R,
+	done: bool,
+}
+
+impl<R> SnapshotEntries<R> {
+	fn new(input: R) -> Self {
+		SnapshotEntries { input, done: false }
+	}
+}
+
+impl<R: Read> Iterator for SnapshotEntries<R> {
+	type Item = Result<client_db::SnapshotEntry, client::error::Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+
+		match Decode::decode(&mut self.input) {
+			Some(Some(entry)) => Some(Ok(entry)),
+			Some(None) => {
+				self.done = true;
+				None
+			},
+			None => {
+				self.done = true;
+				Some(Err("Error reading state snapshot entry".into()))
+			},
+		}
+	}
+}
+
+/// Import a state snapshot written by `export_state` into an empty database.
+///
+/// The snapshot must belong to the chain of the chain spec. Its block becomes the best and
+/// finalized block of the database, from which the node syncs the following blocks, and the
+/// GRANDPA authority set of the snapshot is where GRANDPA starts. The entries are streamed into
+/// the database rather than read into memory first.
+pub fn import_state<F, R>(
+	config: FactoryFullConfiguration<F>,
+	mut input: R,
+) -> error::Result<()>
+	where F: ServiceFactory, R: Read,
+{
+	type Hashing<F> = <<<F as ServiceFactory>::Block as Block>::Header as Header>::Hashing;
+
+	type BlockHash<F> = <<F as ServiceFactory>::Block as Block>::Hash;
+
+	let version: u32 = Decode::decode(&mut input).ok_or("Error reading state snapshot header")?;
+	if version != STATE_SNAPSHOT_VERSION {
+		return Err(format!("Unsupported state snapshot version {}", version).into());
+	}
+	let (genesis_hash, hash, state_root, header, grandpa_set):
+		(BlockHash<F>, BlockHash<F>, BlockHash<F>, <F::Block as Block>::Header, Option<GrandpaAuthoritySet>) =
+		Decode::decode(&mut input).ok_or("Error reading state snapshot header")?;
+	if header.hash() != hash || *header.state_root() != state_root {
+		return Err("State snapshot header does not match its block".into());
+	}
+
+	let (mut genesis_storage, genesis_children) = config.chain_spec.build_storage()?;
+	for (child_key, child) in genesis_children {
+		if !child.is_empty() {
+			genesis_storage.insert(child_key, Hashing::<F>::trie_root(child).as_ref().to_vec());
+		}
+	}
+	let genesis_block = client::genesis::construct_genesis_block::<F::Block>(
+		Hashing::<F>::trie_root(genesis_storage),
+	);
+	if genesis_block.header().hash() != genesis_hash {
+		return Err(format!(
+			"State snapshot is of a chain with genesis {}, expected {}",
+			genesis_hash,
+			genesis_block.header().hash(),
+		).into());
+	}
+
+	let aux = grandpa_set
+		.map(|set| vec![(fg_primitives::aux_keys::SNAPSHOT_AUTHORITY_SET.to_vec(), set.encode())])
+		.unwrap_or_default();
+
+	info!("Importing state of block #{} ({}), state root {}", header.number(), hash, state_root);
+	client_db::import_state_snapshot::<F::Block, _>(
+		database_settings::<F>(&config),
+		genesis_hash,
+		header,
+		SnapshotEntries::new(input),
+		aux,
+	)?;
+
+	Ok(())
+}
+
+fn database_settings<F: ServiceFactory>(config: &FactoryFullConfiguration<F>) -> client_db::DatabaseSettings {
+	client_db::DatabaseSettings {
+		cache_size: config.database_cache_size.map(|u| u as usize),
+		path: config.database_path.as_str().into(),
+		pruning: config.pruning.clone(),
+		blocks_pruning: config.blocks_pruning,
+		state_cache: config.state_cache,
+		engine: config.database,
+	}
+}
+
+/// Compute the statistics of every column of the database.
+pub fn database_stats<F>(config: &FactoryFullConfiguration<F>) -> error::Result<Vec<client_db::ColumnStats>>
+	where F: ServiceFactory,
+{
+	Ok(client_db::database_stats(&database_settings::<F>(config))?)
+}
+
+/// Copy the database to the given engine and path, which must not hold a database yet.
+pub fn migrate_database<F>(
+	config: &FactoryFullConfiguration<F>,
+	engine: DatabaseEngine,
+	path: &str,
+) -> error::Result<()>
+	where F: ServiceFactory,
+{
+	let from = database_settings::<F>(config);
+	let to = client_db::DatabaseSettings {
+		path: path.into(),
+		engine,
+		..database_settings::<F>(config)
+	};
+
+	info!("Migrating the {:?} database at {} to a {:?} database at {}", from.engine, config.database_path, engine, path);
+	let copied = client_db::migrate_database(&from, &to)?;
+	info!("Migrated {} entries", copied);
+
+	Ok(())
+}
+
+/// Read the state at a given block, defaulting to the best block, as the raw storage of a genesis.
+///
+/// Child tries can't be part of a raw genesis: a state with child tries is refused unless
+/// `drop_child_tries`, in which case they are left out along with their roots.
+pub fn state_as_genesis<F>(
+	config: FactoryFullConfiguration<F>,
+	block: Option<FactoryBlockNumber<F>>,
+	drop_child_tries: bool,
+) -> error::Result<StorageOverlay>
+	where F: ServiceFactory,
+{
+	let client = new_client::<F>(&config)?;
+	let block = block.unwrap_or(client.info()?.chain.best_number);
+	let id = BlockId::number(block);
+	let header = client.header(&id)?.ok_or_else(|| format!("Unknown block #{}", block))?;
+	let state = client.state_at(&id)?;
+
+	info!("Reading state of block #{} ({})", block, header.hash());
+	genesis_storage(&state, drop_child_tries)
+}
+
+/// Read the top trie of `state` as raw genesis storage, leaving out its child tries if
+/// `drop_child_tries` and refusing it if it has any otherwise.
+fn genesis_storage<S>(state: &S, drop_child_tries: bool) -> error::Result<StorageOverlay>
+	where S: StateBackend<Blake2Hasher>,
+{
+	let state_err = |e| format!("Error reading state: {:?}", e);
+
+	let mut keys = Vec::new();
+	state.for_keys_with_prefix(&[], |key| keys.push(key.to_vec()));
+
+	let mut storage = StorageOverlay::new();
+	let mut child_tries: usize = 0;
+	for key in keys {
+		if well_known_keys::is_child_storage_key(&key) {
+			child_tries += 1;
+		} else if let Some(value) = state.storage(&key).map_err(state_err)? {
+			storage.insert(key, value);
+		}
+	}
+	if child_tries > 0 {
+		if !drop_child_tries {
+			return Err(format!(
+				"The state has {} child tries, which a raw genesis can't hold; they must be dropped explicitly",
+				child_tries,
+			).into());
+		}
+		warn!("Leaving out {} child tries, which a raw genesis can't hold", child_tries);
+	}
+
+	Ok(storage)
+}
+
 /// Build a chain spec json
 pub fn build_spec<G>(spec: ChainSpec<G>, raw: bool) -> error::Result<String>
 	where G: RuntimeGenesis,
 {
 	Ok(spec.to_json(raw)?)
 }
+
+/// The measurements of a runtime benchmark.
+pub struct BenchmarkResults {
+	/// The benchmark that was run.
+	pub metadata: BenchmarkMetadata,
+	/// The values of the components and the duration of the dispatch in nanoseconds of each run.
+	pub samples: Vec<(Vec<u32>, u64)>,
+}
+
+/// Run the benchmark `name` of the runtime module `module` on top of the best block.
+///
+/// Each component is stepped from its lowest to its highest value in `steps` steps, that is with
+/// `steps + 1` values, while the other components are at their highest value, and every
+/// combination is run `repeat` times. The state changes of the benchmark are discarded after each
+/// run. The runtime is run by a benchmarking executor, the only one giving it the time of the host.
+pub fn benchmark<F>(
+	config: &FactoryFullConfiguration<F>,
+	module: &str,
+	name: &str,
+	steps: u32,
+	repeat: u32,
+	strategy: ExecutionStrategy,
+) -> error::Result<BenchmarkResults>
+	where F: ServiceFactory,
+{
+	let executor = substrate_executor::NativeExecutor::new_benchmarking(config.default_heap_pages);
+	let (client, _) = components::FullComponents::<F>::build_client(config, executor)?;
+	let at = BlockId::Hash(client.info()?.chain.best_hash);
+
+	if !client.runtime_version_at(&at)?.has_api::<dyn Benchmark<F::Block>>() {
+		return Err("The runtime doesn't provide the benchmarking api".into());
+	}
+
+	let call = |method: &str, data: Vec<u8>| {
+		client.executor().call(&at, method, &data, strategy, NeverOffchainExt::new())
+	};
+
+	let encoded = call("Benchmark_benchmarks", Vec::new())?;
+	let metadata = Vec::<BenchmarkMetadata>::decode(&mut &encoded[..])
+		.ok_or("Invalid benchmarks returned by the runtime")?
+		.into_iter()
+		.find(|b| b.module == module.as_bytes() && b.name == name.as_bytes())
+		.ok_or_else(|| format!("Unknown benchmark {}::{}", module, name))?;
+
+	if let Some(component) = metadata.components.iter().find(|c| c.low > c.high) {
+		return Err(format!(
+			"Invalid range {}..={} of component {} of benchmark {}::{}",
+			component.low,
+			component.high,
+			String::from_utf8_lossy(&component.name),
+			module,
+			name,
+		).into());
+	}
+
+	let highest = metadata.components.iter().map(|c| c.high).collect::<Vec<_>>();
+	let mut points = Vec::new();
+	if metadata.components.is_empty() {
+		points.push(Vec::new());
+	}
+	for (index, component) in metadata.components.iter().enumerate() {
+		let steps = steps.max(1) as u64;
+		let range = (component.high - component.low) as u64;
+		let mut values = (0..=steps).map(|step| component.low + (range * step / steps) as u32).collect::<Vec<_>>();
+		values.dedup();
+		for value in values {
+			let mut point = highest.clone();
+			point[index] = value;
+			points.push(point);
+		}
+	}
+
+	info!("Running {} points of benchmark {}::{}, {} times each", points.len(), module, name, repeat);
+	let mut samples = Vec::with_capacity(points.len() * repeat as usize);
+	for point in points {
+		for _ in 0..repeat {
+			let encoded = call("Benchmark_run_benchmark", (module.as_bytes(), name.as_bytes(), &point).encode())?;
+			let duration = Result::<u64, Vec<u8>>::decode(&mut &encoded[..])
+				.ok_or("Invalid benchmark result returned by the runtime")?
+				.map_err(|e| format!("Benchmark failed: {}", String::from_utf8_lossy(&e)))?;
+			samples.push((point.clone(), duration));
+		}
+	}
+
+	Ok(BenchmarkResults { metadata, samples })
+}
+
+/// Call `method` of the runtime in the wasm code found in `state` and `overlay`, keeping the
+/// changes of the call in `overlay`.
+fn call_wasm<E, B, S>(
+	executor: &E,
+	state: &S,
+	overlay: &mut OverlayedChanges,
+	method: &str,
+	call_data: &[u8],
+) -> Result<Vec<u8>, String> where
+	B: Block,
+	E: CallExecutor<B, Blake2Hasher>,
+	S: StateBackend<Blake2Hasher>,
+{
+	executor.call_at_state::<_, _, _, NeverNativeValue, fn() -> _>(
+		state,
+		overlay,
+		method,
+		call_data,
+		state_machine::always_wasm(),
+		None,
+		NeverOffchainExt::new(),
+	)
+		.map(|(result, _, _)| result.into_encoded())
+		.map_err(|e| format!("{:?}", e))
+}
+
+/// Execute the blocks following the block `at` with the runtime `code` instead of the one of the
+/// chain, without importing anything. `at` defaults to `blocks` blocks before the best block.
+///
+/// The new runtime is put in a state overlay on top of the state of `at` in the database, and each
+/// block is executed on top of the changes of the blocks replayed before it. The storage root
+/// computed by the new runtime is compared to the one of the block, with the code of the chain put
+/// back in place. A dry run of the initialisation of the block following `at` is done first, which
+/// runs the `on_runtime_upgrade` migrations if the new runtime has a different `spec_version`.
+/// These migrations run again, once, in the replay of that block. Any divergent storage root is
+/// reported as a failure, noting whether the `spec_version` changed.
+pub fn try_runtime<F>(
+	config: FactoryFullConfiguration<F>,
+	code: Vec<u8>,
+	at: Option<FactoryBlockNumber<F>>,
+	blocks: FactoryBlockNumber<F>,
+) -> error::Result<()>
+	where F: ServiceFactory,
+{
+	let client = new_client::<F>(&config)?;
+	let executor = client.executor();
+	let best = client.info()?.chain.best_number;
+	let at = at.unwrap_or_else(|| if best > blocks { best - blocks } else { As::sa(0) });
+	let at_hash = client.block_hash(at)?.ok_or_else(|| format!("Unknown block #{}", at))?;
+	let at_id = BlockId::Hash(at_hash);
+
+	// dry run of the runtime upgrade.
+	let state = client.state_at(&at_id)?;
+	let mut overlay = OverlayedChanges::default();
+	overlay.set_storage(well_known_keys::CODE.to_vec(), Some(code.clone()));
+	overlay.commit_prospective();
+
+	let encoded = call_wasm::<_, F::Block, _>(executor, &state, &mut overlay, "Core_version", &[])?;
+	let version = RuntimeVersion::decode(&mut &encoded[..]).ok_or("Invalid version of the new runtime")?;
+	let chain_version = client.runtime_version_at(&at_id)?;
+	info!("Chain runtime: {}, new runtime: {}", chain_version, version);
+
+	let upgraded = version.spec_version != chain_version.spec_version;
+	if !upgraded {
+		info!("The spec_version is unchanged, on_runtime_upgrade won't be run");
+	} else {
+		let header = <<F::Block as Block>::Header as Header>::new(
+			at + As::sa(1),
+			Default::default(),
+			Default::default(),
+			at_hash,
+			Default::default(),
+		);
+		let start = Instant::now();
+		match call_wasm::<_, F::Block, _>(executor, &state, &mut overlay, "Core_initialise_block", &header.encode()) {
+			Ok(_) => {
+				overlay.commit_prospective();
+				info!(
+					"Runtime upgrade dry run: initialisation of block #{} changed {} storage keys in {:?}",
+					at + As::sa(1),
+					overlay.into_committed().count() - 1,
+					start.elapsed(),
+				);
+			},
+			Err(e) => warn!("Runtime upgrade dry run panicked: {}", e),
+		}
+	}
+
+	let last = std::cmp::min(at + blocks, best);
+	let mut number = at + As::sa(1);
+	let mut failed = 0u64;
+	let mut executed = 0u64;
+
+	let mut chain_code = state.storage(well_known_keys::CODE)
+		.map_err(|e| format!("Error reading the code at #{}: {}", at, e))?;
+	let mut overlay = OverlayedChanges::default();
+
+	while number <= last {
+		let block = client.block(&BlockId::Number(number))?
+			.ok_or_else(|| format!("Unknown block #{}", number))?
+			.block;
+		let expected_root = *block.header().state_root();
+
+		let start = Instant::now();
+		let result = replay_block::<F::Block, _, _>(executor, &state, &mut overlay, &code, &mut chain_code, block);
+		let elapsed = start.elapsed();
+		executed += 1;
+
+		match result {
+			Ok((failed_extrinsics, _)) if !failed_extrinsics.is_empty() => {
+				failed += 1;
+				warn!("#{}: extrinsics {:?} failed ({:?})", number, failed_extrinsics, elapsed);
+			},
+			Ok((_, root)) if root.as_ref() != expected_root.as_ref() => {
+				failed += 1;
+				warn!(
+					"#{}: divergent storage root, expected {:?}, got {:?}{} ({:?})",
+					number,
+					expected_root,
+					root,
+					if upgraded { ", the spec_version changed" } else { "" },
+					elapsed,
+				);
+			},
+			Ok(_) => info!("#{}: ok ({:?})", number, elapsed),
+			Err(e) => {
+				failed += 1;
+				warn!("#{}: execution panicked: {} ({:?})", number, e, elapsed);
+			},
+		}
+
+		number += As::sa(1);
+	}
+
+	if failed > 0 {
+		return Err(format!("{} of {} blocks failed with the new runtime", failed, executed).into());
+	}
+
+	info!("Executed {} blocks with the new runtime", executed);
+	Ok(())
+}
+
+/// Execute `block` with the runtime `code` on top of `state` and `overlay`, which holds the changes
+/// of the blocks replayed before it, and add its changes to `overlay`.
+///
+/// The storage root is computed with `chain_code` in place of `code`, unless the block changed the
+/// code itself, in which case `chain_code` is updated. Returns the indices of the extrinsics that
+/// failed to apply and the storage root.
+fn replay_block<B, E, S>(
+	executor: &E,
+	state: &S,
+	overlay: &mut OverlayedChanges,
+	code: &[u8],
+	chain_code: &mut Option<Vec<u8>>,
+	block: B,
+) -> Result<(Vec<usize>, H256), String> where
+	B: Block,
+	E: CallExecutor<B, Blake2Hasher>,
+	S: StateBackend<Blake2Hasher>,
+{
+	let (header, extrinsics) = block.deconstruct();
+	overlay.set_storage(well_known_keys::CODE.to_vec(), Some(code.to_vec()));
+	overlay.commit_prospective();
+
+	let failed_extrinsics = match execute_block::<B, _, _>(executor, state, overlay, &header, extrinsics) {
+		Ok(failed_extrinsics) => failed_extrinsics,
+		Err(e) => {
+			overlay.discard_prospective();
+			return Err(e)
+		},
+	};
+	overlay.commit_prospective();
+
+	if overlay.storage(well_known_keys::CODE) != Some(Some(code)) {
+		*chain_code = overlay.storage(well_known_keys::CODE).and_then(|c| c.map(|c| c.to_vec()));
+	}
+	let mut chain_overlay = overlay.clone();
+	chain_overlay.set_storage(well_known_keys::CODE.to_vec(), chain_code.clone());
+	chain_overlay.commit_prospective();
+	let (root, _) = state.storage_root(chain_overlay.into_committed());
+
+	Ok((failed_extrinsics, root))
+}
+
+/// Execute a block the way the block builder does, without checking its storage root, and return
+/// the indices of the extrinsics that failed to apply.
+fn execute_block<B, E, S>(
+	executor: &E,
+	state: &S,
+	overlay: &mut OverlayedChanges,
+	header: &B::Header,
+	extrinsics: Vec<B::Extrinsic>,
+) -> Result<Vec<usize>, String> where
+	B: Block,
+	E: CallExecutor<B, Blake2Hasher>,
+	S: StateBackend<Blake2Hasher>,
+{
+	call_wasm::<_, B, _>(executor, state, overlay, "Core_initialise_block", &header.encode())?;
+
+	let mut failed = Vec::new();
+	for (index, extrinsic) in extrinsics.iter().enumerate() {
+		let encoded = call_wasm::<_, B, _>(executor, state, overlay, "BlockBuilder_apply_extrinsic", &extrinsic.encode())?;
+		match ApplyResult::decode(&mut &encoded[..]) {
+			Some(Ok(_)) => {},
+			_ => failed.push(index),
+		}
+	}
+
+	call_wasm::<_, B, _>(executor, state, overlay, "BlockBuilder_finalise_block", &[])?;
+	Ok(failed)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use client::backend::{Backend, BlockImportOperation, NewBlockState};
+	use consensus_common::BlockOrigin;
+	use substrate_test_client::{self, runtime::{self, Transfer}, AccountKeyring, BlockBuilderExt, TestClient};
+	use serde_derive::{Serialize, Deserialize};
+	use runtime_primitives::ChildrenStorageOverlay;
+	use primitives::twox_128;
+
+	/// A genesis only ever replaced by a raw one.
+	#[derive(Serialize, Deserialize)]
+	struct RawOnlyGenesis;
+
+	impl BuildStorage for RawOnlyGenesis {
+		fn build_storage(self) -> Result<(StorageOverlay, ChildrenStorageOverlay), String> {
+			Ok(Default::default())
+		}
+		fn assimilate_storage(self, _: &mut StorageOverlay, _: &mut ChildrenStorageOverlay) -> Result<(), String> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn replay_should_run_each_block_on_the_replayed_state_of_its_parent() {
+		let client = substrate_test_client::new();
+		for nonce in 0..3 {
+			let mut builder = client.new_block().unwrap();
+			builder.push_transfer(Transfer {
+				from: AccountKeyring::Alice.into(),
+				to: AccountKeyring::Bob.into(),
+				amount: 10,
+				nonce,
+			}).unwrap();
+			client.import(BlockOrigin::Own, builder.bake().unwrap()).unwrap();
+		}
+
+		let replay = |migration: Option<(Vec<u8>, Vec<u8>)>| {
+			let state = client.state_at(&BlockId::Number(0)).unwrap();
+			let code = state.storage(well_known_keys::CODE).unwrap().unwrap();
+			let mut chain_code = Some(code.clone());
+			let mut overlay = OverlayedChanges::default();
+			// the changes of a one-off `on_runtime_upgrade` migration.
+			if let Some((key, value)) = migration {
+				overlay.set_storage(key, Some(value));
+				overlay.commit_prospective();
+			}
+
+			let roots_match = (1..4u64).map(|number| {
+				let block = client.block(&BlockId::Number(number)).unwrap().unwrap().block;
+				let expected_root = *block.header().state_root();
+				let (failed, root) = replay_block::<runtime::Block, _, _>(
+					client.executor(), &state, &mut overlay, &code, &mut chain_code, block,
+				).unwrap();
+				assert!(failed.is_empty());
+				root == expected_root
+			}).collect::<Vec<_>>();
+
+			(roots_match, overlay)
+		};
+
+		let bob_balance = twox_128(&runtime::system::balance_of_key(AccountKeyring::Bob.into())).to_vec();
+		let chain_bob_balance = client.storage(&BlockId::Number(3), &primitives::storage::StorageKey(bob_balance.clone()))
+			.unwrap()
+			.map(|data| data.0);
+
+		let (roots_match, overlay) = replay(None);
+		assert_eq!(roots_match, vec![true, true, true]);
+		assert_eq!(overlay.storage(&bob_balance).map(|v| v.map(|v| v.to_vec())), Some(chain_bob_balance.clone()));
+
+		let (roots_match, overlay) = replay(Some((b":migrated".to_vec(), vec![1])));
+		assert_eq!(roots_match, vec![false, false, false]);
+		assert_eq!(overlay.storage(b":migrated"), Some(Some(&[1u8][..])));
+		assert_eq!(overlay.storage(&bob_balance).map(|v| v.map(|v| v.to_vec())), Some(chain_bob_balance));
+	}
+
+	#[test]
+	fn state_should_round_trip_through_a_snapshot() {
+		let child_a = b":child_storage:default:a".to_vec();
+		let child_b = b":child_storage:default:b".to_vec();
+		let top: StorageOverlay = (0..100u32)
+			.map(|i| (i.encode(), vec![i as u8; 40]))
+			.chain(::std::iter::once((b"~last".to_vec(), vec![1])))
+			.collect();
+		let child_entries = |n: u32| (0..n).map(|i| ((i * 7).encode(), vec![i as u8; 3])).collect::<StorageOverlay>();
+		let children = vec![(child_a.clone(), child_entries(20)), (child_b.clone(), child_entries(5))]
+			.into_iter()
+			.collect();
+
+		let source = client_db::Backend::<runtime::Block>::new_test(u32::max_value(), 0);
+		let mut operation = source.begin_operation().unwrap();
+		let state_root = operation.reset_storage(top.clone(), children).unwrap();
+		let header = runtime::Header::new(0, Default::default(), state_root, Default::default(), Default::default());
+		let hash = header.hash();
+		operation.set_block_data(header.clone(), None, None, NewBlockState::Final).unwrap();
+		source.commit_operation(operation).unwrap();
+
+		let mut snapshot = Vec::new();
+		let state = source.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(write_state_entries(&state, &mut snapshot).unwrap(), 126);
+
+		// the entries of the child tries come between the entries of the top trie.
+		let child_storage_keys = SnapshotEntries::new(&snapshot[..])
+			.map(|entry| entry.unwrap().0)
+			.collect::<Vec<_>>();
+		assert_eq!(child_storage_keys.first(), Some(&None));
+		assert!(child_storage_keys.contains(&Some(child_a.clone())));
+		assert_eq!(child_storage_keys.last(), Some(&None));
+
+		let aux = vec![(b"aux".to_vec(), vec![42])];
+		let target = client_db::Backend::<runtime::Block>::new_test(u32::max_value(), 0);
+		target.import_state_snapshot(Default::default(), header.clone(), SnapshotEntries::new(&snapshot[..]), aux).unwrap();
+
+		let info = client::blockchain::HeaderBackend::info(target.blockchain()).unwrap();
+		assert_eq!((info.best_hash, info.finalized_hash), (hash, hash));
+		assert_eq!(AuxStore::get_aux(&target, b"aux").unwrap(), Some(vec![42]));
+		let imported = target.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(imported.storage(&50u32.encode()).unwrap(), Some(vec![50; 40]));
+		assert_eq!(imported.storage(b"~last").unwrap(), Some(vec![1]));
+		assert_eq!(imported.child_storage(&child_a, &(19 * 7u32).encode()).unwrap(), Some(vec![19; 3]));
+		assert_eq!(imported.child_storage(&child_b, &(4 * 7u32).encode()).unwrap(), Some(vec![4; 3]));
+		assert_eq!(imported.storage_root(::std::iter::empty()).0, state_root);
+
+		// a snapshot whose entries do not match the state root of its block is refused.
+		let tampered = SnapshotEntries::new(&snapshot[..]).map(|entry| entry.map(|(child_key, key, value)| {
+			if key == 50u32.encode() { (child_key, key, vec![0]) } else { (child_key, key, value) }
+		}));
+		let target = client_db::Backend::<runtime::Block>::new_test(u32::max_value(), 0);
+		assert!(target.import_state_snapshot(Default::default(), header, tampered, Vec::new()).is_err());
+		assert!(target.state_at(BlockId::Hash(hash)).is_err());
+
+		// a truncated snapshot is refused as well.
+		let target = client_db::Backend::<runtime::Block>::new_test(u32::max_value(), 0);
+		let truncated = SnapshotEntries::new(&snapshot[..snapshot.len() / 2]);
+		assert!(target.import_state_snapshot(Default::default(), runtime::Header::new(
+			0, Default::default(), state_root, Default::default(), Default::default(),
+		), truncated, Vec::new()).is_err());
+	}
+
+	#[test]
+	fn state_should_round_trip_through_a_raw_spec() {
+		let top: StorageOverlay = (0..10u32).map(|i| (i.encode(), vec![i as u8; 4])).collect();
+		let child = b":child_storage:default:a".to_vec();
+		let children = vec![(child.clone(), vec![(b"key".to_vec(), vec![1])].into_iter().collect())]
+			.into_iter()
+			.collect();
+
+		let backend = client_db::Backend::<runtime::Block>::new_test(u32::max_value(), 0);
+		let mut operation = backend.begin_operation().unwrap();
+		let state_root = operation.reset_storage(top.clone(), children).unwrap();
+		let header = runtime::Header::new(0, Default::default(), state_root, Default::default(), Default::default());
+		let hash = header.hash();
+		operation.set_block_data(header, None, None, NewBlockState::Final).unwrap();
+		backend.commit_operation(operation).unwrap();
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+
+		// child tries are only left out on demand.
+		assert!(genesis_storage(&state, false).is_err());
+		let storage = genesis_storage(&state, true).unwrap();
+		assert_eq!(storage, top);
+
+		let mut spec = ChainSpec::<RawOnlyGenesis>::from_genesis(
+			"Test", "test", || RawOnlyGenesis, Vec::new(), None, None, None, None,
+		);
+		spec.set_raw_genesis(storage.clone());
+		let json = build_spec(spec, false).unwrap();
+
+		let spec = ChainSpec::<RawOnlyGenesis>::from_embedded(Box::leak(json.into_bytes().into_boxed_slice())).unwrap();
+		let (genesis_top, genesis_children) = (&spec).build_storage().unwrap();
+		assert_eq!(genesis_top, storage);
+		assert!(genesis_children.is_empty());
+	}
+}
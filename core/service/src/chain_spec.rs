@@ -31,6 +31,7 @@ enum GenesisSource<G> {
 	File(PathBuf),
 	Embedded(&'static [u8]),
 	Factory(fn() -> G),
+	Raw(HashMap<StorageKey, StorageData>),
 }
 
 impl<G: RuntimeGenesis> Clone for GenesisSource<G> {
@@ -39,6 +40,7 @@ impl<G: RuntimeGenesis> Clone for GenesisSource<G> {
 			GenesisSource::File(ref path) => GenesisSource::File(path.clone()),
 			GenesisSource::Embedded(d) => GenesisSource::Embedded(d),
 			GenesisSource::Factory(f) => GenesisSource::Factory(f),
+			GenesisSource::Raw(ref map) => GenesisSource::Raw(map.clone()),
 		}
 	}
 }
@@ -61,6 +63,7 @@ impl<G: RuntimeGenesis> GenesisSource<G> {
 				Ok(genesis.genesis)
 			},
 			GenesisSource::Factory(f) => Ok(Genesis::Runtime(f())),
+			GenesisSource::Raw(ref map) => Ok(Genesis::Raw(map.clone())),
 		}
 	}
 }
@@ -157,6 +160,13 @@ impl<G: RuntimeGenesis> ChainSpec<G> {
 		self.spec.boot_nodes.push(addr.to_string())
 	}
 
+	/// Replace the genesis of the spec by the given raw storage.
+	pub fn set_raw_genesis(&mut self, storage: StorageOverlay) {
+		self.genesis = GenesisSource::Raw(
+			storage.into_iter().map(|(k, v)| (StorageKey(k), StorageData(v))).collect()
+		);
+	}
+
 	/// Parse json content into a `ChainSpec`
 	pub fn from_embedded(json: &'static [u8]) -> Result<Self, String> {
 		let spec = json::from_slice(json).map_err(|e| format!("Error parsing spec file: {}", e))?;
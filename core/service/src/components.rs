@@ -145,6 +145,7 @@ pub trait StartRPC<C: Components> {
 		rpc_ws: Option<SocketAddr>,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
+		engine_commands: Option<manual_seal::EngineCommandSender<<ComponentBlock<C> as BlockT>::Hash>>,
 	) -> error::Result<Self::ServersHandle>;
 }
 
@@ -163,6 +164,7 @@ impl<C: Components> StartRPC<Self> for C where
 		rpc_ws: Option<SocketAddr>,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
+		engine_commands: Option<manual_seal::EngineCommandSender<<ComponentBlock<C> as BlockT>::Hash>>,
 	) -> error::Result<Self::ServersHandle> {
 		let handler = || {
 			let client = client.clone();
@@ -175,12 +177,17 @@ impl<C: Components> StartRPC<Self> for C where
 			let system = rpc::apis::system::System::new(
 				rpc_system_info.clone(), network.clone(), should_have_peers
 			);
-			rpc::rpc_handler::<ComponentBlock<C>, ComponentExHash<C>, _, _, _, _>(
+			let mut io = rpc::rpc_handler::<ComponentBlock<C>, ComponentExHash<C>, _, _, _, _>(
 				state,
 				chain,
 				author,
 				system,
-			)
+			);
+			if let Some(ref engine_commands) = engine_commands {
+				use manual_seal::rpc::ManualSealApi;
+				io.extend_with(manual_seal::rpc::ManualSeal::new(engine_commands.clone()).to_delegate());
+			}
+			io
 		};
 
 		Ok((
@@ -457,6 +464,9 @@ impl<Factory: ServiceFactory> Components for FullComponents<Factory> {
 			cache_size: config.database_cache_size.map(|u| u as usize),
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			blocks_pruning: config.blocks_pruning,
+			state_cache: config.state_cache,
+			engine: config.database,
 		};
 		Ok((Arc::new(client_db::new_client(
 			db_settings,
@@ -532,6 +542,9 @@ impl<Factory: ServiceFactory> Components for LightComponents<Factory> {
 			cache_size: None,
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			blocks_pruning: config.blocks_pruning,
+			state_cache: config.state_cache,
+			engine: config.database,
 		};
 		let db_storage = client_db::light::LightStorage::new(db_settings)?;
 		let light_blockchain = client::light::new_light_blockchain(db_storage);
@@ -20,7 +20,7 @@ use std::net::SocketAddr;
 use transaction_pool;
 use crate::chain_spec::ChainSpec;
 pub use client::ExecutionStrategies;
-pub use client_db::PruningMode;
+pub use client_db::{PruningMode, BlocksPruning, DatabaseEngine, StateCacheSizes};
 pub use network::config::{NetworkConfiguration, Roles};
 use runtime_primitives::BuildStorage;
 use serde::{Serialize, de::DeserializeOwned};
@@ -48,8 +48,14 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub database_path: String,
 	/// Cache Size for internal database in MiB
 	pub database_cache_size: Option<u32>,
+	/// Engine of the database.
+	pub database: DatabaseEngine,
+	/// Sizes of the state caches.
+	pub state_cache: StateCacheSizes,
 	/// Pruning settings.
 	pub pruning: PruningMode,
+	/// Block bodies pruning settings.
+	pub blocks_pruning: BlocksPruning,
 	/// Additional key seeds.
 	pub keys: Vec<String>,
 	/// Chain configuration.
@@ -66,6 +72,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub rpc_ws: Option<SocketAddr>,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
+	/// Prometheus metrics binding address. `None` if disabled.
+	pub prometheus_endpoint: Option<SocketAddr>,
 	/// The default number of 64KB pages to allocate for Wasm execution
 	pub default_heap_pages: Option<u64>,
 	/// Should offchain workers be executed.
@@ -74,6 +82,25 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub force_authoring: bool,
 	/// Disable GRANDPA when running in validator mode
 	pub disable_grandpa: bool,
+	/// How blocks are sealed by this node.
+	pub sealing: Sealing,
+}
+
+/// How blocks are sealed by the node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sealing {
+	/// Blocks are sealed by the consensus engine of the node.
+	Consensus,
+	/// A block is sealed as soon as the transaction pool has a new ready transaction.
+	Instant,
+	/// Blocks are only sealed on request, through the `engine_createBlock` RPC.
+	Manual,
+}
+
+impl Default for Sealing {
+	fn default() -> Self {
+		Sealing::Consensus
+	}
 }
 
 impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C, G> {
@@ -91,17 +118,22 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			keystore_path: Default::default(),
 			database_path: Default::default(),
 			database_cache_size: Default::default(),
+			database: Default::default(),
+			state_cache: Default::default(),
 			keys: Default::default(),
 			custom: Default::default(),
 			pruning: PruningMode::default(),
+			blocks_pruning: BlocksPruning::default(),
 			execution_strategies: Default::default(),
 			rpc_http: None,
 			rpc_ws: None,
 			telemetry_endpoints: None,
+			prometheus_endpoint: None,
 			default_heap_pages: None,
 			offchain_worker: Default::default(),
 			force_authoring: false,
 			disable_grandpa: false,
+			sealing: Default::default(),
 		};
 		configuration.network.boot_nodes = configuration.chain_spec.boot_nodes().to_vec();
 
@@ -22,6 +22,7 @@
 mod components;
 mod error;
 mod chain_spec;
+mod metrics;
 pub mod config;
 pub mod chain_ops;
 
@@ -38,12 +39,12 @@ use log::{info, warn, debug};
 use parity_codec::{Encode, Decode};
 use primitives::Pair;
 use runtime_primitives::generic::BlockId;
-use runtime_primitives::traits::{Header, As};
+use runtime_primitives::traits::{Block as BlockT, Header, As};
 use substrate_executor::NativeExecutor;
 use tel::{telemetry, SUBSTRATE_INFO};
 
 pub use self::error::{ErrorKind, Error};
-pub use config::{Configuration, Roles, PruningMode};
+pub use config::{Configuration, Roles, PruningMode, BlocksPruning, DatabaseEngine, StateCacheSizes, Sealing};
 pub use chain_spec::{ChainSpec, Properties};
 pub use transaction_pool::txpool::{
 	self, Pool as TransactionPool, Options as TransactionPoolOptions, ChainApi, IntoPoolError
@@ -78,6 +79,7 @@ pub struct Service<Components: components::Components> {
 	signal: Option<Signal>,
 	/// Configuration of this Service
 	pub config: FactoryFullConfiguration<Components::Factory>,
+	engine_commands: parking_lot::Mutex<Option<manual_seal::EngineCommandStream<<ComponentBlock<Components> as BlockT>::Hash>>>,
 	_rpc: Box<::std::any::Any + Send + Sync>,
 	_telemetry: Option<Arc<tel::Telemetry>>,
 	_offchain_workers: Option<Arc<offchain::OffchainWorkers<ComponentClient<Components>, ComponentBlock<Components>>>>,
@@ -290,14 +292,21 @@ impl<Components: components::Components> Service<Components> {
 			impl_version: config.impl_version.into(),
 			properties: config.chain_spec.properties(),
 		};
+		let (engine_commands_sender, engine_commands) = match config.sealing {
+			config::Sealing::Manual => {
+				let (sender, stream) = manual_seal::channel();
+				(Some(sender), Some(stream))
+			},
+			_ => (None, None),
+		};
 		let rpc = Components::RuntimeServices::start_rpc(
 			client.clone(), network.clone(), has_bootnodes, system_info, config.rpc_http,
-			config.rpc_ws, task_executor.clone(), transaction_pool.clone(),
+			config.rpc_ws, task_executor.clone(), transaction_pool.clone(), engine_commands_sender,
 		)?;
 
 		// Telemetry
 		let telemetry = config.telemetry_endpoints.clone().map(|endpoints| {
-			let is_authority = config.roles == Roles::AUTHORITY;
+			let is_authority = config.roles.contains(Roles::AUTHORITY);
 			let network_id = network.local_peer_id().to_base58();
 			let pubkey = format!("{}", public_key);
 			let name = config.name.clone();
@@ -321,6 +330,16 @@ impl<Components: components::Components> Service<Components> {
 			}))
 		});
 
+		// Prometheus
+		if let Some(address) = config.prometheus_endpoint {
+			let server = prometheus::init_prometheus(address)
+				.map_err(|e| format!("Unable to start the Prometheus server on {}: {}", address, e))?;
+			task_executor.spawn(server.select(exit.clone()).then(|_| Ok(())));
+
+			let metrics = metrics::start::<Components>(&client, &network, &transaction_pool);
+			task_executor.spawn(metrics.select(exit.clone()).then(|_| Ok(())));
+		}
+
 		Ok(Service {
 			client,
 			network: Some(network),
@@ -330,6 +349,7 @@ impl<Components: components::Components> Service<Components> {
 			keystore,
 			config,
 			exit,
+			engine_commands: parking_lot::Mutex::new(engine_commands),
 			_rpc: Box::new(rpc),
 			_telemetry: telemetry,
 			_offchain_workers: offchain_workers,
@@ -338,7 +358,7 @@ impl<Components: components::Components> Service<Components> {
 
 	/// give the authority key, if we are an authority and have a key
 	pub fn authority_key(&self) -> Option<primitives::ed25519::Pair> {
-		if self.config.roles != Roles::AUTHORITY { return None }
+		if !self.config.roles.contains(Roles::AUTHORITY) { return None }
 		let keystore = &self.keystore;
 		if let Ok(Some(Ok(key))) =  keystore.contents().map(|keys| keys.get(0)
 				.map(|k| keystore.load(k, "")))
@@ -385,6 +405,12 @@ impl<Components> Service<Components> where Components: components::Components {
 	pub fn on_exit(&self) -> ::exit_future::Exit {
 		self.exit.clone()
 	}
+
+	/// Take the stream of commands received through the `engine_createBlock`
+	/// RPC. Only available once, and only if the node uses manual sealing.
+	pub fn engine_commands(&self) -> Option<manual_seal::EngineCommandStream<<ComponentBlock<Components> as BlockT>::Hash>> {
+		self.engine_commands.lock().take()
+	}
 }
 
 
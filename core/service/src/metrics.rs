@@ -0,0 +1,102 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the client, the network and the transaction pool, updated on every
+//! network status.
+
+use std::sync::Arc;
+use futures::prelude::*;
+use lazy_static::lazy_static;
+use log::warn;
+use network::SyncProvider;
+use prometheus::{IntGauge, IntGaugeVec};
+use runtime_primitives::traits::As;
+
+use crate::components::{Components, ComponentClient, NetworkService};
+use crate::TransactionPool;
+
+lazy_static! {
+	static ref BEST_HEIGHT: IntGauge = prometheus::int_gauge(
+		"block_height_best",
+		"Number of the best block",
+	);
+	static ref FINALIZED_HEIGHT: IntGauge = prometheus::int_gauge(
+		"block_height_finalized",
+		"Number of the last finalized block",
+	);
+	static ref PEERS: IntGauge = prometheus::int_gauge(
+		"network_peers",
+		"Number of connected peers",
+	);
+	static ref BANDWIDTH: IntGaugeVec = prometheus::int_gauge_vec(
+		"network_bandwidth_bytes_per_second",
+		"Average bandwidth of the network",
+		&["direction"],
+	);
+	static ref POOL_TRANSACTIONS: IntGaugeVec = prometheus::int_gauge_vec(
+		"transaction_pool_transactions",
+		"Number of transactions in the transaction pool",
+		&["queue"],
+	);
+	static ref POOL_BYTES: IntGaugeVec = prometheus::int_gauge_vec(
+		"transaction_pool_bytes",
+		"Size of the transactions in the transaction pool",
+		&["queue"],
+	);
+}
+
+/// Returns a future updating the metrics until the network is dropped.
+pub(crate) fn start<C: Components>(
+	client: &Arc<ComponentClient<C>>,
+	network: &Arc<NetworkService<C::Factory>>,
+	transaction_pool: &Arc<TransactionPool<C::TransactionPoolApi>>,
+) -> impl Future<Item=(), Error=()> {
+	let client = Arc::downgrade(client);
+	let txpool = Arc::downgrade(transaction_pool);
+	let status = network.status();
+	let network = Arc::downgrade(network);
+
+	status.for_each(move |status| {
+		PEERS.set(status.num_peers as i64);
+
+		if let Some(client) = client.upgrade() {
+			match client.info() {
+				Ok(info) => {
+					let best_number: u64 = info.chain.best_number.as_();
+					let finalized_number: u64 = info.chain.finalized_number.as_();
+					BEST_HEIGHT.set(best_number as i64);
+					FINALIZED_HEIGHT.set(finalized_number as i64);
+				},
+				Err(e) => warn!("Error getting best block information: {:?}", e),
+			}
+		}
+
+		if let Some(network) = network.upgrade() {
+			BANDWIDTH.with_label_values(&["download"]).set(network.average_download_per_sec() as i64);
+			BANDWIDTH.with_label_values(&["upload"]).set(network.average_upload_per_sec() as i64);
+		}
+
+		if let Some(txpool) = txpool.upgrade() {
+			let pool_status = txpool.status();
+			POOL_TRANSACTIONS.with_label_values(&["ready"]).set(pool_status.ready as i64);
+			POOL_TRANSACTIONS.with_label_values(&["future"]).set(pool_status.future as i64);
+			POOL_BYTES.with_label_values(&["ready"]).set(pool_status.ready_bytes as i64);
+			POOL_BYTES.with_label_values(&["future"]).set(pool_status.future_bytes as i64);
+		}
+
+		Ok(())
+	})
+}
@@ -110,7 +110,10 @@ fn node_config<F: ServiceFactory> (
 		keystore_path: root.join("key").to_str().unwrap().into(),
 		database_path: root.join("db").to_str().unwrap().into(),
 		database_cache_size: None,
+		database: Default::default(),
+		state_cache: Default::default(),
 		pruning: Default::default(),
+		blocks_pruning: Default::default(),
 		keys: keys,
 		chain_spec: (*spec).clone(),
 		custom: Default::default(),
@@ -119,10 +122,12 @@ fn node_config<F: ServiceFactory> (
 		rpc_http: None,
 		rpc_ws: None,
 		telemetry_endpoints: None,
+		prometheus_endpoint: None,
 		default_heap_pages: None,
 		offchain_worker: false,
 		force_authoring: false,
 		disable_grandpa: false,
+		sealing: Default::default(),
 	}
 }
 
@@ -226,6 +226,18 @@ pub fn submit_extrinsic<T: codec::Encode>(data: &T) {
 	).expect("submit_extrinsic cannot be called outside of an Externalities-provided environment.")
 }
 
+/// The current time of the host in nanoseconds, used to measure the duration of benchmarks.
+///
+/// The value is only meaningful relative to other values returned by this function. Only
+/// available with the `benchmarking` feature, since the time is not deterministic.
+#[cfg(feature = "benchmarking")]
+pub fn benchmark_time() -> u64 {
+	let now = std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.expect("the system time is after the unix epoch; qed");
+	now.as_secs() * 1_000_000_000 + now.subsec_nanos() as u64
+}
+
 /// Execute the given closure with global function available whose functionality routes into the
 /// externalities `ext`. Forwards the value that the closure returns.
 // NOTE: need a concrete hasher here due to limitations of the `environmental!` macro, otherwise a type param would have been fine I think.
@@ -290,6 +290,13 @@ extern_functions! {
 
 	/// Submit extrinsic.
 	fn ext_submit_extrinsic(data: *const u8, len: u32);
+
+	//================================
+	// Benchmarking
+	//================================
+
+	/// The current time of the host in nanoseconds.
+	fn ext_benchmark_time() -> u64;
 }
 
 /// Ensures we use the right crypto when calling into native
@@ -613,6 +620,17 @@ pub fn submit_extrinsic<T: codec::Encode>(data: &T) {
 	}
 }
 
+/// The current time of the host in nanoseconds, used to measure the duration of benchmarks.
+///
+/// The value is only meaningful relative to other values returned by this function. Only
+/// available with the `benchmarking` feature, since the time is not deterministic.
+#[cfg(feature = "benchmarking")]
+pub fn benchmark_time() -> u64 {
+	unsafe {
+		ext_benchmark_time.get()()
+	}
+}
+
 /// Trait for things which can be printed.
 pub trait Printable {
 	fn print(self);
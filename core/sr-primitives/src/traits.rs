@@ -288,6 +288,34 @@ pub trait OffchainWorker<BlockNumber> {
 
 impl<N> OffchainWorker<N> for () {}
 
+/// The runtime upgrade trait. Implementing this lets you migrate the storage of your module
+/// when the runtime is upgraded.
+///
+/// It is called exactly once, at the beginning of the first block executed with a new
+/// `spec_version`, before `on_initialise`.
+pub trait OnRuntimeUpgrade {
+	/// The runtime has been upgraded. Implement to migrate the storage of the module.
+	fn on_runtime_upgrade() {}
+}
+
+impl OnRuntimeUpgrade for () {}
+
+/// Provide validation for unsigned extrinsics.
+///
+/// Unsigned extrinsics have no sender paying fees or incrementing a nonce, so
+/// the transaction pool relies on the runtime to tell whether they may be
+/// included, e.g. extrinsics submitted by offchain workers.
+pub trait ValidateUnsigned {
+	/// The call to validate.
+	type Call;
+
+	/// Return the validity of the call.
+	///
+	/// This doesn't execute any side-effects; it merely checks
+	/// whether the call would be accepted if it were included in a block.
+	fn validate_unsigned(call: &Self::Call) -> crate::transaction_validity::TransactionValidity;
+}
+
 macro_rules! tuple_impl {
 	($one:ident,) => {
 		impl<Number: Copy, $one: OnFinalise<Number>> OnFinalise<Number> for ($one,) {
@@ -305,6 +333,11 @@ macro_rules! tuple_impl {
 				$one::generate_extrinsics(n);
 			}
 		}
+		impl<$one: OnRuntimeUpgrade> OnRuntimeUpgrade for ($one,) {
+			fn on_runtime_upgrade() {
+				$one::on_runtime_upgrade();
+			}
+		}
 	};
 	($first:ident, $($rest:ident,)+) => {
 		impl<
@@ -337,6 +370,15 @@ macro_rules! tuple_impl {
 				$($rest::generate_extrinsics(n);)+
 			}
 		}
+		impl<
+			$first: OnRuntimeUpgrade,
+			$($rest: OnRuntimeUpgrade),+
+		> OnRuntimeUpgrade for ($first, $($rest),+) {
+			fn on_runtime_upgrade() {
+				$first::on_runtime_upgrade();
+				$($rest::on_runtime_upgrade();)+
+			}
+		}
 		tuple_impl!($($rest,)+);
 	}
 }
@@ -0,0 +1,182 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checkpoints.
+//!
+//! The state of every canonical block whose number is a multiple of the checkpoint interval is
+//! kept forever. Nodes are not reference counted, so a node deleted by a canonical block is only
+//! handed over to the pruning window if it was inserted after the last checkpoint, i.e. if the first
+//! change to the node since the last checkpoint was an insertion. All other deleted nodes may be part
+//! of the state of a checkpoint and are kept.
+//!
+//! The changes since the last checkpoint are tracked in memory only. After a restart, or after
+//! pending changes are reverted, no node is deleted until the next checkpoint.
+//!
+//! The list of checkpoints is journaled in the DB.
+
+use std::collections::HashMap;
+use crate::codec::{Encode, Decode};
+use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
+use log::trace;
+
+const CHECKPOINTS: &[u8] = b"checkpoints";
+
+/// See module documentation.
+pub struct Checkpoints<BlockHash: Hash, Key: Hash> {
+	interval: u64,
+	/// Numbers and hashes of the checkpoints, in increasing order. Includes pending checkpoints.
+	blocks: Vec<(u64, BlockHash)>,
+	/// For each node changed since the last checkpoint, whether its first change was an insertion.
+	/// `None` if the changes since the last checkpoint are not known.
+	touched: Option<HashMap<Key, bool>>,
+	/// Number of checkpoints added after last call `apply_pending` or `revert_pending`.
+	pending_checkpoints: usize,
+}
+
+impl<BlockHash: Hash, Key: Hash> Checkpoints<BlockHash, Key> {
+	pub fn new<D: MetaDb>(interval: u64, db: &D) -> Result<Checkpoints<BlockHash, Key>, Error<D::Error>> {
+		if interval == 0 {
+			return Err(Error::InvalidCheckpointInterval);
+		}
+		let blocks = match db.get_meta(&to_meta_key(CHECKPOINTS, &())).map_err(|e| Error::Db(e))? {
+			Some(buffer) => Decode::decode(&mut buffer.as_slice()).ok_or(Error::Decoding)?,
+			None => Vec::new(),
+		};
+		trace!(target: "state-db", "Checkpoints every {} blocks, {} stored", interval, blocks.len());
+		Ok(Checkpoints {
+			interval,
+			blocks,
+			touched: None,
+			pending_checkpoints: 0,
+		})
+	}
+
+	/// Whether the state of the given block is kept as a checkpoint.
+	pub fn contains(&self, hash: &BlockHash, number: u64) -> bool {
+		self.blocks.binary_search_by_key(&number, |&(n, _)| n)
+			.map_or(false, |index| self.blocks[index].1 == *hash)
+	}
+
+	/// The checkpoint closest to the given block number.
+	pub fn nearest(&self, number: u64) -> Option<(u64, BlockHash)> {
+		self.blocks.iter()
+			.min_by_key(|&&(n, _)| if n > number { n - number } else { number - n })
+			.cloned()
+	}
+
+	/// Remove the nodes which may be part of the state of a checkpoint from the deleted nodes of a
+	/// canonicalized block, and start a new checkpoint if the block is one.
+	pub fn note_canonical(&mut self, hash: &BlockHash, number: u64, commit: &mut CommitSet<Key>) {
+		match self.touched {
+			Some(ref mut touched) => {
+				for (key, _) in commit.data.inserted.iter() {
+					touched.entry(key.clone()).or_insert(true);
+				}
+				commit.data.deleted.retain(|key| *touched.entry(key.clone()).or_insert(false));
+			},
+			None => commit.data.deleted.clear(),
+		}
+
+		if number % self.interval == 0 {
+			trace!(target: "state-db", "Adding checkpoint {:?} (#{})", hash, number);
+			self.blocks.push((number, hash.clone()));
+			self.pending_checkpoints += 1;
+			self.touched = Some(Default::default());
+			commit.meta.inserted.push((to_meta_key(CHECKPOINTS, &()), self.blocks.encode()));
+		}
+	}
+
+	/// Apply all pending changes
+	pub fn apply_pending(&mut self) {
+		self.pending_checkpoints = 0;
+	}
+
+	/// Revert all pending changes
+	pub fn revert_pending(&mut self) {
+		let len = self.blocks.len() - self.pending_checkpoints;
+		self.blocks.truncate(len);
+		self.pending_checkpoints = 0;
+		self.touched = None;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Checkpoints;
+	use primitives::H256;
+	use crate::test::{make_db, make_commit};
+
+	#[test]
+	fn keeps_deleted_nodes_unless_inserted_since_checkpoint() {
+		let mut db = make_db(&[]);
+		let mut checkpoints: Checkpoints<H256, H256> = Checkpoints::new(2, &db).unwrap();
+
+		// changes before the first checkpoint are not known.
+		let mut commit = make_commit(&[2], &[1]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(1), 1, &mut commit);
+		assert!(commit.data.deleted.is_empty());
+
+		let mut commit = make_commit(&[3], &[2]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(2), 2, &mut commit);
+		assert!(commit.data.deleted.is_empty());
+		db.commit(&commit);
+		checkpoints.apply_pending();
+
+		let mut commit = make_commit(&[4, 5], &[3]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(3), 3, &mut commit);
+		assert!(commit.data.deleted.is_empty());
+
+		let mut commit = make_commit(&[3], &[4, 3]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(4), 4, &mut commit);
+		assert_eq!(commit.data.deleted, vec![H256::from_low_u64_be(4)]);
+		db.commit(&commit);
+		checkpoints.apply_pending();
+
+		let restored: Checkpoints<H256, H256> = Checkpoints::new(2, &db).unwrap();
+		assert_eq!(restored.blocks, checkpoints.blocks);
+		assert!(restored.contains(&H256::from_low_u64_be(2), 2));
+		assert!(!restored.contains(&H256::from_low_u64_be(3), 2));
+		assert!(!restored.contains(&H256::from_low_u64_be(3), 3));
+		assert_eq!(restored.nearest(7), Some((4, H256::from_low_u64_be(4))));
+		assert_eq!(restored.nearest(1), Some((2, H256::from_low_u64_be(2))));
+	}
+
+	#[test]
+	fn revert_pending_forgets_checkpoint_and_changes() {
+		let db = make_db(&[]);
+		let mut checkpoints: Checkpoints<H256, H256> = Checkpoints::new(2, &db).unwrap();
+		let mut commit = make_commit(&[], &[]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(2), 2, &mut commit);
+		checkpoints.apply_pending();
+
+		let mut commit = make_commit(&[4], &[]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(3), 3, &mut commit);
+		let mut commit = make_commit(&[], &[]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(4), 4, &mut commit);
+		checkpoints.revert_pending();
+		assert!(!checkpoints.contains(&H256::from_low_u64_be(4), 4));
+
+		let mut commit = make_commit(&[], &[4]);
+		checkpoints.note_canonical(&H256::from_low_u64_be(3), 3, &mut commit);
+		assert!(commit.data.deleted.is_empty());
+	}
+
+	#[test]
+	fn interval_of_zero_is_refused() {
+		let db = make_db(&[]);
+		assert!(Checkpoints::<H256, H256>::new(0, &db).is_err());
+	}
+}
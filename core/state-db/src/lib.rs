@@ -27,10 +27,11 @@
 //!
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until pruning
-//! constraints are satisfied.
+//! constraints are satisfied. See `Checkpoints` for the states kept beyond the pruning window.
 
 mod noncanonical;
 mod pruning;
+mod checkpoints;
 #[cfg(test)] mod test;
 
 use std::fmt;
@@ -40,6 +41,7 @@ use codec::Codec;
 use std::collections::HashSet;
 use noncanonical::NonCanonicalOverlay;
 use pruning::RefWindow;
+use checkpoints::Checkpoints;
 use log::trace;
 
 /// Database value type.
@@ -78,6 +80,8 @@ pub enum Error<E: fmt::Debug> {
 	InvalidBlockNumber,
 	/// Trying to insert block with unknown parent.
 	InvalidParent,
+	/// Trying to keep checkpoints every 0 blocks.
+	InvalidCheckpointInterval,
 }
 
 impl<E: fmt::Debug> fmt::Debug for Error<E> {
@@ -88,6 +92,7 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlock => write!(f, "Trying to canonicalize invalid block"),
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
+			Error::InvalidCheckpointInterval => write!(f, "Trying to keep checkpoints every 0 blocks"),
 		}
 	}
 }
@@ -102,6 +107,22 @@ pub struct ChangeSet<H: Hash> {
 }
 
 
+/// Estimate of the memory used by the state database overlays.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryInfo {
+	/// Memory used by the non-canonical blocks in bytes.
+	pub non_canonical: usize,
+	/// Memory used by the pruning window in bytes.
+	pub pruning: usize,
+}
+
+impl MemoryInfo {
+	/// Total memory used in bytes.
+	pub fn total(&self) -> usize {
+		self.non_canonical + self.pruning
+	}
+}
+
 /// A set of changes to the backing database.
 #[derive(Default, Debug, Clone)]
 pub struct CommitSet<H: Hash> {
@@ -118,6 +139,8 @@ pub struct Constraints {
 	pub max_blocks: Option<u32>,
 	/// Maximum memory in the pruning overlay.
 	pub max_mem: Option<usize>,
+	/// Keep the state of every canonical block whose number is a multiple of this interval.
+	pub checkpoint_interval: Option<u32>,
 }
 
 /// Pruning mode.
@@ -137,6 +160,16 @@ impl PruningMode {
 		PruningMode::Constrained(Constraints {
 			max_blocks: Some(n),
 			max_mem: None,
+			checkpoint_interval: None,
+		})
+	}
+
+	/// Create a mode that keeps given number of blocks and the state of every `interval`th block.
+	pub fn keep_blocks_and_checkpoints(n: u32, interval: u32) -> PruningMode {
+		PruningMode::Constrained(Constraints {
+			max_blocks: Some(n),
+			max_mem: None,
+			checkpoint_interval: Some(interval),
 		})
 	}
 
@@ -166,6 +199,7 @@ struct StateDbSync<BlockHash: Hash, Key: Hash> {
 	mode: PruningMode,
 	non_canonical: NonCanonicalOverlay<BlockHash, Key>,
 	pruning: Option<RefWindow<BlockHash, Key>>,
+	checkpoints: Option<Checkpoints<BlockHash, Key>>,
 	pinned: HashSet<BlockHash>,
 }
 
@@ -181,10 +215,18 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 			PruningMode::Constrained(_) => Some(RefWindow::new(db)?),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
+		let checkpoints: Option<Checkpoints<BlockHash, Key>> = match mode {
+			PruningMode::Constrained(Constraints {
+				checkpoint_interval: Some(interval),
+				..
+			}) => Some(Checkpoints::new(interval as u64, db)?),
+			_ => None,
+		};
 		Ok(StateDbSync {
 			mode,
 			non_canonical,
 			pruning,
+			checkpoints,
 			pinned: Default::default(),
 		})
 	}
@@ -216,7 +258,13 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 				commit
 			},
 			PruningMode::Constrained(_) => {
-				self.non_canonical.canonicalize(hash)?
+				let mut commit = self.non_canonical.canonicalize(hash)?;
+				if let Some(ref mut checkpoints) = self.checkpoints {
+					let number = self.non_canonical.last_canonicalized_block_number()
+						.expect("a block has just been canonicalized; qed");
+					checkpoints.note_canonical(hash, number, &mut commit);
+				}
+				commit
 			},
 		};
 		if let Some(ref mut pruning) = self.pruning {
@@ -234,7 +282,9 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		match self.mode {
 			PruningMode::ArchiveAll => false,
 			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
-				if self.best_canonical().map(|c| number > c).unwrap_or(true) {
+				if self.checkpoints.as_ref().map_or(false, |checkpoints| checkpoints.contains(hash, number)) {
+					false
+				} else if self.best_canonical().map(|c| number > c).unwrap_or(true) {
 					!self.non_canonical.have_block(hash)
 				} else {
 					self.pruning.as_ref().map_or(false, |pruning| number < pruning.pending() || !pruning.have_block(hash))
@@ -243,6 +293,17 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		}
 	}
 
+	pub fn nearest_checkpoint(&self, number: u64) -> Option<(u64, BlockHash)> {
+		self.checkpoints.as_ref().and_then(|checkpoints| checkpoints.nearest(number))
+	}
+
+	pub fn memory_info(&self) -> MemoryInfo {
+		MemoryInfo {
+			non_canonical: self.non_canonical.mem_used(),
+			pruning: self.pruning.as_ref().map_or(0, |pruning| pruning.mem_used()),
+		}
+	}
+
 	fn prune(&mut self, commit: &mut CommitSet<Key>) {
 		if let (&mut Some(ref mut pruning), &PruningMode::Constrained(ref constraints)) = (&mut self.pruning, &self.mode) {
 			loop {
@@ -299,6 +360,9 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		if let Some(pruning) = &mut self.pruning {
 			pruning.apply_pending();
 		}
+		if let Some(checkpoints) = &mut self.checkpoints {
+			checkpoints.apply_pending();
+		}
 		trace!(target: "forks", "First available: {:?} ({}), Last canon: {:?} ({}), Best forks: {:?}",
 			self.pruning.as_ref().and_then(|p| p.next_hash()),
 			self.pruning.as_ref().map(|p| p.pending()).unwrap_or(0),
@@ -312,6 +376,9 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		if let Some(pruning) = &mut self.pruning {
 			pruning.revert_pending();
 		}
+		if let Some(checkpoints) = &mut self.checkpoints {
+			checkpoints.revert_pending();
+		}
 		self.non_canonical.revert_pending();
 	}
 }
@@ -374,6 +441,16 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 		return self.db.read().is_pruned(hash, number)
 	}
 
+	/// Returns the number and hash of the checkpoint closest to the given block number, if any.
+	pub fn nearest_checkpoint(&self, number: u64) -> Option<(u64, BlockHash)> {
+		self.db.read().nearest_checkpoint(number)
+	}
+
+	/// Returns an estimate of the memory used by the in-memory overlays.
+	pub fn memory_info(&self) -> MemoryInfo {
+		self.db.read().memory_info()
+	}
+
 	/// Apply all pending changes
 	pub fn apply_pending(&self) {
 		self.db.write().apply_pending();
@@ -465,6 +542,31 @@ mod tests {
 		assert!(!sdb.is_pruned(&H256::from_low_u64_be(0), 0));
 	}
 
+	#[test]
+	fn memory_info_accounts_for_overlays() {
+		let db = make_db(&[]);
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(1), &db).unwrap();
+		assert_eq!(state_db.memory_info(), Default::default());
+
+		state_db.insert_block::<io::Error>(
+			&H256::from_low_u64_be(1),
+			1,
+			&H256::from_low_u64_be(0),
+			make_changeset(&[1], &[]),
+		).unwrap();
+		state_db.apply_pending();
+		let info = state_db.memory_info();
+		assert!(info.non_canonical > 0);
+		assert_eq!(info.pruning, 0);
+
+		state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(1)).unwrap();
+		state_db.apply_pending();
+		let info = state_db.memory_info();
+		assert_eq!(info.non_canonical, 0);
+		assert!(info.pruning > 0);
+		assert_eq!(info.total(), info.pruning);
+	}
+
 	#[test]
 	fn canonical_archive_keeps_canonical() {
 		let (db, _) = make_test_db(PruningMode::ArchiveCanonical);
@@ -476,6 +578,7 @@ mod tests {
 		let (db, _) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(0),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
 	}
@@ -485,6 +588,7 @@ mod tests {
 		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(1),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(0), 0));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(1), 1));
@@ -498,6 +602,7 @@ mod tests {
 		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(2),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(0), 0));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(1), 1));
@@ -505,4 +610,32 @@ mod tests {
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(22), 2));
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
+
+	#[test]
+	fn prune_window_1_with_checkpoints() {
+		let mode = PruningMode::keep_blocks_and_checkpoints(1, 2);
+		let mut db = make_db(&[]);
+		let state_db: StateDb<H256, H256> = StateDb::new(mode.clone(), &db).unwrap();
+		let hash = |n: u64| H256::from_low_u64_be(100 + n);
+		for n in 0..5 {
+			let parent = if n == 0 { H256::default() } else { hash(n - 1) };
+			let deleted = if n == 0 { vec![] } else { vec![n] };
+			db.commit(&state_db.insert_block::<io::Error>(&hash(n), n, &parent, make_changeset(&[n + 1], &deleted)).unwrap());
+			state_db.apply_pending();
+			db.commit(&state_db.canonicalize_block::<io::Error>(&hash(n)).unwrap());
+			state_db.apply_pending();
+		}
+
+		// the states of #0 and #2 are kept, the nodes deleted by #1 and #3 being part of them.
+		assert!(db.data_eq(&make_db(&[1, 3, 4, 5])));
+		assert!(!state_db.is_pruned(&hash(0), 0));
+		assert!(state_db.is_pruned(&hash(1), 1));
+		assert!(!state_db.is_pruned(&hash(2), 2));
+		assert!(state_db.is_pruned(&hash(3), 3));
+		assert!(!state_db.is_pruned(&hash(4), 4));
+
+		let restored: StateDb<H256, H256> = StateDb::new(mode, &db).unwrap();
+		assert!(!restored.is_pruned(&hash(2), 2));
+		assert_eq!(restored.nearest_checkpoint(7), Some((4, hash(4))));
+	}
 }
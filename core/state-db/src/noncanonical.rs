@@ -20,7 +20,7 @@
 //! All pending changes are kept in memory until next call to `apply_pending` or
 //! `revert_pending`
 
-use std::fmt;
+use std::{fmt, mem};
 use std::collections::{HashMap, VecDeque, hash_map::Entry};
 use super::{Error, DBValue, ChangeSet, CommitSet, MetaDb, Hash, to_meta_key};
 use crate::codec::{Encode, Decode};
@@ -35,6 +35,8 @@ pub struct NonCanonicalOverlay<BlockHash: Hash, Key: Hash> {
 	levels: VecDeque<Vec<BlockOverlay<BlockHash, Key>>>,
 	parents: HashMap<BlockHash, BlockHash>,
 	pending_canonicalizations: Vec<BlockHash>,
+	/// The last block of `pending_canonicalizations`, with its number, as written to the meta.
+	pending_last_canonicalized: Option<(BlockHash, u64)>,
 	pending_insertions: Vec<BlockHash>,
 	values: HashMap<Key, (u32, DBValue)>, //ref counted
 }
@@ -165,6 +167,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			levels,
 			parents,
 			pending_canonicalizations: Default::default(),
+			pending_last_canonicalized: None,
 			pending_insertions: Default::default(),
 			values: values,
 		})
@@ -247,11 +250,9 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	}
 
 	pub fn last_canonicalized_block_number(&self) -> Option<u64> {
-		match self.last_canonicalized.as_ref().map(|&(_, n)| n) {
-			Some(n) => Some(n + self.pending_canonicalizations.len() as u64),
-			None if !self.pending_canonicalizations.is_empty() => Some(self.pending_canonicalizations.len() as u64),
-			_ => None,
-		}
+		self.pending_last_canonicalized.as_ref()
+			.or(self.last_canonicalized.as_ref())
+			.map(|&(_, n)| n)
 	}
 
 	pub fn last_canonicalized_hash(&self) -> Option<BlockHash> {
@@ -295,12 +296,11 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), canonicalized.encode()));
 		trace!(target: "state-db", "Discarding {} records", commit.meta.deleted.len());
 		self.pending_canonicalizations.push(hash.clone());
+		self.pending_last_canonicalized = Some(canonicalized);
 		Ok(commit)
 	}
 
 	fn apply_canonicalizations(&mut self) {
-		let last = self.pending_canonicalizations.last().cloned();
-		let count = self.pending_canonicalizations.len() as u64;
 		for hash in self.pending_canonicalizations.drain(..) {
 			trace!(target: "state-db", "Post canonicalizing {:?}", hash);
 			let level = self.levels.pop_front().expect("Hash validity is checked in `canonicalize`");
@@ -318,8 +318,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 				discard_values(&mut self.values, overlay.inserted);
 			}
 		}
-		if let Some(hash) = last {
-			let last_canonicalized = (hash, self.last_canonicalized.as_ref().map(|(_, n)| n + count).unwrap_or(count - 1));
+		if let Some(last_canonicalized) = self.pending_last_canonicalized.take() {
 			self.last_canonicalized = Some(last_canonicalized);
 		}
 	}
@@ -332,6 +331,20 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		None
 	}
 
+	/// Estimate of the memory used by the overlay in bytes.
+	pub fn mem_used(&self) -> usize {
+		let values: usize = self.values.values()
+			.map(|(_, value)| mem::size_of::<Key>() + mem::size_of::<u32>() + value.len())
+			.sum();
+		let overlays: usize = self.levels.iter()
+			.flat_map(|level| level.iter())
+			.map(|overlay| mem::size_of::<BlockOverlay<BlockHash, Key>>()
+				+ overlay.journal_key.len()
+				+ (overlay.inserted.len() + overlay.deleted.len()) * mem::size_of::<Key>())
+			.sum();
+		values + overlays + self.parents.len() * 2 * mem::size_of::<BlockHash>()
+	}
+
 	/// Check if the block is in the canonicalization queue. 
 	pub fn have_block(&self, hash: &BlockHash) -> bool {
 		(self.parents.contains_key(hash) || self.pending_insertions.contains(hash))
@@ -379,6 +392,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	/// Revert all pending changes
 	pub fn revert_pending(&mut self) {
 		self.pending_canonicalizations.clear();
+		self.pending_last_canonicalized = None;
 		self.revert_insertions();
 	}
 }
@@ -404,6 +418,30 @@ mod tests {
 		assert!(overlay.parents.is_empty());
 	}
 
+	#[test]
+	fn last_canonicalized_block_number_is_the_one_written_to_meta() {
+		let db = make_db(&[]);
+		let h1 = H256::random();
+		let h2 = H256::random();
+		let mut overlay = NonCanonicalOverlay::<H256, H256>::new(&db).unwrap();
+		overlay.insert::<io::Error>(&h1, 10, &H256::default(), ChangeSet::default()).unwrap();
+		overlay.insert::<io::Error>(&h2, 11, &h1, ChangeSet::default()).unwrap();
+		overlay.apply_pending();
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(9));
+
+		overlay.canonicalize::<io::Error>(&h1).unwrap();
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(10));
+		overlay.revert_pending();
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(9));
+
+		overlay.canonicalize::<io::Error>(&h1).unwrap();
+		overlay.canonicalize::<io::Error>(&h2).unwrap();
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(11));
+		overlay.apply_pending();
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(11));
+		assert_eq!(overlay.last_canonicalized, Some((h2, 11)));
+	}
+
 	#[test]
 	#[should_panic]
 	fn canonicalize_empty_panics() {
@@ -22,6 +22,7 @@
 //! the death list.
 //! The changes are journaled in the DB.
 
+use std::mem;
 use std::collections::{HashMap, HashSet, VecDeque};
 use crate::codec::{Encode, Decode};
 use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
@@ -127,8 +128,14 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		self.death_rows.get(self.pending_prunings).map(|r| r.hash.clone())
 	}
 
+	/// Estimate of the memory used by the window in bytes.
 	pub fn mem_used(&self) -> usize {
-		0
+		let rows: usize = self.death_rows.iter()
+			.map(|row| mem::size_of::<DeathRow<BlockHash, Key>>()
+				+ row.journal_key.len()
+				+ row.deleted.len() * mem::size_of::<Key>())
+			.sum();
+		rows + self.death_index.len() * (mem::size_of::<Key>() + mem::size_of::<u64>())
 	}
 
 	pub fn pending(&self) -> u64 {
@@ -24,7 +24,8 @@ use log::warn;
 use hash_db::Hasher;
 use crate::trie_backend::TrieBackend;
 use crate::trie_backend_essence::TrieBackendStorage;
-use trie::{TrieDBMut, TrieMut, MemoryDB, trie_root, child_trie_root, default_child_trie_root};
+use trie::{TrieDBMut, TrieMut, MemoryDB, DBValue, KeySpacedDBMut, trie_root, child_trie_root,
+	child_trie_keyspace, default_child_trie_root};
 use heapsize::HeapSizeOf;
 
 /// A state backend is used to read state data and can have changes committed
@@ -293,27 +294,36 @@ impl<H: Hasher> Backend<H> for InMemory<H> where H::Out: HeapSizeOf {
 
 	fn try_into_trie_backend(self) -> Option<TrieBackend<Self::TrieBackendStorage, H>> {
 		let mut mdb = MemoryDB::default();
-		let mut root = None;
+		let mut top = HashMap::new();
+		let mut child_roots = Vec::new();
 		for (storage_key, map) in self.inner {
-			if storage_key != None {
-				let _ = insert_into_memory_db::<H, _>(&mut mdb, map.into_iter())?;
-			} else {
-				root = Some(insert_into_memory_db::<H, _>(&mut mdb, map.into_iter())?);
+			match storage_key {
+				Some(storage_key) => {
+					if map.is_empty() {
+						continue;
+					}
+					let keyspace = child_trie_keyspace::<H>(&storage_key);
+					let child_root = insert_into_memory_db::<H, _, _>(
+						&mut KeySpacedDBMut::<_, H>::new(&mut mdb, keyspace.as_ref()),
+						map.into_iter(),
+					)?;
+					child_roots.push((storage_key, child_root.as_ref().to_vec()));
+				},
+				None => top = map,
 			}
 		}
-		let root = match root {
-			Some(root) => root,
-			None => insert_into_memory_db::<H, _>(&mut mdb, ::std::iter::empty())?,
-		};
+		top.extend(child_roots);
+		let root = insert_into_memory_db::<H, _, _>(&mut mdb, top.into_iter())?;
 		Some(TrieBackend::new(mdb, root))
 	}
 }
 
 /// Insert input pairs into memory db.
-pub(crate) fn insert_into_memory_db<H, I>(mdb: &mut MemoryDB<H>, input: I) -> Option<H::Out>
+pub(crate) fn insert_into_memory_db<H, DB, I>(mdb: &mut DB, input: I) -> Option<H::Out>
 	where
 		H: Hasher,
 		H::Out: HeapSizeOf,
+		DB: hash_db::HashDB<H, DBValue>,
 		I: IntoIterator<Item=(Vec<u8>, Vec<u8>)>,
 {
 	let mut root = <H as Hasher>::Out::default();
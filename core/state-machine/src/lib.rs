@@ -628,6 +628,38 @@ where
 	Ok((result, proving_backend.extract_proof()))
 }
 
+/// Generate child storage read proof.
+pub fn prove_child_read<B, H>(
+	backend: B,
+	storage_key: &[u8],
+	key: &[u8],
+) -> Result<(Option<Vec<u8>>, Vec<Vec<u8>>), Box<Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + HeapSizeOf
+{
+	let trie_backend = backend.try_into_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<Error>)?;
+	prove_child_read_on_trie_backend(&trie_backend, storage_key, key)
+}
+
+/// Generate child storage read proof on pre-created trie backend.
+pub fn prove_child_read_on_trie_backend<S, H>(
+	trie_backend: &TrieBackend<S, H>,
+	storage_key: &[u8],
+	key: &[u8]
+) -> Result<(Option<Vec<u8>>, Vec<Vec<u8>>), Box<Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + HeapSizeOf
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	let result = proving_backend.child_storage(storage_key, key).map_err(|e| Box::new(e) as Box<Error>)?;
+	Ok((result, proving_backend.extract_proof()))
+}
+
 /// Check storage read proof, generated by `prove_read` call.
 pub fn read_proof_check<H>(
 	root: H::Out,
@@ -654,6 +686,34 @@ where
 	proving_backend.storage(key).map_err(|e| Box::new(e) as Box<Error>)
 }
 
+/// Check child storage read proof, generated by `prove_child_read` call.
+pub fn read_child_proof_check<H>(
+	root: H::Out,
+	proof: Vec<Vec<u8>>,
+	storage_key: &[u8],
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, Box<Error>>
+where
+	H: Hasher,
+	H::Out: Ord + HeapSizeOf
+{
+	let proving_backend = proving_backend::create_proof_check_backend::<H>(root, proof)?;
+	read_child_proof_check_on_proving_backend(&proving_backend, storage_key, key)
+}
+
+/// Check child storage read proof on pre-created proving backend.
+pub fn read_child_proof_check_on_proving_backend<H>(
+	proving_backend: &TrieBackend<MemoryDB<H>, H>,
+	storage_key: &[u8],
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, Box<Error>>
+where
+	H: Hasher,
+	H::Out: Ord + HeapSizeOf
+{
+	proving_backend.child_storage(storage_key, key).map_err(|e| Box::new(e) as Box<Error>)
+}
+
 /// Sets overlayed changes' changes trie configuration. Returns error if configuration
 /// differs from previous OR config decode has failed.
 pub(crate) fn set_changes_trie_config(overlay: &mut OverlayedChanges, config: Option<Vec<u8>>, final_check: bool) -> Result<(), Box<Error>> {
@@ -918,6 +978,40 @@ mod tests {
 		assert_eq!(local_result2, false);
 	}
 
+	#[test]
+	fn prove_child_read_and_proof_check_works() {
+		let child_key = trie_backend::tests::CHILD_KEY_1;
+		// fetch child read proof from 'remote' full node
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let remote_proof = prove_child_read(remote_backend, child_key, b"value3").unwrap().1;
+		// check proof locally
+		let local_result1 = read_child_proof_check::<Blake2Hasher>(
+			remote_root,
+			remote_proof.clone(),
+			child_key,
+			b"value3",
+		).unwrap();
+		let local_result2 = read_child_proof_check::<Blake2Hasher>(
+			remote_root,
+			remote_proof,
+			child_key,
+			b"value2",
+		).unwrap();
+		// a proof of the child trie root does not prove the contents of the child trie
+		let root_proof = prove_read(trie_backend::tests::test_trie(), child_key).unwrap().1;
+		let local_result3 = read_child_proof_check::<Blake2Hasher>(
+			remote_root,
+			root_proof,
+			child_key,
+			b"value3",
+		).is_ok();
+		// check that results are correct
+		assert_eq!(local_result1, Some(vec![142]));
+		assert_eq!(local_result2, None);
+		assert_eq!(local_result3, false);
+	}
+
 	#[test]
 	fn cannot_change_changes_trie_config() {
 		assert!(new(
@@ -134,7 +134,7 @@ impl OverlayedChanges {
 	/// Inserts the given key-value pair into the prospective change set.
 	///
 	/// `None` can be used to delete a value specified by the given key.
-	pub(crate) fn set_storage(&mut self, key: Vec<u8>, val: Option<Vec<u8>>) {
+	pub fn set_storage(&mut self, key: Vec<u8>, val: Option<Vec<u8>>) {
 		let extrinsic_index = self.extrinsic_index();
 		let entry = self.prospective.top.entry(key).or_default();
 		entry.value = val;
@@ -273,6 +273,23 @@ impl OverlayedChanges {
 		self.committed.top.into_iter().map(|(k, v)| (k, v.value))
 	}
 
+	/// Consume `OverlayedChanges` and take committed set, along with the committed changes
+	/// of every child storage.
+	///
+	/// Panics:
+	/// Will panic if there are any uncommitted prospective changes.
+	pub fn into_committed_with_children(self) -> (
+		impl Iterator<Item=(Vec<u8>, Option<Vec<u8>>)>,
+		impl Iterator<Item=(Vec<u8>, Vec<(Vec<u8>, Option<Vec<u8>>)>)>,
+	) {
+		assert!(self.prospective.is_empty());
+		let OverlayedChangeSet { top, children } = self.committed;
+		(
+			top.into_iter().map(|(k, v)| (k, v.value)),
+			children.into_iter().map(|(storage_key, (_, changes))| (storage_key, changes.into_iter().collect())),
+		)
+	}
+
 	/// Inserts storage entry responsible for current extrinsic index.
 	#[cfg(test)]
 	pub(crate) fn set_extrinsic_index(&mut self, extrinsic_index: u32) {
@@ -268,4 +268,26 @@ mod tests {
 		let proof_check = create_proof_check_backend::<Blake2Hasher>(in_memory_root.into(), proof).unwrap();
 		assert_eq!(proof_check.storage(&[42]).unwrap().unwrap(), vec![42]);
 	}
+
+	#[test]
+	fn proof_recorded_and_checked_with_child() {
+		let child_key = b":child_storage:default:sub1".to_vec();
+		let contents = (0..64).map(|i| (None, vec![i], Some(vec![i])))
+			.chain((28..65).map(|i| (Some(child_key.clone()), vec![i], Some(vec![i; 32]))))
+			.collect::<Vec<_>>();
+		let in_memory = InMemory::<Blake2Hasher>::default().update(contents);
+
+		let trie = in_memory.try_into_trie_backend().unwrap();
+		let trie_root = trie.storage_root(::std::iter::empty()).0;
+		(28..65).for_each(|i| assert_eq!(trie.child_storage(&child_key, &[i]).unwrap().unwrap(), vec![i; 32]));
+
+		let proving = ProvingBackend::new(&trie);
+		assert_eq!(proving.child_storage(&child_key, &[64]).unwrap().unwrap(), vec![64; 32]);
+
+		let proof = proving.extract_proof();
+
+		let proof_check = create_proof_check_backend::<Blake2Hasher>(trie_root, proof).unwrap();
+		assert_eq!(proof_check.child_storage(&child_key, &[64]).unwrap().unwrap(), vec![64; 32]);
+		assert!(proof_check.child_storage(&child_key, &[28]).is_err());
+	}
 }
@@ -189,14 +189,25 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 pub mod tests {
 	use std::collections::HashSet;
 	use primitives::{Blake2Hasher, H256};
-	use trie::{TrieMut, TrieDBMut, PrefixedMemoryDB};
+	use trie::{TrieMut, TrieDBMut, PrefixedMemoryDB, KeySpacedDBMut, child_trie_keyspace};
 	use super::*;
 
+	pub(crate) const CHILD_KEY_1: &[u8] = b":child_storage:default:sub1";
+
 	fn test_db() -> (PrefixedMemoryDB<Blake2Hasher>, H256) {
 		let mut root = H256::default();
 		let mut mdb = PrefixedMemoryDB::<Blake2Hasher>::default();
+		let mut child_root = H256::default();
+		{
+			let keyspace = child_trie_keyspace::<Blake2Hasher>(CHILD_KEY_1);
+			let mut child_mdb = KeySpacedDBMut::<_, Blake2Hasher>::new(&mut mdb, keyspace.as_ref());
+			let mut trie = TrieDBMut::new(&mut child_mdb, &mut child_root);
+			trie.insert(b"value3", &[142]).expect("insert failed");
+			trie.insert(b"value4", &[124]).expect("insert failed");
+		}
 		{
 			let mut trie = TrieDBMut::new(&mut mdb, &mut root);
+			trie.insert(CHILD_KEY_1, child_root.as_ref()).expect("insert failed");
 			trie.insert(b"key", b"value").expect("insert failed");
 			trie.insert(b"value1", &[42]).expect("insert failed");
 			trie.insert(b"value2", &[24]).expect("insert failed");
@@ -218,6 +229,11 @@ pub mod tests {
 		assert_eq!(test_trie().storage(b"key").unwrap(), Some(b"value".to_vec()));
 	}
 
+	#[test]
+	fn read_from_child_storage_returns_some() {
+		assert_eq!(test_trie().child_storage(CHILD_KEY_1, b"value3").unwrap(), Some(vec![142]));
+	}
+
 	#[test]
 	fn read_from_storage_returns_none() {
 		assert_eq!(test_trie().storage(b"non-existing-key").unwrap(), None);
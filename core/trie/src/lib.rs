@@ -23,7 +23,9 @@ mod node_header;
 mod node_codec;
 mod trie_stream;
 
+use std::marker::PhantomData;
 use hash_db::Hasher;
+use substrate_primitives::storage::well_known_keys;
 /// Our `NodeCodec`-specific error.
 pub use error::Error;
 /// The Substrate format implementation of `TrieStream`.
@@ -135,21 +137,32 @@ where
 }
 
 /// Determine whether a child trie key is valid. `child_trie_root` and `child_delta_trie_root` can panic if invalid value is provided to them.
-pub fn is_child_trie_key_valid<H: Hasher>(_storage_key: &[u8]) -> bool {
-	true
+///
+/// A valid key is a child storage key with a non-empty identifier after the prefix shared by all
+/// of them, which its keyspace is derived from.
+pub fn is_child_trie_key_valid<H: Hasher>(storage_key: &[u8]) -> bool {
+	well_known_keys::is_child_storage_key(storage_key)
+		&& storage_key.len() > well_known_keys::CHILD_STORAGE_KEY_PREFIX.len()
+}
+
+/// Determine the keyspace of the nodes of a child trie in the database.
+///
+/// This is the hash of the storage key of the child trie. All keyspaces have the same length, so
+/// no keyspace is a prefix of another one, and nodes of two child tries never share a database key.
+pub fn child_trie_keyspace<H: Hasher>(storage_key: &[u8]) -> H::Out {
+	H::hash(storage_key)
 }
 
 /// Determine the default child trie root.
-pub fn default_child_trie_root<H: Hasher>(_storage_key: &[u8]) -> Vec<u8> {
-	let mut db = MemoryDB::default();
-	let mut root = H::Out::default();
-	let mut empty = TrieDBMut::<H>::new(&mut db, &mut root);
-	empty.commit();
-	empty.root().as_ref().to_vec()
+pub fn default_child_trie_root<H: Hasher>(storage_key: &[u8]) -> Vec<u8> {
+	child_trie_root::<H, _, Vec<u8>, Vec<u8>>(storage_key, ::std::iter::empty())
 }
 
 /// Determine a child trie root given its ordered contents, closed form. H is the default hasher, but a generic
 /// implementation may ignore this type parameter and use other hashers.
+///
+/// The storage key only determines where the nodes of the trie are stored, so the root of a child trie
+/// only depends on its contents.
 pub fn child_trie_root<H: Hasher, I, A, B>(_storage_key: &[u8], input: I) -> Vec<u8> where
 	I: IntoIterator<Item = (A, B)>,
 	A: AsRef<[u8]> + Ord,
@@ -160,7 +173,7 @@ pub fn child_trie_root<H: Hasher, I, A, B>(_storage_key: &[u8], input: I) -> Vec
 
 /// Determine a child trie root given a hash DB and delta values. H is the default hasher, but a generic implementation may ignore this type parameter and use other hashers.
 pub fn child_delta_trie_root<H: Hasher, I, A, B, DB>(
-	_storage_key: &[u8],
+	storage_key: &[u8],
 	db: &mut DB,
 	root_vec: Vec<u8>,
 	delta: I
@@ -168,38 +181,33 @@ pub fn child_delta_trie_root<H: Hasher, I, A, B, DB>(
 	I: IntoIterator<Item = (A, Option<B>)>,
 	A: AsRef<[u8]> + Ord,
 	B: AsRef<[u8]>,
-	DB: hash_db::HashDB<H, trie_db::DBValue> + hash_db::PlainDB<H::Out, trie_db::DBValue>,
+	DB: hash_db::HashDB<H, trie_db::DBValue>,
 {
 	let mut root = H::Out::default();
 	root.as_mut().copy_from_slice(&root_vec); // root is fetched from DB, not writable by runtime, so it's always valid.
 
-	{
-		let mut trie = TrieDBMut::<H>::from_existing(&mut *db, &mut root)?;
-
-		for (key, change) in delta {
-			match change {
-				Some(val) => trie.insert(key.as_ref(), val.as_ref())?,
-				None => trie.remove(key.as_ref())?,
-			};
-		}
-	}
+	let keyspace = child_trie_keyspace::<H>(storage_key);
+	let mut db = KeySpacedDBMut::<_, H>::new(&mut *db, keyspace.as_ref());
+	root = delta_trie_root::<H, _, _, _, _>(&mut db, root, delta)?;
 
 	Ok(root.as_ref().to_vec())
 }
 
 /// Call `f` for all keys in a child trie.
 pub fn for_keys_in_child_trie<H: Hasher, F: FnMut(&[u8]), DB>(
-	_storage_key: &[u8],
+	storage_key: &[u8],
 	db: &DB,
 	root_slice: &[u8],
 	mut f: F
 ) -> Result<(), Box<TrieError<H::Out>>> where
-	DB: hash_db::HashDBRef<H, trie_db::DBValue> + hash_db::PlainDBRef<H::Out, trie_db::DBValue>,
+	DB: hash_db::HashDBRef<H, trie_db::DBValue>,
 {
 	let mut root = H::Out::default();
 	root.as_mut().copy_from_slice(root_slice); // root is fetched from DB, not writable by runtime, so it's always valid.
 
-	let trie = TrieDB::<H>::new(&*db, &root)?;
+	let keyspace = child_trie_keyspace::<H>(storage_key);
+	let db = KeySpacedDB::<_, H>::new(&*db, keyspace.as_ref());
+	let trie = TrieDB::<H>::new(&db, &root)?;
 	let iter = trie.iter()?;
 
 	for x in iter {
@@ -235,33 +243,111 @@ pub fn record_all_keys<H: Hasher, DB>(
 
 /// Read a value from the child trie.
 pub fn read_child_trie_value<H: Hasher, DB>(
-	_storage_key: &[u8],
+	storage_key: &[u8],
 	db: &DB,
 	root_slice: &[u8],
 	key: &[u8]
 ) -> Result<Option<Vec<u8>>, Box<TrieError<H::Out>>> where
-	DB: hash_db::HashDBRef<H, trie_db::DBValue> + hash_db::PlainDBRef<H::Out, trie_db::DBValue>,
+	DB: hash_db::HashDBRef<H, trie_db::DBValue>,
 {
 	let mut root = H::Out::default();
 	root.as_mut().copy_from_slice(root_slice); // root is fetched from DB, not writable by runtime, so it's always valid.
 
-	Ok(TrieDB::<H>::new(&*db, &root)?.get(key).map(|x| x.map(|val| val.to_vec()))?)
+	let keyspace = child_trie_keyspace::<H>(storage_key);
+	let db = KeySpacedDB::<_, H>::new(&*db, keyspace.as_ref());
+	Ok(TrieDB::<H>::new(&db, &root)?.get(key).map(|x| x.map(|val| val.to_vec()))?)
 }
 
 /// Read a value from the child trie with given query.
 pub fn read_child_trie_value_with<H: Hasher, Q: Query<H, Item=DBValue>, DB>(
-	_storage_key: &[u8],
+	storage_key: &[u8],
 	db: &DB,
 	root_slice: &[u8],
 	key: &[u8],
 	query: Q
 ) -> Result<Option<Vec<u8>>, Box<TrieError<H::Out>>> where
-	DB: hash_db::HashDBRef<H, trie_db::DBValue> + hash_db::PlainDBRef<H::Out, trie_db::DBValue>,
+	DB: hash_db::HashDBRef<H, trie_db::DBValue>,
 {
 	let mut root = H::Out::default();
 	root.as_mut().copy_from_slice(root_slice); // root is fetched from DB, not writable by runtime, so it's always valid.
 
-	Ok(TrieDB::<H>::new(&*db, &root)?.get_with(key, query).map(|x| x.map(|val| val.to_vec()))?)
+	let keyspace = child_trie_keyspace::<H>(storage_key);
+	let db = KeySpacedDB::<_, H>::new(&*db, keyspace.as_ref());
+	Ok(TrieDB::<H>::new(&db, &root)?.get_with(key, query).map(|x| x.map(|val| val.to_vec()))?)
+}
+
+/// Read-only `HashDB` view of the nodes of a child trie, stored under the keyspace of the child trie.
+pub struct KeySpacedDB<'a, DB, H>(&'a DB, &'a [u8], PhantomData<H>);
+
+/// `HashDB` view of the nodes of a child trie, stored under the keyspace of the child trie.
+pub struct KeySpacedDBMut<'a, DB, H>(&'a mut DB, &'a [u8], PhantomData<H>);
+
+/// Prepend the keyspace to a node prefix.
+fn keyspace_as_prefix(keyspace: &[u8], prefix: &[u8]) -> Vec<u8> {
+	let mut result = Vec::with_capacity(keyspace.len() + prefix.len());
+	result.extend_from_slice(keyspace);
+	result.extend_from_slice(prefix);
+	result
+}
+
+impl<'a, DB, H> KeySpacedDB<'a, DB, H> {
+	/// Create a view of `db` under `keyspace`.
+	pub fn new(db: &'a DB, keyspace: &'a [u8]) -> Self {
+		KeySpacedDB(db, keyspace, PhantomData)
+	}
+}
+
+impl<'a, DB, H> KeySpacedDBMut<'a, DB, H> {
+	/// Create a view of `db` under `keyspace`.
+	pub fn new(db: &'a mut DB, keyspace: &'a [u8]) -> Self {
+		KeySpacedDBMut(db, keyspace, PhantomData)
+	}
+}
+
+impl<'a, DB, H> hash_db::HashDBRef<H, DBValue> for KeySpacedDB<'a, DB, H> where
+	DB: hash_db::HashDBRef<H, DBValue>,
+	H: Hasher,
+{
+	fn get(&self, key: &H::Out, prefix: &[u8]) -> Option<DBValue> {
+		self.0.get(key, &keyspace_as_prefix(self.1, prefix))
+	}
+
+	fn contains(&self, key: &H::Out, prefix: &[u8]) -> bool {
+		self.0.contains(key, &keyspace_as_prefix(self.1, prefix))
+	}
+}
+
+impl<'a, DB, H> hash_db::HashDB<H, DBValue> for KeySpacedDBMut<'a, DB, H> where
+	DB: hash_db::HashDB<H, DBValue>,
+	H: Hasher,
+{
+	fn get(&self, key: &H::Out, prefix: &[u8]) -> Option<DBValue> {
+		self.0.get(key, &keyspace_as_prefix(self.1, prefix))
+	}
+
+	fn contains(&self, key: &H::Out, prefix: &[u8]) -> bool {
+		self.0.contains(key, &keyspace_as_prefix(self.1, prefix))
+	}
+
+	fn insert(&mut self, prefix: &[u8], value: &[u8]) -> H::Out {
+		self.0.insert(&keyspace_as_prefix(self.1, prefix), value)
+	}
+
+	fn emplace(&mut self, key: H::Out, prefix: &[u8], value: DBValue) {
+		self.0.emplace(key, &keyspace_as_prefix(self.1, prefix), value)
+	}
+
+	fn remove(&mut self, key: &H::Out, prefix: &[u8]) {
+		self.0.remove(key, &keyspace_as_prefix(self.1, prefix))
+	}
+}
+
+impl<'a, DB, H> hash_db::AsHashDB<H, DBValue> for KeySpacedDBMut<'a, DB, H> where
+	DB: hash_db::HashDB<H, DBValue>,
+	H: Hasher,
+{
+	fn as_hash_db<'b>(&'b self) -> &'b (hash_db::HashDB<H, DBValue> + 'b) { self }
+	fn as_hash_db_mut<'b>(&'b mut self) -> &'b mut (hash_db::HashDB<H, DBValue> + 'b) { self }
 }
 
 // Utilities (not exported):
@@ -587,4 +673,34 @@ mod tests {
 
 		assert_eq!(pairs, iter_pairs);
 	}
+
+	#[test]
+	fn child_trie_key_validity() {
+		assert!(is_child_trie_key_valid::<Blake2Hasher>(b":child_storage:default:1"));
+		assert!(!is_child_trie_key_valid::<Blake2Hasher>(b":child_storage:"));
+		assert!(!is_child_trie_key_valid::<Blake2Hasher>(b":code"));
+	}
+
+	#[test]
+	fn child_tries_do_not_share_nodes() {
+		let child1 = b":child_storage:default:1";
+		let child2 = b":child_storage:default:2";
+		let pairs = vec![(b"key".to_vec(), Some(b"value".to_vec()))];
+
+		let mut mdb = PrefixedMemoryDB::<Blake2Hasher>::default();
+		let empty = default_child_trie_root::<Blake2Hasher>(child1);
+		let root = child_delta_trie_root::<Blake2Hasher, _, _, _, _>(
+			child1,
+			&mut mdb,
+			empty,
+			pairs,
+		).unwrap();
+
+		assert_eq!(root, child_trie_root::<Blake2Hasher, _, _, _>(child2, vec![(b"key", b"value")]));
+		assert_eq!(
+			read_child_trie_value::<Blake2Hasher, _>(child1, &mdb, &root, b"key").unwrap(),
+			Some(b"value".to_vec()),
+		);
+		assert!(read_child_trie_value::<Blake2Hasher, _>(child2, &mdb, &root, b"key").is_err());
+	}
 }
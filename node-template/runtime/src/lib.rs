@@ -14,13 +14,14 @@ use primitives::bytes;
 use primitives::{ed25519, sr25519, OpaqueMetadata};
 use runtime_primitives::{
 	ApplyResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
-	traits::{self, NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify}
+	traits::{self, NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify, ValidateUnsigned}
 };
 use client::{
 	block_builder::api::{CheckInherentsResult, InherentData, self as block_builder_api},
 	runtime_api, impl_runtime_apis
 };
 use version::RuntimeVersion;
+use support::traits::Get;
 #[cfg(feature = "std")]
 use version::NativeVersion;
 
@@ -109,6 +110,15 @@ pub fn native_version() -> NativeVersion {
 	}
 }
 
+/// The `spec_version` of the runtime, used to detect runtime upgrades.
+pub struct SpecVersion;
+
+impl Get<u32> for SpecVersion {
+	fn get() -> u32 {
+		VERSION.spec_version
+	}
+}
+
 impl system::Trait for Runtime {
 	/// The identifier used to distinguish between accounts.
 	type AccountId = AccountId;
@@ -132,6 +142,8 @@ impl system::Trait for Runtime {
 	type Log = Log;
 	/// The ubiquitous origin type.
 	type Origin = Origin;
+	/// The runtime version, to run the storage migrations on upgrades.
+	type SpecVersion = SpecVersion;
 }
 
 impl aura::Trait for Runtime {
@@ -225,7 +237,16 @@ pub type UncheckedExtrinsic = generic::UncheckedMortalCompactExtrinsic<Address,
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Nonce, Call>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, Context, Balances, AllModules>;
+pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules>;
+
+impl ValidateUnsigned for Runtime {
+	type Call = Call;
+
+	fn validate_unsigned(_call: &Call) -> TransactionValidity {
+		// No module of this runtime accepts unsigned transactions.
+		TransactionValidity::Invalid(0)
+	}
+}
 
 // Implement our runtime API endpoints. This is just a bunch of proxying.
 impl_runtime_apis! {
@@ -293,3 +314,53 @@ impl_runtime_apis! {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::with_externalities;
+	use support::assert_ok;
+	use runtime_primitives::traits::OnFinalise;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<primitives::Blake2Hasher> {
+		GenesisConfig {
+			consensus: None,
+			system: None,
+			timestamp: Some(TimestampConfig {
+				minimum_period: 5,
+			}),
+			indices: None,
+			balances: None,
+			sudo: None,
+		}.build_storage().unwrap().0.into()
+	}
+
+	fn set_timestamp(n: BlockNumber, now: u64) {
+		System::set_block_number(n);
+		assert_ok!(Timestamp::dispatch(TimestampCall::set(now), Origin::INHERENT));
+		Timestamp::on_finalise(n);
+	}
+
+	#[test]
+	fn blocks_one_slot_apart_should_work() {
+		// what the manual seal engine does, however quickly blocks are sealed.
+		with_externalities(&mut new_test_ext(), || {
+			let slot_duration = Aura::slot_duration();
+			assert_eq!(slot_duration, 10);
+
+			set_timestamp(1, 1_000);
+			set_timestamp(2, 1_000 + slot_duration);
+			set_timestamp(3, 1_000 + 2 * slot_duration);
+			assert_eq!(Timestamp::now(), 1_020);
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Only one block may be authored per slot.")]
+	fn blocks_a_minimum_period_apart_may_share_a_slot() {
+		with_externalities(&mut new_test_ext(), || {
+			set_timestamp(1, 1_000);
+			set_timestamp(2, 1_005);
+		});
+	}
+}
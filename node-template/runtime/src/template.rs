@@ -98,6 +98,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl Trait for Test {
 		type Event = ();
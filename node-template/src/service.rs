@@ -3,13 +3,14 @@
 #![warn(unused_extern_crates)]
 
 use std::sync::Arc;
+use futures::Future;
 use log::info;
 use transaction_pool::{self, txpool::{Pool as TransactionPool}};
 use node_template_runtime::{self, GenesisConfig, opaque::Block, RuntimeApi};
 use substrate_service::{
 	FactoryFullConfiguration, LightComponents, FullComponents, FullBackend,
 	FullClient, LightClient, LightBackend, FullExecutor, LightExecutor,
-	TaskExecutor,
+	TaskExecutor, Sealing,
 };
 use basic_authorship::ProposerFactory;
 use consensus::{import_queue, start_aura, AuraImportQueue, SlotDuration, NothingExtra};
@@ -57,25 +58,58 @@ construct_service_factory! {
 			},
 		AuthoritySetup = {
 			|service: Self::FullService, executor: TaskExecutor, key: Option<Arc<Pair>>| {
-				if let Some(key) = key {
-					info!("Using authority key {}", key.public());
-					let proposer = Arc::new(ProposerFactory {
-						client: service.client(),
-						transaction_pool: service.transaction_pool(),
-						inherents_pool: service.inherents_pool(),
-					});
-					let client = service.client();
-					executor.spawn(start_aura(
-						SlotDuration::get_or_compute(&*client)?,
-						key.clone(),
-						client.clone(),
-						client,
-						proposer,
-						service.network(),
-						service.on_exit(),
-						service.config.custom.inherent_data_providers.clone(),
-						service.config.force_authoring,
-					)?);
+				let proposer = ProposerFactory {
+					client: service.client(),
+					transaction_pool: service.transaction_pool(),
+					inherents_pool: service.inherents_pool(),
+				};
+				let client = service.client();
+				let inherent_data_providers = service.config.custom.inherent_data_providers.clone();
+
+				if service.config.sealing != Sealing::Consensus {
+					// Aura still checks the timestamps, which must fall in a slot of their own.
+					let slot_duration = SlotDuration::get_or_compute(&*client)?.get();
+					inherent_data_providers
+						.register_provider(manual_seal::SlotTimestampProvider::from_best_block(&*client, slot_duration)?)
+						.map_err(|e| format!("Registering timestamp inherent data provider failed: {:?}", e))?;
+				}
+
+				match service.config.sealing {
+					Sealing::Instant => {
+						info!("Running with instant sealing");
+						executor.spawn(manual_seal::run_instant_seal(
+							client.clone(),
+							client,
+							proposer,
+							service.transaction_pool(),
+							inherent_data_providers,
+						).select(service.on_exit()).then(|_| Ok(())));
+					},
+					Sealing::Manual => if let Some(commands) = service.engine_commands() {
+						info!("Running with manual sealing");
+						executor.spawn(manual_seal::run_manual_seal(
+							client.clone(),
+							client,
+							proposer,
+							service.transaction_pool(),
+							commands,
+							inherent_data_providers,
+						).select(service.on_exit()).then(|_| Ok(())));
+					},
+					Sealing::Consensus => if let Some(key) = key {
+						info!("Using authority key {}", key.public());
+						executor.spawn(start_aura(
+							SlotDuration::get_or_compute(&*client)?,
+							key.clone(),
+							client.clone(),
+							client,
+							Arc::new(proposer),
+							service.network(),
+							service.on_exit(),
+							inherent_data_providers,
+							service.config.force_authoring,
+						)?);
+					},
 				}
 
 				Ok(service)
@@ -86,7 +120,11 @@ construct_service_factory! {
 		FullImportQueue = AuraImportQueue<
 			Self::Block,
 		>
-			{ |config: &mut FactoryFullConfiguration<Self> , client: Arc<FullClient<Self>>|
+			{ |config: &mut FactoryFullConfiguration<Self>, client: Arc<FullClient<Self>>| {
+				if config.sealing != Sealing::Consensus {
+					return Ok(manual_seal::import_queue(client, None))
+				}
+
 				import_queue::<_, _, _, Pair>(
 					SlotDuration::get_or_compute(&*client)?,
 					client.clone(),
@@ -95,11 +133,15 @@ construct_service_factory! {
 					NothingExtra,
 					config.custom.inherent_data_providers.clone(),
 				).map_err(Into::into)
-			},
+			}},
 		LightImportQueue = AuraImportQueue<
 			Self::Block,
 		>
-			{ |config: &mut FactoryFullConfiguration<Self>, client: Arc<LightClient<Self>>|
+			{ |config: &mut FactoryFullConfiguration<Self>, client: Arc<LightClient<Self>>| {
+				if config.sealing != Sealing::Consensus {
+					return Ok(manual_seal::import_queue(client, None))
+				}
+
 				import_queue::<_, _, _, Pair>(
 					SlotDuration::get_or_compute(&*client)?,
 					client.clone(),
@@ -108,6 +150,6 @@ construct_service_factory! {
 					NothingExtra,
 					config.custom.inherent_data_providers.clone(),
 				).map_err(Into::into)
-			},
+			}},
 	}
 }
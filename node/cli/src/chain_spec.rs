@@ -20,7 +20,8 @@ use primitives::{ed25519::Public as AuthorityId, ed25519, sr25519, Pair, crypto:
 use node_primitives::AccountId;
 use node_runtime::{ConsensusConfig, CouncilSeatsConfig, CouncilVotingConfig, DemocracyConfig,
 	SessionConfig, StakingConfig, StakerStatus, TimestampConfig, BalancesConfig, TreasuryConfig,
-	SudoConfig, ContractConfig, GrandpaConfig, IndicesConfig, Permill, Perbill};
+	SudoConfig, ContractConfig, GrandpaConfig, IndicesConfig, RandomnessBeaconConfig, RecoveryConfig,
+	Permill, Perbill};
 pub use node_runtime::GenesisConfig;
 use substrate_service;
 use hex_literal::{hex, hex_impl};
@@ -120,8 +121,11 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 			launch_period: 10 * MINUTES,    // 1 day per public referendum
 			voting_period: 10 * MINUTES,    // 3 days to discuss & vote on an active referendum
 			minimum_deposit: 50 * DOLLARS,    // 12000 as the minimum deposit for a referendum
+			preimage_byte_deposit: 1 * CENTS,
 			public_delay: 10 * MINUTES,
 			max_lock_periods: 6,
+			emergency_voting_period: 3 * MINUTES,
+			cooloff_period: 1 * DAYS,
 		}),
 		council_seats: Some(CouncilSeatsConfig {
 			active_council: vec![],
@@ -148,6 +152,17 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 			proposal_bond_minimum: 1 * DOLLARS,
 			spend_period: 1 * DAYS,
 			burn: Permill::from_percent(50),
+			maximum_reason_length: 16384,
+			data_deposit_per_byte: 1 * CENTS,
+			tip_report_deposit_base: 1 * DOLLARS,
+			tip_countdown: 1 * DAYS,
+			tip_finders_fee: Permill::from_percent(20),
+			bounty_deposit_base: 1 * DOLLARS,
+			bounty_payout_delay: 8 * DAYS,
+		}),
+		randomness_beacon: Some(RandomnessBeaconConfig {
+			commit_period: 10 * MINUTES,
+			reveal_period: 5 * MINUTES,
 		}),
 		contract: Some(ContractConfig {
 			transaction_base_fee: 1 * CENTS,
@@ -162,6 +177,12 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 			block_gas_limit: 10_000_000,
 			current_schedule: Default::default(),
 		}),
+		recovery: Some(RecoveryConfig {
+			config_deposit_base: 5 * DOLLARS,
+			friend_deposit_factor: 50 * CENTS,
+			max_friends: 9,
+			recovery_deposit: 5 * DOLLARS,
+		}),
 		sudo: Some(SudoConfig {
 			key: endowed_accounts[0].clone(),
 		}),
@@ -275,8 +296,11 @@ pub fn testnet_genesis(
 			launch_period: 9,
 			voting_period: 18,
 			minimum_deposit: 10,
+			preimage_byte_deposit: 1,
 			public_delay: 0,
 			max_lock_periods: 6,
+			emergency_voting_period: 3,
+			cooloff_period: 36,
 		}),
 		council_seats: Some(CouncilSeatsConfig {
 			active_council: endowed_accounts.iter()
@@ -305,6 +329,17 @@ pub fn testnet_genesis(
 			proposal_bond_minimum: 1_000_000,
 			spend_period: 12 * 60 * 24,
 			burn: Permill::from_percent(50),
+			maximum_reason_length: 16384,
+			data_deposit_per_byte: 10_000,
+			tip_report_deposit_base: 1_000_000,
+			tip_countdown: 12 * 60 * 24,
+			tip_finders_fee: Permill::from_percent(20),
+			bounty_deposit_base: 1_000_000,
+			bounty_payout_delay: 8 * 12 * 60 * 24,
+		}),
+		randomness_beacon: Some(RandomnessBeaconConfig {
+			commit_period: 100,
+			reveal_period: 50,
 		}),
 		contract: Some(ContractConfig {
 			transaction_base_fee: 1,
@@ -319,6 +354,12 @@ pub fn testnet_genesis(
 			block_gas_limit: 10_000_000,
 			current_schedule: Default::default(),
 		}),
+		recovery: Some(RecoveryConfig {
+			config_deposit_base: 500_000,
+			friend_deposit_factor: 50_000,
+			max_friends: 9,
+			recovery_deposit: 500_000,
+		}),
 		sudo: Some(SudoConfig {
 			key: root_key,
 		}),
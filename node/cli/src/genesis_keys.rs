@@ -0,0 +1,277 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replacing the privileged keys of a raw genesis by dev keys, as laid out by the node runtime.
+
+use cli::{error, SharedParams, GetLogFilter};
+use parity_codec::{Compact, Decode, Encode};
+use primitives::{twox_128, sr25519, crypto::Pair, storage::well_known_keys};
+use sr_primitives::{BuildStorage, StorageOverlay};
+use structopt::StructOpt;
+
+/// The `override-genesis-keys` command, replacing the privileged keys of a raw chain spec.
+#[derive(Debug, StructOpt, Clone)]
+pub struct OverrideGenesisKeysCmd {
+	/// Replace the validators, their session keys and the authorities of the genesis by the given
+	/// dev keyring accounts (e.g. `alice`). The first one also replaces the sudo key.
+	#[structopt(long = "dev-authority", value_name = "NAME", raw(required = "true"))]
+	pub dev_authorities: Vec<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl GetLogFilter for OverrideGenesisKeysCmd {
+	fn get_log_filter(&self) -> Option<String> {
+		self.shared_params.get_log_filter()
+	}
+}
+
+/// Dev keys replacing the privileged keys of a genesis.
+struct GenesisKeyOverrides {
+	/// The encoded account id of the new sudo key.
+	sudo_key: Vec<u8>,
+	/// The encoded account ids and session keys of the new validators. The session keys are the
+	/// new Aura and GRANDPA authorities.
+	validators: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Print the chain spec of `--chain` with its privileged keys replaced by dev keys, typically a
+/// spec built by `build-spec --from-state`.
+pub fn override_genesis_keys(cmd: OverrideGenesisKeysCmd) -> error::Result<()> {
+	let overrides = dev_key_overrides(&cmd.dev_authorities)?;
+	let mut spec = cli::load_spec(&cmd.shared_params, crate::load_spec)?;
+	let (mut storage, children) = (&spec).build_storage()?;
+	if !children.is_empty() {
+		return Err("A raw genesis can't hold child tries".into());
+	}
+
+	override_keys(&mut storage, overrides);
+	spec.set_raw_genesis(storage);
+	print!("{}", spec.to_json(true)?);
+
+	Ok(())
+}
+
+/// The keys of the given dev keyring accounts, with sr25519 account ids and ed25519 session keys.
+fn dev_key_overrides(names: &[String]) -> error::Result<GenesisKeyOverrides> {
+	let validators = names.iter().map(|name| {
+		let account = keyring::AuthorityKeyring::iter()
+			.find(|a| a.to_string().eq_ignore_ascii_case(name))
+			.ok_or_else(|| format!("Unknown dev account `{}`", name))?;
+		let account_id = sr25519::Pair::from_string(&format!("//{}", account), None)
+			.expect("dev seeds are valid; qed")
+			.public();
+		Ok((account_id.to_raw_vec(), account.to_raw_public_vec()))
+	}).collect::<Result<Vec<_>, String>>()?;
+
+	Ok(GenesisKeyOverrides {
+		sudo_key: validators[0].0.clone(),
+		validators,
+	})
+}
+
+/// The storage key of the item `item` of the runtime module `module`, followed by the encoded key
+/// `map_key` for an entry of a map, as laid out by `decl_storage!`.
+fn module_storage_key(module: &str, item: &str, map_key: &[u8]) -> Vec<u8> {
+	let mut key = format!("{} {}", module, item).into_bytes();
+	key.extend_from_slice(map_key);
+	twox_128(&key).to_vec()
+}
+
+/// The storage key of the head of the linked map `item` of the runtime module `module`.
+fn linked_map_head_key(module: &str, item: &str) -> Vec<u8> {
+	twox_128(format!("head of {} {}", module, item).as_bytes()).to_vec()
+}
+
+/// Replace the validators of the staking module, if the genesis has one, by the given stashes,
+/// each its own controller with nothing at stake.
+///
+/// The former validators stay bonded but are no longer candidates, so nominations of them are
+/// ignored by the next elections.
+fn override_staking_validators(storage: &mut StorageOverlay, stashes: &[Vec<u8>]) {
+	let elected_key = module_storage_key("Staking", "CurrentElected", &[]);
+	let id_len = match stashes.first() {
+		Some(stash) if storage.contains_key(&elected_key) => stash.len(),
+		_ => return,
+	};
+	// an encoded `Option<AccountId>`, the account ids being as long as those of the stashes.
+	let read_id = |input: &mut &[u8]| -> Option<Option<Vec<u8>>> {
+		let (tag, rest) = input.split_first()?;
+		*input = rest;
+		match tag {
+			0 => Some(None),
+			1 if input.len() >= id_len => {
+				let (id, rest) = input.split_at(id_len);
+				*input = rest;
+				Some(Some(id.to_vec()))
+			},
+			_ => None,
+		}
+	};
+	let write_id = |output: &mut Vec<u8>, id: Option<&Vec<u8>>| match id {
+		Some(id) => {
+			output.push(1);
+			output.extend_from_slice(id);
+		},
+		None => output.push(0),
+	};
+
+	let old_elected = storage.remove(&elected_key).unwrap_or_default();
+	let mut input = &old_elected[..];
+	if let Some(Compact(count)) = Compact::<u32>::decode(&mut input) {
+		for stash in input.chunks(id_len).take(count as usize) {
+			storage.remove(&module_storage_key("Staking", "Stakers", stash));
+		}
+	}
+
+	let head_key = linked_map_head_key("Staking", "Validators");
+	let mut next = storage.remove(&head_key);
+	while let Some(stash) = next {
+		next = storage.remove(&module_storage_key("Staking", "Validators", &stash)).and_then(|entry| {
+			// the compact preferences, followed by the previous and the next entry.
+			let mut input = &entry[..];
+			Compact::<u32>::decode(&mut input)?;
+			Compact::<u128>::decode(&mut input)?;
+			read_id(&mut input)?;
+			read_id(&mut input)?
+		});
+	}
+
+	let mut elected = Compact(stashes.len() as u32).encode();
+	for (i, stash) in stashes.iter().enumerate() {
+		elected.extend_from_slice(stash);
+
+		// the default preferences.
+		let mut entry = (Compact(3u32), Compact(0u32)).encode();
+		write_id(&mut entry, if i == 0 { None } else { stashes.get(i - 1) });
+		write_id(&mut entry, stashes.get(i + 1));
+		storage.insert(module_storage_key("Staking", "Validators", stash), entry);
+
+		// optional values are stored without their tag: the controller is the stash itself...
+		storage.insert(module_storage_key("Staking", "Bonded", stash), stash.clone());
+		// ...whose ledger has nothing at stake or unlocking.
+		storage.insert(module_storage_key("Staking", "Ledger", stash), [&stash[..], &[0, 0, 0][..]].concat());
+		// an exposure with nothing at stake.
+		storage.insert(module_storage_key("Staking", "Stakers", stash), vec![0, 0, 0]);
+	}
+	storage.insert(head_key, stashes[0].clone());
+	storage.insert(elected_key, elected);
+}
+
+/// Replace the items of the authority set stored under `prefix`, if the genesis has one.
+fn override_authorities<I>(storage: &mut StorageOverlay, prefix: &[u8], count_key: &[u8], authorities: I)
+	where I: IntoIterator<Item=Vec<u8>>,
+{
+	if !storage.contains_key(count_key) {
+		return;
+	}
+
+	let old: Vec<_> = storage.keys().filter(|key| key.starts_with(prefix)).cloned().collect();
+	for key in old {
+		storage.remove(&key);
+	}
+
+	let mut count: u32 = 0;
+	for authority in authorities {
+		let mut key = prefix.to_vec();
+		count.encode_to(&mut key);
+		storage.insert(key, authority);
+		count += 1;
+	}
+	storage.insert(count_key.to_vec(), count.encode());
+}
+
+/// Replace the sudo key, the session and staking validators, the session keys and the
+/// authorities of the runtime modules found in the genesis.
+fn override_keys(storage: &mut StorageOverlay, overrides: GenesisKeyOverrides) {
+	let sudo_key = module_storage_key("Sudo", "Key", &[]);
+	if storage.contains_key(&sudo_key) {
+		storage.insert(sudo_key, overrides.sudo_key);
+	}
+
+	let validators_key = module_storage_key("Session", "Validators", &[]);
+	if storage.contains_key(&validators_key) {
+		let mut validators = Compact(overrides.validators.len() as u32).encode();
+		for (account_id, session_key) in &overrides.validators {
+			validators.extend_from_slice(account_id);
+			storage.insert(module_storage_key("Session", "NextKeyFor", account_id), session_key.clone());
+		}
+		storage.insert(validators_key, validators);
+	}
+
+	let stashes = overrides.validators.iter().map(|(account_id, _)| account_id.clone()).collect::<Vec<_>>();
+	override_staking_validators(storage, &stashes);
+
+	override_authorities(
+		storage,
+		well_known_keys::AUTHORITY_PREFIX,
+		well_known_keys::AUTHORITY_COUNT,
+		overrides.validators.iter().map(|(_, session_key)| session_key.clone()),
+	);
+
+	// a pending change would hand finality back to the authorities of the original chain.
+	storage.remove(&module_storage_key("GrandpaFinality", "PendingChange", &[]));
+	override_authorities(
+		storage,
+		fg_primitives::well_known_keys::AUTHORITY_PREFIX,
+		fg_primitives::well_known_keys::AUTHORITY_COUNT,
+		overrides.validators.iter().map(|(_, session_key)| {
+			let mut authority = session_key.clone();
+			1u64.encode_to(&mut authority);
+			authority
+		}),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn staking_validators_should_be_overridden() {
+		let (old, a, b) = (vec![1u8; 32], vec![2u8; 32], vec![3u8; 32]);
+		let prefs = (Compact(3u32), Compact(0u32)).encode();
+		let head_key = linked_map_head_key("Staking", "Validators");
+		let mut storage = StorageOverlay::new();
+		storage.insert(module_storage_key("Staking", "CurrentElected", &[]), [&Compact(1u32).encode()[..], &old[..]].concat());
+		storage.insert(module_storage_key("Staking", "Stakers", &old), vec![4, 4, 0]);
+		storage.insert(head_key.clone(), old.clone());
+		storage.insert(module_storage_key("Staking", "Validators", &old), [&prefs[..], &[0u8, 0][..]].concat());
+
+		override_staking_validators(&mut storage, &[a.clone(), b.clone()]);
+
+		assert!(!storage.contains_key(&module_storage_key("Staking", "Stakers", &old)));
+		assert!(!storage.contains_key(&module_storage_key("Staking", "Validators", &old)));
+		assert_eq!(storage[&head_key], a);
+		assert_eq!(
+			storage[&module_storage_key("Staking", "Validators", &a)],
+			[&prefs[..], &[0u8, 1][..], &b[..]].concat(),
+		);
+		assert_eq!(
+			storage[&module_storage_key("Staking", "Validators", &b)],
+			[&prefs[..], &[1u8][..], &a[..], &[0u8][..]].concat(),
+		);
+		assert_eq!(
+			storage[&module_storage_key("Staking", "CurrentElected", &[])],
+			[&Compact(2u32).encode()[..], &a[..], &b[..]].concat(),
+		);
+		assert_eq!(storage[&module_storage_key("Staking", "Bonded", &a)], a);
+		assert_eq!(storage[&module_storage_key("Staking", "Ledger", &b)], [&b[..], &[0u8, 0, 0][..]].concat());
+		assert_eq!(storage[&module_storage_key("Staking", "Stakers", &b)], vec![0, 0, 0]);
+	}
+}
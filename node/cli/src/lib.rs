@@ -22,10 +22,13 @@
 pub use cli::error;
 pub mod chain_spec;
 mod service;
+mod genesis_keys;
 
 use tokio::prelude::Future;
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
 pub use cli::{VersionInfo, IntoExit, NoCustom};
+use cli::GetLogFilter;
+use structopt::StructOpt;
 use substrate_service::{ServiceFactory, Roles as ServiceRoles};
 use std::ops::Deref;
 use log::info;
@@ -65,6 +68,23 @@ impl ChainSpec {
 	}
 }
 
+/// Custom subcommands of the node.
+#[derive(Clone, Debug, StructOpt)]
+pub enum CustomSubcommands {
+	/// Replace the privileged keys of a raw chain spec, such as one built from the state of a
+	/// chain, by dev keys.
+	#[structopt(name = "override-genesis-keys")]
+	OverrideGenesisKeys(genesis_keys::OverrideGenesisKeysCmd),
+}
+
+impl GetLogFilter for CustomSubcommands {
+	fn get_log_filter(&self) -> Option<String> {
+		match self {
+			CustomSubcommands::OverrideGenesisKeys(cmd) => cmd.get_log_filter(),
+		}
+	}
+}
+
 fn load_spec(id: &str) -> Result<Option<chain_spec::ChainSpec>, String> {
 	Ok(match ChainSpec::from(id) {
 		Some(spec) => Some(spec.load()?),
@@ -78,7 +98,7 @@ pub fn run<I, T, E>(args: I, exit: E, version: cli::VersionInfo) -> error::Resul
 	T: Into<std::ffi::OsString> + Clone,
 	E: IntoExit,
 {
-	cli::parse_and_execute::<service::Factory, NoCustom, NoCustom, _, _, _, _, _>(
+	let custom = cli::parse_and_execute::<service::Factory, CustomSubcommands, NoCustom, _, _, _, _, _>(
 		load_spec, &version, "substrate-node", args, exit,
 		|exit, _custom_args, config| {
 			info!("{}", version.name);
@@ -103,7 +123,12 @@ pub fn run<I, T, E>(args: I, exit: E, version: cli::VersionInfo) -> error::Resul
 				),
 			}.map_err(|e| format!("{:?}", e))
 		}
-	).map_err(Into::into).map(|_| ())
+	)?;
+
+	match custom {
+		Some(CustomSubcommands::OverrideGenesisKeys(cmd)) => genesis_keys::override_genesis_keys(cmd),
+		None => Ok(()),
+	}
 }
 
 fn run_until_exit<T, C, E>(
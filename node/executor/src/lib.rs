@@ -317,6 +317,8 @@ mod tests {
 			council_voting: Some(Default::default()),
 			timestamp: Some(Default::default()),
 			treasury: Some(Default::default()),
+			randomness_beacon: Some(Default::default()),
+			recovery: Some(Default::default()),
 			contract: Some(Default::default()),
 			sudo: Some(Default::default()),
 			grandpa: Some(GrandpaConfig {
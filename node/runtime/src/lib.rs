@@ -21,8 +21,8 @@
 #![recursion_limit="256"]
 
 use rstd::prelude::*;
-use support::construct_runtime;
-use substrate_primitives::u32_trait::{_2, _4};
+use support::{construct_runtime, traits::{Get, EnsureOneOf, EnsureBoth}};
+use substrate_primitives::u32_trait::{_1, _2, _3, _4, _5};
 use node_primitives::{
 	AccountId, AccountIndex, Balance, BlockNumber, Hash, Index, AuthorityId, Signature, AuthoritySignature
 };
@@ -34,7 +34,7 @@ use client::{
 use runtime_primitives::{ApplyResult, generic, create_runtime_str};
 use runtime_primitives::transaction_validity::TransactionValidity;
 use runtime_primitives::traits::{
-	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup,
+	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, ValidateUnsigned,
 };
 use version::RuntimeVersion;
 use council::{motions as council_motions, voting as council_voting};
@@ -58,8 +58,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node"),
 	impl_name: create_runtime_str!("substrate-node"),
 	authoring_version: 10,
-	spec_version: 48,
-	impl_version: 48,
+	spec_version: 49,
+	impl_version: 49,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -72,6 +72,15 @@ pub fn native_version() -> NativeVersion {
 	}
 }
 
+/// The `spec_version` of the runtime, used to detect runtime upgrades.
+pub struct SpecVersion;
+
+impl Get<u32> for SpecVersion {
+	fn get() -> u32 {
+		VERSION.spec_version
+	}
+}
+
 impl system::Trait for Runtime {
 	type Origin = Origin;
 	type Index = Index;
@@ -84,6 +93,7 @@ impl system::Trait for Runtime {
 	type Header = generic::Header<BlockNumber, BlakeTwo256, Log>;
 	type Event = Event;
 	type Log = Log;
+	type SpecVersion = SpecVersion;
 }
 
 impl aura::Trait for Runtime {
@@ -133,12 +143,27 @@ impl staking::Trait for Runtime {
 	type Event = Event;
 	type Slash = ();
 	type Reward = ();
+	type SubmitTransaction = StakingSolutionSubmitter;
+}
+
+/// Submits the election solutions of the staking offchain worker to the transaction pool.
+pub struct StakingSolutionSubmitter;
+
+impl staking::SubmitUnsignedTransaction<staking::Call<Runtime>> for StakingSolutionSubmitter {
+	fn submit_unsigned(call: staking::Call<Runtime>) {
+		let extrinsic = UncheckedExtrinsic::new_unsigned(Call::Staking(call));
+		runtime_io::submit_extrinsic(&extrinsic);
+	}
 }
 
 impl democracy::Trait for Runtime {
 	type Currency = balances::Module<Self>;
 	type Proposal = Call;
 	type Event = Event;
+	type ExternalOrigin = EnsureBoth<council_motions::EnsureMembers<_2>, council_motions::EnsureProportionAtLeast<_1, _2>>;
+	type ExternalMajorityOrigin = council_motions::EnsureProportionAtLeast<_3, _4>;
+	type FastTrackOrigin = council_motions::EnsureProportionAtLeast<_2, _3>;
+	type VetoOrigin = council_motions::EnsureCouncillor<Runtime>;
 }
 
 impl council::Trait for Runtime {
@@ -159,11 +184,12 @@ impl council::motions::Trait for Runtime {
 
 impl treasury::Trait for Runtime {
 	type Currency = balances::Module<Self>;
-	type ApproveOrigin = council_motions::EnsureMembers<_4>;
-	type RejectOrigin = council_motions::EnsureMembers<_2>;
+	type ApproveOrigin = EnsureOneOf<system::EnsureRoot<AccountId>, council_motions::EnsureProportionAtLeast<_3, _5>>;
+	type RejectOrigin = EnsureOneOf<system::EnsureRoot<AccountId>, council_motions::EnsureProportionMoreThan<_1, _2>>;
 	type Event = Event;
 	type MintedForSpending = ();
 	type ProposalRejection = ();
+	type Tippers = Council;
 }
 
 impl contract::Trait for Runtime {
@@ -175,6 +201,18 @@ impl contract::Trait for Runtime {
 	type ComputeDispatchFee = contract::DefaultDispatchFeeComputor<Runtime>;
 	type TrieIdGenerator = contract::TrieIdFromParentCounter<Runtime>;
 	type GasPayment = ();
+	type Randomness = RandomnessBeacon;
+}
+
+impl randomness_beacon::Trait for Runtime {
+	type Event = Event;
+	type Participants = Session;
+}
+
+impl recovery::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = balances::Module<Self>;
 }
 
 impl sudo::Trait for Runtime {
@@ -205,6 +243,7 @@ construct_runtime!(
 		Indices: indices,
 		Balances: balances,
 		Session: session,
+		RandomnessBeacon: randomness_beacon::{Module, Call, Storage, Config<T>, Event<T>},
 		Staking: staking::{default, OfflineWorker},
 		Democracy: democracy,
 		Council: council::{Module, Call, Storage, Event<T>},
@@ -215,6 +254,7 @@ construct_runtime!(
 		Grandpa: grandpa::{Module, Call, Storage, Config<T>, Log(), Event<T>},
 		Treasury: treasury,
 		Contract: contract::{Module, Call, Storage, Config<T>, Event<T>},
+		Recovery: recovery,
 		Sudo: sudo,
 	}
 );
@@ -234,7 +274,62 @@ pub type UncheckedExtrinsic = generic::UncheckedMortalCompactExtrinsic<Address,
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Index, Call>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Balances, AllModules>;
+pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Balances, Runtime, AllModules>;
+
+impl ValidateUnsigned for Runtime {
+	type Call = Call;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		match call {
+			Call::Staking(call) => Staking::validate_unsigned(call),
+			_ => TransactionValidity::Invalid(0),
+		}
+	}
+}
+
+/// The benchmarks of the runtime modules, only available with the `runtime-benchmarks` feature.
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarks {
+	use super::*;
+	use benchmarking_primitives::BenchmarkMetadata;
+
+	pub fn metadata() -> Vec<BenchmarkMetadata> {
+		let mut metadata = benchmarking::metadata(
+			"balances",
+			&balances::benchmarking::benchmarks::<Runtime, balances::DefaultInstance>(),
+		);
+		metadata.extend(benchmarking::metadata("staking", &staking::benchmarking::benchmarks::<Runtime>()));
+		metadata.extend(benchmarking::metadata("democracy", &democracy::benchmarking::benchmarks::<Runtime>()));
+		metadata
+	}
+
+	pub fn run(module: &[u8], name: &[u8], components: &[u32]) -> Result<u64, &'static str> {
+		match module {
+			b"balances" => benchmarking::run(
+				&balances::benchmarking::benchmarks::<Runtime, balances::DefaultInstance>(),
+				name,
+				components,
+			),
+			b"staking" => benchmarking::run(&staking::benchmarking::benchmarks::<Runtime>(), name, components),
+			b"democracy" => benchmarking::run(&democracy::benchmarking::benchmarks::<Runtime>(), name, components),
+			_ => Err("unknown module"),
+		}
+	}
+}
+
+#[cfg(not(feature = "runtime-benchmarks"))]
+mod benchmarks {
+	use rstd::prelude::*;
+	use benchmarking_primitives::BenchmarkMetadata;
+
+	pub fn metadata() -> Vec<BenchmarkMetadata> {
+		Vec::new()
+	}
+
+	pub fn run(_: &[u8], _: &[u8], _: &[u32]) -> Result<u64, &'static str> {
+		Err("runtime built without the `runtime-benchmarks` feature")
+	}
+}
 
 impl_runtime_apis! {
 	impl client_api::Core<Block> for Runtime {
@@ -295,6 +390,16 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl benchmarking_primitives::Benchmark<Block> for Runtime {
+		fn benchmarks() -> Vec<benchmarking_primitives::BenchmarkMetadata> {
+			benchmarks::metadata()
+		}
+
+		fn run_benchmark(module: Vec<u8>, name: Vec<u8>, components: Vec<u32>) -> Result<u64, Vec<u8>> {
+			benchmarks::run(&module, &name, &components).map_err(|e| e.as_bytes().to_vec())
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_pending_change(digest: &DigestFor<Block>)
 			-> Option<ScheduledChange<NumberFor<Block>>>
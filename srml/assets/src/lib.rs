@@ -155,6 +155,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl Trait for Test {
 		type Event = ();
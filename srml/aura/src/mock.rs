@@ -50,6 +50,7 @@ impl system::Trait for Test {
 	type Header = Header;
 	type Event = ();
 	type Log = DigestItem;
+	type SpecVersion = ();
 }
 
 impl timestamp::Trait for Test {
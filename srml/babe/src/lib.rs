@@ -0,0 +1,312 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Consensus extension module for BABE consensus.
+//!
+//! Tracks the BABE epochs: it collects the VRF outputs of primary slot
+//! authors and turns them into the randomness of the next-but-one epoch, and
+//! fixes the authority set of an epoch one epoch in advance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use timestamp;
+
+use rstd::{result, prelude::*};
+use srml_support::storage::StorageValue;
+use srml_support::{decl_storage, decl_module};
+use primitives::traits::As;
+use system::ensure_inherent;
+use inherents::{RuntimeString, InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
+#[cfg(feature = "std")]
+use inherents::{InherentDataProviders, ProvideInherentData};
+#[cfg(feature = "std")]
+use timestamp::TimestampInherentData;
+#[cfg(feature = "std")]
+use parity_codec::Decode;
+use babe_primitives::{
+	AuthorityId, BabeConfiguration, Epoch, Randomness, VrfOutput, VRF_OUTPUT_LENGTH,
+};
+
+mod mock;
+mod tests;
+
+/// The BABE inherent identifier.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"babeslot";
+
+/// The type of the BABE inherent: the slot number, and the VRF output of the
+/// author if the slot was claimed as a primary slot.
+pub type InherentType = (u64, Option<VrfOutput>);
+
+/// Auxiliary trait to extract BABE inherent data.
+pub trait BabeInherentData {
+	/// Get BABE inherent data.
+	fn babe_inherent_data(&self) -> result::Result<InherentType, RuntimeString>;
+	/// Replace BABE inherent data.
+	fn babe_replace_inherent_data(&mut self, new: InherentType);
+}
+
+impl BabeInherentData for InherentData {
+	fn babe_inherent_data(&self) -> result::Result<InherentType, RuntimeString> {
+		self.get_data(&INHERENT_IDENTIFIER)
+			.and_then(|r| r.ok_or_else(|| "BABE inherent data not found".into()))
+	}
+
+	fn babe_replace_inherent_data(&mut self, new: InherentType) {
+		self.replace_data(INHERENT_IDENTIFIER, &new);
+	}
+}
+
+/// Provides the slot inherent data for BABE. The VRF output is only known
+/// once the slot has been claimed, so the authoring node replaces it later.
+#[cfg(feature = "std")]
+pub struct InherentDataProvider {
+	slot_duration: u64,
+}
+
+#[cfg(feature = "std")]
+impl InherentDataProvider {
+	pub fn new(slot_duration: u64) -> Self {
+		Self {
+			slot_duration
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl ProvideInherentData for InherentDataProvider {
+	fn on_register(
+		&self,
+		providers: &InherentDataProviders,
+	) -> result::Result<(), RuntimeString> {
+		if !providers.has_provider(&timestamp::INHERENT_IDENTIFIER) {
+			// Add the timestamp inherent data provider, as we require it.
+			providers.register_provider(timestamp::InherentDataProvider)
+		} else {
+			Ok(())
+		}
+	}
+
+	fn inherent_identifier(&self) -> &'static inherents::InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> result::Result<(), RuntimeString> {
+		let timestamp = inherent_data.timestamp_inherent_data()?;
+		let slot_num = timestamp / self.slot_duration;
+		inherent_data.put_data(INHERENT_IDENTIFIER, &(slot_num, None::<VrfOutput>))
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		RuntimeString::decode(&mut &error[..]).map(Into::into)
+	}
+}
+
+pub trait Trait: timestamp::Trait + consensus::Trait<SessionKey=AuthorityId> {}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Babe {
+		/// The index of the current epoch.
+		pub EpochIndex get(epoch_index): u64;
+		/// The first slot of the current epoch.
+		pub EpochStartSlot get(epoch_start_slot): u64;
+		/// The number of slots in an epoch.
+		pub EpochDuration get(epoch_duration) config(): u64 = 100;
+		/// The probability of a slot having a primary author, as a fraction.
+		pub PrimaryProbability get(primary_probability) config(): (u64, u64) = (1, 4);
+		/// Whether blocks may be authored in secondary slots.
+		pub SecondarySlots get(secondary_slots) config(): bool = true;
+
+		/// The authorities of the current epoch.
+		pub Authorities get(authorities) build(|config: &GenesisConfig<T>| {
+			config.authorities.clone()
+		}): Vec<AuthorityId>;
+		/// The authorities of the next epoch.
+		pub NextAuthorities get(next_authorities) build(|config: &GenesisConfig<T>| {
+			config.authorities.clone()
+		}): Vec<AuthorityId>;
+
+		/// The randomness of the current epoch.
+		pub CurrentRandomness get(randomness) build(|config: &GenesisConfig<T>| {
+			config.randomness
+		}): Randomness;
+		/// The randomness of the next epoch.
+		pub NextRandomness get(next_randomness) build(|config: &GenesisConfig<T>| {
+			config.randomness
+		}): Randomness;
+		/// The VRF outputs collected in the current epoch. They are mixed into the
+		/// randomness of the epoch after the next one.
+		UnderConstruction: Vec<VrfOutput>;
+
+		/// The slot of the current block.
+		pub CurrentSlot get(current_slot): u64;
+		/// Was the slot noted in this block?
+		DidNoteSlot: bool;
+	}
+	add_extra_genesis {
+		config(authorities): Vec<AuthorityId>;
+		config(randomness): Randomness;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// Note the slot of the current block, and the VRF output of its author if
+		/// it was claimed as a primary slot.
+		///
+		/// This call should be invoked exactly once per block. It will panic at the finalization phase,
+		/// if this call hasn't been invoked by that time.
+		///
+		/// The dispatch origin for this call must be `Inherent`.
+		fn note_slot(origin, slot: u64, vrf_output: Option<VrfOutput>) {
+			ensure_inherent(origin)?;
+			assert!(!<Self as Store>::DidNoteSlot::exists(), "BABE slot must be noted only once in the block");
+			assert!(slot > Self::current_slot(), "Only one block may be authored per slot.");
+			<Self as Store>::DidNoteSlot::put(true);
+
+			Self::do_note_slot(slot, vrf_output);
+		}
+
+		fn on_finalise() {
+			assert!(<Self as Store>::DidNoteSlot::take(), "BABE slot must be noted once in the block");
+			assert!(
+				<timestamp::Module<T>>::now().as_() / Self::slot_duration() == Self::current_slot(),
+				"timestamp set in block doesn't match slot noted by BABE"
+			);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Determine the BABE slot-duration based on the timestamp module configuration.
+	pub fn slot_duration() -> u64 {
+		// we double the minimum block-period so each author can always propose within
+		// the majority of their slot.
+		<timestamp::Module<T>>::minimum_period().as_().saturating_mul(2)
+	}
+
+	/// The BABE configuration, as exposed to the node through the runtime API.
+	pub fn configuration() -> BabeConfiguration {
+		BabeConfiguration {
+			slot_duration: Self::slot_duration(),
+			epoch_length: Self::epoch_duration(),
+			c: Self::primary_probability(),
+			secondary_slots: Self::secondary_slots(),
+		}
+	}
+
+	/// The current epoch.
+	pub fn epoch() -> Epoch {
+		Epoch {
+			epoch_index: Self::epoch_index(),
+			start_slot: Self::epoch_start_slot(),
+			duration: Self::epoch_duration(),
+			authorities: Self::authorities(),
+			randomness: Self::randomness(),
+		}
+	}
+
+	/// The epoch following the current one.
+	pub fn next_epoch() -> Epoch {
+		Epoch {
+			epoch_index: Self::epoch_index() + 1,
+			start_slot: Self::epoch_start_slot().saturating_add(Self::epoch_duration()),
+			duration: Self::epoch_duration(),
+			authorities: Self::next_authorities(),
+			randomness: Self::next_randomness(),
+		}
+	}
+
+	fn do_note_slot(slot: u64, vrf_output: Option<VrfOutput>) {
+		let duration = Self::epoch_duration();
+		assert!(duration > 0, "BABE epoch duration cannot be zero.");
+
+		let start_slot = Self::epoch_start_slot();
+		if slot >= start_slot.saturating_add(duration) {
+			// epochs without any block are skipped; the new epoch starts at the
+			// epoch boundary right before `slot`.
+			Self::rotate_epoch(start_slot + (slot - start_slot) / duration * duration);
+		}
+
+		<CurrentSlot<T>>::put(slot);
+		if let Some(vrf_output) = vrf_output {
+			<UnderConstruction<T>>::mutate(|outputs| outputs.push(vrf_output));
+		}
+	}
+
+	fn rotate_epoch(start_slot: u64) {
+		let epoch_index = Self::epoch_index() + 1;
+		<EpochIndex<T>>::put(epoch_index);
+		<EpochStartSlot<T>>::put(start_slot);
+
+		<Authorities<T>>::put(Self::next_authorities());
+		<NextAuthorities<T>>::put(<consensus::Module<T>>::authorities());
+
+		let randomness = Self::next_randomness();
+		<CurrentRandomness<T>>::put(randomness);
+		let vrf_outputs = <UnderConstruction<T>>::take();
+		<NextRandomness<T>>::put(compute_randomness(randomness, epoch_index + 1, vrf_outputs));
+	}
+}
+
+/// Compute the randomness of an epoch from the randomness of its predecessor
+/// and the VRF outputs collected two epochs before it.
+fn compute_randomness(
+	last_epoch_randomness: Randomness,
+	epoch_index: u64,
+	vrf_outputs: Vec<VrfOutput>,
+) -> Randomness {
+	let mut s = Vec::with_capacity(40 + vrf_outputs.len() * VRF_OUTPUT_LENGTH);
+	s.extend_from_slice(&last_epoch_randomness);
+	s.extend_from_slice(&epoch_index.to_le_bytes());
+
+	for vrf_output in vrf_outputs {
+		s.extend_from_slice(&vrf_output[..]);
+	}
+
+	runtime_io::blake2_256(&s)
+}
+
+impl<T: Trait> ProvideInherent for Module<T> {
+	type Call = Call<T>;
+	type Error = MakeFatalError<RuntimeString>;
+	const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+	fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+		let (slot, vrf_output) = data.babe_inherent_data().expect("Gets and decodes BABE inherent data");
+
+		Some(Call::note_slot(slot, vrf_output))
+	}
+
+	fn check_inherent(call: &Self::Call, data: &InherentData) -> result::Result<(), Self::Error> {
+		let noted = match call {
+			Call::note_slot(ref slot, ref vrf_output) => (*slot, vrf_output.clone()),
+			_ => return Ok(()),
+		};
+
+		let expected = data.babe_inherent_data()?;
+
+		if noted.0 != expected.0 {
+			Err(RuntimeString::from("slot noted in block doesn't match slot in seal").into())
+		} else if noted.1 != expected.1 {
+			Err(RuntimeString::from("VRF output noted in block doesn't match VRF output in seal").into())
+		} else {
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,108 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+#![cfg(test)]
+
+use primitives::{BuildStorage, traits::IdentityLookup, testing::{Digest, DigestItem, Header}};
+use srml_support::impl_outer_origin;
+use runtime_io;
+use substrate_primitives::{H256, Blake2Hasher, sr25519};
+use babe_primitives::AuthorityId;
+use crate::{Trait, Module, GenesisConfig};
+
+impl_outer_origin!{
+	pub enum Origin for Test {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Test;
+
+/// The test digest item only knows about ed25519 authorities, so consensus
+/// logs over sr25519 keys are dropped.
+pub struct ConsensusLog;
+
+impl From<consensus::Log<Test>> for ConsensusLog {
+	fn from(_: consensus::Log<Test>) -> Self {
+		ConsensusLog
+	}
+}
+
+impl From<ConsensusLog> for DigestItem {
+	fn from(_: ConsensusLog) -> Self {
+		DigestItem::Other(Vec::new())
+	}
+}
+
+impl consensus::Trait for Test {
+	type Log = ConsensusLog;
+	type SessionKey = AuthorityId;
+	type InherentOfflineReport = ();
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = ::primitives::traits::BlakeTwo256;
+	type Digest = Digest;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type Log = DigestItem;
+	type SpecVersion = ();
+}
+
+impl timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+}
+
+impl Trait for Test {}
+
+/// The authority key with the given index.
+pub fn authority(i: u8) -> AuthorityId {
+	sr25519::Public([i; 32])
+}
+
+pub fn new_test_ext(authorities: Vec<u8>) -> runtime_io::TestExternalities<Blake2Hasher> {
+	let authorities: Vec<_> = authorities.into_iter().map(authority).collect();
+	let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+	t.extend(consensus::GenesisConfig::<Test>{
+		code: vec![],
+		authorities: authorities.clone(),
+	}.build_storage().unwrap().0);
+	t.extend(timestamp::GenesisConfig::<Test>{
+		minimum_period: 1,
+	}.build_storage().unwrap().0);
+	t.extend(GenesisConfig::<Test>{
+		epoch_duration: 10,
+		primary_probability: (1, 4),
+		secondary_slots: true,
+		authorities,
+		randomness: [0; 32],
+	}.build_storage().unwrap().0);
+	t.into()
+}
+
+pub type System = system::Module<Test>;
+pub type Timestamp = timestamp::Module<Test>;
+pub type Consensus = consensus::Module<Test>;
+pub type Babe = Module<Test>;
@@ -0,0 +1,113 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+#![cfg(test)]
+
+use primitives::traits::OnFinalise;
+use runtime_io::with_externalities;
+use srml_support::{assert_ok, storage::StorageValue};
+use inherents::{InherentData, ProvideInherent};
+use crate::mock::{Babe, Consensus, Timestamp, Origin, Test, authority, new_test_ext};
+use crate::{Call, DidNoteSlot, BabeInherentData, INHERENT_IDENTIFIER, compute_randomness};
+
+/// Note a slot as if in a new block.
+fn note_slot(slot: u64, vrf_output: Option<[u8; 32]>) {
+	<DidNoteSlot<Test>>::kill();
+	assert_ok!(Babe::dispatch(Call::note_slot(slot, vrf_output), Origin::INHERENT));
+}
+
+#[test]
+fn first_block_starts_new_epoch() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		assert_eq!(Babe::epoch_index(), 0);
+
+		note_slot(1005, None);
+
+		assert_eq!(Babe::epoch_index(), 1);
+		assert_eq!(Babe::epoch_start_slot(), 1000);
+		assert_eq!(Babe::current_slot(), 1005);
+		assert_eq!(Babe::epoch().authorities, vec![authority(1), authority(2), authority(3)]);
+		assert_eq!(Babe::next_epoch().start_slot, 1010);
+	});
+}
+
+#[test]
+fn vrf_outputs_feed_randomness_of_next_but_one_epoch() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		note_slot(1000, Some([1; 32]));
+		let next_randomness = Babe::next_randomness();
+
+		note_slot(1004, Some([2; 32]));
+
+		// the outputs of this epoch don't affect the next epoch.
+		note_slot(1011, None);
+		assert_eq!(Babe::epoch_index(), 2);
+		assert_eq!(Babe::randomness(), next_randomness);
+		assert_eq!(
+			Babe::next_randomness(),
+			compute_randomness(next_randomness, 3, vec![[1; 32], [2; 32]]),
+		);
+	});
+}
+
+#[test]
+fn next_authorities_are_taken_from_consensus_on_rotation() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		note_slot(1000, None);
+
+		Consensus::set_authorities(&[authority(4)]);
+		note_slot(1010, None);
+		assert_eq!(Babe::authorities(), vec![authority(1), authority(2), authority(3)]);
+		assert_eq!(Babe::next_authorities(), vec![authority(4)]);
+
+		note_slot(1020, None);
+		assert_eq!(Babe::authorities(), vec![authority(4)]);
+	});
+}
+
+#[test]
+#[should_panic(expected = "BABE slot must be noted only once in the block")]
+fn double_note_slot_should_fail() {
+	with_externalities(&mut new_test_ext(vec![1]), || {
+		note_slot(1000, None);
+		let _ = Babe::dispatch(Call::note_slot(1001, None), Origin::INHERENT);
+	});
+}
+
+#[test]
+#[should_panic(expected = "timestamp set in block doesn't match slot noted by BABE")]
+fn slot_must_match_timestamp() {
+	with_externalities(&mut new_test_ext(vec![1]), || {
+		Timestamp::set_timestamp(2000);
+		note_slot(1001, None);
+		Babe::on_finalise(1);
+	});
+}
+
+#[test]
+fn check_inherent_compares_with_seal() {
+	let mut data = InherentData::new();
+	data.put_data(INHERENT_IDENTIFIER, &(1000u64, Some([1u8; 32]))).unwrap();
+
+	assert!(Babe::check_inherent(&Call::note_slot(1000, Some([1; 32])), &data).is_ok());
+	assert!(Babe::check_inherent(&Call::note_slot(1001, Some([1; 32])), &data).is_err());
+	assert!(Babe::check_inherent(&Call::note_slot(1000, None), &data).is_err());
+
+	data.babe_replace_inherent_data((1000, None));
+	assert!(Babe::check_inherent(&Call::note_slot(1000, None), &data).is_ok());
+}
@@ -0,0 +1,43 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of the balances module.
+
+use super::*;
+use srml_benchmarking::{BenchmarkDefinition, account};
+use system::RawOrigin;
+
+/// The benchmarks of the balances module.
+pub fn benchmarks<T: Trait<I>, I: Instance>() -> Vec<BenchmarkDefinition<Call<T, I>, T::Origin>> {
+	vec![
+		BenchmarkDefinition { name: "transfer", components: &[("e", 2, 1000)], setup: transfer::<T, I> },
+	]
+}
+
+/// Transfer `e` times the existential deposit to an account which doesn't exist yet, so that the
+/// transfer has to create it.
+fn transfer<T: Trait<I>, I: Instance>(components: &[u32]) -> result::Result<(Call<T, I>, T::Origin), &'static str> {
+	let value = <Module<T, I>>::existential_deposit() * <T::Balance as As<u64>>::sa(components[0] as u64);
+	let fees = <Module<T, I>>::transfer_fee() + <Module<T, I>>::creation_fee();
+
+	let caller: T::AccountId = account("caller", 0);
+	let _ = <Module<T, I> as Currency<_>>::make_free_balance_be(&caller, value + value + fees);
+
+	let recipient: T::AccountId = account("recipient", 0);
+	let call = Call::transfer(T::Lookup::unlookup(recipient), value);
+
+	Ok((call, RawOrigin::Signed(caller).into()))
+}
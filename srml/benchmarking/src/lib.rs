@@ -0,0 +1,165 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for modules to declare benchmarks of their dispatchable calls.
+//!
+//! A module declares its benchmarks as a list of [`BenchmarkDefinition`]s, usually behind the
+//! `runtime-benchmarks` feature. Each benchmark has a number of components, the parameters the
+//! cost of the call depends on, and a setup function which builds the worst case storage for the
+//! given component values, e.g. a number of existing nominators, and returns the call to time.
+//!
+//! The runtime implements the [`Benchmark`] runtime api on top of [`metadata`] and [`run`], and
+//! the `benchmark` subcommand of the node calls it repeatedly for a range of component values.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::dispatch::Dispatchable;
+
+pub use benchmarking_primitives::{Benchmark, BenchmarkMetadata, BenchmarkComponent};
+
+/// The setup of a benchmark: prepares the storage for the given component values and returns the
+/// call to benchmark with its origin.
+pub type BenchmarkSetup<Call, Origin> = fn(&[u32]) -> Result<(Call, Origin), &'static str>;
+
+/// A benchmark of a dispatchable call.
+pub struct BenchmarkDefinition<Call, Origin> {
+	/// The name of the benchmark, usually the name of the call.
+	pub name: &'static str,
+	/// The name and the lowest and highest value of each component.
+	pub components: &'static [(&'static str, u32, u32)],
+	/// Builds the worst case storage for the component values and returns the call.
+	pub setup: BenchmarkSetup<Call, Origin>,
+}
+
+/// The metadata of the `benchmarks` of the module `module`.
+pub fn metadata<Call, Origin>(
+	module: &str,
+	benchmarks: &[BenchmarkDefinition<Call, Origin>],
+) -> Vec<BenchmarkMetadata> {
+	benchmarks.iter().map(|benchmark| BenchmarkMetadata {
+		module: module.as_bytes().to_vec(),
+		name: benchmark.name.as_bytes().to_vec(),
+		components: benchmark.components.iter().map(|&(name, low, high)| BenchmarkComponent {
+			name: name.as_bytes().to_vec(),
+			low,
+			high,
+		}).collect(),
+	}).collect()
+}
+
+/// Run the benchmark `name` of `benchmarks` for the given component values.
+///
+/// The setup is run first, then the time of the dispatch of the returned call is measured. Returns
+/// the duration of the dispatch in nanoseconds.
+pub fn run<Call, Origin>(
+	benchmarks: &[BenchmarkDefinition<Call, Origin>],
+	name: &[u8],
+	components: &[u32],
+) -> Result<u64, &'static str> where
+	Call: Dispatchable<Origin=Origin>,
+{
+	let benchmark = benchmarks.iter()
+		.find(|benchmark| benchmark.name.as_bytes() == name)
+		.ok_or("unknown benchmark")?;
+
+	if components.len() != benchmark.components.len() {
+		return Err("wrong number of components");
+	}
+	if components.iter().zip(benchmark.components).any(|(v, &(_, low, high))| *v < low || *v > high) {
+		return Err("component value out of range");
+	}
+
+	let (call, origin) = (benchmark.setup)(components)?;
+
+	let start = runtime_io::benchmark_time();
+	call.dispatch(origin)?;
+	let end = runtime_io::benchmark_time();
+
+	Ok(end.saturating_sub(start))
+}
+
+/// A deterministic account for benchmark setups, derived from `name` and `index`.
+pub fn account<AccountId: Decode + Default>(name: &'static str, index: u32) -> AccountId {
+	let entropy = (name.as_bytes(), index).using_encoded(runtime_io::blake2_256);
+	AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::{with_externalities, TestExternalities};
+
+	#[derive(Debug, PartialEq)]
+	struct Call(u32);
+
+	impl Dispatchable for Call {
+		type Origin = bool;
+		type Trait = ();
+
+		fn dispatch(self, origin: bool) -> srml_support::dispatch::DispatchResult {
+			if origin {
+				runtime_io::set_storage(b"dispatched", &self.0.encode());
+				Ok(())
+			} else {
+				Err("bad origin".into())
+			}
+		}
+	}
+
+	fn setup(components: &[u32]) -> Result<(Call, bool), &'static str> {
+		Ok((Call(components[0]), components[1] > 0))
+	}
+
+	fn benchmarks() -> Vec<BenchmarkDefinition<Call, bool>> {
+		vec![
+			BenchmarkDefinition { name: "call", components: &[("x", 1, 10), ("o", 0, 1)], setup },
+		]
+	}
+
+	#[test]
+	fn metadata_lists_benchmarks_and_components() {
+		assert_eq!(metadata("Test", &benchmarks()), vec![BenchmarkMetadata {
+			module: b"Test".to_vec(),
+			name: b"call".to_vec(),
+			components: vec![
+				BenchmarkComponent { name: b"x".to_vec(), low: 1, high: 10 },
+				BenchmarkComponent { name: b"o".to_vec(), low: 0, high: 1 },
+			],
+		}]);
+	}
+
+	#[test]
+	fn run_dispatches_the_call_of_the_setup() {
+		with_externalities(&mut TestExternalities::default(), || {
+			assert!(run(&benchmarks(), b"call", &[7, 1]).is_ok());
+			assert_eq!(runtime_io::storage(b"dispatched"), Some(7u32.encode()));
+
+			assert_eq!(run(&benchmarks(), b"call", &[7, 0]), Err("bad origin"));
+			assert_eq!(run(&benchmarks(), b"other", &[7, 1]), Err("unknown benchmark"));
+			assert_eq!(run(&benchmarks(), b"call", &[7]), Err("wrong number of components"));
+			assert_eq!(run(&benchmarks(), b"call", &[11, 1]), Err("component value out of range"));
+		});
+	}
+
+	#[test]
+	fn accounts_are_deterministic() {
+		assert_eq!(account::<u64>("user", 1), account::<u64>("user", 1));
+		assert!(account::<u64>("user", 1) != account::<u64>("user", 2));
+		assert!(account::<u64>("user", 1) != account::<u64>("caller", 1));
+	}
+}
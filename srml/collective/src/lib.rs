@@ -0,0 +1,634 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collective system: Members of a set of account IDs can make their collective feelings known
+//! through dispatched calls from one of two specialised origins.
+//!
+//! This is the voting of the council's `motions` module made generic over its membership, and
+//! instantiable so that a runtime may have several independent collectives (a council, a
+//! technical committee, ...). The members are set by root or, more usefully, by another module
+//! through the `ChangeMembers` trait, e.g. the `membership` module.
+//!
+//! A motion proposed with a threshold is executed as soon as enough members approve it, or
+//! dropped as soon as it can no longer be approved. Otherwise, once its voting period has ended,
+//! anyone may close it: members who have not voted are then counted as voting the same way as the
+//! prime member, if there is one, and against the motion otherwise.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use rstd::{result, marker::PhantomData};
+use parity_codec::{Encode, Decode};
+use substrate_primitives::u32_trait::Value as U32;
+use primitives::traits::{Hash, EnsureOrigin};
+use srml_support::{
+	dispatch::{Dispatchable, Parameter}, traits::{self, ChangeMembers, Contains, MembersOrigin},
+	StorageValue, StorageMap, decl_module, decl_event, decl_storage, ensure, TypeInfo,
+};
+use system::{self, ensure_signed};
+
+/// Simple index type for proposal counting.
+pub type ProposalIndex = u32;
+
+/// A number of members.
+///
+/// This also serves as a number of voting members, and since for motions, each member may
+/// vote exactly once, therefore also the number of votes for any given motion.
+pub type MemberCount = u32;
+
+pub trait Trait<I=DefaultInstance>: system::Trait {
+	/// The outer origin type.
+	type Origin: From<RawOrigin<Self::AccountId, I>>;
+
+	/// The outer call dispatch type.
+	type Proposal: Parameter + Dispatchable<Origin=<Self as Trait<I>>::Origin>;
+
+	/// The outer event type.
+	type Event: From<Event<Self, I>> + Into<<Self as system::Trait>::Event>;
+}
+
+/// Origin for the collective module.
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RawOrigin<AccountId, I> {
+	/// It has been condoned by a given number of members of the collective from a given total.
+	Members(MemberCount, MemberCount),
+	/// It has been condoned by a single member of the collective.
+	Member(AccountId),
+	/// Dummy to manage the fact we have instancing.
+	_Phantom(PhantomData<I>),
+}
+
+impl<AccountId, I> MembersOrigin for RawOrigin<AccountId, I> {
+	fn members(&self) -> Option<(MemberCount, MemberCount)> {
+		match *self {
+			RawOrigin::Members(n, m) => Some((n, m)),
+			_ => None,
+		}
+	}
+}
+
+/// Origin for the collective module.
+pub type Origin<T, I=DefaultInstance> = RawOrigin<<T as system::Trait>::AccountId, I>;
+
+/// The votes on an active motion.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Votes<AccountId, BlockNumber> {
+	/// The proposal's unique index.
+	pub index: ProposalIndex,
+	/// The number of approval votes that are needed to pass the motion.
+	pub threshold: MemberCount,
+	/// The current set of voters that approved it.
+	pub ayes: Vec<AccountId>,
+	/// The current set of voters that rejected it.
+	pub nays: Vec<AccountId>,
+	/// The block after which the motion may be closed.
+	pub end: BlockNumber,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait<I>, I: Instance=DefaultInstance> as Collective {
+		/// How long each motion is open for voting before it may be closed.
+		pub MotionDuration get(motion_duration) config(): T::BlockNumber;
+		/// The hashes of the active proposals.
+		pub Proposals get(proposals): Vec<T::Hash>;
+		/// Actual proposal for a given hash, if it's current.
+		pub ProposalOf get(proposal_of): map T::Hash => Option<<T as Trait<I>>::Proposal>;
+		/// Votes on a given proposal, if it is ongoing.
+		pub Voting get(voting): map T::Hash => Option<Votes<T::AccountId, T::BlockNumber>>;
+		/// Proposals so far.
+		pub ProposalCount get(proposal_count): u32;
+		/// The current members of the collective. This is stored sorted (just by value).
+		pub Members get(members) config(): Vec<T::AccountId>;
+		/// The member whose vote counts for those who abstain, if any. Always a member.
+		pub Prime get(prime): Option<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T, I: Instance=DefaultInstance> where
+		<T as system::Trait>::Hash,
+		<T as system::Trait>::AccountId
+	{
+		/// A motion (given hash) has been proposed (by given account) with a threshold (given
+		/// `MemberCount`).
+		Proposed(AccountId, ProposalIndex, Hash, MemberCount),
+		/// A motion (given hash) has been voted on by given account, leaving
+		/// a tally (yes votes and no votes given respectively as `MemberCount`).
+		Voted(AccountId, Hash, bool, MemberCount, MemberCount),
+		/// A motion was approved by the required threshold.
+		Approved(Hash),
+		/// A motion was not approved by the required threshold.
+		Disapproved(Hash),
+		/// A motion was executed; `bool` is true if returned without error.
+		Executed(Hash, bool),
+		/// A single member did some action; `bool` is true if returned without error.
+		MemberExecuted(Hash, bool),
+		/// A motion was closed after its voting period, with the given yes and no votes once
+		/// abstentions were counted.
+		Closed(Hash, MemberCount, MemberCount),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait<I>, I: Instance=DefaultInstance> for enum Call where origin: <T as system::Trait>::Origin {
+		fn deposit_event<T, I>() = default;
+
+		/// Set the collective's membership manually to `new_members`. Votes on active motions are
+		/// kept for those who remain members. The prime member is cleared.
+		fn set_members(new_members: Vec<T::AccountId>) {
+			let mut new_members = new_members;
+			new_members.sort();
+			<Self as ChangeMembers<T::AccountId>>::set_members_sorted(&new_members[..], &Self::members()[..]);
+		}
+
+		/// Dispatch a proposal from a member using the `Member` origin.
+		fn execute(origin, proposal: Box<<T as Trait<I>>::Proposal>) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "proposer not a member");
+
+			let proposal_hash = T::Hashing::hash_of(&proposal);
+			let ok = proposal.dispatch(RawOrigin::Member(who).into()).is_ok();
+			Self::deposit_event(RawEvent::MemberExecuted(proposal_hash, ok));
+		}
+
+		/// Propose a motion, to be dispatched with the `Members` origin once `threshold` members
+		/// approve it. With a threshold below two, it is dispatched immediately.
+		fn propose(origin, #[compact] threshold: MemberCount, proposal: Box<<T as Trait<I>>::Proposal>) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "proposer not a member");
+
+			let proposal_hash = T::Hashing::hash_of(&proposal);
+
+			ensure!(!<ProposalOf<T, I>>::exists(proposal_hash), "duplicate proposals not allowed");
+
+			if threshold < 2 {
+				let seats = Self::members().len() as MemberCount;
+				let ok = proposal.dispatch(RawOrigin::Members(1, seats).into()).is_ok();
+				Self::deposit_event(RawEvent::Executed(proposal_hash, ok));
+			} else {
+				let index = Self::proposal_count();
+				<ProposalCount<T, I>>::mutate(|i| *i += 1);
+				<Proposals<T, I>>::mutate(|proposals| proposals.push(proposal_hash));
+				<ProposalOf<T, I>>::insert(proposal_hash, *proposal);
+				let end = <system::Module<T>>::block_number() + Self::motion_duration();
+				let votes = Votes { index, threshold, ayes: vec![who.clone()], nays: vec![], end };
+				<Voting<T, I>>::insert(proposal_hash, votes);
+
+				Self::deposit_event(RawEvent::Proposed(who, index, proposal_hash, threshold));
+			}
+		}
+
+		/// Vote on an active motion. The motion is executed or dropped as soon as its outcome is
+		/// certain.
+		fn vote(origin, proposal: T::Hash, #[compact] index: ProposalIndex, approve: bool) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "voter not a member");
+
+			let mut voting = Self::voting(&proposal).ok_or("proposal must exist")?;
+			ensure!(voting.index == index, "mismatched index");
+
+			let position_yes = voting.ayes.iter().position(|a| a == &who);
+			let position_no = voting.nays.iter().position(|a| a == &who);
+
+			if approve {
+				if position_yes.is_none() {
+					voting.ayes.push(who.clone());
+				} else {
+					return Err("duplicate vote ignored")
+				}
+				if let Some(pos) = position_no {
+					voting.nays.swap_remove(pos);
+				}
+			} else {
+				if position_no.is_none() {
+					voting.nays.push(who.clone());
+				} else {
+					return Err("duplicate vote ignored")
+				}
+				if let Some(pos) = position_yes {
+					voting.ayes.swap_remove(pos);
+				}
+			}
+
+			let yes_votes = voting.ayes.len() as MemberCount;
+			let no_votes = voting.nays.len() as MemberCount;
+			Self::deposit_event(RawEvent::Voted(who, proposal, approve, yes_votes, no_votes));
+
+			let seats = Self::members().len() as MemberCount;
+			let approved = yes_votes >= voting.threshold;
+			let disapproved = seats.saturating_sub(no_votes) < voting.threshold;
+			if approved {
+				Self::finalize_proposal(proposal, voting.threshold, seats, true);
+			} else if disapproved {
+				Self::finalize_proposal(proposal, voting.threshold, seats, false);
+			} else {
+				<Voting<T, I>>::insert(&proposal, voting);
+			}
+		}
+
+		/// Close a motion whose voting period has ended, counting the members who have not voted
+		/// as voting the same way as the prime member, or against the motion if there is none.
+		fn close(origin, proposal: T::Hash, #[compact] index: ProposalIndex) {
+			ensure_signed(origin)?;
+
+			let voting = Self::voting(&proposal).ok_or("proposal must exist")?;
+			ensure!(voting.index == index, "mismatched index");
+			ensure!(<system::Module<T>>::block_number() >= voting.end, "voting period has not ended");
+
+			let seats = Self::members().len() as MemberCount;
+			let mut yes_votes = voting.ayes.len() as MemberCount;
+			let mut no_votes = voting.nays.len() as MemberCount;
+			let abstentions = seats.saturating_sub(yes_votes).saturating_sub(no_votes);
+			let prime_approves = Self::prime().map_or(false, |prime| voting.ayes.contains(&prime));
+			if prime_approves {
+				yes_votes += abstentions;
+			} else {
+				no_votes += abstentions;
+			}
+			Self::deposit_event(RawEvent::Closed(proposal, yes_votes, no_votes));
+
+			Self::finalize_proposal(proposal, voting.threshold, seats, yes_votes >= voting.threshold);
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// Is `who` a member of the collective?
+	pub fn is_member(who: &T::AccountId) -> bool {
+		Self::members().contains(who)
+	}
+
+	/// Execute an approved motion, or drop a disapproved one, and remove it from the active
+	/// motions.
+	fn finalize_proposal(proposal: T::Hash, threshold: MemberCount, seats: MemberCount, approved: bool) {
+		if approved {
+			Self::deposit_event(RawEvent::Approved(proposal));
+
+			// execute motion, assuming it exists.
+			if let Some(p) = <ProposalOf<T, I>>::take(&proposal) {
+				let ok = p.dispatch(RawOrigin::Members(threshold, seats).into()).is_ok();
+				Self::deposit_event(RawEvent::Executed(proposal, ok));
+			}
+		} else {
+			Self::deposit_event(RawEvent::Disapproved(proposal));
+			<ProposalOf<T, I>>::remove(&proposal);
+		}
+
+		// remove vote
+		<Voting<T, I>>::remove(&proposal);
+		<Proposals<T, I>>::mutate(|proposals| proposals.retain(|h| h != &proposal));
+	}
+}
+
+impl<T: Trait<I>, I: Instance> ChangeMembers<T::AccountId> for Module<T, I> {
+	/// Update the members, removing the votes of outgoing members from active motions. The prime
+	/// member is cleared; it is up to the caller to set it again if it is still a member.
+	fn change_members_sorted(_incoming: &[T::AccountId], outgoing: &[T::AccountId], new: &[T::AccountId]) {
+		let mut outgoing = outgoing.to_vec();
+		outgoing.sort();
+		for h in Self::proposals().into_iter() {
+			<Voting<T, I>>::mutate(h, |v|
+				if let Some(mut votes) = v.take() {
+					votes.ayes.retain(|i| outgoing.binary_search(i).is_err());
+					votes.nays.retain(|i| outgoing.binary_search(i).is_err());
+					*v = Some(votes);
+				}
+			);
+		}
+		<Members<T, I>>::put(new.to_vec());
+		<Prime<T, I>>::kill();
+	}
+
+	fn set_prime(prime: Option<T::AccountId>) {
+		match prime {
+			Some(prime) => <Prime<T, I>>::put(prime),
+			None => <Prime<T, I>>::kill(),
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Contains<T::AccountId> for Module<T, I> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		Self::members()
+	}
+}
+
+/// Ensure that the origin `o` represents at least `n` members of the collective. Returns
+/// `Ok` with `n` or an `Err` otherwise.
+pub fn ensure_members<OuterOrigin, AccountId, I>(o: OuterOrigin, n: MemberCount)
+	-> result::Result<MemberCount, &'static str>
+	where OuterOrigin: Into<Option<RawOrigin<AccountId, I>>>
+{
+	match o.into() {
+		Some(RawOrigin::Members(x, _)) if x >= n => Ok(n),
+		_ => Err("bad origin: expected to be a threshold number of members"),
+	}
+}
+
+/// Ensure that the origin is a single member of the collective, returning their account.
+pub struct EnsureMember<AccountId, I=DefaultInstance>(PhantomData<(AccountId, I)>);
+impl<O, AccountId, I> EnsureOrigin<O> for EnsureMember<AccountId, I>
+	where O: Into<Option<RawOrigin<AccountId, I>>>
+{
+	type Success = AccountId;
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into() {
+			Some(RawOrigin::Member(id)) => Ok(id),
+			_ => Err("bad origin: expected to be a member"),
+		}
+	}
+}
+
+/// Ensure that the origin represents at least `N` members of the collective.
+pub struct EnsureMembers<N: U32, AccountId, I=DefaultInstance>(PhantomData<(N, AccountId, I)>);
+impl<O, N: U32, AccountId, I> EnsureOrigin<O> for EnsureMembers<N, AccountId, I>
+	where O: Into<Option<RawOrigin<AccountId, I>>>
+{
+	type Success = (MemberCount, MemberCount);
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into() {
+			Some(RawOrigin::Members(n, m)) if n >= N::VALUE => Ok((n, m)),
+			_ => Err("bad origin: expected to be a threshold number of members"),
+		}
+	}
+}
+
+/// Ensure that the origin represents more than `N/D` of the collective's members.
+pub type EnsureProportionMoreThan<N, D, AccountId, I=DefaultInstance> =
+	traits::EnsureProportionMoreThan<N, D, RawOrigin<AccountId, I>>;
+
+/// Ensure that the origin represents at least `N/D` of the collective's members.
+pub type EnsureProportionAtLeast<N, D, AccountId, I=DefaultInstance> =
+	traits::EnsureProportionAtLeast<N, D, RawOrigin<AccountId, I>>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::with_externalities;
+	use srml_support::{Hashable, assert_ok, assert_noop, impl_outer_origin, impl_outer_event, impl_outer_dispatch};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::BuildStorage;
+	use primitives::traits::{BlakeTwo256, IdentityLookup};
+	use primitives::testing::{Digest, DigestItem, Header};
+	use system::{EventRecord, Phase};
+	use hex_literal::{hex, hex_impl};
+
+	mod collective {
+		pub use crate::*;
+	}
+
+	impl_outer_origin! {
+		pub enum Origin for Test {
+			collective<T, Instance1>
+		}
+	}
+
+	impl_outer_event! {
+		pub enum Event for Test {
+			balances<T>, collective<T, Instance1>,
+		}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = Event;
+		type Log = DigestItem;
+		type SpecVersion = ();
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = Event;
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+	}
+	impl Trait<Instance1> for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = Event;
+	}
+
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Collective = Module<Test, Instance1>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+		t.extend(GenesisConfig::<Test, Instance1> {
+			motion_duration: 3,
+			members: vec![1, 2, 3],
+			_genesis_phantom_data: Default::default(),
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn make_proposal(value: u64) -> Call {
+		Call::Balances(balances::Call::set_balance(42, value.into(), 0))
+	}
+
+	fn record(event: Event) -> EventRecord<Event> {
+		EventRecord { phase: Phase::ApplyExtrinsic(0), event }
+	}
+
+	#[test]
+	fn basic_environment_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_eq!(Collective::members(), vec![1, 2, 3]);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+		});
+	}
+
+	#[test]
+	fn propose_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_eq!(Collective::proposals(), vec![hash]);
+			assert_eq!(Collective::proposal_of(&hash), Some(proposal));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![1], nays: vec![], end: 4 })
+			);
+
+			assert_eq!(System::events(), vec![
+				record(Event::collective(RawEvent::Proposed(
+					1,
+					0,
+					hex!["cd0b662a49f004093b80600415cf4126399af0d27ed6c185abeb1469c17eb5bf"].into(),
+					3,
+				))),
+			]);
+		});
+	}
+
+	#[test]
+	fn non_members_cannot_propose_or_vote() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_noop!(
+				Collective::propose(Origin::signed(42), 3, Box::new(proposal.clone())),
+				"proposer not a member"
+			);
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_noop!(Collective::vote(Origin::signed(42), hash.clone(), 0, true), "voter not a member");
+			assert_noop!(Collective::vote(Origin::signed(2), hash.clone(), 1, true), "mismatched index");
+			assert_noop!(Collective::vote(Origin::signed(1), hash.clone(), 0, true), "duplicate vote ignored");
+		});
+	}
+
+	#[test]
+	fn approval_executes_with_members_origin() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 2, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+
+			assert_eq!(System::events(), vec![
+				record(Event::collective(RawEvent::Proposed(1, 0, hash.clone(), 2))),
+				record(Event::collective(RawEvent::Voted(2, hash.clone(), true, 2, 0))),
+				record(Event::collective(RawEvent::Approved(hash.clone()))),
+				// `set_balance` requires root.
+				record(Event::collective(RawEvent::Executed(hash.clone(), false))),
+			]);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+			assert_eq!(Collective::voting(&hash), None);
+		});
+	}
+
+	#[test]
+	fn disapproval_drops_motion() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, false));
+
+			assert_eq!(System::events(), vec![
+				record(Event::collective(RawEvent::Proposed(1, 0, hash.clone(), 3))),
+				record(Event::collective(RawEvent::Voted(2, hash.clone(), false, 1, 1))),
+				record(Event::collective(RawEvent::Disapproved(hash.clone()))),
+			]);
+			assert_eq!(Collective::proposal_of(&hash), None);
+		});
+	}
+
+	#[test]
+	fn close_counts_abstentions_against_without_prime() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 2, Box::new(proposal.clone())));
+			// 4 members, so a single approval can neither pass nor fail the motion yet.
+			assert_ok!(Collective::set_members(vec![1, 2, 3, 4]));
+
+			assert_noop!(Collective::close(Origin::signed(4), hash.clone(), 0), "voting period has not ended");
+
+			System::set_block_number(4);
+			assert_ok!(Collective::close(Origin::signed(4), hash.clone(), 0));
+			assert_eq!(System::events(), vec![
+				record(Event::collective(RawEvent::Proposed(1, 0, hash.clone(), 2))),
+				record(Event::collective(RawEvent::Closed(hash.clone(), 1, 3))),
+				record(Event::collective(RawEvent::Disapproved(hash.clone()))),
+			]);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+		});
+	}
+
+	#[test]
+	fn close_counts_abstentions_with_prime() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::set_members(vec![1, 2, 3, 4]));
+			<Collective as ChangeMembers<u64>>::set_prime(Some(1));
+
+			System::set_block_number(4);
+			assert_ok!(Collective::close(Origin::signed(4), hash.clone(), 0));
+			assert_eq!(System::events(), vec![
+				record(Event::collective(RawEvent::Proposed(1, 0, hash.clone(), 3))),
+				record(Event::collective(RawEvent::Closed(hash.clone(), 4, 0))),
+				record(Event::collective(RawEvent::Approved(hash.clone()))),
+				record(Event::collective(RawEvent::Executed(hash.clone(), false))),
+			]);
+		});
+	}
+
+	#[test]
+	fn removed_members_lose_their_votes() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = make_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+			<Collective as ChangeMembers<u64>>::set_prime(Some(2));
+
+			assert_ok!(Collective::set_members(vec![2, 3, 4]));
+			assert_eq!(Collective::members(), vec![2, 3, 4]);
+			assert_eq!(Collective::voting(&hash).unwrap().ayes, vec![2]);
+			assert_eq!(Collective::prime(), None);
+			assert!(!Collective::is_member(&1));
+		});
+	}
+
+	#[test]
+	fn member_origins_are_checked() {
+		type Origin = RawOrigin<u64, Instance1>;
+		assert_eq!(EnsureMember::<u64, Instance1>::ensure_origin(Origin::Member(1)), Ok(1));
+		assert!(EnsureMember::<u64, Instance1>::ensure_origin(Origin::Members(2, 3)).is_err());
+		assert_eq!(
+			EnsureMembers::<substrate_primitives::u32_trait::_2, u64, Instance1>::ensure_origin(Origin::Members(2, 3)),
+			Ok((2, 3))
+		);
+		assert!(
+			EnsureMembers::<substrate_primitives::u32_trait::_3, u64, Instance1>::ensure_origin(Origin::Members(2, 3))
+				.is_err()
+		);
+		assert_eq!(ensure_members(Origin::Members(3, 3), 2), Ok(2));
+	}
+}
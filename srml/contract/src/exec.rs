@@ -22,9 +22,12 @@ use rstd::prelude::*;
 use rstd::cell::RefCell;
 use rstd::rc::Rc;
 use runtime_primitives::traits::{CheckedAdd, CheckedSub, Zero};
-use srml_support::traits::{WithdrawReason, Currency};
+use srml_support::traits::{WithdrawReason, Currency, Randomness};
 use timestamp;
 
+/// The subject under which contracts draw from `Trait::Randomness`.
+const CONTRACT_SUBJECT: &[u8] = b"contract";
+
 pub type AccountIdOf<T> = <T as system::Trait>::AccountId;
 pub type CallOf<T> = <T as Trait>::Call;
 pub type MomentOf<T> = <T as timestamp::Trait>::Moment;
@@ -103,7 +106,7 @@ pub trait Ext {
 	/// Returns a reference to the timestamp of the current block
 	fn now(&self) -> &MomentOf<Self::T>;
 
-	/// Returns a reference to the random seed for the current block
+	/// Returns a reference to the random seed exposed to contracts, taken from `Trait::Randomness`
 	fn random_seed(&self) -> &SeedOf<Self::T>;
 }
 
@@ -327,7 +330,7 @@ where
 							caller: self.self_account.clone(),
 							value_transferred: value,
 							timestamp: timestamp::Module::<T>::now(),
-							random_seed: system::Module::<T>::random_seed(),
+							random_seed: T::Randomness::random(CONTRACT_SUBJECT).0,
 						},
 						input_data,
 						empty_output_buf,
@@ -401,7 +404,7 @@ where
 						caller: self.self_account.clone(),
 						value_transferred: endowment,
 						timestamp: timestamp::Module::<T>::now(),
-						random_seed: system::Module::<T>::random_seed(),
+						random_seed: T::Randomness::random(CONTRACT_SUBJECT).0,
 					},
 					input_data,
 					EmptyOutputBuf::new(),
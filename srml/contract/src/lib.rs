@@ -73,8 +73,8 @@ use rstd::marker::PhantomData;
 use parity_codec::{Codec, Encode, Decode};
 use runtime_primitives::traits::{Hash, As, SimpleArithmetic,Bounded, StaticLookup};
 use srml_support::dispatch::{Result, Dispatchable};
-use srml_support::{Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage, storage::child};
-use srml_support::traits::{OnFreeBalanceZero, OnUnbalanced, Currency};
+use srml_support::{Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage, storage::child, TypeInfo};
+use srml_support::traits::{OnFreeBalanceZero, OnUnbalanced, Currency, Randomness};
 use system::{ensure_signed, RawOrigin};
 use timestamp;
 
@@ -91,7 +91,7 @@ pub trait ComputeDispatchFee<Call, Balance> {
 	fn compute_dispatch_fee(call: &Call) -> Balance;
 }
 
-#[derive(Encode,Decode,Clone,Debug)]
+#[derive(Encode,Decode,Clone,Debug,TypeInfo)]
 /// Information for managing an acocunt and its sub trie abstraction.
 /// This is the required info to cache for an account
 pub struct AccountInfo {
@@ -162,6 +162,9 @@ pub trait Trait: timestamp::Trait {
 
 	/// Handler for the unbalanced reduction when making a gas payment.
 	type GasPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The source of the random seed exposed to contracts.
+	type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
 }
 
 /// Simple contract address determintator.
@@ -204,6 +207,9 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
 		fn deposit_event<T>() = default;
 
+		/// The schedule used to meter contracts, unless configured or updated otherwise.
+		const DefaultSchedule: Schedule<T::Gas> = Schedule::default();
+
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
@@ -450,7 +456,7 @@ impl<T: Trait> Config<T> {
 
 /// Definition of the cost schedule and other parameterizations for wasm vm.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-#[derive(Clone, Encode, Decode, PartialEq, Eq)]
+#[derive(Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
 pub struct Schedule<Gas> {
 	/// Version of the schedule.
 	pub version: u32,
@@ -76,6 +76,7 @@ impl system::Trait for Test {
 	type Header = Header;
 	type Event = MetaEvent;
 	type Log = DigestItem;
+	type SpecVersion = ();
 }
 impl balances::Trait for Test {
 	type Balance = u64;
@@ -104,6 +105,7 @@ impl Trait for Test {
 	type ComputeDispatchFee = DummyComputeDispatchFee;
 	type TrieIdGenerator = DummyTrieIdGenerator;
 	type GasPayment = ();
+	type Randomness = ();
 }
 
 type Balances = balances::Module<Test>;
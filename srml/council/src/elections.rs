@@ -0,0 +1,524 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Council elections by sequential Phragmén.
+//!
+//! An alternative to the presentation-based elections of the `seats` module. Voters reserve a
+//! bond, lock some of their balance and approve any number of candidates. At the end of every
+//! term an election is run automatically over all candidates, sitting members and runners-up,
+//! with the sequential Phragmén method of the staking module. The winners are written to the
+//! `ActiveCouncil` of the `seats` module, which is what `motions` reads, while the next best are
+//! kept as runners-up to fill any seat vacated during the term.
+//!
+//! When this module is used, the elections of the `seats` module should be disabled by setting
+//! its desired seats to zero.
+
+use rstd::prelude::*;
+use primitives::traits::{Zero, As, Bounded, StaticLookup, Hash};
+use srml_support::{
+	StorageValue, StorageMap, EnumerableStorageMap, decl_module, decl_storage, decl_event, ensure,
+	traits::{
+		Currency, ReservableCurrency, LockableCurrency, OnUnbalanced, WithdrawReason, LockIdentifier,
+		Randomness,
+	}
+};
+use staking::phragmen;
+use system::{self, ensure_signed};
+use crate::seats;
+
+const ELECTIONS_ID: LockIdentifier = *b"phrelect";
+
+type BalanceOf<T> = <<T as democracy::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as democracy::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+pub trait Trait: seats::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Handler for the unbalanced reduction when a candidate has lost an election.
+	type LoserCandidate: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Handler for the unbalanced reduction when a member has been kicked.
+	type KickedMember: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The source of randomness used to break ties between equally backed candidates.
+	type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// Approve `votes` as councillors, backing each of them with `value` of the voter's
+		/// balance. Only candidates, members and runners-up may be approved, each at most once.
+		/// The value is locked until the voter is removed, and the voting bond is reserved the
+		/// first time one votes. Any previous vote is replaced.
+		fn vote(origin, votes: Vec<T::AccountId>, #[compact] value: BalanceOf<T>) {
+			let who = ensure_signed(origin)?;
+
+			let mut votes = votes;
+			votes.sort();
+			votes.dedup();
+			ensure!(!votes.is_empty(), "cannot vote for no candidates");
+			let candidates = Self::candidates();
+			let allowed_votes = candidates.len()
+				+ <seats::Module<T>>::active_council().len()
+				+ Self::runners_up().len();
+			ensure!(votes.len() <= allowed_votes, "cannot vote more than candidates");
+			ensure!(
+				votes.iter().all(|v| candidates.binary_search(v).is_ok() || Self::is_member(v) || Self::is_runner_up(v)),
+				"cannot vote for non-candidate"
+			);
+			ensure!(!value.is_zero(), "cannot vote with no stake");
+
+			let bond = if Self::is_voter(&who) { Zero::zero() } else { Self::voting_bond() };
+			ensure!(value + bond <= T::Currency::free_balance(&who), "voter's balance too low");
+
+			if !bond.is_zero() {
+				T::Currency::reserve(&who, bond)
+					.map_err(|_| "voter can not pay voting bond")?;
+				<VotingBondOf<T>>::insert(&who, bond);
+			}
+			T::Currency::set_lock(ELECTIONS_ID, &who, value, Bounded::max_value(), WithdrawReason::Transfer.into());
+			<StakeOf<T>>::insert(&who, value);
+			<VotesOf<T>>::insert(&who, votes);
+		}
+
+		/// Remove a voter, unlocking their balance and returning their voting bond.
+		fn remove_voter(origin) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_voter(&who), "must be a voter");
+
+			<VotesOf<T>>::remove(&who);
+			<StakeOf<T>>::remove(&who);
+			T::Currency::remove_lock(ELECTIONS_ID, &who);
+			T::Currency::unreserve(&who, <VotingBondOf<T>>::take(&who));
+		}
+
+		/// Submit oneself for candidacy in the next election.
+		///
+		/// The candidacy bond is reserved until the candidate loses an election, in which case it
+		/// is slashed, or leaves the council or runners-up, in which case it is returned.
+		fn submit_candidacy(origin) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!Self::is_member(&who), "member cannot re-submit candidacy");
+			ensure!(!Self::is_runner_up(&who), "runner-up cannot re-submit candidacy");
+			let mut candidates = Self::candidates();
+			let index = candidates.binary_search(&who).err().ok_or("duplicate candidate submission")?;
+
+			// NOTE: This must be last as it has side-effects.
+			let bond = Self::candidacy_bond();
+			T::Currency::reserve(&who, bond)
+				.map_err(|_| "candidate has not enough funds")?;
+			<CandidacyBondOf<T>>::insert(&who, bond);
+
+			candidates.insert(index, who);
+			<Candidates<T>>::put(candidates);
+		}
+
+		/// Renounce being a candidate, runner-up or member, returning the candidacy bond. A
+		/// member's seat is filled by the best runner-up, if any.
+		fn renounce_candidacy(origin) {
+			let who = ensure_signed(origin)?;
+
+			if Self::is_member(&who) {
+				Self::remove_and_replace_member(&who);
+				Self::deposit_event(RawEvent::MemberRenounced(who.clone()));
+			} else if let Some(index) = Self::runners_up().iter().position(|r| *r == who) {
+				<RunnersUp<T>>::mutate(|runners_up| { runners_up.remove(index); });
+			} else {
+				let mut candidates = Self::candidates();
+				let index = candidates.binary_search(&who)
+					.map_err(|_| "origin is not a candidate, member or runner-up")?;
+				candidates.remove(index);
+				<Candidates<T>>::put(candidates);
+			}
+			T::Currency::unreserve(&who, <CandidacyBondOf<T>>::take(&who));
+		}
+
+		/// Remove a particular member, slashing their candidacy bond. The seat is filled by the
+		/// best runner-up, if any, for the rest of the term.
+		fn remove_member(who: <T::Lookup as StaticLookup>::Source) {
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::is_member(&who), "not a member");
+
+			let imbalance = T::Currency::slash_reserved(&who, <CandidacyBondOf<T>>::take(&who)).0;
+			T::KickedMember::on_unbalanced(imbalance);
+			Self::remove_and_replace_member(&who);
+			Self::deposit_event(RawEvent::MemberKicked(who));
+		}
+
+		fn on_finalise(n: T::BlockNumber) {
+			Self::end_block(n);
+		}
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as CouncilElections {
+		// parameters
+		/// How much should be reserved in order to submit one's candidacy.
+		pub CandidacyBond get(candidacy_bond) config(): BalanceOf<T> = BalanceOf::<T>::sa(9);
+		/// How much should be reserved in order to be able to submit votes.
+		pub VotingBond get(voting_bond) config(): BalanceOf<T>;
+		/// Number of accounts that should be sitting on the council.
+		pub DesiredMembers get(desired_members) config(): u32;
+		/// Number of runners-up to keep between elections.
+		pub DesiredRunnersUp get(desired_runners_up) config(): u32;
+		/// How long each term lasts. An election is held at the end of every term.
+		pub TermDuration get(term_duration) config(): T::BlockNumber = T::BlockNumber::sa(1000);
+
+		// permanent state (changes only at elections and when members leave)
+		/// The runners-up of the last election, best first. They fill seats vacated during a term.
+		pub RunnersUp get(runners_up): Vec<T::AccountId>;
+		/// The number of elections held so far.
+		pub ElectionRounds get(election_rounds): u32;
+
+		// persistent state (changes constantly)
+		/// The present candidate list, sorted. Members and runners-up are not included, though
+		/// they stand in every election.
+		pub Candidates get(candidates): Vec<T::AccountId>;
+		/// The candidates approved by each voter.
+		pub VotesOf get(votes_of): linked_map T::AccountId => Vec<T::AccountId>;
+		/// The locked balance with which each voter backs their approvals.
+		pub StakeOf get(stake_of): map T::AccountId => BalanceOf<T>;
+		/// The voting bond reserved by each voter, returned when they are removed.
+		pub VotingBondOf get(voting_bond_of): map T::AccountId => BalanceOf<T>;
+		/// The candidacy bond reserved by each candidate, member and runner-up.
+		pub CandidacyBondOf get(candidacy_bond_of): map T::AccountId => BalanceOf<T>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where <T as system::Trait>::AccountId {
+		/// A new term has begun with the given members and runners-up.
+		NewTerm(Vec<AccountId>, Vec<AccountId>),
+		/// A member has been removed, and their bond slashed.
+		MemberKicked(AccountId),
+		/// A member has renounced their seat.
+		MemberRenounced(AccountId),
+	}
+);
+
+impl<T: Trait> Module<T> {
+	// exposed immutables.
+
+	/// Is `who` currently a member of the council?
+	pub fn is_member(who: &T::AccountId) -> bool {
+		<seats::Module<T>>::active_council().iter().any(|(m, _)| m == who)
+	}
+
+	/// Is `who` currently a runner-up?
+	pub fn is_runner_up(who: &T::AccountId) -> bool {
+		Self::runners_up().contains(who)
+	}
+
+	/// Is `who` currently a voter?
+	pub fn is_voter(who: &T::AccountId) -> bool {
+		<VotesOf<T>>::exists(who)
+	}
+
+	// Private
+
+	/// Hold an election if the current term is ending.
+	fn end_block(n: T::BlockNumber) {
+		let term_duration = Self::term_duration();
+		if !term_duration.is_zero() && (n % term_duration).is_zero() {
+			Self::do_phragmen();
+		}
+	}
+
+	/// Remove `who` from the council. Their seat is given to the best runner-up, if any, for the
+	/// rest of the term; otherwise it remains vacant until the next election.
+	fn remove_and_replace_member(who: &T::AccountId) {
+		let mut council = <seats::Module<T>>::active_council();
+		if let Some(index) = council.iter().position(|(m, _)| m == who) {
+			let (_, expiry) = council.remove(index);
+			let mut runners_up = Self::runners_up();
+			if !runners_up.is_empty() {
+				council.push((runners_up.remove(0), expiry));
+				council.sort_by_key(|&(_, expiry)| expiry);
+				<RunnersUp<T>>::put(runners_up);
+			}
+			<seats::ActiveCouncil<T>>::put(council);
+		}
+	}
+
+	/// Elect a new council and runners-up from all candidates, members and runners-up, and start
+	/// a new term.
+	///
+	/// Candidates who end up neither members nor runners-up lose their bond. Members and
+	/// runners-up who are not re-elected have theirs returned.
+	fn do_phragmen() {
+		let desired_members = Self::desired_members() as usize;
+		let desired_runners_up = Self::desired_runners_up() as usize;
+
+		let candidates = <Candidates<T>>::take();
+		let old_members = <seats::Module<T>>::active_council().into_iter()
+			.map(|(m, _)| m)
+			.collect::<Vec<_>>();
+		let old_runners_up = Self::runners_up();
+
+		// Phragmén elects the first of equally scored candidates; shuffle them so that ties are
+		// broken at random rather than by the order of candidacy.
+		let (seed, _) = T::Randomness::random(&b"phrelect"[..]);
+		let mut shuffled = candidates.iter()
+			.chain(old_members.iter())
+			.chain(old_runners_up.iter())
+			.map(|c| (T::Hashing::hash_of(&(seed, c)), c.clone()))
+			.collect::<Vec<_>>();
+		shuffled.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+		let all_candidates = shuffled.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+		let voters = <VotesOf<T>>::enumerate()
+			.map(|(who, votes)| {
+				let stake = Self::stake_of(&who);
+				(who, stake, votes)
+			})
+			.collect::<Vec<_>>();
+
+		let mut new_members = phragmen::elect_by_approval(
+			desired_members + desired_runners_up,
+			all_candidates,
+			voters,
+		);
+		let new_runners_up = new_members.split_off(new_members.len().min(desired_members));
+		let elected = |who: &T::AccountId| new_members.contains(who) || new_runners_up.contains(who);
+
+		for loser in candidates.iter().filter(|c| !elected(c)) {
+			let imbalance = T::Currency::slash_reserved(loser, <CandidacyBondOf<T>>::take(loser)).0;
+			T::LoserCandidate::on_unbalanced(imbalance);
+		}
+		for outgoing in old_members.iter().chain(old_runners_up.iter()).filter(|o| !elected(o)) {
+			T::Currency::unreserve(outgoing, <CandidacyBondOf<T>>::take(outgoing));
+		}
+
+		let expiry = <system::Module<T>>::block_number() + Self::term_duration();
+		<seats::ActiveCouncil<T>>::put(new_members.iter().cloned().map(|m| (m, expiry)).collect::<Vec<_>>());
+		<RunnersUp<T>>::put(new_runners_up.clone());
+		<ElectionRounds<T>>::mutate(|r| *r += 1);
+
+		Self::deposit_event(RawEvent::NewTerm(new_members, new_runners_up));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::*;
+	use crate::tests::Origin;
+	use srml_support::{assert_ok, assert_noop};
+
+	#[test]
+	fn elections_basic_environment_works() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_eq!(CouncilElections::desired_members(), 2);
+			assert_eq!(CouncilElections::desired_runners_up(), 1);
+			assert_eq!(CouncilElections::term_duration(), 5);
+			assert_eq!(CouncilElections::election_rounds(), 0);
+			assert_eq!(CouncilElections::candidates(), Vec::<u64>::new());
+			assert_eq!(CouncilElections::runners_up(), Vec::<u64>::new());
+			assert_eq!(Council::active_council(), vec![]);
+		});
+	}
+
+	#[test]
+	fn submit_candidacy_should_work() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(4)));
+			assert_noop!(CouncilElections::submit_candidacy(Origin::signed(4)), "duplicate candidate submission");
+
+			assert_eq!(CouncilElections::candidates(), vec![4, 5]);
+			assert_eq!(Balances::reserved_balance(&4), 3);
+			assert_eq!(Balances::reserved_balance(&5), 3);
+		});
+	}
+
+	#[test]
+	fn vote_should_lock_and_reserve() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![], 10), "cannot vote for no candidates");
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![5], 10), "cannot vote more than candidates");
+
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![5], 0), "cannot vote with no stake");
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![5], 19), "voter's balance too low");
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![5], 18));
+
+			assert_eq!(CouncilElections::votes_of(&2), vec![5]);
+			assert_eq!(CouncilElections::stake_of(&2), 18);
+			assert_eq!(Balances::reserved_balance(&2), 2);
+			assert_eq!(Balances::locks(&2).len(), 1);
+
+			assert_ok!(CouncilElections::remove_voter(Origin::signed(2)));
+			assert_noop!(CouncilElections::remove_voter(Origin::signed(2)), "must be a voter");
+			assert!(!CouncilElections::is_voter(&2));
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::locks(&2), vec![]);
+		});
+	}
+
+	#[test]
+	fn vote_should_only_approve_candidates_once() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(4)));
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![4, 3], 10), "cannot vote for non-candidate");
+			assert_noop!(CouncilElections::vote(Origin::signed(2), vec![5, 5, 5], 0), "cannot vote with no stake");
+
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![5, 4, 5, 4], 10));
+			assert_eq!(CouncilElections::votes_of(&2), vec![4, 5]);
+		});
+	}
+
+	#[test]
+	fn bonds_should_be_returned_as_reserved() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![5], 10));
+
+			<CandidacyBond<Test>>::put(10);
+			<VotingBond<Test>>::put(5);
+
+			assert_ok!(CouncilElections::renounce_candidacy(Origin::signed(5)));
+			assert_eq!(Balances::reserved_balance(&5), 0);
+			assert_eq!(Balances::free_balance(&5), 50);
+			assert_ok!(CouncilElections::remove_voter(Origin::signed(2)));
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::free_balance(&2), 20);
+		});
+	}
+
+	#[test]
+	fn simple_election_should_work() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(4)));
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![5], 10));
+			assert_ok!(CouncilElections::vote(Origin::signed(3), vec![4, 5], 20));
+
+			System::set_block_number(4);
+			CouncilElections::end_block(System::block_number());
+			assert_eq!(Council::active_council(), vec![]);
+
+			System::set_block_number(5);
+			CouncilElections::end_block(System::block_number());
+
+			assert_eq!(Council::active_council(), vec![(5, 10), (4, 10)]);
+			assert!(CouncilMotions::is_councillor(&5));
+			assert_eq!(CouncilElections::candidates(), Vec::<u64>::new());
+			assert_eq!(CouncilElections::runners_up(), Vec::<u64>::new());
+			assert_eq!(CouncilElections::election_rounds(), 1);
+			// members keep their bond.
+			assert_eq!(Balances::reserved_balance(&4), 3);
+			assert_eq!(Balances::reserved_balance(&5), 3);
+
+			// incumbents stand again without resubmitting, but 4 loses its backing.
+			assert_ok!(CouncilElections::remove_voter(Origin::signed(3)));
+			System::set_block_number(10);
+			CouncilElections::end_block(System::block_number());
+
+			assert_eq!(Council::active_council(), vec![(5, 15)]);
+			assert_eq!(CouncilElections::election_rounds(), 2);
+			// outgoing members get their bond back.
+			assert_eq!(Balances::reserved_balance(&4), 0);
+			assert_eq!(Balances::free_balance(&4), 40);
+		});
+	}
+
+	#[test]
+	fn runners_up_and_losers_should_be_decided() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			for c in 3..7 {
+				assert_ok!(CouncilElections::submit_candidacy(Origin::signed(c)));
+			}
+			assert_ok!(CouncilElections::vote(Origin::signed(1), vec![3], 5));
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![4], 15));
+			assert_ok!(CouncilElections::vote(Origin::signed(5), vec![5], 30));
+			assert_ok!(CouncilElections::vote(Origin::signed(6), vec![6], 40));
+
+			System::set_block_number(5);
+			CouncilElections::end_block(System::block_number());
+
+			assert_eq!(Council::active_council(), vec![(6, 10), (5, 10)]);
+			assert_eq!(CouncilElections::runners_up(), vec![4]);
+			assert_eq!(Balances::reserved_balance(&4), 3);
+			// the loser is slashed.
+			assert_eq!(Balances::reserved_balance(&3), 0);
+			assert_eq!(Balances::free_balance(&3), 27);
+		});
+	}
+
+	#[test]
+	fn runner_up_should_fill_vacated_seats() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			for c in 4..7 {
+				assert_ok!(CouncilElections::submit_candidacy(Origin::signed(c)));
+			}
+			assert_ok!(CouncilElections::vote(Origin::signed(2), vec![4], 15));
+			assert_ok!(CouncilElections::vote(Origin::signed(5), vec![5], 30));
+			assert_ok!(CouncilElections::vote(Origin::signed(6), vec![6], 40));
+
+			System::set_block_number(5);
+			CouncilElections::end_block(System::block_number());
+			assert_eq!(Council::active_council(), vec![(6, 10), (5, 10)]);
+			assert_eq!(CouncilElections::runners_up(), vec![4]);
+
+			System::set_block_number(6);
+			assert_noop!(CouncilElections::submit_candidacy(Origin::signed(6)), "member cannot re-submit candidacy");
+			assert_noop!(CouncilElections::submit_candidacy(Origin::signed(4)), "runner-up cannot re-submit candidacy");
+
+			// a renouncing member gets their bond back and is replaced by the runner-up.
+			assert_ok!(CouncilElections::renounce_candidacy(Origin::signed(6)));
+			assert_eq!(Council::active_council(), vec![(5, 10), (4, 10)]);
+			assert_eq!(CouncilElections::runners_up(), Vec::<u64>::new());
+			// only the voting bond remains reserved.
+			assert_eq!(Balances::reserved_balance(&6), 2);
+
+			// a kicked member loses their bond, and the seat stays vacant without runners-up.
+			assert_noop!(CouncilElections::remove_member(6), "not a member");
+			assert_ok!(CouncilElections::remove_member(5));
+			assert_eq!(Council::active_council(), vec![(4, 10)]);
+			assert_eq!(Balances::reserved_balance(&5), 2);
+			assert_eq!(Balances::free_balance(&5), 45);
+		});
+	}
+
+	#[test]
+	fn renounce_candidacy_should_return_bond() {
+		with_externalities(&mut new_test_ext(false), || {
+			System::set_block_number(1);
+			assert_noop!(
+				CouncilElections::renounce_candidacy(Origin::signed(5)),
+				"origin is not a candidate, member or runner-up"
+			);
+			assert_ok!(CouncilElections::submit_candidacy(Origin::signed(5)));
+			assert_eq!(Balances::reserved_balance(&5), 3);
+
+			assert_ok!(CouncilElections::renounce_candidacy(Origin::signed(5)));
+			assert_eq!(CouncilElections::candidates(), Vec::<u64>::new());
+			assert_eq!(Balances::reserved_balance(&5), 0);
+		});
+	}
+}
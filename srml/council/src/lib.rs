@@ -21,6 +21,7 @@
 pub mod voting;
 pub mod motions;
 pub mod seats;
+pub mod elections;
 
 pub use crate::seats::{Trait, Module, RawEvent, Event, VoteIndex};
 
@@ -35,7 +36,7 @@ mod tests {
 	pub use primitives::traits::{BlakeTwo256, IdentityLookup};
 	pub use primitives::testing::{Digest, DigestItem, Header};
 	pub use substrate_primitives::{Blake2Hasher};
-	pub use {seats, motions, voting};
+	pub use {seats, motions, voting, elections};
 
 	impl_outer_origin! {
 		pub enum Origin for Test {
@@ -45,7 +46,7 @@ mod tests {
 
 	impl_outer_event! {
 		pub enum Event for Test {
-			balances<T>, democracy<T>, seats<T>, voting<T>, motions<T>,
+			balances<T>, democracy<T>, seats<T>, voting<T>, motions<T>, elections<T>,
 		}
 	}
 
@@ -71,6 +72,7 @@ mod tests {
 		type Header = Header;
 		type Event = Event;
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -85,6 +87,10 @@ mod tests {
 		type Currency = balances::Module<Self>;
 		type Proposal = Call;
 		type Event = Event;
+		type ExternalOrigin = system::EnsureRoot<u64>;
+		type ExternalMajorityOrigin = system::EnsureRoot<u64>;
+		type FastTrackOrigin = system::EnsureRoot<u64>;
+		type VetoOrigin = motions::EnsureCouncillor<Test>;
 	}
 	impl seats::Trait for Test {
 		type Event = Event;
@@ -99,6 +105,12 @@ mod tests {
 	impl voting::Trait for Test {
 		type Event = Event;
 	}
+	impl elections::Trait for Test {
+		type Event = Event;
+		type LoserCandidate = ();
+		type KickedMember = ();
+		type Randomness = ();
+	}
 
 	pub fn new_test_ext(with_council: bool) -> runtime_io::TestExternalities<Blake2Hasher> {
 		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
@@ -115,8 +127,11 @@ mod tests {
 			launch_period: 1,
 			voting_period: 3,
 			minimum_deposit: 1,
+			preimage_byte_deposit: 0,
 			public_delay: 0,
 			max_lock_periods: 6,
+			emergency_voting_period: 1,
+			cooloff_period: 2,
 		}.build_storage().unwrap().0);
 		t.extend(seats::GenesisConfig::<Test> {
 			candidacy_bond: 9,
@@ -139,6 +154,13 @@ mod tests {
 			voting_period: 1,
 			enact_delay_period: 0,
 		}.build_storage().unwrap().0);
+		t.extend(elections::GenesisConfig::<Test> {
+			candidacy_bond: 3,
+			voting_bond: 2,
+			desired_members: 2,
+			desired_runners_up: 1,
+			term_duration: 5,
+		}.build_storage().unwrap().0);
 		runtime_io::TestExternalities::new(t)
 	}
 
@@ -148,4 +170,5 @@ mod tests {
 	pub type Council = seats::Module<Test>;
 	pub type CouncilVoting = voting::Module<Test>;
 	pub type CouncilMotions = motions::Module<Test>;
+	pub type CouncilElections = elections::Module<Test>;
 }
@@ -22,6 +22,7 @@ use substrate_primitives::u32_trait::Value as U32;
 use primitives::traits::{Hash, EnsureOrigin};
 use srml_support::dispatch::{Dispatchable, Parameter};
 use srml_support::{StorageValue, StorageMap, decl_module, decl_event, decl_storage, ensure};
+use srml_support::traits::{self, MembersOrigin};
 use super::{Trait as CouncilTrait, Module as Council};
 use system::{self, ensure_signed};
 
@@ -43,8 +44,17 @@ pub trait Trait: CouncilTrait {
 #[derive(PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum Origin {
-	/// It has been condoned by a given number of council members.
-	Members(u32),
+	/// It has been condoned by a given number of council members (the first `u32`) out of the
+	/// council's size at the time (the second `u32`).
+	Members(u32, u32),
+}
+
+impl MembersOrigin for Origin {
+	fn members(&self) -> Option<(u32, u32)> {
+		match *self {
+			Origin::Members(n, m) => Some((n, m)),
+		}
+	}
 }
 
 decl_event!(
@@ -76,7 +86,8 @@ decl_module! {
 			ensure!(!<ProposalOf<T>>::exists(proposal_hash), "duplicate proposals not allowed");
 
 			if threshold < 2 {
-				let ok = proposal.dispatch(Origin::Members(1).into()).is_ok();
+				let seats = <Council<T>>::active_council().len() as u32;
+				let ok = proposal.dispatch(Origin::Members(1, seats).into()).is_ok();
 				Self::deposit_event(RawEvent::Executed(proposal_hash, ok));
 			} else {
 				let index = Self::proposal_count();
@@ -134,7 +145,7 @@ decl_module! {
 
 					// execute motion, assuming it exists.
 					if let Some(p) = <ProposalOf<T>>::take(&proposal) {
-						let ok = p.dispatch(Origin::Members(threshold).into()).is_ok();
+						let ok = p.dispatch(Origin::Members(threshold, potential_votes).into()).is_ok();
 						Self::deposit_event(RawEvent::Executed(proposal, ok));
 					}
 				} else {
@@ -182,11 +193,12 @@ pub fn ensure_council_members<OuterOrigin>(o: OuterOrigin, n: u32) -> result::Re
 	where OuterOrigin: Into<Option<Origin>>
 {
 	match o.into() {
-		Some(Origin::Members(x)) if x >= n => Ok(n),
+		Some(Origin::Members(x, _)) if x >= n => Ok(n),
 		_ => Err("bad origin: expected to be a threshold number of council members"),
 	}
 }
 
+/// Ensure that the origin represents at least `N` council members.
 pub struct EnsureMembers<N: U32>(::rstd::marker::PhantomData<N>);
 impl<O, N: U32> EnsureOrigin<O> for EnsureMembers<N>
 	where O: Into<Option<Origin>>
@@ -197,6 +209,27 @@ impl<O, N: U32> EnsureOrigin<O> for EnsureMembers<N>
 	}
 }
 
+/// Ensure that the origin represents more than `N/D` of the council, returning the number of
+/// members that condoned it and the size of the council.
+pub type EnsureProportionMoreThan<N, D> = traits::EnsureProportionMoreThan<N, D, Origin>;
+
+/// Ensure that the origin represents at least `N/D` of the council, returning the number of
+/// members that condoned it and the size of the council.
+pub type EnsureProportionAtLeast<N, D> = traits::EnsureProportionAtLeast<N, D, Origin>;
+
+/// Ensure that the origin is signed by a sitting councillor, returning their account.
+pub struct EnsureCouncillor<T>(::rstd::marker::PhantomData<T>);
+impl<O, T: Trait> EnsureOrigin<O> for EnsureCouncillor<T>
+	where O: Into<Option<system::RawOrigin<T::AccountId>>>
+{
+	type Success = T::AccountId;
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		let who = ensure_signed(o)?;
+		ensure!(<Module<T>>::is_councillor(&who), "bad origin: expected to be a councillor");
+		Ok(who)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -352,4 +385,34 @@ mod tests {
 			]);
 		});
 	}
+
+	#[test]
+	fn council_origins_work() {
+		use substrate_primitives::u32_trait::{_1, _2, _3};
+		use srml_support::traits::{EnsureOneOf, EnsureBoth, Either};
+		type MoreThanHalf = EnsureProportionMoreThan<_1, _2>;
+		type AtLeastTwoThirds = EnsureProportionAtLeast<_2, _3>;
+		type RootOrTwoThirds = EnsureOneOf<system::EnsureRoot<u64>, AtLeastTwoThirds>;
+		type TwoAndMoreThanHalf = EnsureBoth<EnsureMembers<_2>, MoreThanHalf>;
+
+		let members = |n, m| -> Origin { super::Origin::Members(n, m).into() };
+
+		assert_eq!(EnsureMembers::<_2>::ensure_origin(members(2, 3)), Ok(2));
+		assert!(EnsureMembers::<_3>::ensure_origin(members(2, 3)).is_err());
+		assert!(EnsureMembers::<_1>::ensure_origin(Origin::signed(1)).is_err());
+
+		assert_eq!(MoreThanHalf::ensure_origin(members(2, 3)), Ok((2, 3)));
+		assert!(MoreThanHalf::ensure_origin(members(2, 4)).is_err());
+		assert_eq!(AtLeastTwoThirds::ensure_origin(members(2, 3)), Ok((2, 3)));
+		assert!(AtLeastTwoThirds::ensure_origin(members(3, 5)).is_err());
+
+		assert_eq!(RootOrTwoThirds::ensure_origin(Origin::ROOT), Ok(Either::Left(())));
+		assert_eq!(RootOrTwoThirds::ensure_origin(members(4, 6)), Ok(Either::Right((4, 6))));
+		assert!(RootOrTwoThirds::ensure_origin(members(3, 6)).is_err());
+		assert!(RootOrTwoThirds::ensure_origin(Origin::signed(1)).is_err());
+
+		assert_eq!(TwoAndMoreThanHalf::ensure_origin(members(2, 3)), Ok((2, (2, 3))));
+		assert!(TwoAndMoreThanHalf::ensure_origin(members(1, 1)).is_err());
+		assert!(TwoAndMoreThanHalf::ensure_origin(members(2, 4)).is_err());
+	}
 }
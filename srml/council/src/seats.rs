@@ -21,7 +21,7 @@ use primitives::traits::{Zero, One, As, StaticLookup};
 use runtime_io::print;
 use srml_support::{
 	StorageValue, StorageMap, dispatch::Result, decl_storage, decl_event, ensure,
-	traits::{Currency, ReservableCurrency, OnUnbalanced}
+	traits::{Currency, ReservableCurrency, OnUnbalanced, Contains}
 };
 use democracy;
 use system::{self, ensure_signed};
@@ -557,6 +557,14 @@ impl<T: Trait> Module<T> {
 	}
 }
 
+impl<T: Trait> Contains<T::AccountId> for Module<T> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		let mut members = Self::active_council().into_iter().map(|(a, _)| a).collect::<Vec<_>>();
+		members.sort();
+		members
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -19,6 +19,7 @@
 use rstd::prelude::*;
 use rstd::borrow::Borrow;
 use primitives::traits::{Hash, As, Zero};
+use parity_codec::Encode;
 use runtime_io::print;
 use srml_support::dispatch::Result;
 use srml_support::{StorageValue, StorageMap, IsSubType, decl_module, decl_storage, decl_event, ensure};
@@ -220,7 +221,9 @@ impl<T: Trait> Module<T> {
 						(_, 0, 0) => democracy::VoteThreshold::SuperMajorityAgainst,
 						_ => democracy::VoteThreshold::SimpleMajority,
 					};
-					<democracy::Module<T>>::internal_start_referendum(proposal, threshold, period).map(|_| ())?;
+					// The council knows the proposal in full; there is no need for anyone to note it.
+					<democracy::Module<T>>::internal_note_preimage(proposal.encode());
+					<democracy::Module<T>>::internal_start_referendum(proposal_hash, threshold, period).map(|_| ())?;
 				}
 			}
 		}
@@ -234,6 +237,7 @@ mod tests {
 	use crate::tests::*;
 	use crate::tests::{Call, Origin};
 	use srml_support::{Hashable, assert_ok, assert_noop};
+	use primitives::traits::OnFinalise;
 	use democracy::{ReferendumInfo, VoteThreshold};
 
 	#[test]
@@ -269,8 +273,8 @@ mod tests {
 		with_externalities(&mut new_test_ext(true), || {
 			System::set_block_number(1);
 			let proposal = set_balance_proposal(42);
-			assert_ok!(Democracy::internal_start_referendum(proposal.clone(), VoteThreshold::SuperMajorityApprove, 0), 0);
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, proposal, VoteThreshold::SuperMajorityApprove, 0))]);
+			assert_ok!(Democracy::internal_start_referendum(H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0), 0);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0))]);
 
 			let cancellation = cancel_referendum_proposal(0);
 			let hash = cancellation.blake2_256().into();
@@ -292,7 +296,7 @@ mod tests {
 		with_externalities(&mut new_test_ext(true), || {
 			System::set_block_number(1);
 			let proposal = set_balance_proposal(42);
-			assert_ok!(Democracy::internal_start_referendum(proposal.clone(), VoteThreshold::SuperMajorityApprove, 0), 0);
+			assert_ok!(Democracy::internal_start_referendum(H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0), 0);
 
 			let cancellation = cancel_referendum_proposal(0);
 			let hash = cancellation.blake2_256().into();
@@ -303,7 +307,7 @@ mod tests {
 
 			System::set_block_number(2);
 			assert_ok!(CouncilVoting::end_block(System::block_number()));
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, proposal, VoteThreshold::SuperMajorityApprove, 0))]);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0))]);
 		});
 	}
 
@@ -312,7 +316,7 @@ mod tests {
 		with_externalities(&mut new_test_ext(true), || {
 			System::set_block_number(1);
 			let proposal = set_balance_proposal(42);
-			assert_ok!(Democracy::internal_start_referendum(proposal.clone(), VoteThreshold::SuperMajorityApprove, 0), 0);
+			assert_ok!(Democracy::internal_start_referendum(H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0), 0);
 
 			let cancellation = cancel_referendum_proposal(0);
 			let hash = cancellation.blake2_256().into();
@@ -322,7 +326,7 @@ mod tests {
 
 			System::set_block_number(2);
 			assert_ok!(CouncilVoting::end_block(System::block_number()));
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, proposal, VoteThreshold::SuperMajorityApprove, 0))]);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(4, H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityApprove, 0))]);
 		});
 	}
 
@@ -386,7 +390,7 @@ mod tests {
 			System::set_block_number(4);
 			assert_ok!(CouncilVoting::end_block(System::block_number()));
 			assert_eq!(CouncilVoting::proposals().len(), 0);
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(7, set_balance_proposal(42), VoteThreshold::SimpleMajority, 0))]);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(7, H256::from(set_balance_proposal(42).blake2_256()), VoteThreshold::SimpleMajority, 0))]);
 		});
 	}
 
@@ -451,7 +455,7 @@ mod tests {
 			System::set_block_number(2);
 			assert_ok!(CouncilVoting::end_block(System::block_number()));
 			assert_eq!(CouncilVoting::proposals().len(), 0);
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(5, proposal, VoteThreshold::SuperMajorityAgainst, 0))]);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(5, H256::from(proposal.blake2_256()), VoteThreshold::SuperMajorityAgainst, 0))]);
 		});
 	}
 
@@ -469,7 +473,30 @@ mod tests {
 			System::set_block_number(2);
 			assert_ok!(CouncilVoting::end_block(System::block_number()));
 			assert_eq!(CouncilVoting::proposals().len(), 0);
-			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(5, proposal, VoteThreshold::SimpleMajority, 0))]);
+			assert_eq!(Democracy::active_referendums(), vec![(0, ReferendumInfo::new(5, H256::from(proposal.blake2_256()), VoteThreshold::SimpleMajority, 0))]);
+		});
+	}
+
+	#[test]
+	fn passed_proposal_should_be_enacted_after_referendum() {
+		with_externalities(&mut new_test_ext(true), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(CouncilVoting::propose(Origin::signed(1), Box::new(proposal.clone())));
+			assert_ok!(CouncilVoting::vote(Origin::signed(2), hash, true));
+			assert_ok!(CouncilVoting::vote(Origin::signed(3), hash, true));
+
+			System::set_block_number(2);
+			assert_ok!(CouncilVoting::end_block(System::block_number()));
+			assert!(Democracy::preimages(&hash).is_some());
+			assert_ok!(Democracy::vote(Origin::signed(1), 0, democracy::Vote::new(true, 0)));
+
+			System::set_block_number(5);
+			Democracy::on_finalise(5);
+			assert_eq!(Democracy::active_referendums(), vec![]);
+			assert!(Democracy::preimages(&hash).is_none());
+			assert_eq!(Balances::free_balance(&42), 42);
 		});
 	}
 
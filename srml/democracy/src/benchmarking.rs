@@ -0,0 +1,87 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of the democracy module.
+//!
+//! The benchmarked proposals are calls of this module, hence the proposal type must be
+//! constructible from them.
+
+use super::*;
+use srml_benchmarking::{BenchmarkDefinition, account};
+use system::RawOrigin;
+
+/// The benchmarks of the democracy module.
+pub fn benchmarks<T: Trait>() -> Vec<BenchmarkDefinition<Call<T>, T::Origin>> where
+	T::Proposal: From<Call<T>>,
+{
+	vec![
+		BenchmarkDefinition { name: "propose", components: &[("p", 0, 100)], setup: propose::<T> },
+		BenchmarkDefinition { name: "second", components: &[("s", 0, 100)], setup: second::<T> },
+		BenchmarkDefinition { name: "vote", components: &[("v", 0, 1000)], setup: vote::<T> },
+	]
+}
+
+/// A funded account able to pay the minimum deposit many times over.
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index);
+	let balance = <Module<T>>::minimum_deposit() * <BalanceOf<T> as As<u64>>::sa(1000);
+	let _ = T::Currency::make_free_balance_be(&who, balance);
+	who
+}
+
+/// A harmless proposal.
+fn proposal<T: Trait>() -> T::Proposal where T::Proposal: From<Call<T>> {
+	Call::<T>::second(0).into()
+}
+
+/// Propose with `p` public proposals already pending.
+fn propose<T: Trait>(components: &[u32]) -> result::Result<(Call<T>, T::Origin), &'static str> where
+	T::Proposal: From<Call<T>>,
+{
+	let proposer = funded_account::<T>("proposer", 0);
+	let proposal_hash = T::Hashing::hash_of(&proposal::<T>());
+	let props = (0..components[0]).map(|i| (i, proposal_hash, proposer.clone())).collect::<Vec<_>>();
+	<PublicPropCount<T>>::put(components[0]);
+	<PublicProps<T>>::put(props);
+
+	let call = Call::propose(proposal_hash, <Module<T>>::minimum_deposit());
+	Ok((call, RawOrigin::Signed(proposer).into()))
+}
+
+/// Second a proposal which already has `s` seconds.
+fn second<T: Trait>(components: &[u32]) -> result::Result<(Call<T>, T::Origin), &'static str> {
+	let seconders = (0..components[0]).map(|i| account("seconder", i)).collect::<Vec<_>>();
+	<DepositOf<T>>::insert(0, (<Module<T>>::minimum_deposit(), seconders));
+
+	let caller = funded_account::<T>("caller", 0);
+	Ok((Call::second(0), RawOrigin::Signed(caller).into()))
+}
+
+/// Vote in a referendum which already has `v` voters.
+fn vote<T: Trait>(components: &[u32]) -> result::Result<(Call<T>, T::Origin), &'static str> where
+	T::Proposal: From<Call<T>>,
+{
+	let index = <Module<T>>::internal_start_referendum(
+		T::Hashing::hash_of(&proposal::<T>()),
+		VoteThreshold::SuperMajorityApprove,
+		Zero::zero(),
+	)?;
+	let voters = (0..components[0]).map(|i| account("voter", i)).collect::<Vec<_>>();
+	<VotersFor<T>>::insert(index, voters);
+
+	let caller = funded_account::<T>("caller", 0);
+	Ok((Call::vote(index, Vote::new(true, 1)), RawOrigin::Signed(caller).into()))
+}
@@ -15,23 +15,32 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Democratic system: Handles administration of general stakeholder voting.
+//!
+//! Proposals and referenda refer to the action to be taken only by its hash. The encoded action
+//! itself (its preimage) must be noted with `note_preimage` before it is enacted, under a deposit
+//! proportional to its length.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use rstd::prelude::*;
 use rstd::result;
-use primitives::traits::{Zero, As, Bounded};
+use primitives::traits::{Zero, One, As, Bounded, Hash, EnsureOrigin};
 use parity_codec::{Encode, Decode};
-use srml_support::{StorageValue, StorageMap, Parameter, Dispatchable, IsSubType, EnumerableStorageMap};
-use srml_support::{decl_module, decl_storage, decl_event, ensure};
+use srml_support::{StorageValue, StorageMap, Parameter, Dispatchable, IsSubType};
+use srml_support::{decl_module, decl_storage, decl_event, ensure, TypeInfo};
 use srml_support::traits::{Currency, ReservableCurrency, LockableCurrency, WithdrawReason, LockIdentifier};
 use srml_support::dispatch::Result;
 use system::ensure_signed;
 
 mod vote_threshold;
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
 pub use vote_threshold::{Approved, VoteThreshold};
 
 const DEMOCRACY_ID: LockIdentifier = *b"democrac";
+const DELEGATION_ID: LockIdentifier = *b"delegate";
+/// The storage prefix of the dispatch queue from before it held the hashes of proposals.
+const DISPATCH_QUEUE_OF_PROPOSALS: &[u8] = b"Democracy DispatchQueue";
 
 /// A proposal index.
 pub type PropIndex = u32;
@@ -40,10 +49,8 @@ pub type ReferendumIndex = u32;
 /// A number of lock periods.
 pub type LockPeriods = i8;
 
-const MAX_RECURSION_LIMIT: u32 = 16;
-
 /// A number of lock periods, plus a vote, one way or the other.
-#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Vote(i8);
 
@@ -77,16 +84,32 @@ pub trait Trait: system::Trait + Sized {
 	type Proposal: Parameter + Dispatchable<Origin=Self::Origin> + IsSubType<Module<Self>>;
 
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Origin from which the next tabled referendum may be forced. This is a normal
+	/// "super-majority-required" referendum.
+	type ExternalOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Origin from which the next tabled referendum may be forced; this allows for the tabling of
+	/// a majority-carries referendum.
+	type ExternalMajorityOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Origin from which the next majority-carries external proposal may be tabled immediately,
+	/// with a voting period as short as `EmergencyVotingPeriod`.
+	type FastTrackOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Origin from which any external proposal may be vetoed, blacklisting it until the cooloff
+	/// period ends. Each identity may veto a given proposal only once.
+	type VetoOrigin: EnsureOrigin<Self::Origin, Success=Self::AccountId>;
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event<T>() = default;
 
-		/// Propose a sensitive action to be taken.
+		/// Propose a sensitive action to be taken, identified by the hash of its encoding.
 		fn propose(
 			origin,
-			proposal: Box<T::Proposal>,
+			proposal_hash: T::Hash,
 			#[compact] value: BalanceOf<T>
 		) {
 			let who = ensure_signed(origin)?;
@@ -100,7 +123,7 @@ decl_module! {
 			<DepositOf<T>>::insert(index, (value, vec![who.clone()]));
 
 			let mut props = Self::public_props();
-			props.push((index, (*proposal).clone(), who));
+			props.push((index, proposal_hash, who));
 			<PublicProps<T>>::put(props);
 
 			Self::deposit_event(RawEvent::Proposed(index, value));
@@ -119,23 +142,34 @@ decl_module! {
 
 		/// Vote in a referendum. If `vote.is_aye()`, the vote is to enact the proposal;
 		/// otherwise it is a vote to keep the status quo.
+		///
+		/// The voter's balance, along with any balance delegated to them, is counted immediately
+		/// and locked until the referendum ends.
 		fn vote(origin, #[compact] ref_index: ReferendumIndex, vote: Vote) {
 			let who = ensure_signed(origin)?;
 			ensure!(vote.multiplier() <= Self::max_lock_periods(), "vote has too great a strength");
-			ensure!(Self::is_active_referendum(ref_index), "vote given for invalid referendum.");
-			ensure!(!T::Currency::total_balance(&who).is_zero(),
-					"transactor must have balance to signal approval.");
-			if !<VoteOf<T>>::exists(&(ref_index, who.clone())) {
+			let info = Self::referendum_info(ref_index).ok_or("vote given for invalid referendum.")?;
+			ensure!(!<Delegations<T>>::exists(&who), "account is delegating its vote");
+			let balance = T::Currency::total_balance(&who);
+			ensure!(!balance.is_zero(), "transactor must have balance to signal approval.");
+
+			if <VoteOf<T>>::exists(&(ref_index, who.clone())) {
+				Self::tally_vote(ref_index, &who, false);
+			} else {
 				<VotersFor<T>>::mutate(ref_index, |voters| voters.push(who.clone()));
 			}
-			<VoteOf<T>>::insert(&(ref_index, who), vote);
+			<VoteOf<T>>::insert(&(ref_index, who.clone()), vote);
+			<VoteBalanceOf<T>>::insert(&(ref_index, who.clone()), balance);
+			Self::tally_vote(ref_index, &who, true);
+
+			T::Currency::extend_lock(DEMOCRACY_ID, &who, balance, info.end, WithdrawReason::Transfer.into());
 		}
 
 		/// Start a referendum.
-		fn start_referendum(proposal: Box<T::Proposal>, threshold: VoteThreshold, delay: T::BlockNumber) -> Result {
+		fn start_referendum(proposal_hash: T::Hash, threshold: VoteThreshold, delay: T::BlockNumber) -> Result {
 			Self::inject_referendum(
 				<system::Module<T>>::block_number() + Self::voting_period(),
-				*proposal,
+				proposal_hash,
 				threshold,
 				delay,
 			).map(|_| ())
@@ -149,28 +183,168 @@ decl_module! {
 		/// Cancel a proposal queued for enactment.
 		pub fn cancel_queued(#[compact] when: T::BlockNumber, #[compact] which: u32) -> Result {
 			let which = which as usize;
-			<DispatchQueue<T>>::mutate(when, |items| if items.len() > which { items[which] = None });
+			Self::migrate_dispatch_queue_at(when);
+			let cancelled = <EnactmentQueue<T>>::mutate(when, |items| if items.len() > which { items[which].take() } else { None });
+			if let Some((proposal_hash, _)) = cancelled {
+				Self::unqueue_preimage(&proposal_hash);
+			}
 			Ok(())
 		}
 
+		/// Schedule a super-majority-carries referendum to be tabled next, once it is legal to
+		/// schedule an external referendum.
+		fn external_propose(origin, proposal_hash: T::Hash) {
+			T::ExternalOrigin::ensure_origin(origin)?;
+			Self::note_external(proposal_hash, VoteThreshold::SuperMajorityApprove)?;
+		}
+
+		/// Schedule a majority-carries referendum to be tabled next, once it is legal to schedule
+		/// an external referendum.
+		fn external_propose_majority(origin, proposal_hash: T::Hash) {
+			T::ExternalMajorityOrigin::ensure_origin(origin)?;
+			Self::note_external(proposal_hash, VoteThreshold::SimpleMajority)?;
+		}
+
+		/// Table the scheduled majority-carries external proposal immediately as a referendum
+		/// lasting `voting_period` blocks, which may be no shorter than `EmergencyVotingPeriod`.
+		/// If it passes, it is enacted `delay` blocks later.
+		fn fast_track(
+			origin,
+			proposal_hash: T::Hash,
+			voting_period: T::BlockNumber,
+			delay: T::BlockNumber
+		) {
+			T::FastTrackOrigin::ensure_origin(origin)?;
+			let (e_proposal_hash, threshold) = Self::next_external().ok_or("no proposal made")?;
+			ensure!(threshold == VoteThreshold::SimpleMajority, "next external proposal not simple majority");
+			ensure!(proposal_hash == e_proposal_hash, "invalid hash");
+			ensure!(voting_period >= Self::emergency_voting_period(), "voting period too low");
+
+			<NextExternal<T>>::kill();
+			let now = <system::Module<T>>::block_number();
+			Self::inject_referendum(now + voting_period, proposal_hash, threshold, delay)?;
+		}
+
+		/// Veto and blacklist the scheduled external proposal until the cooloff period ends.
+		fn veto_external(origin, proposal_hash: T::Hash) {
+			let who = T::VetoOrigin::ensure_origin(origin)?;
+
+			let (e_proposal_hash, _) = Self::next_external().ok_or("no external proposal")?;
+			ensure!(proposal_hash == e_proposal_hash, "unknown proposal");
+
+			let mut existing_vetoers = Self::blacklist(&proposal_hash)
+				.map(|(_, vetoers)| vetoers)
+				.unwrap_or_else(Vec::new);
+			let insert_position = existing_vetoers.binary_search(&who)
+				.err().ok_or("identity may not veto a proposal twice")?;
+			existing_vetoers.insert(insert_position, who.clone());
+
+			let until = <system::Module<T>>::block_number() + Self::cooloff_period();
+			<Blacklist<T>>::insert(&proposal_hash, (until, existing_vetoers));
+
+			Self::deposit_event(RawEvent::Vetoed(who, proposal_hash, until));
+			<NextExternal<T>>::kill();
+		}
+
+		/// Register the preimage of an upcoming proposal, reserving a deposit proportional to its
+		/// length. The deposit is returned once the proposal is enacted.
+		fn note_preimage(origin, encoded_proposal: Vec<u8>) {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
+			ensure!(!<Preimages<T>>::exists(&proposal_hash), "preimage already noted");
+
+			let deposit = BalanceOf::<T>::sa(encoded_proposal.len() as u64) * Self::preimage_byte_deposit();
+			T::Currency::reserve(&who, deposit)
+				.map_err(|_| "not enough free balance for preimage deposit")?;
+
+			let now = <system::Module<T>>::block_number();
+			<Preimages<T>>::insert(proposal_hash, (encoded_proposal, who.clone(), deposit, now));
+
+			Self::deposit_event(RawEvent::PreimageNoted(proposal_hash, who, deposit));
+		}
+
+		/// Remove an unused preimage, claiming its deposit. The provider of the preimage may reap
+		/// it once a voting period has passed since it was noted; anyone else must additionally
+		/// wait out the public enactment delay. A preimage may not be reaped while its proposal is
+		/// still proposed, in a referendum or queued for enactment.
+		fn reap_preimage(origin, proposal_hash: T::Hash) {
+			let who = ensure_signed(origin)?;
+
+			let (_, provider, deposit, then) = Self::preimages(&proposal_hash).ok_or("unknown preimage")?;
+			let now = <system::Module<T>>::block_number();
+			let additional = if who == provider { Zero::zero() } else { Self::public_delay() };
+			ensure!(now >= then + Self::voting_period() + additional, "too early");
+			ensure!(!Self::is_preimage_in_use(&proposal_hash), "preimage still in use");
+
+			let _ = T::Currency::repatriate_reserved(&provider, &who, deposit);
+			<Preimages<T>>::remove(&proposal_hash);
+
+			Self::deposit_event(RawEvent::PreimageReaped(proposal_hash, provider, deposit, who));
+		}
+
 		fn on_finalise(n: T::BlockNumber) {
 			if let Err(e) = Self::end_block(n) {
 				runtime_io::print(e);
 			}
 		}
 
-		/// Delegate vote.
-		pub fn delegate(origin, to: T::AccountId, lock_periods: LockPeriods) -> Result {
+		fn on_runtime_upgrade() {
+			let version = Self::storage_version();
+			if version < 1 {
+				Self::migrate_to_proposal_hashes();
+			}
+			if version < 2 {
+				Self::migrate_to_delegated_balances();
+				<StorageVersion<T>>::put(2);
+			}
+		}
+
+		/// Delegate `balance` to be voted with by `to`, at a strength of `lock_periods`. The
+		/// balance is locked until it is undelegated, and for `lock_periods` multiples of the
+		/// public delay thereafter. Any previous delegation is replaced.
+		///
+		/// Delegations are not passed on: `to` must not be delegating, and an account others
+		/// delegate to cannot delegate itself.
+		pub fn delegate(
+			origin,
+			to: T::AccountId,
+			lock_periods: LockPeriods,
+			#[compact] balance: BalanceOf<T>
+		) -> Result {
 			let who = ensure_signed(origin)?;
-			<Delegations<T>>::insert(who.clone(), (to.clone(), lock_periods.clone()));
+			ensure!(who != to, "cannot delegate to self");
+			ensure!(!<Delegations<T>>::exists(&to), "target is delegating");
+			ensure!(Self::delegated(&who).1.is_zero(), "account is delegated to");
+			ensure!(lock_periods >= 0 && lock_periods <= Self::max_lock_periods(), "delegation has too great a strength");
+			ensure!(balance <= T::Currency::free_balance(&who), "delegator's balance too low");
+			ensure!(
+				!Self::active_referendums().iter().any(|(i, _)| <VoteOf<T>>::exists(&(*i, who.clone()))),
+				"account has voted in an active referendum"
+			);
+
+			if let Some((old_to, old_lock_periods, old_balance)) = <Delegations<T>>::take(&who) {
+				Self::tally_delegation(&old_to, old_lock_periods, old_balance, false);
+			}
+			<Delegations<T>>::insert(&who, (to.clone(), lock_periods, balance));
+			Self::tally_delegation(&to, lock_periods, balance, true);
+
+			T::Currency::set_lock(DELEGATION_ID, &who, balance, Bounded::max_value(), WithdrawReason::Transfer.into());
+
 			Self::deposit_event(RawEvent::Delegated(who, to));
 			Ok(())
 		}
 
-		/// Undelegate vote.
+		/// Undelegate vote. The delegated balance remains locked for the delegation's number of
+		/// lock periods.
 		fn undelegate(origin) -> Result {
 			let who = ensure_signed(origin)?;
-			<Delegations<T>>::remove(who.clone());
+			let (to, lock_periods, balance) = <Delegations<T>>::take(&who).ok_or("not delegating")?;
+			Self::tally_delegation(&to, lock_periods, balance, false);
+
+			let now = <system::Module<T>>::block_number();
+			let locked_until = now + Self::public_delay() * T::BlockNumber::sa(lock_periods as u64);
+			T::Currency::set_lock(DELEGATION_ID, &who, balance, locked_until, WithdrawReason::Transfer.into());
+
 			Self::deposit_event(RawEvent::Undelegated(who));
 			Ok(())
 		}
@@ -178,23 +352,23 @@ decl_module! {
 }
 
 /// Info regarding an ongoing referendum.
-#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct ReferendumInfo<BlockNumber: Parameter, Proposal: Parameter> {
+pub struct ReferendumInfo<BlockNumber: Parameter, Hash: Parameter> {
 	/// When voting on this referendum will end.
 	end: BlockNumber,
-	/// The proposal being voted on.
-	proposal: Proposal,
+	/// The hash of the proposal being voted on.
+	proposal_hash: Hash,
 	/// The thresholding mechanism to determine whether it passed.
 	threshold: VoteThreshold,
 	/// The delay (in blocks) to wait after a successful referendum before deploying.
 	delay: BlockNumber,
 }
 
-impl<BlockNumber: Parameter, Proposal: Parameter> ReferendumInfo<BlockNumber, Proposal> {
+impl<BlockNumber: Parameter, Hash: Parameter> ReferendumInfo<BlockNumber, Hash> {
 	/// Create a new instance.
-	pub fn new(end: BlockNumber, proposal: Proposal, threshold: VoteThreshold, delay: BlockNumber) -> Self {
-		ReferendumInfo { end, proposal, threshold, delay }
+	pub fn new(end: BlockNumber, proposal_hash: Hash, threshold: VoteThreshold, delay: BlockNumber) -> Self {
+		ReferendumInfo { end, proposal_hash, threshold, delay }
 	}
 }
 
@@ -204,13 +378,18 @@ decl_storage! {
 		/// The number of (public) proposals that have been made so far.
 		pub PublicPropCount get(public_prop_count) build(|_| 0 as PropIndex) : PropIndex;
 		/// The public proposals. Unsorted.
-		pub PublicProps get(public_props): Vec<(PropIndex, T::Proposal, T::AccountId)>;
+		pub PublicProps get(public_props): Vec<(PropIndex, T::Hash, T::AccountId)>;
 		/// Those who have locked a deposit.
 		pub DepositOf get(deposit_of): map PropIndex => Option<(BalanceOf<T>, Vec<T::AccountId>)>;
+		/// Map of hashes to the proposal preimage, along with who registered it and their deposit.
+		/// The block number is the block at which it was noted.
+		pub Preimages get(preimages): map T::Hash => Option<(Vec<u8>, T::AccountId, BalanceOf<T>, T::BlockNumber)>;
 		/// How often (in blocks) new public referenda are launched.
 		pub LaunchPeriod get(launch_period) config(): T::BlockNumber = T::BlockNumber::sa(1000);
 		/// The minimum amount to be used as a deposit for a public referendum proposal.
 		pub MinimumDeposit get(minimum_deposit) config(): BalanceOf<T>;
+		/// The amount of balance that must be deposited per byte of preimage stored.
+		pub PreimageByteDeposit get(preimage_byte_deposit) config(): BalanceOf<T>;
 		/// The delay before enactment for all public referenda.
 		pub PublicDelay get(public_delay) config(): T::BlockNumber;
 		/// The maximum number of additional lock periods a voter may offer to strengthen their vote. Multiples of `PublicDelay`.
@@ -218,15 +397,22 @@ decl_storage! {
 
 		/// How often (in blocks) to check for new votes.
 		pub VotingPeriod get(voting_period) config(): T::BlockNumber = T::BlockNumber::sa(1000);
+		/// The minimum voting period allowed for a fast-tracked referendum.
+		pub EmergencyVotingPeriod get(emergency_voting_period) config(): T::BlockNumber = T::BlockNumber::sa(10);
+		/// How long (in blocks) a vetoed external proposal remains blacklisted.
+		pub CooloffPeriod get(cooloff_period) config(): T::BlockNumber = T::BlockNumber::sa(1000);
 
 		/// The next free referendum index, aka the number of referendums started so far.
 		pub ReferendumCount get(referendum_count) build(|_| 0 as ReferendumIndex): ReferendumIndex;
-		/// The next referendum index that should be tallied.
+		/// The lowest referendum index that may still be untallied.
 		pub NextTally get(next_tally) build(|_| 0 as ReferendumIndex): ReferendumIndex;
 		/// Information concerning any given referendum.
-		pub ReferendumInfoOf get(referendum_info): map ReferendumIndex => Option<(ReferendumInfo<T::BlockNumber, T::Proposal>)>;
-		/// Queue of successful referenda to be dispatched.
-		pub DispatchQueue get(dispatch_queue): map T::BlockNumber => Vec<Option<(T::Proposal, ReferendumIndex)>>;
+		pub ReferendumInfoOf get(referendum_info): map ReferendumIndex => Option<(ReferendumInfo<T::BlockNumber, T::Hash>)>;
+		/// Queue of successful referenda to be dispatched. The queue of a block may still be held
+		/// under the previous name, in full, until the block is due; see `migrate_dispatch_queue_at`.
+		pub EnactmentQueue get(dispatch_queue): map T::BlockNumber => Vec<Option<(T::Hash, ReferendumIndex)>>;
+		/// The number of entries in the dispatch queue for each proposal hash.
+		pub QueuedPreimages get(queued_preimages): map T::Hash => u32;
 
 		/// Get the voters for the current proposal.
 		pub VotersFor get(voters_for): map ReferendumIndex => Vec<T::AccountId>;
@@ -235,16 +421,44 @@ decl_storage! {
 		/// voter when called with the referendum (you'll get the default `Vote` value otherwise). If you don't want to check
 		/// `voters_for`, then you can also check for simple existence with `VoteOf::exists` first.
 		pub VoteOf get(vote_of): map (ReferendumIndex, T::AccountId) => Vote;
-
-		/// Get the account (and lock periods) to which another account is delegating vote.
-		pub Delegations get(delegations): linked_map T::AccountId => (T::AccountId, LockPeriods);
+		/// The balance of a particular voter counted in a given referendum, excluding delegations.
+		pub VoteBalanceOf get(vote_balance_of): map (ReferendumIndex, T::AccountId) => BalanceOf<T>;
+		/// The running tally of a given referendum: approving votes, rejecting votes and the total
+		/// balance of all voters (including delegators).
+		pub TallyOf get(tally_of): map ReferendumIndex => (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>);
+
+		/// Get the account (and lock periods and balance) to which another account is delegating vote.
+		pub Delegations get(delegations): map T::AccountId => Option<(T::AccountId, LockPeriods, BalanceOf<T>)>;
+		/// The votes (balance weighted by lock periods) and balance delegated to an account.
+		pub Delegated get(delegated): map T::AccountId => (BalanceOf<T>, BalanceOf<T>);
+
+		/// True if the last referendum tabled was submitted externally. False if it was a public
+		/// proposal.
+		pub LastTabledWasExternal get(last_tabled_was_external): bool;
+		/// The referendum to be tabled whenever it would be valid to table an external proposal,
+		/// along with its threshold.
+		pub NextExternal get(next_external): Option<(T::Hash, VoteThreshold)>;
+		/// A record of who vetoed what, by proposal hash. Maps to the block number until which the
+		/// proposal may not be resubmitted, and the (sorted) identities that vetoed it.
+		pub Blacklist get(blacklist): map T::Hash => Option<(T::BlockNumber, Vec<T::AccountId>)>;
+
+		/// The version of the storage layout, checked by `on_runtime_upgrade`. Chains started
+		/// before proposals were held by hash have no version; version 1 has delegations of whole
+		/// accounts and no running tallies.
+		pub StorageVersion get(storage_version) build(|_| 2u32): u32;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where Balance = BalanceOf<T>, <T as system::Trait>::AccountId {
+	pub enum Event<T> where
+		Balance = BalanceOf<T>,
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::Hash,
+		<T as system::Trait>::BlockNumber
+	{
 		Proposed(PropIndex, Balance),
 		Tabled(PropIndex, Balance, Vec<AccountId>),
+		ExternalTabled,
 		Started(ReferendumIndex, VoteThreshold),
 		Passed(ReferendumIndex),
 		NotPassed(ReferendumIndex),
@@ -252,6 +466,18 @@ decl_event!(
 		Executed(ReferendumIndex, bool),
 		Delegated(AccountId, AccountId),
 		Undelegated(AccountId),
+		/// An external proposal has been vetoed; it is blacklisted until the given block.
+		Vetoed(AccountId, Hash, BlockNumber),
+		/// A proposal's preimage was noted, and the deposit taken.
+		PreimageNoted(Hash, AccountId, Balance),
+		/// A proposal preimage was removed and used (the deposit was returned).
+		PreimageUsed(Hash, AccountId, Balance),
+		/// A proposal could not be executed because its preimage was invalid.
+		PreimageInvalid(Hash, ReferendumIndex),
+		/// A proposal could not be executed because its preimage was missing.
+		PreimageMissing(Hash, ReferendumIndex),
+		/// A registered preimage was removed and the deposit collected by the reaper (last item).
+		PreimageReaped(Hash, AccountId, Balance, AccountId),
 	}
 );
 
@@ -270,7 +496,7 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Get all referendums currently active.
-	pub fn active_referendums() -> Vec<(ReferendumIndex, ReferendumInfo<T::BlockNumber, T::Proposal>)> {
+	pub fn active_referendums() -> Vec<(ReferendumIndex, ReferendumInfo<T::BlockNumber, T::Hash>)> {
 		let next = Self::next_tally();
 		let last = Self::referendum_count();
 		(next..last).into_iter()
@@ -279,70 +505,26 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Get all referendums ready for tally at block `n`.
-	pub fn maturing_referendums_at(n: T::BlockNumber) -> Vec<(ReferendumIndex, ReferendumInfo<T::BlockNumber, T::Proposal>)> {
+	pub fn maturing_referendums_at(n: T::BlockNumber) -> Vec<(ReferendumIndex, ReferendumInfo<T::BlockNumber, T::Hash>)> {
 		let next = Self::next_tally();
 		let last = Self::referendum_count();
 		(next..last).into_iter()
 			.filter_map(|i| Self::referendum_info(i).map(|info| (i, info)))
-			.take_while(|&(_, ref info)| info.end == n)
+			.filter(|&(_, ref info)| info.end == n)
 			.collect()
 	}
 
-	/// Get the voters for the current proposal.
+	/// Get the tally of a referendum: approving votes, rejecting votes and the total balance of
+	/// all voters.
 	pub fn tally(ref_index: ReferendumIndex) -> (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) {
-		let (approve, against, capital): (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) = Self::voters_for(ref_index).iter()
-			.map(|voter| (
-				T::Currency::total_balance(voter), Self::vote_of((ref_index, voter.clone()))
-			))
-			.map(|(bal, vote)|
-				if vote.is_aye() {
-					(bal * BalanceOf::<T>::sa(vote.multiplier() as u64), Zero::zero(), bal)
-				} else {
-					(Zero::zero(), bal * BalanceOf::<T>::sa(vote.multiplier() as u64), bal)
-				}
-			).fold((Zero::zero(), Zero::zero(), Zero::zero()), |(a, b, c), (d, e, f)| (a + d, b + e, c + f));
-		let (del_approve, del_against, del_capital) = Self::tally_delegation(ref_index);
-		(approve + del_approve, against + del_against, capital + del_capital)
-	}
-
-	/// Get the delegated voters for the current proposal.
-	/// I think this goes into a worker once https://github.com/paritytech/substrate/issues/1458 is done. 
-	fn tally_delegation(ref_index: ReferendumIndex) -> (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) {
-		Self::voters_for(ref_index).iter()
-			.fold((Zero::zero(), Zero::zero(), Zero::zero()), |(approve_acc, against_acc, capital_acc), voter| {
-				let vote = Self::vote_of((ref_index, voter.clone()));
-				let (votes, balance) = Self::delegated_votes(ref_index, voter.clone(), vote.multiplier(), MAX_RECURSION_LIMIT);
-				if vote.is_aye() {
-					(approve_acc + votes, against_acc, capital_acc + balance)
-				} else {
-					(approve_acc, against_acc + votes, capital_acc + balance)
-				}
-			})
-	}
-
-	fn delegated_votes(
-		ref_index: ReferendumIndex,
-		to: T::AccountId,
-		min_lock_periods: LockPeriods,
-		recursion_limit: u32,
-	) -> (BalanceOf<T>, BalanceOf<T>) {
-		if recursion_limit == 0 { return (Zero::zero(), Zero::zero()); }
-		<Delegations<T>>::enumerate()
-			.filter(|(delegator, (delegate, _))| *delegate == to && !<VoteOf<T>>::exists(&(ref_index, delegator.clone())))
-			.fold((Zero::zero(), Zero::zero()), |(votes_acc, balance_acc), (delegator, (_delegate, periods))| {
-				let lock_periods = if min_lock_periods <= periods { min_lock_periods } else { periods };
-				let balance = T::Currency::total_balance(&delegator);
-				let votes = T::Currency::total_balance(&delegator) * BalanceOf::<T>::sa(lock_periods as u64);
-				let (del_votes, del_balance) = Self::delegated_votes(ref_index, delegator, lock_periods, recursion_limit - 1);
-				(votes_acc + votes + del_votes, balance_acc + balance + del_balance)
-			})
+		Self::tally_of(ref_index)
 	}
 
 	// Exposed mutables.
 
 	/// Start a referendum. Can be called directly by the council.
-	pub fn internal_start_referendum(proposal: T::Proposal, threshold: VoteThreshold, delay: T::BlockNumber) -> result::Result<ReferendumIndex, &'static str> {
-		<Module<T>>::inject_referendum(<system::Module<T>>::block_number() + <Module<T>>::voting_period(), proposal, threshold, delay)
+	pub fn internal_start_referendum(proposal_hash: T::Hash, threshold: VoteThreshold, delay: T::BlockNumber) -> result::Result<ReferendumIndex, &'static str> {
+		<Module<T>>::inject_referendum(<system::Module<T>>::block_number() + <Module<T>>::voting_period(), proposal_hash, threshold, delay)
 	}
 
 	/// Remove a referendum. Can be called directly by the council.
@@ -351,22 +533,158 @@ impl<T: Trait> Module<T> {
 		<Module<T>>::clear_referendum(ref_index);
 	}
 
+	/// Note the preimage of a proposal without taking a deposit, unless it is noted already.
+	/// Can be called directly by the council, whose proposals are known in full.
+	pub fn internal_note_preimage(encoded_proposal: Vec<u8>) -> T::Hash {
+		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
+		if !<Preimages<T>>::exists(&proposal_hash) {
+			let now = <system::Module<T>>::block_number();
+			<Preimages<T>>::insert(proposal_hash, (encoded_proposal, T::AccountId::default(), Zero::zero(), now));
+		}
+		proposal_hash
+	}
+
 	// private.
 
-	/// Start a referendum
+	/// Whether `proposal_hash` is proposed publicly or externally, in an active referendum or
+	/// queued for enactment.
+	fn is_preimage_in_use(proposal_hash: &T::Hash) -> bool {
+		Self::public_props().iter().any(|(_, h, _)| h == proposal_hash)
+			|| Self::next_external().map_or(false, |(h, _)| &h == proposal_hash)
+			|| Self::active_referendums().iter().any(|(_, info)| &info.proposal_hash == proposal_hash)
+			|| Self::queued_preimages(proposal_hash) > 0
+	}
+
+	/// Queue `proposal_hash` for enactment at `when`.
+	fn queue_preimage(when: T::BlockNumber, proposal_hash: T::Hash, index: ReferendumIndex) {
+		<EnactmentQueue<T>>::mutate(when, |q| q.push(Some((proposal_hash, index))));
+		<QueuedPreimages<T>>::mutate(&proposal_hash, |n| *n += 1);
+	}
+
+	/// Drop one entry of `proposal_hash` from the count of the dispatch queue.
+	fn unqueue_preimage(proposal_hash: &T::Hash) {
+		let n = Self::queued_preimages(proposal_hash).saturating_sub(1);
+		if n == 0 {
+			<QueuedPreimages<T>>::remove(proposal_hash);
+		} else {
+			<QueuedPreimages<T>>::insert(proposal_hash, n);
+		}
+	}
+
+	/// Migrate public proposals, active referenda and the dispatch queue from holding proposals
+	/// in full to holding their hashes, noting each proposal as a preimage without a deposit.
+	///
+	/// The dispatch queue can't be enumerated, so only the entries up to a public delay from now
+	/// are migrated right away. Any later entry is migrated once its block is due or one of its
+	/// entries is cancelled.
+	fn migrate_to_proposal_hashes() {
+		let note = |proposal: T::Proposal| Self::internal_note_preimage(proposal.encode());
+
+		let _ = <PublicProps<T>>::translate(|props: Option<Vec<(PropIndex, T::Proposal, T::AccountId)>>|
+			props.map(|props| props.into_iter().map(|(i, p, who)| (i, note(p), who)).collect())
+		);
+
+		for i in Self::next_tally()..Self::referendum_count() {
+			let _ = <ReferendumInfoOf<T>>::translate(i,
+				|(end, proposal, threshold, delay): (T::BlockNumber, T::Proposal, VoteThreshold, T::BlockNumber)|
+					ReferendumInfo { end, proposal_hash: note(proposal), threshold, delay }
+			);
+		}
+
+		let now = <system::Module<T>>::block_number();
+		let mut when = now;
+		while when <= now + Self::public_delay() {
+			Self::migrate_dispatch_queue_at(when);
+			when = when + One::one();
+		}
+	}
+
+	/// Move the entries of the dispatch queue of block `when` that were queued with proposals in
+	/// full, under the previous name of the queue, to the front of its current queue, noting each
+	/// proposal as a preimage without a deposit.
+	fn migrate_dispatch_queue_at(when: T::BlockNumber) {
+		let mut key = DISPATCH_QUEUE_OF_PROPOSALS.to_vec();
+		when.encode_to(&mut key);
+		let queue: Option<Vec<Option<(T::Proposal, ReferendumIndex)>>> = srml_support::storage::take(&key);
+		if let Some(queue) = queue {
+			let mut migrated = queue.into_iter().map(|item| item.map(|(proposal, index)| {
+				let proposal_hash = Self::internal_note_preimage(proposal.encode());
+				<QueuedPreimages<T>>::mutate(&proposal_hash, |n| *n += 1);
+				(proposal_hash, index)
+			})).collect::<Vec<_>>();
+			migrated.extend(<EnactmentQueue<T>>::take(when));
+			<EnactmentQueue<T>>::insert(when, migrated);
+		}
+	}
+
+	/// Migrate from delegations of whole accounts, held in a linked map and counted only when a
+	/// referendum ended, to delegations of a fixed balance with tallies kept as votes are cast.
+	///
+	/// Each delegation is carried over with the delegator's free balance, locked until undelegated.
+	/// Delegations to an account that is itself delegating, and those of accounts that voted in an
+	/// active referendum, can't be expressed any more and are dropped; those accounts must delegate
+	/// again. The votes already cast in active referenda are then counted and locked.
+	fn migrate_to_delegated_balances() {
+		let mut delegations = Vec::new();
+		let mut next: Option<T::AccountId> = srml_support::storage::take(b"head of Democracy Delegations");
+		while let Some(who) = next {
+			// linked map entries are stored with their (previous, next) linkage.
+			let key = <Delegations<T>>::key_for(&who);
+			let entry: Option<((T::AccountId, LockPeriods), (Option<T::AccountId>, Option<T::AccountId>))> =
+				srml_support::storage::take(&key);
+			next = entry.and_then(|((to, lock_periods), (_, next))| {
+				delegations.push((who, to, lock_periods));
+				next
+			});
+		}
+
+		let active = Self::active_referendums();
+		let has_voted = |who: &T::AccountId| active.iter().any(|(i, _)| <VoteOf<T>>::exists(&(*i, who.clone())));
+		let max_lock_periods = Self::max_lock_periods();
+		for (who, to, lock_periods) in delegations.iter() {
+			if who == to || delegations.iter().any(|(d, _, _)| d == to) || has_voted(who) {
+				continue;
+			}
+			let lock_periods = (*lock_periods).max(0).min(max_lock_periods);
+			let balance = T::Currency::free_balance(who);
+			<Delegations<T>>::insert(who, (to.clone(), lock_periods, balance));
+			<Delegated<T>>::mutate(to, |(v, b)| {
+				*v = *v + balance * BalanceOf::<T>::sa(lock_periods as u64);
+				*b = *b + balance;
+			});
+			T::Currency::set_lock(DELEGATION_ID, who, balance, Bounded::max_value(), WithdrawReason::Transfer.into());
+		}
+
+		for (ref_index, info) in active {
+			for who in Self::voters_for(ref_index) {
+				let balance = T::Currency::total_balance(&who);
+				<VoteBalanceOf<T>>::insert(&(ref_index, who.clone()), balance);
+				Self::tally_vote(ref_index, &who, true);
+				T::Currency::extend_lock(DEMOCRACY_ID, &who, balance, info.end, WithdrawReason::Transfer.into());
+			}
+		}
+	}
+
+	/// Schedule an external proposal to be tabled next, unless it is still blacklisted.
+	fn note_external(proposal_hash: T::Hash, threshold: VoteThreshold) -> Result {
+		ensure!(!<NextExternal<T>>::exists(), "proposal already made");
+		if let Some((until, _)) = Self::blacklist(&proposal_hash) {
+			ensure!(<system::Module<T>>::block_number() >= until, "proposal still blacklisted");
+		}
+		<NextExternal<T>>::put((proposal_hash, threshold));
+		Ok(())
+	}
+
+	/// Start a referendum. Referenda need not end in the order they are started.
 	fn inject_referendum(
 		end: T::BlockNumber,
-		proposal: T::Proposal,
+		proposal_hash: T::Hash,
 		threshold: VoteThreshold,
 		delay: T::BlockNumber,
 	) -> result::Result<ReferendumIndex, &'static str> {
 		let ref_index = Self::referendum_count();
-		if ref_index > 0 && Self::referendum_info(ref_index - 1).map(|i| i.end > end).unwrap_or(false) {
-			Err("Cannot inject a referendum that ends earlier than preceeding referendum")?
-		}
-
 		<ReferendumCount<T>>::put(ref_index + 1);
-		<ReferendumInfoOf<T>>::insert(ref_index, ReferendumInfo { end, proposal, threshold, delay });
+		<ReferendumInfoOf<T>>::insert(ref_index, ReferendumInfo { end, proposal_hash, threshold, delay });
 		Self::deposit_event(RawEvent::Started(ref_index, threshold));
 		Ok(ref_index)
 	}
@@ -374,25 +692,116 @@ impl<T: Trait> Module<T> {
 	/// Remove all info on a referendum.
 	fn clear_referendum(ref_index: ReferendumIndex) {
 		<ReferendumInfoOf<T>>::remove(ref_index);
-		<VotersFor<T>>::remove(ref_index);
-		for v in Self::voters_for(ref_index) {
-			<VoteOf<T>>::remove((ref_index, v));
+		<TallyOf<T>>::remove(ref_index);
+		for v in <VotersFor<T>>::take(ref_index) {
+			<VoteOf<T>>::remove((ref_index, v.clone()));
+			<VoteBalanceOf<T>>::remove((ref_index, v));
 		}
 	}
 
-	/// Enact a proposal from a referendum.
-	fn enact_proposal(proposal: T::Proposal, index: ReferendumIndex) {
-		let ok = proposal.dispatch(system::RawOrigin::Root.into()).is_ok();
-		Self::deposit_event(RawEvent::Executed(index, ok));
+	/// Add (or, if `!add`, remove) the vote of `who`, including everything delegated to them, to
+	/// the tally of `ref_index`.
+	fn tally_vote(ref_index: ReferendumIndex, who: &T::AccountId, add: bool) {
+		let vote = Self::vote_of((ref_index, who.clone()));
+		let balance = Self::vote_balance_of((ref_index, who.clone()));
+		let (delegated_votes, delegated_balance) = Self::delegated(who);
+		let votes = balance * BalanceOf::<T>::sa(vote.multiplier() as u64) + delegated_votes;
+		Self::adjust_tally(ref_index, vote.is_aye(), votes, balance + delegated_balance, add);
 	}
 
+	/// Add (or, if `!add`, remove) a delegation of `balance` at `lock_periods` to `to`, updating
+	/// the tally of every active referendum in which `to` has voted.
+	fn tally_delegation(to: &T::AccountId, lock_periods: LockPeriods, balance: BalanceOf<T>, add: bool) {
+		let votes = balance * BalanceOf::<T>::sa(lock_periods as u64);
+		<Delegated<T>>::mutate(to, |(v, b)| if add {
+			*v = *v + votes;
+			*b = *b + balance;
+		} else {
+			*v = *v - votes;
+			*b = *b - balance;
+		});
+		for (ref_index, _) in Self::active_referendums() {
+			if <VoteOf<T>>::exists(&(ref_index, to.clone())) {
+				let vote = Self::vote_of((ref_index, to.clone()));
+				Self::adjust_tally(ref_index, vote.is_aye(), votes, balance, add);
+			}
+		}
+	}
+
+	/// Add (or, if `!add`, remove) `votes` on the side of `aye` and `balance` to the tally of
+	/// `ref_index`.
+	fn adjust_tally(ref_index: ReferendumIndex, aye: bool, votes: BalanceOf<T>, balance: BalanceOf<T>, add: bool) {
+		<TallyOf<T>>::mutate(ref_index, |(approve, against, capital)| {
+			let side = if aye { approve } else { against };
+			if add {
+				*side = *side + votes;
+				*capital = *capital + balance;
+			} else {
+				*side = *side - votes;
+				*capital = *capital - balance;
+			}
+		});
+	}
+
+	/// Enact a proposal from a referendum. Its preimage is consumed unless it is still in use by
+	/// another proposal, referendum or queued dispatch.
+	fn enact_proposal(proposal_hash: T::Hash, index: ReferendumIndex) {
+		if let Some((encoded_proposal, who, deposit, _)) = Self::preimages(&proposal_hash) {
+			let consumed = !Self::is_preimage_in_use(&proposal_hash);
+			if consumed {
+				<Preimages<T>>::remove(&proposal_hash);
+			}
+
+			if let Some(proposal) = T::Proposal::decode(&mut &encoded_proposal[..]) {
+				if consumed {
+					let _ = T::Currency::unreserve(&who, deposit);
+					Self::deposit_event(RawEvent::PreimageUsed(proposal_hash, who, deposit));
+				}
+
+				let ok = proposal.dispatch(system::RawOrigin::Root.into()).is_ok();
+				Self::deposit_event(RawEvent::Executed(index, ok));
+			} else {
+				// the preimage was never a valid proposal; its deposit is forfeit.
+				if consumed {
+					let _ = T::Currency::slash_reserved(&who, deposit);
+				}
+				Self::deposit_event(RawEvent::PreimageInvalid(proposal_hash, index));
+			}
+		} else {
+			Self::deposit_event(RawEvent::PreimageMissing(proposal_hash, index));
+		}
+	}
+
+	/// Table the next referendum, alternating between external and public proposals whenever
+	/// both are waiting.
 	fn launch_next(now: T::BlockNumber) -> Result {
+		if <LastTabledWasExternal<T>>::take() {
+			Self::launch_public(now.clone()).or_else(|_| Self::launch_external(now))
+		} else {
+			Self::launch_external(now.clone()).or_else(|_| Self::launch_public(now))
+		}
+	}
+
+	/// Table the scheduled external proposal, if any.
+	fn launch_external(now: T::BlockNumber) -> Result {
+		if let Some((proposal_hash, threshold)) = <NextExternal<T>>::take() {
+			<LastTabledWasExternal<T>>::put(true);
+			Self::deposit_event(RawEvent::ExternalTabled);
+			Self::inject_referendum(now + Self::voting_period(), proposal_hash, threshold, Self::public_delay())?;
+			Ok(())
+		} else {
+			Err("No external proposal waiting")
+		}
+	}
+
+	/// Table the public proposal with the most backing, if any.
+	fn launch_public(now: T::BlockNumber) -> Result {
 		let mut public_props = Self::public_props();
 		if let Some((winner_index, _)) = public_props.iter()
 			.enumerate()
 			.max_by_key(|x| Self::locked_for((x.1).0).unwrap_or_else(Zero::zero)/*defensive only: All current public proposals have an amount locked*/)
 		{
-			let (prop_index, proposal, _) = public_props.swap_remove(winner_index);
+			let (prop_index, proposal_hash, _) = public_props.swap_remove(winner_index);
 			<PublicProps<T>>::put(public_props);
 
 			if let Some((deposit, depositors)) = <DepositOf<T>>::take(prop_index) {//: (BalanceOf<T>, Vec<T::AccountId>) =
@@ -401,14 +810,15 @@ impl<T: Trait> Module<T> {
 					T::Currency::unreserve(d, deposit);
 				}
 				Self::deposit_event(RawEvent::Tabled(prop_index, deposit, depositors));
-				Self::inject_referendum(now + Self::voting_period(), proposal, VoteThreshold::SuperMajorityApprove, Self::public_delay())?;
+				Self::inject_referendum(now + Self::voting_period(), proposal_hash, VoteThreshold::SuperMajorityApprove, Self::public_delay())?;
 			}
+			Ok(())
+		} else {
+			Err("No public proposals waiting")
 		}
-
-		Ok(())
 	}
 
-	fn bake_referendum(now: T::BlockNumber, index: ReferendumIndex, info: ReferendumInfo<T::BlockNumber, T::Proposal>) -> Result {
+	fn bake_referendum(now: T::BlockNumber, index: ReferendumIndex, info: ReferendumInfo<T::BlockNumber, T::Hash>) -> Result {
 		let (approve, against, capital) = Self::tally(index);
 		let total_issuance = T::Currency::total_issuance();
 		let approved = info.threshold.approved(approve, against, capital, total_issuance);
@@ -433,14 +843,13 @@ impl<T: Trait> Module<T> {
 		if approved {
 			Self::deposit_event(RawEvent::Passed(index));
 			if info.delay.is_zero() {
-				Self::enact_proposal(info.proposal, index);
+				Self::enact_proposal(info.proposal_hash, index);
 			} else {
-				<DispatchQueue<T>>::mutate(now + info.delay, |q| q.push(Some((info.proposal, index))));
+				Self::queue_preimage(now + info.delay, info.proposal_hash, index);
 			}
 		} else {
 			Self::deposit_event(RawEvent::NotPassed(index));
 		}
-		<NextTally<T>>::put(index + 1);
 
 		Ok(())
 	}
@@ -449,7 +858,9 @@ impl<T: Trait> Module<T> {
 	fn end_block(now: T::BlockNumber) -> Result {
 		// pick out another public referendum if it's time.
 		if (now % Self::launch_period()).is_zero() {
-			Self::launch_next(now.clone())?;
+			// Errors come from the queue being empty. we don't really care about that, and even if
+			// we did, there is nothing we can do here.
+			let _ = Self::launch_next(now.clone());
 		}
 
 		// tally up votes for any expiring referenda.
@@ -457,8 +868,18 @@ impl<T: Trait> Module<T> {
 			Self::bake_referendum(now.clone(), index, info)?;
 		}
 
-		for (proposal, index) in <DispatchQueue<T>>::take(now).into_iter().filter_map(|x| x) {
-			Self::enact_proposal(proposal, index);
+		// referenda may mature out of order; skip past all those already tallied or cancelled.
+		let last = Self::referendum_count();
+		let mut next = Self::next_tally();
+		while next < last && !Self::is_active_referendum(next) {
+			next += 1;
+		}
+		<NextTally<T>>::put(next);
+
+		Self::migrate_dispatch_queue_at(now);
+		for (proposal_hash, index) in <EnactmentQueue<T>>::take(now).into_iter().filter_map(|x| x) {
+			Self::unqueue_preimage(&proposal_hash);
+			Self::enact_proposal(proposal_hash, index);
 		}
 		Ok(())
 	}
@@ -468,10 +889,10 @@ impl<T: Trait> Module<T> {
 mod tests {
 	use super::*;
 	use runtime_io::with_externalities;
-	use srml_support::{impl_outer_origin, impl_outer_dispatch, assert_noop, assert_ok};
+	use srml_support::{impl_outer_origin, impl_outer_dispatch, assert_noop, assert_ok, traits::Get};
 	use substrate_primitives::{H256, Blake2Hasher};
 	use primitives::BuildStorage;
-	use primitives::traits::{BlakeTwo256, IdentityLookup};
+	use primitives::traits::{BlakeTwo256, IdentityLookup, OnRuntimeUpgrade};
 	use primitives::testing::{Digest, DigestItem, Header};
 	use balances::BalanceLock;
 
@@ -504,6 +925,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -514,10 +936,31 @@ mod tests {
 		type TransferPayment = ();
 		type DustRemoval = ();
 	}
+	pub struct EnsureSignedBy<Who>(::std::marker::PhantomData<Who>);
+	impl<Who: Get<Vec<u64>>> EnsureOrigin<Origin> for EnsureSignedBy<Who> {
+		type Success = u64;
+		fn ensure_origin(o: Origin) -> result::Result<u64, &'static str> {
+			let who = ensure_signed(o)?;
+			ensure!(Who::get().contains(&who), "bad origin: not an allowed account");
+			Ok(who)
+		}
+	}
+	pub struct Two;
+	impl Get<Vec<u64>> for Two { fn get() -> Vec<u64> { vec![2] } }
+	pub struct Three;
+	impl Get<Vec<u64>> for Three { fn get() -> Vec<u64> { vec![3] } }
+	pub struct Five;
+	impl Get<Vec<u64>> for Five { fn get() -> Vec<u64> { vec![5] } }
+	pub struct OneToFive;
+	impl Get<Vec<u64>> for OneToFive { fn get() -> Vec<u64> { vec![1, 2, 3, 4, 5] } }
 	impl Trait for Test {
 		type Currency = balances::Module<Self>;
 		type Proposal = Call;
 		type Event = ();
+		type ExternalOrigin = EnsureSignedBy<Two>;
+		type ExternalMajorityOrigin = EnsureSignedBy<Three>;
+		type FastTrackOrigin = EnsureSignedBy<Five>;
+		type VetoOrigin = EnsureSignedBy<OneToFive>;
 	}
 
 	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
@@ -539,8 +982,11 @@ mod tests {
 			launch_period: 1,
 			voting_period: 1,
 			minimum_deposit: 1,
+			preimage_byte_deposit: 0,
 			public_delay,
 			max_lock_periods: 6,
+			emergency_voting_period: 1,
+			cooloff_period: 2,
 		}.build_storage().unwrap().0);
 		runtime_io::TestExternalities::new(t)
 	}
@@ -583,7 +1029,7 @@ mod tests {
 	fn invalid_vote_strength_should_not_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_noop!(Democracy::vote(Origin::signed(1), r, Vote::new(true, 7)), "vote has too great a strength");
 			assert_noop!(Democracy::vote(Origin::signed(1), r, Vote::new(false, 7)), "vote has too great a strength");
 		});
@@ -593,17 +1039,538 @@ mod tests {
 		Call::Balances(balances::Call::set_balance(42, value.into(), 0))
 	}
 
+	fn set_balance_proposal_hash(value: u64) -> H256 {
+		BlakeTwo256::hash(&set_balance_proposal(value).encode()[..])
+	}
+
+	fn set_balance_proposal_hash_and_note(value: u64) -> H256 {
+		let p = set_balance_proposal(value);
+		match Democracy::note_preimage(Origin::signed(6), p.encode()) {
+			Ok(_) => (),
+			Err(x) if x == "preimage already noted" => (),
+			Err(x) => panic!(x),
+		}
+		BlakeTwo256::hash(&p.encode()[..])
+	}
+
 	fn propose_set_balance(who: u64, value: u64, locked: u64) -> super::Result {
-		Democracy::propose(Origin::signed(who), Box::new(set_balance_proposal(value)), locked.into())
+		Democracy::propose(Origin::signed(who), set_balance_proposal_hash(value), locked.into())
+	}
+
+	fn propose_set_balance_and_note(who: u64, value: u64, locked: u64) -> super::Result {
+		Democracy::propose(Origin::signed(who), set_balance_proposal_hash_and_note(value), locked.into())
+	}
+
+	#[test]
+	fn missing_preimage_should_fail() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			assert_eq!(Balances::free_balance(&42), 0);
+		});
+	}
+
+	#[test]
+	fn preimage_deposit_should_be_required_and_returned() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// fee of 100 is too much.
+			<PreimageByteDeposit<Test>>::put(100);
+			assert_noop!(
+				Democracy::note_preimage(Origin::signed(6), vec![0; 500]),
+				"not enough free balance for preimage deposit"
+			);
+			// fee of 1 is reasonable.
+			<PreimageByteDeposit<Test>>::put(1);
+			let len = set_balance_proposal(2).encode().len() as u64;
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
+
+			assert_eq!(Balances::reserved_balance(&6), len);
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			assert_eq!(Balances::reserved_balance(&6), 0);
+			assert_eq!(Balances::free_balance(&6), 60);
+			assert_eq!(Balances::free_balance(&42), 2);
+		});
+	}
+
+	#[test]
+	fn invalid_preimage_deposit_should_be_slashed() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<PreimageByteDeposit<Test>>::put(1);
+			assert_ok!(Democracy::note_preimage(Origin::signed(6), vec![0xff; 10]));
+			let h = BlakeTwo256::hash(&[0xff; 10][..]);
+			let r = Democracy::inject_referendum(1, h, VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			assert!(Democracy::preimages(h).is_none());
+			assert_eq!(Balances::reserved_balance(&6), 0);
+			assert_eq!(Balances::free_balance(&6), 50);
+		});
+	}
+
+	#[test]
+	fn preimage_deposit_should_be_reapable_earlier_by_owner() {
+		with_externalities(&mut new_test_ext_with_public_delay(1), || {
+			System::set_block_number(1);
+			<PreimageByteDeposit<Test>>::put(1);
+			let h = set_balance_proposal_hash_and_note(2);
+
+			assert_noop!(Democracy::reap_preimage(Origin::signed(6), h), "too early");
+
+			System::set_block_number(2);
+			assert_ok!(Democracy::reap_preimage(Origin::signed(6), h));
+
+			assert!(Democracy::preimages(h).is_none());
+			assert_eq!(Balances::reserved_balance(&6), 0);
+			assert_eq!(Balances::free_balance(&6), 60);
+		});
+	}
+
+	#[test]
+	fn preimage_deposit_should_be_reapable_by_others_after_delay() {
+		with_externalities(&mut new_test_ext_with_public_delay(1), || {
+			System::set_block_number(1);
+			<PreimageByteDeposit<Test>>::put(1);
+			let len = set_balance_proposal(2).encode().len() as u64;
+			let h = set_balance_proposal_hash_and_note(2);
+
+			System::set_block_number(2);
+			assert_noop!(Democracy::reap_preimage(Origin::signed(5), h), "too early");
+
+			System::set_block_number(3);
+			assert_ok!(Democracy::reap_preimage(Origin::signed(5), h));
+
+			assert_eq!(Balances::reserved_balance(&6), 0);
+			assert_eq!(Balances::free_balance(&6), 60 - len);
+			assert_eq!(Balances::free_balance(&5), 50 + len);
+		});
+	}
+
+	#[test]
+	fn preimage_should_not_be_reapable_while_in_use() {
+		with_externalities(&mut new_test_ext_with_public_delay(1), || {
+			System::set_block_number(1);
+			<PreimageByteDeposit<Test>>::put(1);
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
+			let h = set_balance_proposal_hash(2);
+
+			System::set_block_number(3);
+			assert_noop!(Democracy::reap_preimage(Origin::signed(5), h), "preimage still in use");
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			let r = 0;
+			assert!(Democracy::is_active_referendum(r));
+			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
+			assert_noop!(Democracy::reap_preimage(Origin::signed(5), h), "preimage still in use");
+
+			System::set_block_number(4);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Democracy::queued_preimages(h), 1);
+			assert_noop!(Democracy::reap_preimage(Origin::signed(5), h), "preimage still in use");
+
+			System::set_block_number(5);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Democracy::queued_preimages(h), 0);
+			assert_eq!(Balances::free_balance(&42), 2);
+			assert_eq!(Balances::free_balance(&6), 60);
+		});
+	}
+
+	#[test]
+	fn proposals_should_be_migrated_to_hashes() {
+		with_externalities(&mut new_test_ext_with_public_delay(2), || {
+			System::set_block_number(1);
+			<StorageVersion<Test>>::kill();
+			srml_support::storage::put(<PublicProps<Test>>::key(), &vec![(0u32, set_balance_proposal(1), 1u64)]);
+			<ReferendumCount<Test>>::put(1);
+			srml_support::storage::put(
+				&<ReferendumInfoOf<Test>>::key_for(0),
+				&(3u64, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0u64),
+			);
+			let legacy_queue_key = |when: u64| {
+				let mut key = DISPATCH_QUEUE_OF_PROPOSALS.to_vec();
+				when.encode_to(&mut key);
+				key
+			};
+			srml_support::storage::put(&legacy_queue_key(3), &vec![Some((set_balance_proposal(3), 0u32)), None]);
+			srml_support::storage::put(&legacy_queue_key(10), &vec![Some((set_balance_proposal(4), 1u32))]);
+
+			Democracy::on_runtime_upgrade();
+
+			assert_eq!(Democracy::storage_version(), 2);
+			assert_eq!(Democracy::public_props(), vec![(0, set_balance_proposal_hash(1), 1)]);
+			assert_eq!(
+				Democracy::referendum_info(0),
+				Some(ReferendumInfo::new(3, set_balance_proposal_hash(2), VoteThreshold::SuperMajorityApprove, 0))
+			);
+			assert_eq!(Democracy::dispatch_queue(3), vec![Some((set_balance_proposal_hash(3), 0)), None]);
+			assert_eq!(Democracy::queued_preimages(set_balance_proposal_hash(3)), 1);
+			for value in 1..4 {
+				let (_, provider, deposit, _) = Democracy::preimages(set_balance_proposal_hash(value)).unwrap();
+				assert_eq!((provider, deposit), (0, 0));
+			}
+
+			// a second upgrade leaves the migrated storage alone.
+			Democracy::on_runtime_upgrade();
+			assert_eq!(Democracy::public_props(), vec![(0, set_balance_proposal_hash(1), 1)]);
+
+			assert_eq!(Democracy::end_block(3), Ok(()));
+			assert_eq!(Balances::free_balance(&42), 3);
+
+			// entries beyond the public delay are migrated once they are due.
+			assert_eq!(Democracy::dispatch_queue(10), vec![]);
+			assert!(Democracy::preimages(set_balance_proposal_hash(4)).is_none());
+			assert_eq!(Democracy::end_block(10), Ok(()));
+			assert!(srml_support::storage::get_raw(&legacy_queue_key(10)).is_none());
+			assert_eq!(Balances::free_balance(&42), 4);
+		});
+	}
+
+	#[test]
+	fn preimage_should_be_kept_for_another_referendum_on_the_same_proposal() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<PreimageByteDeposit<Test>>::put(1);
+			let h = set_balance_proposal_hash_and_note(2);
+			let r0 = Democracy::internal_start_referendum(h, VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r1 = Democracy::internal_start_referendum(h, VoteThreshold::SuperMajorityApprove, 1).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r0, AYE));
+			assert_ok!(Democracy::vote(Origin::signed(1), r1, AYE));
+
+			System::set_block_number(2);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Balances::free_balance(&42), 2);
+			assert!(Democracy::preimages(h).is_some());
+			assert!(Balances::reserved_balance(&6) > 0);
+
+			System::set_block_number(3);
+			let _ = Balances::make_free_balance_be(&42, 0);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Balances::free_balance(&42), 2);
+			assert!(Democracy::preimages(h).is_none());
+			assert_eq!(Balances::reserved_balance(&6), 0);
+			assert_eq!(Balances::free_balance(&6), 60);
+		});
+	}
+
+	#[test]
+	fn delegations_should_be_migrated_to_balances() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<StorageVersion<Test>>::put(1);
+			let r = Democracy::internal_start_referendum(
+				set_balance_proposal_hash(2),
+				VoteThreshold::SuperMajorityApprove,
+				0,
+			).unwrap();
+			<VotersFor<Test>>::insert(r, vec![1, 5, 6]);
+			<VoteOf<Test>>::insert(&(r, 1), AYE);
+			<VoteOf<Test>>::insert(&(r, 5), NAY);
+			<VoteOf<Test>>::insert(&(r, 6), NAY);
+
+			// 3 delegates to a delegator and 6 has voted, so both delegations are dropped.
+			let old_delegations: Vec<(u64, (u64, LockPeriods), (Option<u64>, Option<u64>))> = vec![
+				(2, (1, 1), (None, Some(3))),
+				(3, (2, 1), (Some(2), Some(4))),
+				(4, (5, 9), (Some(3), Some(6))),
+				(6, (1, 2), (Some(4), None)),
+			];
+			srml_support::storage::put(b"head of Democracy Delegations", &2u64);
+			for (who, delegation, linkage) in old_delegations {
+				srml_support::storage::put(&<Delegations<Test>>::key_for(who), &(delegation, linkage));
+			}
+
+			Democracy::on_runtime_upgrade();
+
+			assert_eq!(Democracy::storage_version(), 2);
+			assert!(!srml_support::storage::exists(b"head of Democracy Delegations"));
+			assert_eq!(Democracy::delegations(2), Some((1, 1, 20)));
+			assert_eq!(Democracy::delegations(3), None);
+			assert_eq!(Democracy::delegations(4), Some((5, 6, 40)));
+			assert_eq!(Democracy::delegations(6), None);
+			assert_eq!(Democracy::delegated(1), (20, 20));
+			assert_eq!(Democracy::delegated(5), (240, 40));
+
+			assert_eq!(Democracy::vote_balance_of((r, 5)), 50);
+			assert_eq!(Democracy::tally(r), (30, 350, 180));
+			assert_eq!(Balances::locks(2), vec![BalanceLock { id: DELEGATION_ID, amount: 20, until: u64::max_value(), reasons: WithdrawReason::Transfer.into() }]);
+			assert_eq!(Balances::locks(3), vec![]);
+			assert_eq!(Balances::locks(6), vec![BalanceLock { id: DEMOCRACY_ID, amount: 60, until: 2, reasons: WithdrawReason::Transfer.into() }]);
+		});
+	}
+
+	#[test]
+	fn external_referendum_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			assert_noop!(
+				Democracy::external_propose(Origin::signed(1), set_balance_proposal_hash(2)),
+				"bad origin: not an allowed account"
+			);
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(2)));
+			assert_noop!(
+				Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash(1)),
+				"proposal already made"
+			);
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(
+				Democracy::referendum_info(0),
+				Some(ReferendumInfo {
+					end: 1,
+					proposal_hash: set_balance_proposal_hash(2),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn external_majority_referendum_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			assert_noop!(
+				Democracy::external_propose_majority(Origin::signed(2), set_balance_proposal_hash(2)),
+				"bad origin: not an allowed account"
+			);
+			assert_ok!(Democracy::external_propose_majority(Origin::signed(3), set_balance_proposal_hash_and_note(2)));
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(
+				Democracy::referendum_info(0),
+				Some(ReferendumInfo {
+					end: 1,
+					proposal_hash: set_balance_proposal_hash(2),
+					threshold: VoteThreshold::SimpleMajority,
+					delay: 0,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn fast_track_referendum_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			let h = set_balance_proposal_hash_and_note(2);
+			assert_noop!(Democracy::fast_track(Origin::signed(5), h, 3, 2), "no proposal made");
+			assert_ok!(Democracy::external_propose_majority(Origin::signed(3), h));
+			assert_noop!(Democracy::fast_track(Origin::signed(1), h, 3, 2), "bad origin: not an allowed account");
+			assert_noop!(Democracy::fast_track(Origin::signed(5), h, 0, 0), "voting period too low");
+			assert_ok!(Democracy::fast_track(Origin::signed(5), h, 3, 2));
+
+			assert!(Democracy::next_external().is_none());
+			assert_eq!(
+				Democracy::referendum_info(0),
+				Some(ReferendumInfo {
+					end: 3,
+					proposal_hash: h,
+					threshold: VoteThreshold::SimpleMajority,
+					delay: 2,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn fast_track_referendum_fails_when_no_simple_majority() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			let h = set_balance_proposal_hash_and_note(2);
+			assert_ok!(Democracy::external_propose(Origin::signed(2), h));
+			assert_noop!(
+				Democracy::fast_track(Origin::signed(5), h, 3, 2),
+				"next external proposal not simple majority"
+			);
+		});
+	}
+
+	#[test]
+	fn fast_tracked_referendum_may_end_before_earlier_ones() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let r = Democracy::inject_referendum(3, set_balance_proposal_hash_and_note(3), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
+
+			let h = set_balance_proposal_hash_and_note(2);
+			assert_ok!(Democracy::external_propose_majority(Origin::signed(3), h));
+			assert_ok!(Democracy::fast_track(Origin::signed(5), h, 1, 0));
+			assert_ok!(Democracy::vote(Origin::signed(1), r + 1, AYE));
+
+			System::set_block_number(2);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Balances::free_balance(&42), 2);
+			assert_eq!(Democracy::next_tally(), r);
+
+			System::set_block_number(3);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+			assert_eq!(Balances::free_balance(&42), 3);
+			assert_eq!(Democracy::next_tally(), r + 2);
+		});
+	}
+
+	#[test]
+	fn veto_external_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			let h = set_balance_proposal_hash_and_note(2);
+			assert_ok!(Democracy::external_propose(Origin::signed(2), h));
+			assert!(Democracy::next_external().is_some());
+
+			// ...but it's vetoed.
+			assert_ok!(Democracy::veto_external(Origin::signed(3), h));
+			// cancelled.
+			assert!(Democracy::next_external().is_none());
+			// fails - same proposal can't be resubmitted.
+			assert_noop!(Democracy::external_propose(Origin::signed(2), h), "proposal still blacklisted");
+
+			System::set_block_number(1);
+			// fails as we're still in cooloff period.
+			assert_noop!(Democracy::external_propose(Origin::signed(2), h), "proposal still blacklisted");
+
+			System::set_block_number(2);
+			// works; as we're out of the cooloff period.
+			assert_ok!(Democracy::external_propose(Origin::signed(2), h));
+			assert!(Democracy::next_external().is_some());
+
+			// 3 can't veto the same thing twice.
+			assert_noop!(Democracy::veto_external(Origin::signed(3), h), "identity may not veto a proposal twice");
+			// 6 may not veto at all.
+			assert_noop!(Democracy::veto_external(Origin::signed(6), h), "bad origin: not an allowed account");
+
+			// 4 vetoes.
+			assert_ok!(Democracy::veto_external(Origin::signed(4), h));
+			assert_eq!(Democracy::blacklist(h), Some((4, vec![3, 4])));
+
+			System::set_block_number(3);
+			// fails as we're still in cooloff period.
+			assert_noop!(Democracy::external_propose(Origin::signed(2), h), "proposal still blacklisted");
+
+			// different proposal works fine.
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(3)));
+		});
+	}
+
+	#[test]
+	fn external_and_public_interleaving_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(1)));
+			assert_ok!(propose_set_balance_and_note(6, 2, 2));
+
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// both waiting: external goes first.
+			assert_eq!(
+				Democracy::referendum_info(0),
+				Some(ReferendumInfo {
+					end: 1,
+					proposal_hash: set_balance_proposal_hash(1),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+			// replenish external
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(3)));
+
+			System::set_block_number(1);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// both waiting: public goes next.
+			assert_eq!(
+				Democracy::referendum_info(1),
+				Some(ReferendumInfo {
+					end: 2,
+					proposal_hash: set_balance_proposal_hash(2),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+			// don't replenish public
+
+			System::set_block_number(2);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// external goes next.
+			assert_eq!(
+				Democracy::referendum_info(2),
+				Some(ReferendumInfo {
+					end: 3,
+					proposal_hash: set_balance_proposal_hash(3),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+			// replenish external
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(5)));
+
+			System::set_block_number(3);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// external goes again because there's no public waiting.
+			assert_eq!(
+				Democracy::referendum_info(3),
+				Some(ReferendumInfo {
+					end: 4,
+					proposal_hash: set_balance_proposal_hash(5),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+			// replenish both
+			assert_ok!(Democracy::external_propose(Origin::signed(2), set_balance_proposal_hash_and_note(7)));
+			assert_ok!(propose_set_balance_and_note(6, 4, 2));
+
+			System::set_block_number(4);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// public goes now since external went last time.
+			assert_eq!(
+				Democracy::referendum_info(4),
+				Some(ReferendumInfo {
+					end: 5,
+					proposal_hash: set_balance_proposal_hash(4),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+			// replenish public again
+			assert_ok!(propose_set_balance_and_note(6, 6, 2));
+
+			System::set_block_number(5);
+			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
+
+			// external goes now since public went last time.
+			assert_eq!(
+				Democracy::referendum_info(5),
+				Some(ReferendumInfo {
+					end: 6,
+					proposal_hash: set_balance_proposal_hash(7),
+					threshold: VoteThreshold::SuperMajorityApprove,
+					delay: 0,
+				})
+			);
+		});
 	}
 
 	#[test]
 	fn locked_for_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			assert_ok!(propose_set_balance(1, 2, 2));
-			assert_ok!(propose_set_balance(1, 4, 4));
-			assert_ok!(propose_set_balance(1, 3, 3));
+			assert_ok!(propose_set_balance_and_note(1, 2, 2));
+			assert_ok!(propose_set_balance_and_note(1, 4, 4));
+			assert_ok!(propose_set_balance_and_note(1, 3, 3));
 			assert_eq!(Democracy::locked_for(0), Some(2));
 			assert_eq!(Democracy::locked_for(1), Some(4));
 			assert_eq!(Democracy::locked_for(2), Some(3));
@@ -614,7 +1581,7 @@ mod tests {
 	fn single_proposal_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 
 			System::set_block_number(2);
@@ -636,14 +1603,14 @@ mod tests {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
 
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 			System::set_block_number(2);
 			let r = 0;
 
 			// Delegate vote.
-			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 100));
+			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 1, 20));
 
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 
@@ -661,28 +1628,29 @@ mod tests {
 	}
 
 	#[test]
-	fn single_proposal_should_work_with_cyclic_delegation() {
+	fn nested_delegation_should_not_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
 
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 			System::set_block_number(2);
 			let r = 0;
 
-			// Check behaviour with cycle.
-			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 100));
-			assert_ok!(Democracy::delegate(Origin::signed(3), 2, 100));
-			assert_ok!(Democracy::delegate(Origin::signed(1), 3, 100));
-			
+			// Delegations can't be chained in either direction.
+			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 1, 20));
+			assert_noop!(Democracy::delegate(Origin::signed(3), 2, 1, 30), "target is delegating");
+			assert_noop!(Democracy::delegate(Origin::signed(1), 4, 1, 10), "account is delegated to");
+			assert_noop!(Democracy::delegate(Origin::signed(1), 1, 1, 10), "cannot delegate to self");
+
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 
 			assert_eq!(Democracy::referendum_count(), 1);
 			assert_eq!(Democracy::voters_for(r), vec![1]);
 
-			// Delegated vote is counted.
-			assert_eq!(Democracy::tally(r), (60, 0, 60));
+			// Only the direct delegation exists to be counted.
+			assert_eq!(Democracy::tally(r), (30, 0, 30));
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 
 			assert_eq!(Balances::free_balance(&42), 2);
@@ -690,12 +1658,12 @@ mod tests {
 	}
 
 	#[test]
-	/// If transactor already voted, delegated vote is overwriten.
+	/// If transactor already voted, they cannot delegate.
 	fn single_proposal_should_work_with_vote_and_delegation() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
 
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 			System::set_block_number(2);
@@ -707,13 +1675,16 @@ mod tests {
 			assert_ok!(Democracy::vote(Origin::signed(2), r, AYE));
 
 			// Delegate vote.
-			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 100));
+			assert_noop!(
+				Democracy::delegate(Origin::signed(2), 1, 1, 20),
+				"account has voted in an active referendum"
+			);
 
 			assert_eq!(Democracy::referendum_count(), 1);
 			assert_eq!(Democracy::voters_for(r), vec![1, 2]);
 			assert_eq!(Democracy::vote_of((r, 1)), AYE);
 
-			// Delegated vote is not counted.
+			// Direct votes are counted.
 			assert_eq!(Democracy::tally(r), (30, 0, 30));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
@@ -727,11 +1698,12 @@ mod tests {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
 
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 			// Delegate and undelegate vote.
-			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 100));
+			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 1, 20));
 			assert_ok!(Democracy::undelegate(Origin::signed(2)));
+			assert_noop!(Democracy::undelegate(Origin::signed(2)), "not delegating");
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 			System::set_block_number(2);
@@ -752,12 +1724,12 @@ mod tests {
 	}
 
 	#[test]
-	/// If transactor voted, delegated vote is overwriten.
+	/// If transactor is delegating, they cannot vote.
 	fn single_proposal_should_work_with_delegation_and_vote() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
 
-			assert_ok!(propose_set_balance(1, 2, 1));
+			assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 			System::set_block_number(2);
@@ -766,16 +1738,16 @@ mod tests {
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 
 			// Delegate vote.
-			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 100));
+			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 1, 20));
 
 			// Vote.
-			assert_ok!(Democracy::vote(Origin::signed(2), r, AYE));
+			assert_noop!(Democracy::vote(Origin::signed(2), r, AYE), "account is delegating its vote");
 
 			assert_eq!(Democracy::referendum_count(), 1);
-			assert_eq!(Democracy::voters_for(r), vec![1, 2]);
+			assert_eq!(Democracy::voters_for(r), vec![1]);
 			assert_eq!(Democracy::vote_of((r, 1)), AYE);
 
-			// Delegated vote is not counted.
+			// Delegated vote is counted.
 			assert_eq!(Democracy::tally(r), (30, 0, 30));
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
@@ -788,7 +1760,7 @@ mod tests {
 	fn deposit_for_proposals_should_be_taken() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			assert_ok!(propose_set_balance(1, 2, 5));
+			assert_ok!(propose_set_balance_and_note(1, 2, 5));
 			assert_ok!(Democracy::second(Origin::signed(2), 0));
 			assert_ok!(Democracy::second(Origin::signed(5), 0));
 			assert_ok!(Democracy::second(Origin::signed(5), 0));
@@ -803,7 +1775,7 @@ mod tests {
 	fn deposit_for_proposals_should_be_returned() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			assert_ok!(propose_set_balance(1, 2, 5));
+			assert_ok!(propose_set_balance_and_note(1, 2, 5));
 			assert_ok!(Democracy::second(Origin::signed(2), 0));
 			assert_ok!(Democracy::second(Origin::signed(5), 0));
 			assert_ok!(Democracy::second(Origin::signed(5), 0));
@@ -835,7 +1807,7 @@ mod tests {
 	fn poor_seconder_should_not_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			assert_ok!(propose_set_balance(2, 2, 11));
+			assert_ok!(propose_set_balance_and_note(2, 2, 11));
 			assert_noop!(Democracy::second(Origin::signed(1), 0), "seconder\'s balance too low");
 		});
 	}
@@ -844,9 +1816,9 @@ mod tests {
 	fn runners_up_should_come_after() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(0);
-			assert_ok!(propose_set_balance(1, 2, 2));
-			assert_ok!(propose_set_balance(1, 4, 4));
-			assert_ok!(propose_set_balance(1, 3, 3));
+			assert_ok!(propose_set_balance_and_note(1, 2, 2));
+			assert_ok!(propose_set_balance_and_note(1, 4, 4));
+			assert_ok!(propose_set_balance_and_note(1, 3, 3));
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 
 			System::set_block_number(1);
@@ -869,7 +1841,7 @@ mod tests {
 	fn simple_passing_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 
 			assert_eq!(Democracy::voters_for(r), vec![1]);
@@ -886,7 +1858,7 @@ mod tests {
 	fn cancel_referendum_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 			assert_ok!(Democracy::cancel_referendum(r.into()));
 
@@ -900,7 +1872,7 @@ mod tests {
 	fn simple_failing_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, NAY));
 
 			assert_eq!(Democracy::voters_for(r), vec![1]);
@@ -917,7 +1889,7 @@ mod tests {
 	fn controversial_voting_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 			assert_ok!(Democracy::vote(Origin::signed(2), r, NAY));
 			assert_ok!(Democracy::vote(Origin::signed(3), r, NAY));
@@ -937,7 +1909,7 @@ mod tests {
 	fn delayed_enactment_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 1).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 1).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, AYE));
 			assert_ok!(Democracy::vote(Origin::signed(2), r, AYE));
 			assert_ok!(Democracy::vote(Origin::signed(3), r, AYE));
@@ -961,7 +1933,7 @@ mod tests {
 	fn lock_voting_should_work() {
 		with_externalities(&mut new_test_ext_with_public_delay(1), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, Vote::new(false, 6)));
 			assert_ok!(Democracy::vote(Origin::signed(2), r, Vote::new(true, 5)));
 			assert_ok!(Democracy::vote(Origin::signed(3), r, Vote::new(true, 4)));
@@ -973,12 +1945,12 @@ mod tests {
 
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 
-			assert_eq!(Balances::locks(1), vec![]);
+			assert_eq!(Balances::locks(1), vec![BalanceLock { id: DEMOCRACY_ID, amount: 10, until: 1, reasons: WithdrawReason::Transfer.into() }]);
 			assert_eq!(Balances::locks(2), vec![BalanceLock { id: DEMOCRACY_ID, amount: u64::max_value(), until: 6, reasons: WithdrawReason::Transfer.into() }]);
 			assert_eq!(Balances::locks(3), vec![BalanceLock { id: DEMOCRACY_ID, amount: u64::max_value(), until: 5, reasons: WithdrawReason::Transfer.into() }]);
 			assert_eq!(Balances::locks(4), vec![BalanceLock { id: DEMOCRACY_ID, amount: u64::max_value(), until: 4, reasons: WithdrawReason::Transfer.into() }]);
 			assert_eq!(Balances::locks(5), vec![BalanceLock { id: DEMOCRACY_ID, amount: u64::max_value(), until: 3, reasons: WithdrawReason::Transfer.into() }]);
-			assert_eq!(Balances::locks(6), vec![]);
+			assert_eq!(Balances::locks(6), vec![BalanceLock { id: DEMOCRACY_ID, amount: 60, until: 1, reasons: WithdrawReason::Transfer.into() }]);
 
 			System::set_block_number(2);
 			assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
@@ -991,12 +1963,12 @@ mod tests {
 	fn lock_voting_should_work_with_delegation() {
 		with_externalities(&mut new_test_ext_with_public_delay(1), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(1), r, Vote::new(false, 6)));
 			assert_ok!(Democracy::vote(Origin::signed(2), r, Vote::new(true, 5)));
 			assert_ok!(Democracy::vote(Origin::signed(3), r, Vote::new(true, 4)));
 			assert_ok!(Democracy::vote(Origin::signed(4), r, Vote::new(true, 3)));
-			assert_ok!(Democracy::delegate(Origin::signed(5), 2, 2));
+			assert_ok!(Democracy::delegate(Origin::signed(5), 2, 2, 50));
 			assert_ok!(Democracy::vote(Origin::signed(6), r, Vote::new(false, 1)));
 
 			assert_eq!(Democracy::tally(r), (440, 120, 210));
@@ -1010,11 +1982,37 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn delegation_should_lock_and_update_tally() {
+		with_externalities(&mut new_test_ext_with_public_delay(1), || {
+			System::set_block_number(1);
+			let r = Democracy::inject_referendum(2, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			assert_ok!(Democracy::vote(Origin::signed(1), r, Vote::new(true, 2)));
+			assert_eq!(Democracy::tally(r), (20, 0, 10));
+
+			// delegating to a voter updates the tally immediately.
+			assert_noop!(Democracy::delegate(Origin::signed(2), 1, 7, 20), "delegation has too great a strength");
+			assert_noop!(Democracy::delegate(Origin::signed(2), 1, 3, 21), "delegator's balance too low");
+			assert_ok!(Democracy::delegate(Origin::signed(2), 1, 3, 20));
+			assert_eq!(Democracy::tally(r), (80, 0, 30));
+			assert_eq!(Balances::locks(2), vec![BalanceLock { id: DELEGATION_ID, amount: 20, until: u64::max_value(), reasons: WithdrawReason::Transfer.into() }]);
+
+			// changing the vote moves the delegated votes with it.
+			assert_ok!(Democracy::vote(Origin::signed(1), r, Vote::new(false, 1)));
+			assert_eq!(Democracy::tally(r), (0, 70, 30));
+
+			// undelegating removes them, and the lock lasts for the delegation's lock periods.
+			assert_ok!(Democracy::undelegate(Origin::signed(2)));
+			assert_eq!(Democracy::tally(r), (0, 10, 10));
+			assert_eq!(Balances::locks(2), vec![BalanceLock { id: DELEGATION_ID, amount: 20, until: 4, reasons: WithdrawReason::Transfer.into() }]);
+		});
+	}
+
 	#[test]
 	fn controversial_low_turnout_voting_should_work() {
 		with_externalities(&mut new_test_ext(), || {
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(5), r, NAY));
 			assert_ok!(Democracy::vote(Origin::signed(6), r, AYE));
 
@@ -1033,7 +2031,7 @@ mod tests {
 			assert_eq!(Balances::total_issuance(), 210);
 
 			System::set_block_number(1);
-			let r = Democracy::inject_referendum(1, set_balance_proposal(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
+			let r = Democracy::inject_referendum(1, set_balance_proposal_hash_and_note(2), VoteThreshold::SuperMajorityApprove, 0).unwrap();
 			assert_ok!(Democracy::vote(Origin::signed(4), r, AYE));
 			assert_ok!(Democracy::vote(Origin::signed(5), r, NAY));
 			assert_ok!(Democracy::vote(Origin::signed(6), r, AYE));
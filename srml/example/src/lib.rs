@@ -283,6 +283,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -23,7 +23,8 @@ use rstd::marker::PhantomData;
 use rstd::result;
 use primitives::traits::{
 	self, Header, Zero, One, Checkable, Applyable, CheckEqual, OnFinalise,
-	OnInitialise, Hash, As, Digest, NumberFor, Block as BlockT, OffchainWorker
+	OnInitialise, Hash, As, Digest, NumberFor, Block as BlockT, OffchainWorker,
+	ValidateUnsigned, OnRuntimeUpgrade,
 };
 use srml_support::{Dispatchable, traits::MakePayment};
 use parity_codec::{Codec, Encode};
@@ -56,8 +57,8 @@ pub trait ExecuteBlock<Block: BlockT> {
 	fn execute_extrinsics_without_checks(block_number: NumberFor<Block>, extrinsics: Vec<Block::Extrinsic>);
 }
 
-pub struct Executive<System, Block, Context, Payment, AllModules>(
-	PhantomData<(System, Block, Context, Payment, AllModules)>
+pub struct Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>(
+	PhantomData<(System, Block, Context, Payment, UnsignedValidator, AllModules)>
 );
 
 impl<
@@ -65,19 +66,22 @@ impl<
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
 	Payment: MakePayment<System::AccountId>,
-	AllModules: OnInitialise<System::BlockNumber> + OnFinalise<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> ExecuteBlock<Block> for Executive<System, Block, Context, Payment, AllModules> where
+	UnsignedValidator,
+	AllModules: OnInitialise<System::BlockNumber> + OnFinalise<System::BlockNumber> + OffchainWorker<System::BlockNumber>
+		+ OnRuntimeUpgrade,
+> ExecuteBlock<Block> for Executive<System, Block, Context, Payment, UnsignedValidator, AllModules> where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId>,
 	<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call: Dispatchable,
-	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>
+	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>,
+	UnsignedValidator: ValidateUnsigned<Call=<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call>,
 {
 	fn execute_block(block: Block) {
-		Executive::<System, Block, Context, Payment, AllModules>::execute_block(block);
+		Executive::<System, Block, Context, Payment, UnsignedValidator, AllModules>::execute_block(block);
 	}
 
 	fn execute_extrinsics_without_checks(block_number: NumberFor<Block>, extrinsics: Vec<Block::Extrinsic>) {
-		Executive::<System, Block, Context, Payment, AllModules>::execute_extrinsics_without_checks(block_number, extrinsics);
+		Executive::<System, Block, Context, Payment, UnsignedValidator, AllModules>::execute_extrinsics_without_checks(block_number, extrinsics);
 	}
 }
 
@@ -86,12 +90,15 @@ impl<
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
 	Payment: MakePayment<System::AccountId>,
-	AllModules: OnInitialise<System::BlockNumber> + OnFinalise<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> Executive<System, Block, Context, Payment, AllModules> where
+	UnsignedValidator,
+	AllModules: OnInitialise<System::BlockNumber> + OnFinalise<System::BlockNumber> + OffchainWorker<System::BlockNumber>
+		+ OnRuntimeUpgrade,
+> Executive<System, Block, Context, Payment, UnsignedValidator, AllModules> where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId>,
 	<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call: Dispatchable,
-	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>
+	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>,
+	UnsignedValidator: ValidateUnsigned<Call=<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call>,
 {
 	/// Start the execution of a particular block.
 	pub fn initialise_block(header: &System::Header) {
@@ -100,6 +107,9 @@ impl<
 
 	fn initialise_block_impl(block_number: &System::BlockNumber, parent_hash: &System::Hash, extrinsics_root: &System::Hash) {
 		<system::Module<System>>::initialise(block_number, parent_hash, extrinsics_root);
+		if <system::Module<System>>::note_runtime_version() {
+			<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+		}
 		<AllModules as OnInitialise<System::BlockNumber>>::on_initialise(*block_number);
 	}
 
@@ -233,9 +243,9 @@ impl<
 		let r = f.dispatch(s.into());
 		<system::Module<System>>::note_applied_extrinsic(&r, encoded_len as u32);
 
-		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e {
-			primitives::BLOCK_FULL => Err(internal::ApplyError::FullBlock),
-			e => Ok(internal::ApplyOutcome::Fail(e))
+		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e.message {
+			Some(primitives::BLOCK_FULL) => Err(internal::ApplyError::FullBlock),
+			_ => Ok(internal::ApplyOutcome::Fail(e.into()))
 		})
 	}
 
@@ -268,7 +278,6 @@ impl<
 	pub fn validate_transaction(uxt: Block::Extrinsic) -> TransactionValidity {
 		// Note errors > 0 are from ApplyError
 		const UNKNOWN_ERROR: i8 = -127;
-		const MISSING_SENDER: i8 = -20;
 		const INVALID_INDEX: i8 = -10;
 
 		let encoded_len = uxt.encode().len();
@@ -311,12 +320,12 @@ impl<
 				provides: vec![(sender, *index).encode()],
 				longevity: TransactionLongevity::max_value(),
 			}
+		} else if xt.sender().is_none() {
+			// unsigned extrinsics are validated by the runtime modules they call into.
+			let (call, _) = xt.deconstruct();
+			UnsignedValidator::validate_unsigned(&call)
 		} else {
-			return TransactionValidity::Invalid(if xt.sender().is_none() {
-				MISSING_SENDER
-			} else {
-				INVALID_INDEX
-			})
+			TransactionValidity::Invalid(INVALID_INDEX)
 		}
 	}
 
@@ -335,9 +344,10 @@ mod tests {
 	use primitives::BuildStorage;
 	use primitives::traits::{Header as HeaderT, BlakeTwo256, IdentityLookup};
 	use primitives::testing::{Digest, DigestItem, Header, Block};
-	use srml_support::{traits::Currency, impl_outer_origin, impl_outer_event};
+	use srml_support::{traits::{Currency, Get}, impl_outer_origin, impl_outer_event};
 	use system;
 	use hex_literal::{hex, hex_impl};
+	use std::cell::Cell;
 
 	impl_outer_origin! {
 		pub enum Origin for Runtime {
@@ -350,6 +360,29 @@ mod tests {
 		}
 	}
 
+	thread_local! {
+		static SPEC_VERSION: Cell<u32> = Cell::new(0);
+		static RUNTIME_UPGRADES: Cell<u32> = Cell::new(0);
+	}
+
+	pub struct SpecVersion;
+	impl Get<u32> for SpecVersion {
+		fn get() -> u32 {
+			SPEC_VERSION.with(|v| v.get())
+		}
+	}
+
+	/// Modules of the runtime, counting the runtime upgrades.
+	pub struct CountUpgrades;
+	impl OnInitialise<u64> for CountUpgrades {}
+	impl OnFinalise<u64> for CountUpgrades {}
+	impl OffchainWorker<u64> for CountUpgrades {}
+	impl OnRuntimeUpgrade for CountUpgrades {
+		fn on_runtime_upgrade() {
+			RUNTIME_UPGRADES.with(|u| u.set(u.get() + 1));
+		}
+	}
+
 	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Runtime;
@@ -365,6 +398,7 @@ mod tests {
 		type Header = Header;
 		type Event = MetaEvent;
 		type Log = DigestItem;
+		type SpecVersion = SpecVersion;
 	}
 	impl balances::Trait for Runtime {
 		type Balance = u64;
@@ -376,8 +410,24 @@ mod tests {
 		type TransferPayment = ();
 	}
 
+	impl ValidateUnsigned for Runtime {
+		type Call = Call<Runtime>;
+
+		fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::set_balance(_, _, _) => TransactionValidity::Valid {
+					priority: 0,
+					requires: vec![],
+					provides: vec![],
+					longevity: TransactionLongevity::max_value(),
+				},
+				_ => TransactionValidity::Invalid(0),
+			}
+		}
+	}
+
 	type TestXt = primitives::testing::TestXt<Call<Runtime>>;
-	type Executive = super::Executive<Runtime, Block<TestXt>, system::ChainContext<Runtime>, balances::Module<Runtime>, ()>;
+	type Executive = super::Executive<Runtime, Block<TestXt>, system::ChainContext<Runtime>, balances::Module<Runtime>, Runtime, ()>;
 
 	#[test]
 	fn balance_transfer_dispatch_works() {
@@ -499,4 +549,46 @@ mod tests {
 		run_test(false);
 		run_test(true);
 	}
+
+	#[test]
+	fn unsigned_transactions_are_validated_by_unsigned_validator() {
+		let valid = primitives::testing::TestXt(None, 0, Call::set_balance(33, 69, 0));
+		let invalid = primitives::testing::TestXt(None, 0, Call::transfer(33, 69));
+
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(Executive::validate_transaction(valid), TransactionValidity::Valid {
+				priority: 0,
+				requires: vec![],
+				provides: vec![],
+				longevity: TransactionLongevity::max_value(),
+			});
+			assert_eq!(Executive::validate_transaction(invalid), TransactionValidity::Invalid(0));
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_hook_runs_once_per_spec_version() {
+		type Executive = super::Executive<
+			Runtime, Block<TestXt>, system::ChainContext<Runtime>, balances::Module<Runtime>, Runtime, CountUpgrades
+		>;
+		let upgrades = || RUNTIME_UPGRADES.with(|u| u.get());
+		let header = |n| Header::new(n, H256::default(), H256::default(), [69u8; 32].into(), Digest::default());
+
+		with_externalities(&mut new_test_ext(), || {
+			Executive::initialise_block(&header(1));
+			assert_eq!(upgrades(), 0);
+
+			SPEC_VERSION.with(|v| v.set(1));
+			Executive::initialise_block(&header(2));
+			assert_eq!(upgrades(), 1);
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), 1);
+
+			Executive::initialise_block(&header(3));
+			assert_eq!(upgrades(), 1);
+
+			SPEC_VERSION.with(|v| v.set(2));
+			Executive::initialise_block(&header(4));
+			assert_eq!(upgrades(), 2);
+		});
+	}
 }
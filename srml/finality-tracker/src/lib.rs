@@ -296,6 +296,7 @@ mod tests {
 				type Header = Header;
 				type Event = ();
 				type Log = DigestItem;
+				type SpecVersion = ();
 			}
 
 			type System = system::Module<Test>;
@@ -36,7 +36,7 @@ use rstd::prelude::*;
 use parity_codec as codec;
 use codec::{Encode, Decode};
 use fg_primitives::ScheduledChange;
-use srml_support::{Parameter, decl_event, decl_storage, decl_module};
+use srml_support::{Parameter, decl_event, decl_storage, decl_module, TypeInfo};
 use srml_support::dispatch::Result;
 use srml_support::storage::StorageValue;
 use srml_support::storage::unhashed::StorageVec;
@@ -149,7 +149,7 @@ pub struct OldStoredPendingChange<N, SessionKey> {
 }
 
 /// A stored pending change.
-#[derive(Encode)]
+#[derive(Encode, TypeInfo)]
 pub struct StoredPendingChange<N, SessionKey> {
 	/// The block number this was scheduled at.
 	pub scheduled_at: N,
@@ -19,6 +19,7 @@
 #[cfg(feature = "std")]
 use std::fmt;
 use crate::{Member, Decode, Encode, As, Input, Output};
+use srml_support::registry::{TypeInfo, Registry, TypeMetadata, VariantMetadata, FieldMetadata};
 
 /// An indices-aware address, which can be either a direct `AccountId` or
 /// an index.
@@ -100,6 +101,22 @@ impl<AccountId, AccountIndex> Encode for Address<AccountId, AccountIndex> where
 	}
 }
 
+/// Described by the prefix byte of each encoding. Indices lower than `0xf0` are encoded as that
+/// single byte and can't be described by a variant.
+impl<AccountId, AccountIndex> TypeInfo for Address<AccountId, AccountIndex> where
+	AccountId: Member + TypeInfo,
+	AccountIndex: Member + TypeInfo,
+{
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::variant("srml_indices::address::Address", &[
+			VariantMetadata::new("Index16", 0xfc, &[FieldMetadata::unnamed(registry.register::<u16>())]),
+			VariantMetadata::new("Index32", 0xfd, &[FieldMetadata::unnamed(registry.register::<u32>())]),
+			VariantMetadata::new("Index", 0xfe, &[FieldMetadata::unnamed(registry.register::<AccountIndex>())]),
+			VariantMetadata::new("Id", 0xff, &[FieldMetadata::unnamed(registry.register::<AccountId>())]),
+		])
+	}
+}
+
 impl<AccountId, AccountIndex> Default for Address<AccountId, AccountIndex> where
 	AccountId: Member + Default,
 	AccountIndex: Member,
@@ -0,0 +1,416 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Membership: Maintains a set of account IDs, e.g. the members of a collective.
+//!
+//! Members are added, removed, swapped or reset by configurable origins, and every change is
+//! pushed to `MembershipChanged`, along with the prime member. The prime member can only be a
+//! member; it is cleared when it leaves the set.
+//!
+//! The initial members are also pushed to `MembershipChanged` at genesis, so the module receiving
+//! them must have its genesis built first.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use srml_support::{
+	StorageValue, decl_module, decl_storage, decl_event, ensure,
+	traits::ChangeMembers,
+};
+use primitives::traits::{EnsureOrigin, StaticLookup};
+#[cfg(feature = "std")]
+use runtime_io::with_storage;
+use system::ensure_signed;
+
+pub trait Trait<I=DefaultInstance>: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self, I>> + Into<<Self as system::Trait>::Event>;
+
+	/// Required origin for adding a member (though can always be Root).
+	type AddOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Required origin for removing a member (though can always be Root).
+	type RemoveOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Required origin for adding and removing a member in a single action.
+	type SwapOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Required origin for resetting membership.
+	type ResetOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Required origin for setting or clearing the prime member.
+	type PrimeOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The receiver of the signal for when the membership has been changed.
+	type MembershipChanged: ChangeMembers<Self::AccountId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait<I>, I: Instance=DefaultInstance> as Membership {
+		/// The current membership, stored as an ordered Vec.
+		pub Members get(members): Vec<T::AccountId>;
+		/// The current prime member, if one exists.
+		pub Prime get(prime): Option<T::AccountId>;
+	}
+	add_extra_genesis {
+		config(members): Vec<T::AccountId>;
+		build(|storage: &mut primitives::StorageOverlay, _: &mut primitives::ChildrenStorageOverlay, config: &GenesisConfig<T, I>| {
+			with_storage(storage, || {
+				let mut members = config.members.clone();
+				members.sort();
+				T::MembershipChanged::set_members_sorted(&members[..], &[]);
+				<Members<T, I>>::put(members);
+			});
+		});
+	}
+}
+
+decl_event!(
+	pub enum Event<T, I: Instance=DefaultInstance> where
+		<T as system::Trait>::AccountId
+	{
+		/// The given member was added; see the transaction for who.
+		MemberAdded(AccountId),
+		/// The given member was removed; see the transaction for who.
+		MemberRemoved(AccountId),
+		/// Two members were swapped; the first left and the second joined.
+		MembersSwapped(AccountId, AccountId),
+		/// The membership was reset; see the transaction for who the new set is.
+		MembersReset,
+		/// One of the members' keys changed, from the first account to the second.
+		KeyChanged(AccountId, AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait<I>, I: Instance=DefaultInstance> for enum Call where origin: T::Origin {
+		fn deposit_event<T, I>() = default;
+
+		/// Add a member `who` to the set.
+		fn add_member(origin, who: <T::Lookup as StaticLookup>::Source) {
+			T::AddOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let mut members = Self::members();
+			let location = members.binary_search(&who).err().ok_or("already a member")?;
+			members.insert(location, who.clone());
+			<Members<T, I>>::put(&members);
+
+			T::MembershipChanged::change_members_sorted(&[who.clone()], &[], &members[..]);
+			Self::rejig_prime(&members);
+
+			Self::deposit_event(RawEvent::MemberAdded(who));
+		}
+
+		/// Remove a member `who` from the set.
+		fn remove_member(origin, who: <T::Lookup as StaticLookup>::Source) {
+			T::RemoveOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let mut members = Self::members();
+			let location = members.binary_search(&who).ok().ok_or("not a member")?;
+			members.remove(location);
+			<Members<T, I>>::put(&members);
+
+			T::MembershipChanged::change_members_sorted(&[], &[who.clone()], &members[..]);
+			Self::rejig_prime(&members);
+
+			Self::deposit_event(RawEvent::MemberRemoved(who));
+		}
+
+		/// Swap out one member `remove` for another `add`. The prime member is kept unless it is
+		/// the one swapped out.
+		fn swap_member(
+			origin,
+			remove: <T::Lookup as StaticLookup>::Source,
+			add: <T::Lookup as StaticLookup>::Source
+		) {
+			T::SwapOrigin::ensure_origin(origin)?;
+			let remove = T::Lookup::lookup(remove)?;
+			let add = T::Lookup::lookup(add)?;
+
+			if remove == add { return Ok(()) }
+
+			let mut members = Self::members();
+			let location = members.binary_search(&remove).ok().ok_or("not a member")?;
+			ensure!(members.binary_search(&add).is_err(), "already a member");
+			members.remove(location);
+			let location = members.binary_search(&add).err().expect("checked above; qed");
+			members.insert(location, add.clone());
+			<Members<T, I>>::put(&members);
+
+			T::MembershipChanged::change_members_sorted(&[add.clone()], &[remove.clone()], &members[..]);
+			Self::rejig_prime(&members);
+
+			Self::deposit_event(RawEvent::MembersSwapped(remove, add));
+		}
+
+		/// Change the membership to a new set, disregarding the existing membership. The prime
+		/// member is kept if it remains a member.
+		fn reset_members(origin, members: Vec<T::AccountId>) {
+			T::ResetOrigin::ensure_origin(origin)?;
+
+			let mut members = members;
+			members.sort();
+			members.dedup();
+			T::MembershipChanged::set_members_sorted(&members[..], &Self::members()[..]);
+			Self::rejig_prime(&members);
+			<Members<T, I>>::put(members);
+
+			Self::deposit_event(RawEvent::MembersReset);
+		}
+
+		/// Swap out the sending member for some other key `new`, keeping their position as prime
+		/// member if they held it.
+		fn change_key(origin, new: <T::Lookup as StaticLookup>::Source) {
+			let remove = ensure_signed(origin)?;
+			let new = T::Lookup::lookup(new)?;
+
+			if remove == new { return Ok(()) }
+
+			let mut members = Self::members();
+			let location = members.binary_search(&remove).ok().ok_or("not a member")?;
+			ensure!(members.binary_search(&new).is_err(), "already a member");
+			members.remove(location);
+			let location = members.binary_search(&new).err().expect("checked above; qed");
+			members.insert(location, new.clone());
+			<Members<T, I>>::put(&members);
+
+			T::MembershipChanged::change_members_sorted(&[new.clone()], &[remove.clone()], &members[..]);
+			if Self::prime() == Some(remove.clone()) {
+				<Prime<T, I>>::put(&new);
+			}
+			Self::rejig_prime(&members);
+
+			Self::deposit_event(RawEvent::KeyChanged(remove, new));
+		}
+
+		/// Set the prime member. Must be a current member.
+		fn set_prime(origin, who: <T::Lookup as StaticLookup>::Source) {
+			T::PrimeOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			ensure!(Self::members().binary_search(&who).is_ok(), "not a member");
+			<Prime<T, I>>::put(&who);
+			T::MembershipChanged::set_prime(Some(who));
+		}
+
+		/// Remove the prime member if it exists.
+		fn clear_prime(origin) {
+			T::PrimeOrigin::ensure_origin(origin)?;
+
+			<Prime<T, I>>::kill();
+			T::MembershipChanged::set_prime(None);
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// Push the prime member to `MembershipChanged` again after a change of membership, or clear
+	/// it if it is no longer one of `members`.
+	fn rejig_prime(members: &[T::AccountId]) {
+		if let Some(prime) = Self::prime() {
+			match members.binary_search(&prime) {
+				Ok(_) => T::MembershipChanged::set_prime(Some(prime)),
+				Err(_) => {
+					<Prime<T, I>>::kill();
+					T::MembershipChanged::set_prime(None);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin};
+	use runtime_io::with_externalities;
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::BuildStorage;
+	use primitives::traits::{BlakeTwo256, IdentityLookup};
+	use primitives::testing::{Digest, DigestItem, Header};
+	use system::EnsureRoot;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type Log = DigestItem;
+		type SpecVersion = ();
+	}
+
+	thread_local! {
+		static MEMBERS: RefCell<Vec<u64>> = RefCell::new(vec![]);
+		static PRIME: RefCell<Option<u64>> = RefCell::new(None);
+	}
+
+	pub struct TestChangeMembers;
+	impl ChangeMembers<u64> for TestChangeMembers {
+		fn change_members_sorted(incoming: &[u64], outgoing: &[u64], new: &[u64]) {
+			let mut old_plus_incoming = MEMBERS.with(|m| m.borrow().to_vec());
+			old_plus_incoming.extend_from_slice(incoming);
+			old_plus_incoming.sort();
+			let mut new_plus_outgoing = new.to_vec();
+			new_plus_outgoing.extend_from_slice(outgoing);
+			new_plus_outgoing.sort();
+			assert_eq!(old_plus_incoming, new_plus_outgoing);
+
+			MEMBERS.with(|m| *m.borrow_mut() = new.to_vec());
+			PRIME.with(|p| *p.borrow_mut() = None);
+		}
+
+		fn set_prime(who: Option<u64>) {
+			PRIME.with(|p| *p.borrow_mut() = who);
+		}
+	}
+
+	impl Trait for Test {
+		type Event = ();
+		type AddOrigin = EnsureRoot<u64>;
+		type RemoveOrigin = EnsureRoot<u64>;
+		type SwapOrigin = EnsureRoot<u64>;
+		type ResetOrigin = EnsureRoot<u64>;
+		type PrimeOrigin = EnsureRoot<u64>;
+		type MembershipChanged = TestChangeMembers;
+	}
+
+	type Membership = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+		// We use default for brevity, but you can configure as desired if needed.
+		t.extend(GenesisConfig::<Test> {
+			members: vec![30, 10, 20],
+			_genesis_phantom_data: Default::default(),
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn changed_members() -> Vec<u64> {
+		MEMBERS.with(|m| m.borrow().clone())
+	}
+
+	fn changed_prime() -> Option<u64> {
+		PRIME.with(|p| *p.borrow())
+	}
+
+	#[test]
+	fn query_membership_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(Membership::members(), vec![10, 20, 30]);
+			assert_eq!(changed_members(), vec![10, 20, 30]);
+		});
+	}
+
+	#[test]
+	fn add_member_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Membership::add_member(Origin::signed(5), 15), "bad origin: expected to be a root origin");
+			assert_noop!(Membership::add_member(Origin::ROOT, 10), "already a member");
+			assert_ok!(Membership::add_member(Origin::ROOT, 15));
+			assert_eq!(Membership::members(), vec![10, 15, 20, 30]);
+			assert_eq!(changed_members(), Membership::members());
+		});
+	}
+
+	#[test]
+	fn remove_member_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Membership::remove_member(Origin::ROOT, 15), "not a member");
+			assert_ok!(Membership::set_prime(Origin::ROOT, 20));
+			assert_ok!(Membership::remove_member(Origin::ROOT, 20));
+			assert_eq!(Membership::members(), vec![10, 30]);
+			assert_eq!(changed_members(), Membership::members());
+			assert_eq!(Membership::prime(), None);
+			assert_eq!(changed_prime(), None);
+		});
+	}
+
+	#[test]
+	fn swap_member_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Membership::swap_member(Origin::ROOT, 5, 25), "not a member");
+			assert_noop!(Membership::swap_member(Origin::ROOT, 10, 30), "already a member");
+			assert_ok!(Membership::set_prime(Origin::ROOT, 30));
+			assert_ok!(Membership::swap_member(Origin::ROOT, 20, 20));
+			assert_eq!(Membership::members(), vec![10, 20, 30]);
+			assert_ok!(Membership::swap_member(Origin::ROOT, 10, 25));
+			assert_eq!(Membership::members(), vec![20, 25, 30]);
+			assert_eq!(changed_members(), Membership::members());
+			// the prime member is kept.
+			assert_eq!(changed_prime(), Some(30));
+		});
+	}
+
+	#[test]
+	fn change_key_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Membership::set_prime(Origin::ROOT, 10));
+			assert_noop!(Membership::change_key(Origin::signed(3), 25), "not a member");
+			assert_noop!(Membership::change_key(Origin::signed(10), 20), "already a member");
+			assert_ok!(Membership::change_key(Origin::signed(10), 40));
+			assert_eq!(Membership::members(), vec![20, 30, 40]);
+			assert_eq!(changed_members(), Membership::members());
+			assert_eq!(Membership::prime(), Some(40));
+			assert_eq!(changed_prime(), Some(40));
+		});
+	}
+
+	#[test]
+	fn reset_members_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Membership::set_prime(Origin::ROOT, 20));
+			assert_ok!(Membership::reset_members(Origin::ROOT, vec![20, 40, 30]));
+			assert_eq!(Membership::members(), vec![20, 30, 40]);
+			assert_eq!(changed_members(), Membership::members());
+			assert_eq!(changed_prime(), Some(20));
+
+			assert_ok!(Membership::reset_members(Origin::ROOT, vec![10, 40, 30]));
+			assert_eq!(Membership::members(), vec![10, 30, 40]);
+			assert_eq!(changed_members(), Membership::members());
+			assert_eq!(Membership::prime(), None);
+			assert_eq!(changed_prime(), None);
+		});
+	}
+
+	#[test]
+	fn prime_must_be_a_member() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Membership::set_prime(Origin::ROOT, 4), "not a member");
+			assert_ok!(Membership::set_prime(Origin::ROOT, 20));
+			assert_eq!(changed_prime(), Some(20));
+			assert_ok!(Membership::clear_prime(Origin::ROOT));
+			assert_eq!(Membership::prime(), None);
+			assert_eq!(changed_prime(), None);
+		});
+	}
+}
@@ -253,6 +253,159 @@ impl std::fmt::Debug for DefaultByteGetter {
 	}
 }
 
+/// All the metadata about a module constant.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct ModuleConstantMetadata {
+	pub name: DecodeDifferentStr,
+	pub ty: DecodeDifferentStr,
+	pub value: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// All the metadata about a module error.
+///
+/// The index of an error is its position in the list of errors of its module, starting at 1;
+/// index 0 is reserved for untyped errors.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct ErrorMetadata {
+	pub name: DecodeDifferentStr,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// Identifier of a type in the type registry of the metadata: the index of its description in
+/// `RuntimeMetadataV5::types`.
+pub type TypeId = u32;
+
+/// The structural description of a type, as it is encoded.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct TypeMetadata {
+	/// The path of the type, e.g. `srml_balances::VestingSchedule`; empty for types which are
+	/// fully described by their definition, like primitives, sequences and tuples.
+	pub path: DecodeDifferentStr,
+	pub def: TypeDef,
+}
+
+impl TypeMetadata {
+	/// A struct with the given fields.
+	pub fn composite(path: &'static str, fields: &[FieldMetadata]) -> Self {
+		TypeMetadata { path: DecodeDifferent::Encode(path), def: TypeDef::Composite(fields.to_vec()) }
+	}
+
+	/// An enum with the given variants.
+	pub fn variant(path: &'static str, variants: &[VariantMetadata]) -> Self {
+		TypeMetadata { path: DecodeDifferent::Encode(path), def: TypeDef::Variant(variants.to_vec()) }
+	}
+
+	/// A type with the given definition.
+	pub fn new(path: &'static str, def: TypeDef) -> Self {
+		TypeMetadata { path: DecodeDifferent::Encode(path), def }
+	}
+}
+
+/// The definition of a type.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub enum TypeDef {
+	/// A struct, encoded as its fields in order.
+	Composite(Vec<FieldMetadata>),
+	/// An enum, encoded as the index of the variant followed by its fields.
+	Variant(Vec<VariantMetadata>),
+	/// A sequence of values of the given type, prefixed with its compact encoded length.
+	Sequence(TypeId),
+	/// A fixed size array, encoded without length.
+	Array {
+		len: u32,
+		ty: TypeId,
+	},
+	/// A tuple, encoded as its elements in order.
+	Tuple(Vec<TypeId>),
+	/// A primitive type.
+	Primitive(PrimitiveType),
+	/// The compact encoding of the given unsigned integer type.
+	Compact(TypeId),
+}
+
+/// A field of a struct or of an enum variant.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct FieldMetadata {
+	/// The name of the field, `None` for tuple-like structs and variants.
+	pub name: Option<DecodeDifferentStr>,
+	pub ty: TypeId,
+}
+
+impl FieldMetadata {
+	/// A named field.
+	pub fn named(name: &'static str, ty: TypeId) -> Self {
+		FieldMetadata { name: Some(DecodeDifferent::Encode(name)), ty }
+	}
+
+	/// An unnamed field.
+	pub fn unnamed(ty: TypeId) -> Self {
+		FieldMetadata { name: None, ty }
+	}
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct VariantMetadata {
+	pub name: DecodeDifferentStr,
+	/// The index of the variant in the encoding of the enum.
+	pub index: u8,
+	pub fields: Vec<FieldMetadata>,
+}
+
+impl VariantMetadata {
+	pub fn new(name: &'static str, index: u8, fields: &[FieldMetadata]) -> Self {
+		VariantMetadata { name: DecodeDifferent::Encode(name), index, fields: fields.to_vec() }
+	}
+}
+
+/// A primitive type.
+#[derive(Clone, Copy, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub enum PrimitiveType {
+	Bool,
+	Char,
+	Str,
+	U8,
+	U16,
+	U32,
+	U64,
+	U128,
+	I8,
+	I16,
+	I32,
+	I64,
+	I128,
+}
+
+/// The types of the calls, events and storage of a module.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct ModuleTypesMetadata {
+	/// The `Call` enum of the module.
+	pub call: Option<TypeId>,
+	/// The `Event` enum of the module.
+	pub event: Option<TypeId>,
+	/// The types of the storage entries, in the order of the storage functions of the module.
+	pub storage: Vec<StorageTypeMetadata>,
+}
+
+/// The types of a storage entry.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct StorageTypeMetadata {
+	pub name: DecodeDifferentStr,
+	/// The keys of the entry: none for a value, one for a map and two for a double map.
+	pub keys: Vec<TypeId>,
+	pub value: TypeId,
+}
+
 /// A storage function type.
 #[derive(Clone, PartialEq, Eq, Encode)]
 #[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
@@ -312,8 +465,12 @@ pub enum RuntimeMetadata {
 	V1(RuntimeMetadataDeprecated),
 	/// Version 2 for runtime metadata. No longer used.
 	V2(RuntimeMetadataDeprecated),
-	/// Version 3 for runtime metadata.
-	V3(RuntimeMetadataV3),
+	/// Version 3 for runtime metadata. No longer used.
+	V3(RuntimeMetadataDeprecated),
+	/// Version 4 for runtime metadata. No longer used.
+	V4(RuntimeMetadataDeprecated),
+	/// Version 5 for runtime metadata.
+	V5(RuntimeMetadataV5),
 }
 
 /// Enum that should fail.
@@ -336,8 +493,10 @@ impl Decode for RuntimeMetadataDeprecated {
 /// The metadata of a runtime.
 #[derive(Eq, Encode, PartialEq)]
 #[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
-pub struct RuntimeMetadataV3 {
-	pub modules: DecodeDifferentArray<ModuleMetadata>,
+pub struct RuntimeMetadataV5 {
+	/// The registry of the types used by the modules, indexed by `TypeId`.
+	pub types: Vec<TypeMetadata>,
+	pub modules: Vec<ModuleMetadata>,
 }
 
 /// All metadata about an runtime module.
@@ -349,9 +508,13 @@ pub struct ModuleMetadata {
 	pub storage: ODFnA<StorageFunctionMetadata>,
 	pub calls: ODFnA<FunctionMetadata>,
 	pub event: ODFnA<EventMetadata>,
+	pub constants: DFnA<ModuleConstantMetadata>,
+	pub errors: DFnA<ErrorMetadata>,
+	pub types: ModuleTypesMetadata,
 }
 
-type ODFnA<T> = Option<DecodeDifferent<FnEncode<&'static [T]>, Vec<T>>>;
+type ODFnA<T> = Option<DFnA<T>>;
+type DFnA<T> = DecodeDifferent<FnEncode<&'static [T]>, Vec<T>>;
 
 impl Into<primitives::OpaqueMetadata> for RuntimeMetadataPrefixed {
 	fn into(self) -> primitives::OpaqueMetadata {
@@ -0,0 +1,341 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Randomness beacon: on-chain randomness from a commit-reveal scheme among a set of participants,
+//! typically the session validators.
+//!
+//! Time is split into rounds of `commit_period + reveal_period` blocks. During the commit period
+//! each participant may submit the hash of its own account and a secret of its choosing. During
+//! the reveal period it submits the secret itself. At the end of the round all revealed secrets
+//! are mixed with the previous output into the new output.
+//!
+//! Unlike `system::random_seed`, block authors cannot grind the output directly. It can still be
+//! biased by participants withholding their reveals, which is reported with an `Unrevealed` event,
+//! and by the authors of the last blocks of the reveal period, who may censor the reveals of
+//! others once they have seen them. The output is known to nobody until the reveal period starts,
+//! so `random` returns it together with the last block of the commit period.
+//!
+//! Participants submit their commitments and reveals as ordinary signed transactions. Until the
+//! first round closes with an output, `random` falls back on `system::random_seed`, which is only
+//! unpredictable until the parent block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use primitives::traits::{Hash, Zero, One, Saturating};
+use srml_support::{
+	StorageValue, decl_module, decl_storage, decl_event, ensure,
+	traits::{Contains, Randomness},
+};
+use system::ensure_signed;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The accounts allowed to contribute to the beacon.
+	type Participants: Contains<Self::AccountId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as RandomnessBeacon {
+		/// The number of blocks at the start of each round during which commitments are accepted.
+		pub CommitPeriod get(commit_period) config(): T::BlockNumber;
+		/// The number of blocks at the end of each round during which secrets are revealed.
+		pub RevealPeriod get(reveal_period) config(): T::BlockNumber;
+
+		/// The commitments of this round that have not been revealed yet.
+		pub Commitments get(commitments): Vec<(T::AccountId, T::Hash)>;
+		/// The secrets revealed so far this round.
+		pub Secrets get(secrets): Vec<T::Hash>;
+
+		/// The latest output, with the block number up to which it was unpredictable.
+		pub Output get(output): (T::Hash, T::BlockNumber);
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::Hash
+	{
+		/// A participant committed to a secret.
+		Committed(AccountId),
+		/// A participant revealed its secret.
+		Revealed(AccountId),
+		/// A round closed with a new output, mixed from the given number of secrets.
+		NewOutput(Hash, u32),
+		/// These participants committed but did not reveal their secrets in time.
+		Unrevealed(Vec<AccountId>),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// Commit to a secret for this round. `commitment` must be the hash of the sender's
+		/// account and the secret, as a tuple.
+		fn commit(origin, commitment: T::Hash) {
+			let who = ensure_signed(origin)?;
+			ensure!(T::Participants::contains(&who), "not a participant");
+			ensure!(Self::in_commit_period(), "not in the commit period");
+
+			let mut commitments = Self::commitments();
+			ensure!(commitments.iter().all(|(c, _)| c != &who), "already committed");
+			commitments.push((who.clone(), commitment));
+			<Commitments<T>>::put(commitments);
+
+			Self::deposit_event(RawEvent::Committed(who));
+		}
+
+		/// Reveal the secret committed to earlier this round.
+		fn reveal(origin, secret: T::Hash) {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::in_commit_period(), "not in the reveal period");
+
+			let mut commitments = Self::commitments();
+			let index = commitments.iter().position(|(c, _)| c == &who).ok_or("no commitment")?;
+			ensure!(
+				commitments[index].1 == T::Hashing::hash_of(&(&who, &secret)),
+				"secret does not match commitment"
+			);
+			commitments.swap_remove(index);
+			<Commitments<T>>::put(commitments);
+			<Secrets<T>>::mutate(|secrets| secrets.push(secret));
+
+			Self::deposit_event(RawEvent::Revealed(who));
+		}
+
+		fn on_finalise(n: T::BlockNumber) {
+			let round_length = Self::commit_period() + Self::reveal_period();
+			if !round_length.is_zero() && ((n + One::one()) % round_length).is_zero() {
+				Self::close_round(n);
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether the current block is in the commit period of its round.
+	fn in_commit_period() -> bool {
+		let round_length = Self::commit_period() + Self::reveal_period();
+		!round_length.is_zero()
+			&& <system::Module<T>>::block_number() % round_length < Self::commit_period()
+	}
+
+	/// Mix the revealed secrets into the new output, at the last block `n` of a round.
+	fn close_round(n: T::BlockNumber) {
+		let unrevealed = <Commitments<T>>::take().into_iter().map(|(who, _)| who).collect::<Vec<_>>();
+		if !unrevealed.is_empty() {
+			Self::deposit_event(RawEvent::Unrevealed(unrevealed));
+		}
+
+		let mut secrets = <Secrets<T>>::take();
+		if secrets.is_empty() {
+			return
+		}
+		// the order of reveals is up to block authors; don't let it matter.
+		secrets.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+		let (previous, _) = Self::output();
+		let output = T::Hashing::hash_of(&(previous, &secrets));
+		<Output<T>>::put((output, n - Self::reveal_period()));
+
+		Self::deposit_event(RawEvent::NewOutput(output, secrets.len() as u32));
+	}
+}
+
+impl<T: Trait> Randomness<T::Hash, T::BlockNumber> for Module<T> {
+	fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+		if !<Output<T>>::exists() {
+			let seed = <system::Module<T>>::random_seed();
+			let parent = <system::Module<T>>::block_number().saturating_sub(One::one());
+			return (T::Hashing::hash_of(&(subject, seed)), parent);
+		}
+
+		let (output, unpredictable_until) = Self::output();
+		(T::Hashing::hash_of(&(subject, output)), unpredictable_until)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin};
+	use runtime_io::with_externalities;
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::BuildStorage;
+	use primitives::traits::{BlakeTwo256, IdentityLookup, OnFinalise};
+	use primitives::testing::{Digest, DigestItem, Header};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type Log = DigestItem;
+		type SpecVersion = ();
+	}
+
+	pub struct OneToThree;
+	impl Contains<u64> for OneToThree {
+		fn sorted_members() -> Vec<u64> {
+			vec![1, 2, 3]
+		}
+	}
+
+	impl Trait for Test {
+		type Event = ();
+		type Participants = OneToThree;
+	}
+
+	type System = system::Module<Test>;
+	type Beacon = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+		t.extend(GenesisConfig::<Test> {
+			commit_period: 3,
+			reveal_period: 2,
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn commitment(who: u64, secret: u64) -> H256 {
+		BlakeTwo256::hash_of(&(who, self::secret(secret)))
+	}
+
+	fn secret(secret: u64) -> H256 {
+		H256::from([secret as u8; 32])
+	}
+
+	fn finalise_until(n: u64) {
+		while System::block_number() < n {
+			Beacon::on_finalise(System::block_number());
+			System::set_block_number(System::block_number() + 1);
+		}
+	}
+
+	#[test]
+	fn commit_and_reveal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Beacon::commit(Origin::signed(1), commitment(1, 10)));
+			assert_ok!(Beacon::commit(Origin::signed(2), commitment(2, 20)));
+			assert_noop!(Beacon::commit(Origin::signed(4), commitment(4, 40)), "not a participant");
+			assert_noop!(Beacon::commit(Origin::signed(1), commitment(1, 11)), "already committed");
+			assert_noop!(Beacon::reveal(Origin::signed(1), secret(10)), "not in the reveal period");
+
+			finalise_until(3);
+			assert_noop!(Beacon::commit(Origin::signed(3), commitment(3, 30)), "not in the commit period");
+			assert_noop!(Beacon::reveal(Origin::signed(3), secret(30)), "no commitment");
+			assert_noop!(Beacon::reveal(Origin::signed(1), secret(20)), "secret does not match commitment");
+			assert_ok!(Beacon::reveal(Origin::signed(1), secret(10)));
+			assert_noop!(Beacon::reveal(Origin::signed(1), secret(10)), "no commitment");
+			assert_ok!(Beacon::reveal(Origin::signed(2), secret(20)));
+			assert_eq!(Beacon::commitments(), vec![]);
+			assert_eq!(Beacon::secrets(), vec![secret(10), secret(20)]);
+
+			assert_eq!(Beacon::output(), (H256::default(), 0));
+			finalise_until(5);
+			let expected = BlakeTwo256::hash_of(&(H256::default(), vec![secret(10), secret(20)]));
+			assert_eq!(Beacon::output(), (expected, 2));
+			assert_eq!(Beacon::secrets(), vec![]);
+		});
+	}
+
+	#[test]
+	fn reveal_order_should_not_matter() {
+		let run = |first: u64, second: u64| with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Beacon::commit(Origin::signed(1), commitment(1, 10)));
+			assert_ok!(Beacon::commit(Origin::signed(2), commitment(2, 20)));
+			finalise_until(3);
+			assert_ok!(Beacon::reveal(Origin::signed(first), secret(first * 10)));
+			assert_ok!(Beacon::reveal(Origin::signed(second), secret(second * 10)));
+			finalise_until(5);
+			Beacon::output()
+		});
+		assert_eq!(run(1, 2), run(2, 1));
+	}
+
+	#[test]
+	fn unrevealed_commitments_should_be_dropped() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Beacon::commit(Origin::signed(1), commitment(1, 10)));
+			assert_ok!(Beacon::commit(Origin::signed(2), commitment(2, 20)));
+			finalise_until(3);
+			assert_ok!(Beacon::reveal(Origin::signed(2), secret(20)));
+			finalise_until(5);
+			assert_eq!(Beacon::commitments(), vec![]);
+			let first = Beacon::output();
+			assert_eq!(first.1, 2);
+
+			// a round without reveals leaves the output untouched.
+			assert_ok!(Beacon::commit(Origin::signed(1), commitment(1, 10)));
+			finalise_until(10);
+			assert_eq!(Beacon::output(), first);
+		});
+	}
+
+	#[test]
+	fn random_should_depend_on_subject() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Beacon::commit(Origin::signed(3), commitment(3, 30)));
+			finalise_until(3);
+			assert_ok!(Beacon::reveal(Origin::signed(3), secret(30)));
+			finalise_until(5);
+
+			let (a, at) = Beacon::random(b"a");
+			let (b, _) = Beacon::random(b"b");
+			assert_eq!(at, 2);
+			assert!(a != b);
+			assert_eq!(Beacon::random_seed(), Beacon::random(&[][..]));
+		});
+	}
+
+	#[test]
+	fn random_should_fall_back_on_system_seed_before_first_output() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(4);
+			System::set_random_seed(H256::repeat_byte(7));
+			assert_eq!(Beacon::random(b"a"), (BlakeTwo256::hash_of(&(&b"a"[..], H256::repeat_byte(7))), 3));
+
+			System::set_random_seed(H256::repeat_byte(8));
+			assert_eq!(Beacon::random(b"a"), (BlakeTwo256::hash_of(&(&b"a"[..], H256::repeat_byte(8))), 3));
+
+			// a round without reveals does not produce an output.
+			finalise_until(5);
+			assert_eq!(Beacon::random(b"a").1, 4);
+		});
+	}
+}
@@ -0,0 +1,475 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recovery: social recovery of accounts whose keys have been lost.
+//!
+//! An account makes itself recoverable by registering a set of friends, the number of them that
+//! must vouch for a recovery and a delay period. This reserves a deposit that grows with the
+//! number of friends.
+//!
+//! Once the key is lost, a rescuer (typically a new account of the same owner) reserves a deposit
+//! and initiates a recovery of the lost account. The friends vouch for the rescuer, and once
+//! enough of them have done so and the delay period has passed since the recovery was initiated,
+//! the rescuer may claim the lost account. From then on it can dispatch any call as the lost
+//! account with `as_recovered`.
+//!
+//! While the recovery is active the lost account may close it, which moves the rescuer's deposit
+//! to the lost account. This is the defence against malicious recovery attempts, and is also how
+//! a successful rescuer gets its deposit back, by closing the recovery as the lost account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+use serde_derive::{Serialize, Deserialize};
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use primitives::traits::{As, StaticLookup};
+use srml_support::{
+	StorageMap, Parameter, Dispatchable, decl_module, decl_storage, decl_event, ensure,
+	TypeInfo, traits::{Currency, ReservableCurrency},
+};
+use system::{ensure_signed, RawOrigin};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type, dispatched on behalf of recovered accounts.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin>;
+
+	/// The currency in which deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+}
+
+/// The recovery configuration of an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct RecoveryConfig<AccountId, Balance, BlockNumber> {
+	/// The number of blocks after a recovery is initiated before it may be claimed.
+	delay_period: BlockNumber,
+	/// The deposit reserved for the configuration.
+	deposit: Balance,
+	/// The friends who may vouch for a recovery, sorted.
+	friends: Vec<AccountId>,
+	/// The number of friends who must vouch for a recovery.
+	threshold: u32,
+}
+
+/// An attempt to recover an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct ActiveRecovery<AccountId, Balance, BlockNumber> {
+	/// The block at which the recovery was initiated.
+	created: BlockNumber,
+	/// The deposit reserved by the rescuer.
+	deposit: Balance,
+	/// The friends who have vouched so far, sorted.
+	friends: Vec<AccountId>,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Recovery {
+		/// The deposit reserved for making an account recoverable, regardless of its friends.
+		pub ConfigDepositBase get(config_deposit_base) config(): BalanceOf<T>;
+		/// The additional deposit reserved for each friend of a recoverable account.
+		pub FriendDepositFactor get(friend_deposit_factor) config(): BalanceOf<T>;
+		/// The maximum number of friends of a recoverable account.
+		pub MaxFriends get(max_friends) config(): u32;
+		/// The deposit reserved by a rescuer for initiating a recovery.
+		pub RecoveryDeposit get(recovery_deposit) config(): BalanceOf<T>;
+
+		/// The recovery configuration of each recoverable account.
+		pub Recoverable get(recovery_config):
+			map T::AccountId => Option<RecoveryConfig<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+		/// The active recoveries, keyed by the lost account and the rescuer.
+		pub ActiveRecoveries get(active_recovery):
+			map (T::AccountId, T::AccountId) => Option<ActiveRecovery<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+		/// The number of active recoveries of each account.
+		pub ActiveRecoveryCount get(active_recovery_count): map T::AccountId => u32;
+		/// The account each rescuer may act as, once it has claimed a recovery.
+		pub Recovered get(recovered_account): map T::AccountId => Option<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId
+	{
+		/// An account was made recoverable.
+		RecoveryCreated(AccountId),
+		/// A recovery of the first account was initiated by the second.
+		RecoveryInitiated(AccountId, AccountId),
+		/// A recovery of the first account by the second was vouched for by the third.
+		RecoveryVouched(AccountId, AccountId, AccountId),
+		/// A recovery of the first account by the second was closed.
+		RecoveryClosed(AccountId, AccountId),
+		/// The first account was recovered by the second.
+		AccountRecovered(AccountId, AccountId),
+		/// An account is no longer recoverable.
+		RecoveryRemoved(AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// Dispatch `call` as `account`, which the sender has recovered.
+		fn as_recovered(origin, account: T::AccountId, call: Box<<T as Trait>::Call>) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::recovered_account(&who) == Some(account.clone()), "not allowed to act as this account");
+			call.dispatch(RawOrigin::Signed(account).into())?;
+		}
+
+		/// Allow `rescuer` to act as `lost` without going through a recovery.
+		fn set_recovered(lost: T::AccountId, rescuer: T::AccountId) {
+			<Recovered<T>>::insert(&rescuer, &lost);
+			Self::deposit_event(RawEvent::AccountRecovered(lost, rescuer));
+		}
+
+		/// Make the sender recoverable by `threshold` of `friends` vouching for a rescuer, with a
+		/// `delay_period` between the start of a recovery and its claim.
+		fn create_recovery(origin, friends: Vec<T::AccountId>, threshold: u32, delay_period: T::BlockNumber) {
+			let who = ensure_signed(origin)?;
+			ensure!(!<Recoverable<T>>::exists(&who), "already recoverable");
+			ensure!(Self::active_recovery_count(&who) == 0, "recoveries still active");
+			ensure!(threshold >= 1, "threshold must be at least one");
+			ensure!(!friends.is_empty(), "no friends given");
+			ensure!(friends.len() as u32 <= Self::max_friends(), "too many friends");
+			ensure!(threshold as usize <= friends.len(), "threshold exceeds number of friends");
+
+			let mut friends = friends;
+			friends.sort();
+			ensure!(friends.windows(2).all(|w| w[0] != w[1]), "duplicate friends");
+
+			let deposit = Self::config_deposit_base()
+				+ Self::friend_deposit_factor() * <BalanceOf<T> as As<u64>>::sa(friends.len() as u64);
+			T::Currency::reserve(&who, deposit)?;
+
+			<Recoverable<T>>::insert(&who, RecoveryConfig { delay_period, deposit, friends, threshold });
+			Self::deposit_event(RawEvent::RecoveryCreated(who));
+		}
+
+		/// Start recovering `account` to the sender, reserving the recovery deposit.
+		fn initiate_recovery(origin, account: <T::Lookup as StaticLookup>::Source) {
+			let who = ensure_signed(origin)?;
+			let account = T::Lookup::lookup(account)?;
+			ensure!(<Recoverable<T>>::exists(&account), "not recoverable");
+			ensure!(!<ActiveRecoveries<T>>::exists(&(account.clone(), who.clone())), "recovery already started");
+
+			let deposit = Self::recovery_deposit();
+			T::Currency::reserve(&who, deposit)?;
+
+			let recovery = ActiveRecovery {
+				created: <system::Module<T>>::block_number(),
+				deposit,
+				friends: vec![],
+			};
+			<ActiveRecoveries<T>>::insert(&(account.clone(), who.clone()), recovery);
+			<ActiveRecoveryCount<T>>::mutate(&account, |c| *c += 1);
+			Self::deposit_event(RawEvent::RecoveryInitiated(account, who));
+		}
+
+		/// Vouch, as a friend of `lost`, for its recovery by `rescuer`.
+		fn vouch_recovery(
+			origin,
+			lost: <T::Lookup as StaticLookup>::Source,
+			rescuer: <T::Lookup as StaticLookup>::Source
+		) {
+			let who = ensure_signed(origin)?;
+			let lost = T::Lookup::lookup(lost)?;
+			let rescuer = T::Lookup::lookup(rescuer)?;
+			let config = Self::recovery_config(&lost).ok_or("not recoverable")?;
+			let key = (lost.clone(), rescuer.clone());
+			let mut recovery = Self::active_recovery(&key).ok_or("recovery not started")?;
+
+			ensure!(config.friends.binary_search(&who).is_ok(), "not a friend");
+			let location = recovery.friends.binary_search(&who).err().ok_or("already vouched")?;
+			recovery.friends.insert(location, who.clone());
+			<ActiveRecoveries<T>>::insert(&key, recovery);
+
+			Self::deposit_event(RawEvent::RecoveryVouched(lost, rescuer, who));
+		}
+
+		/// Claim the recovery of `account` once enough friends have vouched for the sender and
+		/// the delay period has passed.
+		fn claim_recovery(origin, account: <T::Lookup as StaticLookup>::Source) {
+			let who = ensure_signed(origin)?;
+			let account = T::Lookup::lookup(account)?;
+			let config = Self::recovery_config(&account).ok_or("not recoverable")?;
+			let recovery = Self::active_recovery(&(account.clone(), who.clone())).ok_or("recovery not started")?;
+
+			let now = <system::Module<T>>::block_number();
+			ensure!(recovery.created + config.delay_period <= now, "delay period not passed");
+			// only vouches of current friends count, in case the configuration has changed.
+			let vouches = recovery.friends.iter().filter(|f| config.friends.binary_search(*f).is_ok()).count();
+			ensure!(vouches as u32 >= config.threshold, "not enough friends vouched");
+
+			<Recovered<T>>::insert(&who, &account);
+			Self::deposit_event(RawEvent::AccountRecovered(account, who));
+		}
+
+		/// Close an active recovery of the sender by `rescuer`, moving the rescuer's deposit to
+		/// the sender.
+		fn close_recovery(origin, rescuer: <T::Lookup as StaticLookup>::Source) {
+			let who = ensure_signed(origin)?;
+			let rescuer = T::Lookup::lookup(rescuer)?;
+			let recovery = <ActiveRecoveries<T>>::take(&(who.clone(), rescuer.clone()))
+				.ok_or("recovery not started")?;
+			<ActiveRecoveryCount<T>>::mutate(&who, |c| *c = c.saturating_sub(1));
+
+			let _ = T::Currency::repatriate_reserved(&rescuer, &who, recovery.deposit);
+			Self::deposit_event(RawEvent::RecoveryClosed(who, rescuer));
+		}
+
+		/// Make the sender no longer recoverable, returning its configuration deposit. All active
+		/// recoveries of the sender must have been closed first.
+		fn remove_recovery(origin) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::active_recovery_count(&who) == 0, "recoveries still active");
+			let config = <Recoverable<T>>::take(&who).ok_or("not recoverable")?;
+
+			T::Currency::unreserve(&who, config.deposit);
+			Self::deposit_event(RawEvent::RecoveryRemoved(who));
+		}
+
+		/// Give up the ability to act as `account`.
+		fn cancel_recovered(origin, account: <T::Lookup as StaticLookup>::Source) {
+			let who = ensure_signed(origin)?;
+			let account = T::Lookup::lookup(account)?;
+			ensure!(Self::recovered_account(&who) == Some(account), "not allowed to act as this account");
+
+			<Recovered<T>>::remove(&who);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch};
+	use runtime_io::with_externalities;
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::BuildStorage;
+	use primitives::traits::{BlakeTwo256, IdentityLookup};
+	use primitives::testing::{Digest, DigestItem, Header};
+
+	mod recovery {
+		pub use crate::*;
+	}
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			recovery::Recovery,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type Log = DigestItem;
+		type SpecVersion = ();
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnNewAccount = ();
+		type OnFreeBalanceZero = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type Currency = balances::Module<Test>;
+	}
+
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Recovery = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)],
+			transaction_base_fee: 0,
+			transaction_byte_fee: 0,
+			transfer_fee: 0,
+			creation_fee: 0,
+			existential_deposit: 0,
+			vesting: vec![],
+		}.build_storage().unwrap().0);
+		t.extend(GenesisConfig::<Test>{
+			config_deposit_base: 10,
+			friend_deposit_factor: 1,
+			max_friends: 3,
+			recovery_deposit: 10,
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn transfer(dest: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(balances::Call::transfer(dest, value)))
+	}
+
+	#[test]
+	fn create_recovery_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Recovery::create_recovery(Origin::signed(5), vec![], 1, 10), "no friends given");
+			assert_noop!(Recovery::create_recovery(Origin::signed(5), vec![2], 0, 10), "threshold must be at least one");
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![2], 2, 10),
+				"threshold exceeds number of friends"
+			);
+			assert_noop!(Recovery::create_recovery(Origin::signed(5), vec![1, 2, 3, 4], 2, 10), "too many friends");
+			assert_noop!(Recovery::create_recovery(Origin::signed(5), vec![2, 2], 2, 10), "duplicate friends");
+
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![4, 2, 3], 2, 10));
+			assert_eq!(Balances::reserved_balance(&5), 13);
+			assert_eq!(Recovery::recovery_config(&5), Some(RecoveryConfig {
+				delay_period: 10,
+				deposit: 13,
+				friends: vec![2, 3, 4],
+				threshold: 2,
+			}));
+			assert_noop!(Recovery::create_recovery(Origin::signed(5), vec![2], 1, 10), "already recoverable");
+
+			assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
+			assert_eq!(Balances::reserved_balance(&5), 0);
+			assert_eq!(Recovery::recovery_config(&5), None);
+		});
+	}
+
+	#[test]
+	fn recovery_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![2, 3, 4], 2, 10));
+			assert_noop!(Recovery::initiate_recovery(Origin::signed(1), 2), "not recoverable");
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			assert_noop!(Recovery::initiate_recovery(Origin::signed(1), 5), "recovery already started");
+			assert_eq!(Balances::reserved_balance(&1), 10);
+
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(1), 5, 1), "not a friend");
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 3), "recovery not started");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 1), "already vouched");
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "delay period not passed");
+
+			System::set_block_number(11);
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "not enough friends vouched");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 5, 1));
+			assert_noop!(
+				Recovery::as_recovered(Origin::signed(1), 5, transfer(1, 50)),
+				"not allowed to act as this account"
+			);
+			assert_ok!(Recovery::claim_recovery(Origin::signed(1), 5));
+			assert_eq!(Recovery::recovered_account(&1), Some(5));
+
+			// the rescuer closes the recovery as the lost account, which returns its deposit.
+			let close = Box::new(Call::Recovery(recovery::Call::close_recovery(1)));
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, close));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, transfer(1, 60)));
+			assert_eq!(Balances::free_balance(&1), 150);
+			assert_eq!(Balances::free_balance(&5), 37);
+
+			assert_ok!(Recovery::cancel_recovered(Origin::signed(1), 5));
+			assert_noop!(
+				Recovery::as_recovered(Origin::signed(1), 5, transfer(1, 10)),
+				"not allowed to act as this account"
+			);
+		});
+	}
+
+	#[test]
+	fn lost_account_can_close_malicious_recovery() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![2, 3, 4], 2, 10));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+
+			assert_noop!(Recovery::close_recovery(Origin::signed(5), 2), "recovery not started");
+			assert_ok!(Recovery::close_recovery(Origin::signed(5), 1));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Balances::free_balance(&1), 90);
+			assert_eq!(Balances::free_balance(&5), 97);
+			assert_eq!(Recovery::active_recovery(&(5, 1)), None);
+
+			System::set_block_number(11);
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery not started");
+		});
+	}
+
+	#[test]
+	fn set_recovered_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Recovery::set_recovered(5, 1));
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, transfer(1, 40)));
+			assert_eq!(Balances::free_balance(&1), 140);
+			assert_eq!(Balances::free_balance(&5), 60);
+		});
+	}
+
+	#[test]
+	fn recovery_config_cannot_change_while_recoveries_are_active() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![2, 3, 4], 2, 10));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+			assert_eq!(Recovery::active_recovery_count(&5), 1);
+
+			// rotating the friends is refused while the old vouches are still around.
+			assert_noop!(Recovery::remove_recovery(Origin::signed(5)), "recoveries still active");
+
+			assert_ok!(Recovery::close_recovery(Origin::signed(5), 1));
+			assert_eq!(Recovery::active_recovery_count(&5), 0);
+			assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![1, 4], 1, 0));
+
+			System::set_block_number(11);
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery not started");
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 1), "not a friend");
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "not enough friends vouched");
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 5, 1));
+			assert_ok!(Recovery::claim_recovery(Origin::signed(1), 5));
+		});
+	}
+}
@@ -22,7 +22,7 @@
 use rstd::prelude::*;
 use primitives::traits::{As, Zero, One, Convert};
 use srml_support::{StorageValue, StorageMap, for_each_tuple, decl_module, decl_event, decl_storage};
-use srml_support::{dispatch::Result, traits::OnFreeBalanceZero};
+use srml_support::{dispatch::Result, traits::{OnFreeBalanceZero, Contains}};
 use system::ensure_signed;
 use rstd::ops::Mul;
 
@@ -222,6 +222,15 @@ impl<T: Trait> OnFreeBalanceZero<T::AccountId> for Module<T> {
 	}
 }
 
+/// The validators of the current session.
+impl<T: Trait> Contains<T::AccountId> for Module<T> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		let mut validators = Self::validators();
+		validators.sort();
+		validators
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -267,6 +276,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl timestamp::Trait for Test {
 		type Moment = u64;
@@ -0,0 +1,81 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of the staking module.
+
+use super::*;
+use srml_benchmarking::{BenchmarkDefinition, account};
+use system::RawOrigin;
+
+/// The benchmarks of the staking module.
+pub fn benchmarks<T: Trait>() -> Vec<BenchmarkDefinition<Call<T>, T::Origin>> {
+	vec![
+		BenchmarkDefinition { name: "bond", components: &[], setup: bond::<T> },
+		BenchmarkDefinition {
+			name: "nominate",
+			components: &[("n", 1, MAX_NOMINATIONS as u32)],
+			setup: nominate::<T>,
+		},
+	]
+}
+
+/// The amount each benchmark stash is funded with.
+fn stake<T: Trait>() -> BalanceOf<T> {
+	T::Currency::minimum_balance() * <BalanceOf<T> as As<u64>>::sa(100)
+}
+
+/// Fund the stash `name` of index `index` and bond it to a new controller.
+fn bonded_stash<T: Trait>(name: &'static str, index: u32) -> (T::AccountId, T::AccountId) {
+	let stash: T::AccountId = account(name, index);
+	let controller: T::AccountId = account("controller", index);
+	let _ = T::Currency::make_free_balance_be(&stash, stake::<T>());
+
+	<Bonded<T>>::insert(&stash, controller.clone());
+	<Payee<T>>::insert(&stash, RewardDestination::Staked);
+	<Module<T>>::update_ledger(&controller, &StakingLedger {
+		stash: stash.clone(),
+		total: stake::<T>(),
+		active: stake::<T>(),
+		unlocking: vec![],
+	});
+
+	(stash, controller)
+}
+
+/// Bond a funded stash to a new controller.
+fn bond<T: Trait>(_: &[u32]) -> result::Result<(Call<T>, T::Origin), &'static str> {
+	let stash: T::AccountId = account("stash", 0);
+	let _ = T::Currency::make_free_balance_be(&stash, stake::<T>());
+
+	let controller: T::AccountId = account("controller", 0);
+	let call = Call::bond(T::Lookup::unlookup(controller), stake::<T>(), RewardDestination::Staked);
+
+	Ok((call, RawOrigin::Signed(stash).into()))
+}
+
+/// Nominate `n` validators, replacing the validator preferences of the nominator.
+fn nominate<T: Trait>(components: &[u32]) -> result::Result<(Call<T>, T::Origin), &'static str> {
+	let targets = (0..components[0]).map(|i| {
+		let (stash, _) = bonded_stash::<T>("validator", i);
+		<Validators<T>>::insert(&stash, ValidatorPrefs::default());
+		T::Lookup::unlookup(stash)
+	}).collect::<Vec<_>>();
+
+	let (stash, controller) = bonded_stash::<T>("nominator", components[0]);
+	<Validators<T>>::insert(&stash, ValidatorPrefs::default());
+
+	Ok((Call::nominate(targets), RawOrigin::Signed(controller).into()))
+}
@@ -199,6 +199,17 @@
 //!
 //! The current election algorithm is implemented based on Phragmén. The reference implementation can be found [here](https://github.com/w3f/consensus/tree/master/NPoS).
 //!
+//! Running Phragmén on-chain gets more expensive as the number of nominators grows, so the election is preferably
+//! computed off-chain:
+//! - When the last session of an era starts, the election window opens and the current validator and nominator
+//!     candidates are kept in a snapshot.
+//! - The offchain worker of each validator runs Phragmén with the equalise pass over the snapshot and submits the
+//!     result as an unsigned `submit_election_solution()` transaction, in a compact form referring to the snapshot.
+//! - The solution is checked on-chain and scored by the minimal backing stake of the winners, the sum of their
+//!     backing stakes and the sum of their squares. Only a solution with a better score than the queued one is kept.
+//! - At the end of the era, the queued solution is enacted. If there is none, or if the stakers it relies on have
+//!     since changed their role, nominations or stake, Phragmén is run on-chain as a fallback.
+//!
 //! ## GenesisConfig
 //!
 //! See the [`GensisConfig`] for a list of attributes that can be provided.
@@ -221,26 +232,33 @@ use runtime_io::with_storage;
 use rstd::{prelude::*, result};
 use parity_codec::{HasCompact, Encode, Decode};
 use srml_support::{StorageValue, StorageMap, EnumerableStorageMap, dispatch::Result};
-use srml_support::{decl_module, decl_event, decl_storage, ensure};
+use srml_support::{decl_module, decl_event, decl_storage, ensure, TypeInfo};
 use srml_support::traits::{
 	Currency, OnFreeBalanceZero, OnDilution, LockIdentifier, LockableCurrency, WithdrawReasons,
 	OnUnbalanced, Imbalance
 };
 use session::OnSessionChange;
 use primitives::Perbill;
-use primitives::traits::{Zero, One, As, StaticLookup, CheckedSub, Saturating, Bounded};
+use primitives::traits::{
+	Zero, One, As, StaticLookup, CheckedAdd, CheckedSub, Saturating, Bounded, ValidateUnsigned,
+};
+use primitives::transaction_validity::TransactionValidity;
 #[cfg(feature = "std")]
 use primitives::{Serialize, Deserialize};
-use system::ensure_signed;
+use system::{ensure_signed, ensure_inherent};
 
 mod mock;
 mod tests;
-mod phragmen;
+pub mod phragmen;
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
 
-use phragmen::{elect, ElectionConfig};
+use phragmen::{elect, ElectionConfig, evaluate_score, is_score_better};
+pub use phragmen::ElectionScore;
 
 const RECENT_OFFLINE_COUNT: usize = 32;
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
+const DEFAULT_SESSIONS_PER_ERA: u64 = 1000;
 const MAX_NOMINATIONS: usize = 16;
 const MAX_UNSTAKE_THRESHOLD: u32 = 10;
 
@@ -256,7 +274,7 @@ pub enum StakerStatus<AccountId> {
 }
 
 /// A destination account for payment.
-#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum RewardDestination {
 	/// Pay into the stash account, increasing the amount at stake accordingly.
@@ -274,7 +292,7 @@ impl Default for RewardDestination {
 }
 
 /// Preference of what happens on a slash event.
-#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct ValidatorPrefs<Balance: HasCompact> {
 	/// Validator should ensure this many more slashes than is necessary before being unstaked.
@@ -295,7 +313,7 @@ impl<B: Default + HasCompact + Copy> Default for ValidatorPrefs<B> {
 }
 
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be unlocked.
-#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct UnlockChunk<Balance: HasCompact, BlockNumber: HasCompact> {
 	/// Amount of funds to be unlocked.
@@ -307,7 +325,7 @@ pub struct UnlockChunk<Balance: HasCompact, BlockNumber: HasCompact> {
 }
 
 /// The ledger of a (bonded) stash.
-#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct StakingLedger<AccountId, Balance: HasCompact, BlockNumber: HasCompact> {
 	/// The stash account whose balance is actually locked and at stake.
@@ -347,7 +365,7 @@ impl<
 }
 
 /// The amount of exposure (to slashing) than an individual nominator has.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 	/// The stash account of the nominator in question.
@@ -358,7 +376,7 @@ pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 }
 
 /// A snapshot of the stake backing a single validator in the system.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Exposure<AccountId, Balance: HasCompact> {
 	/// The total balance backing this validator.
@@ -371,6 +389,29 @@ pub struct Exposure<AccountId, Balance: HasCompact> {
 	pub others: Vec<IndividualExposure<AccountId, Balance>>,
 }
 
+/// A compact election solution, computed off-chain. Validators and nominators are referred to by
+/// their index in the snapshot taken when the election window opened.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CompactSolution<Balance> {
+	/// The indices of the elected validators in the validator snapshot.
+	pub winners: Vec<u32>,
+	/// The index of each nominator in the nominator snapshot, with the stake it assigns to each of the
+	/// winners it nominated, referred to by position in `winners`. Sorted by nominator index.
+	pub assignments: Vec<(u32, Vec<(u32, Balance)>)>,
+}
+
+/// Something which can submit the election solution computed by the offchain worker as an unsigned
+/// transaction.
+pub trait SubmitUnsignedTransaction<Call> {
+	/// Submit the given call. Only available in the offchain worker context.
+	fn submit_unsigned(call: Call);
+}
+
+impl<Call> SubmitUnsignedTransaction<Call> for () {
+	fn submit_unsigned(_call: Call) {}
+}
+
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 type PositiveImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::PositiveImbalance;
 type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
@@ -392,10 +433,16 @@ pub trait Trait: system::Trait + session::Trait {
 
 	/// Handler for the unbalanced increment when rewarding a staker.
 	type Reward: OnUnbalanced<PositiveImbalanceOf<Self>>;
+
+	/// Submits the election solutions computed by the offchain worker.
+	type SubmitTransaction: SubmitUnsignedTransaction<Call<Self>>;
 }
 
 const STAKING_ID: LockIdentifier = *b"staking ";
 
+/// Error code of an invalid election solution transaction.
+const INVALID_SOLUTION: i8 = -50;
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Staking {
 
@@ -404,7 +451,7 @@ decl_storage! {
 		/// Minimum number of staking participants before emergency conditions are imposed.
 		pub MinimumValidatorCount get(minimum_validator_count) config(): u32 = DEFAULT_MINIMUM_VALIDATOR_COUNT;
 		/// The length of a staking era in sessions.
-		pub SessionsPerEra get(sessions_per_era) config(): T::BlockNumber = T::BlockNumber::sa(1000);
+		pub SessionsPerEra get(sessions_per_era) config(): T::BlockNumber = T::BlockNumber::sa(DEFAULT_SESSIONS_PER_ERA);
 		/// Maximum reward, per validator, that is provided per acceptable session.
 		pub SessionReward get(session_reward) config(): Perbill = Perbill::from_billionths(60);
 		/// Slash, per validator that is taken for the first time they are found to be offline.
@@ -479,6 +526,18 @@ decl_storage! {
 
 		/// Most recent `RECENT_OFFLINE_COUNT` instances. (who it was, when it was reported, how many instances they were offline for).
 		pub RecentlyOffline get(recently_offline): Vec<(T::AccountId, T::BlockNumber, u32)>;
+
+		/// The block at which the election window of the current era opened, if it is open.
+		pub ElectionWindow get(election_window): Option<T::BlockNumber>;
+		/// Snapshot of the validator candidates, taken when the election window opened.
+		pub SnapshotValidators get(snapshot_validators): Option<Vec<T::AccountId>>;
+		/// Snapshot of the nominators, taken when the election window opened.
+		pub SnapshotNominators get(snapshot_nominators): Option<Vec<T::AccountId>>;
+		/// The best election result submitted in the current election window, to be enacted at the
+		/// end of the era.
+		pub QueuedElected get(queued_elected): Option<Vec<(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>)>>;
+		/// The score of `QueuedElected`.
+		pub QueuedScore get(queued_score): Option<ElectionScore>;
 	}
 	add_extra_genesis {
 		config(stakers): Vec<(T::AccountId, T::AccountId, BalanceOf<T>, StakerStatus<T::AccountId>)>;
@@ -517,6 +576,12 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event<T>() = default;
 
+		/// The length of an era in sessions, unless configured otherwise.
+		const DefaultSessionsPerEra: T::BlockNumber = T::BlockNumber::sa(DEFAULT_SESSIONS_PER_ERA);
+
+		/// The minimum number of validators, unless configured otherwise.
+		const DefaultMinimumValidatorCount: u32 = DEFAULT_MINIMUM_VALIDATOR_COUNT;
+
 		/// Take the origin account as a stash and lock up `value` of its balance. `controller` will be the
 		/// account that controls it.
 		///
@@ -718,6 +783,35 @@ decl_module! {
 		fn set_invulnerables(validators: Vec<T::AccountId>) {
 			<Invulnerables<T>>::put(validators);
 		}
+
+		/// Submit an election solution for the next era, computed off-chain. It is kept if it is valid
+		/// and its `score` is better than the one of the queued solution.
+		///
+		/// The dispatch origin for this call must be _Inherent_, i.e. it is submitted as an unsigned
+		/// transaction by the offchain worker.
+		fn submit_election_solution(
+			origin,
+			#[compact] era: T::BlockNumber,
+			solution: CompactSolution<BalanceOf<T>>,
+			score: ElectionScore
+		) {
+			ensure_inherent(origin)?;
+			let elected = Self::check_solution(era, &solution, score)?;
+
+			<QueuedElected<T>>::put(elected);
+			<QueuedScore<T>>::put(score);
+			Self::deposit_event(RawEvent::SolutionStored(score));
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			if Self::election_window() == Some(now) {
+				if let Some((solution, score)) = Self::offchain_election() {
+					T::SubmitTransaction::submit_unsigned(
+						Call::submit_election_solution(Self::current_era(), solution, score)
+					);
+				}
+			}
+		}
 	}
 }
 
@@ -730,6 +824,8 @@ decl_event!(
 		OfflineWarning(AccountId, u32),
 		/// One validator (and their nominators) has been slashed by the given amount.
 		OfflineSlash(AccountId, Balance),
+		/// An election solution with the given score has been queued for the next era.
+		SolutionStored(ElectionScore),
 	}
 );
 
@@ -858,6 +954,150 @@ impl<T: Trait> Module<T> {
 		{
 			Self::new_era();
 		}
+
+		// The era ends with the session that has just started: open the election window.
+		if ((session_index + One::one() - Self::last_era_length_change()) % Self::sessions_per_era()).is_zero() {
+			Self::open_election_window();
+		}
+	}
+
+	/// Open the election window of the current era, taking a snapshot of the candidates.
+	fn open_election_window() {
+		<SnapshotValidators<T>>::put(<Validators<T>>::enumerate().map(|(who, _)| who).collect::<Vec<_>>());
+		<SnapshotNominators<T>>::put(<Nominators<T>>::enumerate().map(|(who, _)| who).collect::<Vec<_>>());
+		<QueuedElected<T>>::kill();
+		<QueuedScore<T>>::kill();
+		<ElectionWindow<T>>::put(<system::Module<T>>::block_number());
+	}
+
+	/// Close the election window, returning the queued election result, if any.
+	fn close_election_window() -> Option<Vec<(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>)>> {
+		<ElectionWindow<T>>::kill();
+		<SnapshotValidators<T>>::kill();
+		<SnapshotNominators<T>>::kill();
+		<QueuedScore<T>>::kill();
+		<QueuedElected<T>>::take()
+	}
+
+	/// Compute the exposures of the winners of a compact solution against the snapshot.
+	///
+	/// Checks that every winner is a validator candidate, that nominators only back winners they
+	/// nominated and that they don't assign more than their stake.
+	fn solution_exposures(
+		solution: &CompactSolution<BalanceOf<T>>,
+	) -> result::Result<(Vec<T::AccountId>, Vec<Exposure<T::AccountId, BalanceOf<T>>>), &'static str> {
+		let validators = Self::snapshot_validators().ok_or("no validator snapshot")?;
+		let nominators = Self::snapshot_nominators().ok_or("no nominator snapshot")?;
+
+		let mut winners = Vec::with_capacity(solution.winners.len());
+		let mut exposures = Vec::with_capacity(solution.winners.len());
+		for &index in &solution.winners {
+			let who = validators.get(index as usize).ok_or("invalid winner index")?;
+			ensure!(!winners.contains(who), "duplicate winner");
+			ensure!(<Validators<T>>::exists(who), "winner is not a validator candidate");
+
+			let own = Self::slashable_balance_of(who);
+			winners.push(who.clone());
+			exposures.push(Exposure { total: own, own, others: vec![] });
+		}
+
+		let mut last_nominator = None;
+		for (index, distribution) in &solution.assignments {
+			ensure!(last_nominator.map_or(true, |last| *index > last), "assignments not sorted by nominator");
+			last_nominator = Some(*index);
+
+			let who = nominators.get(*index as usize).ok_or("invalid nominator index")?;
+			let targets = Self::nominators(who);
+			let mut used = <BalanceOf<T>>::zero();
+			for &(position, value) in distribution {
+				let winner = winners.get(position as usize).ok_or("invalid winner position")?;
+				ensure!(targets.contains(winner), "nominator backs a validator it did not nominate");
+
+				let exposure = &mut exposures[position as usize];
+				ensure!(!exposure.others.iter().any(|i| i.who == *who), "duplicate assignment");
+				used = used.checked_add(&value).ok_or("assigned stake overflow")?;
+				exposure.total = exposure.total.saturating_add(value);
+				exposure.others.push(IndividualExposure { who: who.clone(), value });
+			}
+			ensure!(used <= Self::slashable_balance_of(who), "nominator assigns more than its stake");
+		}
+
+		Ok((winners, exposures))
+	}
+
+	/// Check an election solution submitted for `era` and claiming `score`. Returns the election
+	/// result if the solution is valid and better than the queued one.
+	fn check_solution(
+		era: T::BlockNumber,
+		solution: &CompactSolution<BalanceOf<T>>,
+		score: ElectionScore,
+	) -> result::Result<Vec<(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>)>, &'static str> {
+		ensure!(Self::election_window().is_some(), "election window is closed");
+		ensure!(era == Self::current_era(), "solution is not for the current era");
+		if let Some(queued) = Self::queued_score() {
+			ensure!(is_score_better(score, queued), "solution is not better than the queued one");
+		}
+		ensure!(solution.winners.len() == Self::validator_count() as usize, "wrong number of winners");
+		ensure!(
+			!solution.winners.is_empty()
+				&& solution.winners.len() >= Self::minimum_validator_count() as usize,
+			"not enough winners"
+		);
+
+		let (winners, exposures) = Self::solution_exposures(solution)?;
+		ensure!(evaluate_score(&exposures) == score, "claimed score does not match the solution");
+
+		Ok(winners.into_iter().zip(exposures.into_iter()).collect())
+	}
+
+	/// Run the election over the snapshot, with the equalise pass, and turn the result into a compact
+	/// solution. Meant to be run off-chain.
+	fn offchain_election() -> Option<(CompactSolution<BalanceOf<T>>, ElectionScore)> {
+		let validators = Self::snapshot_validators()?;
+		let nominators = Self::snapshot_nominators()?;
+		let rounds = Self::validator_count() as usize;
+
+		let candidates = validators.clone();
+		let voters = nominators.clone();
+		let elected_candidates = elect::<T, _, _, _, _>(
+			|| rounds,
+			move || Box::new(candidates.clone().into_iter().map(|who| {
+				let prefs = Self::validators(&who);
+				(who, prefs)
+			})) as Box<dyn Iterator<Item=_>>,
+			move || Box::new(voters.clone().into_iter().map(|who| {
+				let targets = Self::nominators(&who);
+				(who, targets)
+			})) as Box<dyn Iterator<Item=_>>,
+			Self::slashable_balance_of,
+			Self::minimum_validator_count() as usize,
+			ElectionConfig::<BalanceOf<T>> {
+				equalise: true,
+				tolerance: <BalanceOf<T>>::sa(10 as u64),
+				iterations: 10,
+			}
+		)?;
+
+		// not enough candidates: leave it to the on-chain election.
+		if elected_candidates.len() != rounds {
+			return None
+		}
+
+		let mut winners = Vec::with_capacity(rounds);
+		let mut assignments = rstd::collections::btree_map::BTreeMap::<u32, Vec<(u32, BalanceOf<T>)>>::new();
+		for (position, candidate) in elected_candidates.iter().enumerate() {
+			winners.push(validators.iter().position(|v| *v == candidate.who)? as u32);
+			for i in candidate.exposure.others.iter().filter(|i| !i.value.is_zero()) {
+				let index = nominators.iter().position(|n| *n == i.who)? as u32;
+				assignments.entry(index).or_insert_with(Vec::new).push((position as u32, i.value));
+			}
+		}
+
+		let solution = CompactSolution { winners, assignments: assignments.into_iter().collect() };
+		let (_, exposures) = Self::solution_exposures(&solution).ok()?;
+		let score = evaluate_score(&exposures);
+
+		Some((solution, score))
 	}
 
 	/// The era has changed - enact new staking set.
@@ -900,28 +1140,55 @@ impl<T: Trait> Module<T> {
 		Self::bonded(stash).and_then(Self::ledger).map(|l| l.total).unwrap_or_default()
 	}
 
+	/// Whether a queued election result is still backed by the current candidates and stakes.
+	///
+	/// Stakers may change their role, nominations and stake while the election window is open,
+	/// so a result computed against the snapshot is only enacted if every winner is still a
+	/// validator candidate, every nominator still nominates the winners it backs, and nobody is
+	/// exposed for more than it has bonded.
+	fn is_still_backed(elected: &[(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>)]) -> bool {
+		let mut nominated = rstd::collections::btree_map::BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+		for (who, exposure) in elected {
+			if !<Validators<T>>::exists(who) || exposure.own > Self::slashable_balance_of(who) {
+				return false;
+			}
+			for individual in &exposure.others {
+				if !Self::nominators(&individual.who).contains(who) {
+					return false;
+				}
+				*nominated.entry(individual.who.clone()).or_insert_with(Zero::zero) += individual.value;
+			}
+		}
+		nominated.into_iter().all(|(who, value)| value <= Self::slashable_balance_of(&who))
+	}
+
 	/// Select a new validator set from the assembled stakers and their role preferences.
 	///
 	/// Returns the new SlotStake value.
 	fn select_validators() -> BalanceOf<T> {
-		let rounds = || <ValidatorCount<T>>::get() as usize;
-		let validators = || <Validators<T>>::enumerate();
-		let nominators = || <Nominators<T>>::enumerate();
-		let min_validator_count = Self::minimum_validator_count() as usize;
-		let maybe_elected_candidates = elect::<T, _, _, _, _>(
-			rounds,
-			validators,
-			nominators,
-			Self::slashable_balance_of,
-			min_validator_count,
-			ElectionConfig::<BalanceOf<T>> {
-				equalise: false,
-				tolerance: <BalanceOf<T>>::sa(10 as u64),
-				iterations: 10,
-			}
-		);
+		// Prefer the result computed off-chain, falling back to the on-chain election.
+		let maybe_elected = Self::close_election_window()
+			.filter(|elected| Self::is_still_backed(elected))
+			.or_else(|| {
+				let rounds = || <ValidatorCount<T>>::get() as usize;
+				let validators = || <Validators<T>>::enumerate();
+				let nominators = || <Nominators<T>>::enumerate();
+				let min_validator_count = Self::minimum_validator_count() as usize;
+				elect::<T, _, _, _, _>(
+					rounds,
+					validators,
+					nominators,
+					Self::slashable_balance_of,
+					min_validator_count,
+					ElectionConfig::<BalanceOf<T>> {
+						equalise: false,
+						tolerance: <BalanceOf<T>>::sa(10 as u64),
+						iterations: 10,
+					}
+				).map(|elected| elected.into_iter().map(|c| (c.who, c.exposure)).collect::<Vec<_>>())
+			});
 
-		if let Some(elected_candidates) = maybe_elected_candidates {
+		if let Some(elected) = maybe_elected {
 			// Clear Stakers and reduce their slash_count.
 			for v in Self::current_elected().iter() {
 				<Stakers<T>>::remove(v);
@@ -932,17 +1199,17 @@ impl<T: Trait> Module<T> {
 			}
 
 			// Populate Stakers and figure out the minimum stake behind a slot.
-			let mut slot_stake = elected_candidates[0].exposure.total;
-			for c in &elected_candidates {
-				if c.exposure.total < slot_stake {
-					slot_stake = c.exposure.total;
+			let mut slot_stake = elected[0].1.total;
+			for (who, exposure) in &elected {
+				if exposure.total < slot_stake {
+					slot_stake = exposure.total;
 				}
-				<Stakers<T>>::insert(c.who.clone(), c.exposure.clone());
+				<Stakers<T>>::insert(who.clone(), exposure.clone());
 			}
 			<SlotStake<T>>::put(&slot_stake);
 
 			// Set the new validator set.
-			let elected_stashes = elected_candidates.into_iter().map(|i| i.who).collect::<Vec<_>>();
+			let elected_stashes = elected.into_iter().map(|(who, _)| who).collect::<Vec<_>>();
 			<CurrentElected<T>>::put(&elected_stashes);
 			<session::Module<T>>::set_validators(
 				&elected_stashes.into_iter().map(|s| Self::bonded(s).unwrap_or_default()).collect::<Vec<_>>()
@@ -1046,3 +1313,24 @@ impl<T: Trait> consensus::OnOfflineReport<Vec<u32>> for Module<T> {
 		}
 	}
 }
+
+impl<T: Trait> ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::submit_election_solution(era, solution, score) = call {
+			if Self::check_solution(*era, solution, *score).is_err() {
+				return TransactionValidity::Invalid(INVALID_SOLUTION)
+			}
+
+			TransactionValidity::Valid {
+				priority: score[0].min(u64::max_value() as u128) as u64,
+				requires: vec![],
+				provides: vec![(&b"StakingElection"[..], *era, *score).encode()],
+				longevity: <session::Module<T>>::length().as_(),
+			}
+		} else {
+			TransactionValidity::Invalid(INVALID_SOLUTION)
+		}
+	}
+}
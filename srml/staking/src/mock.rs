@@ -52,6 +52,7 @@ impl system::Trait for Test {
 	type Header = Header;
 	type Event = ();
 	type Log = DigestItem;
+	type SpecVersion = ();
 }
 impl balances::Trait for Test {
 	type Balance = u64;
@@ -77,6 +78,7 @@ impl Trait for Test {
 	type Event = ();
 	type Slash = ();
 	type Reward = ();
+	type SubmitTransaction = ();
 }
 
 pub struct ExtBuilder {
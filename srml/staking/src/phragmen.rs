@@ -17,12 +17,17 @@
 //! Rust implementation of the Phragmén election algorithm.
 
 use rstd::prelude::*;
+use rstd::cmp::Ordering;
 use primitives::Perquintill;
 use primitives::traits::{Zero, As, Bounded, CheckedMul, Saturating};
 use parity_codec::{HasCompact, Encode, Decode};
 use crate::{Exposure, BalanceOf, Trait, ValidatorPrefs, IndividualExposure};
 
 
+/// The score of an election result: the minimal backing stake of the elected validators, the sum of
+/// their backing stakes, and the sum of the squares of their backing stakes.
+pub type ElectionScore = [u128; 3];
+
 /// Configure the behavior of the Phragmen election.
 /// Might be deprecated.
 pub struct ElectionConfig<Balance: HasCompact> {
@@ -160,48 +165,7 @@ pub fn elect<T: Trait + 'static, FR, FN, FV, FS>(
 
 	// 4- If we have more candidates then needed, run Phragmén.
 	if candidates.len() >= rounds {
-		elected_candidates = Vec::with_capacity(rounds);
-		// Main election loop
-		for _round in 0..rounds {
-			// Loop 1: initialize score
-			for c in &mut candidates {
-				if !c.elected {
-					c.score = Perquintill::from_xth(c.approval_stake.as_());
-				}
-			}
-			// Loop 2: increment score.
-			for n in &nominators {
-				for e in &n.edges {
-					let c = &mut candidates[e.candidate_index];
-					if !c.elected {
-						// Note: This seems to never overflow, ok to be safe though
-						let temp = n.budget.as_().saturating_mul(*n.load) / c.approval_stake.as_();
-						// Note: This seems to never overflow, ok to be safe though
-						c.score = Perquintill::from_quintillionths((*c.score).saturating_add(temp));
-					}
-				}
-			}
-
-			// Find the best
-			let winner = candidates
-				.iter_mut()
-				.filter(|c| !c.elected)
-				.min_by_key(|c| *c.score)
-				.expect("candidates length is checked to be >0; qed");
-
-			// loop 3: update nominator and edge load
-			winner.elected = true;
-			for n in &mut nominators {
-				for e in &mut n.edges {
-					if e.who == winner.who {
-						e.load = Perquintill::from_quintillionths(*winner.score - *n.load);
-						n.load = winner.score;
-					}
-				}
-			}
-
-			elected_candidates.push(winner.clone());
-		} // end of all rounds
+		elected_candidates = run_rounds(rounds, &mut candidates, &mut nominators);
 
 		// 4.1- Update backing stake of candidates and nominators
 		for n in &mut nominators {
@@ -274,6 +238,97 @@ pub fn elect<T: Trait + 'static, FR, FN, FV, FS>(
 	Some(elected_candidates)
 }
 
+/// Run `rounds` rounds of sequential Phragmén, marking each winner as elected and updating the load
+/// of the nominators and edges backing it. Returns the winners in the order they were elected.
+///
+/// There must be at least `rounds` candidates, none of which may have zero approval stake.
+fn run_rounds<AccountId, Balance>(
+	rounds: usize,
+	candidates: &mut Vec<Candidate<AccountId, Balance>>,
+	nominators: &mut Vec<Nominator<AccountId, Balance>>,
+) -> Vec<Candidate<AccountId, Balance>> where
+	AccountId: Clone + PartialEq,
+	Balance: HasCompact + Clone + As<u64>,
+{
+	let mut elected_candidates = Vec::with_capacity(rounds);
+	// Main election loop
+	for _round in 0..rounds {
+		// Loop 1: initialize score
+		for c in candidates.iter_mut() {
+			if !c.elected {
+				c.score = Perquintill::from_xth(c.approval_stake.as_());
+			}
+		}
+		// Loop 2: increment score.
+		for n in nominators.iter() {
+			for e in &n.edges {
+				let c = &mut candidates[e.candidate_index];
+				if !c.elected {
+					// Note: This seems to never overflow, ok to be safe though
+					let temp = n.budget.as_().saturating_mul(*n.load) / c.approval_stake.as_();
+					// Note: This seems to never overflow, ok to be safe though
+					c.score = Perquintill::from_quintillionths((*c.score).saturating_add(temp));
+				}
+			}
+		}
+
+		// Find the best
+		let winner = candidates
+			.iter_mut()
+			.filter(|c| !c.elected)
+			.min_by_key(|c| *c.score)
+			.expect("candidates length is checked to be >0; qed");
+
+		// loop 3: update nominator and edge load
+		winner.elected = true;
+		for n in nominators.iter_mut() {
+			for e in &mut n.edges {
+				if e.who == winner.who {
+					e.load = Perquintill::from_quintillionths(*winner.score - *n.load);
+					n.load = winner.score;
+				}
+			}
+		}
+
+		elected_candidates.push(winner.clone());
+	} // end of all rounds
+	elected_candidates
+}
+
+/// Elect up to `count` of `candidates` by sequential Phragmén, where each voter backs every
+/// candidate it approves of with its whole stake. This is the plain approval-voting form of
+/// [`elect`], with no self-votes, exposures or equalisation.
+///
+/// Returns the winners in the order they were elected. Candidates not approved by any voter with a
+/// non-zero stake are never elected.
+pub fn elect_by_approval<AccountId, Balance>(
+	count: usize,
+	candidates: Vec<AccountId>,
+	voters: Vec<(AccountId, Balance, Vec<AccountId>)>,
+) -> Vec<AccountId> where
+	AccountId: Clone + PartialEq + Default,
+	Balance: HasCompact + Copy + Default + As<u64> + Zero + Saturating,
+{
+	// Candidates without approval can neither be scored nor elected.
+	let mut candidates = candidates.into_iter()
+		.filter(|c| voters.iter().any(|(_, stake, approvals)| !stake.is_zero() && approvals.contains(c)))
+		.map(|who| Candidate { who, ..Default::default() })
+		.collect::<Vec<Candidate<AccountId, Balance>>>();
+	let mut nominators = voters.into_iter().map(|(who, budget, approvals)| {
+		let mut edges: Vec<Edge<AccountId, Balance>> = Vec::with_capacity(approvals.len());
+		for a in &approvals {
+			if let Some(idx) = candidates.iter().position(|c| c.who == *a) {
+				candidates[idx].approval_stake = candidates[idx].approval_stake.saturating_add(budget);
+				edges.push(Edge { who: a.clone(), candidate_index: idx, ..Default::default() });
+			}
+		}
+		Nominator { who, edges, budget, load: Perquintill::zero() }
+	}).collect::<Vec<_>>();
+
+	let rounds = count.min(candidates.len());
+	run_rounds(rounds, &mut candidates, &mut nominators).into_iter().map(|c| c.who).collect()
+}
+
 pub fn equalise<T: Trait + 'static>(
 	nominator: &mut Nominator<T::AccountId, BalanceOf<T>>,
 	elected_candidates: &mut Vec<Candidate<T::AccountId, BalanceOf<T>>>,
@@ -362,3 +417,36 @@ pub fn equalise<T: Trait + 'static>(
 	});
 	difference
 }
+
+/// Compute the score of an election result from the exposures of the elected validators.
+pub fn evaluate_score<AccountId, Balance: HasCompact + As<u64>>(
+	exposures: &[Exposure<AccountId, Balance>],
+) -> ElectionScore {
+	let mut min_stake = u128::max_value();
+	let mut sum_stake = 0u128;
+	let mut sum_squares = 0u128;
+
+	for exposure in exposures {
+		let stake = exposure.total.as_() as u128;
+		min_stake = min_stake.min(stake);
+		sum_stake = sum_stake.saturating_add(stake);
+		sum_squares = sum_squares.saturating_add(stake.saturating_mul(stake));
+	}
+
+	if exposures.is_empty() {
+		min_stake = 0;
+	}
+
+	[min_stake, sum_stake, sum_squares]
+}
+
+/// Whether `this` score is strictly better than `that` score: the minimal stake is maximised first,
+/// then the sum of stakes, then the sum of squares of stakes is minimised, so that stake is spread
+/// as evenly as possible.
+pub fn is_score_better(this: ElectionScore, that: ElectionScore) -> bool {
+	match this[0].cmp(&that[0]).then(this[1].cmp(&that[1])) {
+		Ordering::Greater => true,
+		Ordering::Less => false,
+		Ordering::Equal => this[2] < that[2],
+	}
+}
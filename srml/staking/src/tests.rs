@@ -1986,4 +1986,267 @@ fn phragmen_should_not_overflow_ultimate() {
 
 		assert_eq!(Session::validators(), vec![4, 2]);
 	})
-}
\ No newline at end of file
+}
+
+#[test]
+fn election_window_opens_with_snapshot() {
+	with_externalities(&mut ExtBuilder::default()
+		.sessions_per_era(2)
+		.build(),
+	|| {
+		assert_eq!(Staking::election_window(), None);
+
+		// Session 1 is the last session of era 0: the window opens and the candidates are kept.
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::current_era(), 0);
+		assert_eq!(Staking::election_window(), Some(1));
+		let mut validators = Staking::snapshot_validators().unwrap();
+		validators.sort();
+		assert_eq!(validators, vec![11, 21]);
+		assert_eq!(Staking::snapshot_nominators(), Some(vec![101]));
+
+		// The era changes and the window is closed.
+		System::set_block_number(2);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::current_era(), 1);
+		assert_eq!(Staking::election_window(), None);
+		assert_eq!(Staking::snapshot_validators(), None);
+		assert_eq!(Staking::snapshot_nominators(), None);
+
+		// It opens again in the last session of era 1.
+		System::set_block_number(3);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::election_window(), Some(3));
+	});
+}
+
+#[test]
+fn offchain_election_solution_is_enacted() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::election_window(), Some(1));
+
+		let (solution, score) = Staking::offchain_election().unwrap();
+		// With the equalise pass, 101 backs 11 only, which has the lowest stake.
+		assert_eq!(score, [1500, 3500, 1500 * 1500 + 2000 * 2000]);
+		assert_eq!(solution.assignments.len(), 1);
+
+		assert_ok!(Staking::submit_election_solution(Origin::INHERENT, Staking::current_era(), solution, score));
+		assert_eq!(Staking::queued_score(), Some(score));
+
+		System::set_block_number(2);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::stakers(&11).total, 1500);
+		assert_eq!(Staking::stakers(&11).others, vec![IndividualExposure { who: 101, value: 500 }]);
+		assert_eq!(Staking::stakers(&21).total, 2000);
+		assert!(Staking::stakers(&21).others.is_empty());
+		assert_eq!(Staking::slot_stake(), 1500);
+
+		// The queued solution is consumed and a new window is open.
+		assert_eq!(Staking::queued_elected(), None);
+		assert_eq!(Staking::queued_score(), None);
+		assert_eq!(Staking::election_window(), Some(2));
+	});
+}
+
+#[test]
+fn outdated_election_solution_falls_back_on_chain() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+
+		let (solution, score) = Staking::offchain_election().unwrap();
+		assert_ok!(Staking::submit_election_solution(Origin::INHERENT, Staking::current_era(), solution, score));
+		assert!(Staking::queued_elected().is_some());
+
+		// 101 no longer nominates 11, which it backs in the queued solution.
+		assert_ok!(Staking::nominate(Origin::signed(100), vec![21]));
+
+		System::set_block_number(2);
+		Session::check_rotate_session(System::block_number());
+		let mut elected = Staking::current_elected();
+		elected.sort();
+		assert_eq!(elected, vec![11, 21]);
+		assert!(Staking::stakers(&11).others.is_empty());
+		assert_eq!(Staking::stakers(&21).others, vec![IndividualExposure { who: 101, value: 500 }]);
+	});
+}
+
+#[test]
+fn invalid_or_worse_solutions_are_rejected() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		let era = Staking::current_era();
+		let (solution, score) = (CompactSolution { winners: vec![0, 1], assignments: vec![] }, [1000, 3000, 5000000]);
+
+		// The window is not open yet.
+		assert_noop!(
+			Staking::submit_election_solution(Origin::INHERENT, era, solution.clone(), score),
+			"election window is closed"
+		);
+
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		let era = Staking::current_era();
+
+		assert_noop!(
+			Staking::submit_election_solution(Origin::signed(10), era, solution.clone(), score),
+			"bad origin: expected to be an inherent origin"
+		);
+		assert_noop!(
+			Staking::submit_election_solution(Origin::INHERENT, era + 1, solution.clone(), score),
+			"solution is not for the current era"
+		);
+		assert_noop!(
+			Staking::submit_election_solution(
+				Origin::INHERENT, era, CompactSolution { winners: vec![0], assignments: vec![] }, score
+			),
+			"wrong number of winners"
+		);
+		assert_noop!(
+			Staking::submit_election_solution(
+				Origin::INHERENT, era, CompactSolution { winners: vec![0, 0], assignments: vec![] }, score
+			),
+			"duplicate winner"
+		);
+		assert_noop!(
+			Staking::submit_election_solution(
+				Origin::INHERENT, era, CompactSolution { winners: vec![0, 1], assignments: vec![(0, vec![(0, 501)])] }, score
+			),
+			"nominator assigns more than its stake"
+		);
+		assert_noop!(
+			Staking::submit_election_solution(Origin::INHERENT, era, solution.clone(), [1000, 3000, 0]),
+			"claimed score does not match the solution"
+		);
+
+		// Without any nomination, the score is the own stakes of the validators.
+		assert_ok!(Staking::submit_election_solution(Origin::INHERENT, era, solution.clone(), score));
+
+		// The same solution is not better than the queued one.
+		assert_noop!(
+			Staking::submit_election_solution(Origin::INHERENT, era, solution, score),
+			"solution is not better than the queued one"
+		);
+
+		// The off-chain result is better.
+		let (solution, score) = Staking::offchain_election().unwrap();
+		assert_ok!(Staking::submit_election_solution(Origin::INHERENT, era, solution, score));
+		assert_eq!(Staking::queued_score(), Some(score));
+	});
+}
+
+#[test]
+fn empty_or_undersized_solutions_are_rejected() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		let era = Staking::current_era();
+		let score = [0, 0, 0];
+
+		// Not even an empty election is allowed to replace the validators.
+		<ValidatorCount<Test>>::put(0);
+		assert_noop!(
+			Staking::submit_election_solution(
+				Origin::INHERENT, era, CompactSolution { winners: vec![], assignments: vec![] }, score
+			),
+			"not enough winners"
+		);
+
+		// Nor one with fewer winners than the minimum.
+		<ValidatorCount<Test>>::put(2);
+		<MinimumValidatorCount<Test>>::put(3);
+		assert_noop!(
+			Staking::submit_election_solution(
+				Origin::INHERENT, era, CompactSolution { winners: vec![0, 1], assignments: vec![] }, score
+			),
+			"not enough winners"
+		);
+		assert_eq!(Staking::queued_score(), None);
+	});
+}
+
+#[test]
+fn election_falls_back_on_chain_without_solution() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::election_window(), Some(1));
+		assert_eq!(Staking::queued_elected(), None);
+
+		System::set_block_number(2);
+		Session::check_rotate_session(System::block_number());
+		let mut elected = Staking::current_elected();
+		elected.sort();
+		assert_eq!(elected, vec![11, 21]);
+		assert_eq!(Staking::election_window(), Some(2));
+	});
+}
+
+#[test]
+fn election_solution_transaction_is_validated() {
+	with_externalities(&mut ExtBuilder::default()
+		.build(),
+	|| {
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		let era = Staking::current_era();
+		let (solution, score) = Staking::offchain_election().unwrap();
+
+		assert_eq!(
+			Staking::validate_unsigned(&Call::submit_election_solution(era, solution.clone(), [0, 0, 0])),
+			TransactionValidity::Invalid(INVALID_SOLUTION)
+		);
+		assert_eq!(
+			Staking::validate_unsigned(&Call::submit_election_solution(era, solution.clone(), score)),
+			TransactionValidity::Valid {
+				priority: 1500,
+				requires: vec![],
+				provides: vec![(&b"StakingElection"[..], era, score).encode()],
+				longevity: 1,
+			}
+		);
+		assert_eq!(
+			Staking::validate_unsigned(&Call::set_invulnerables(vec![])),
+			TransactionValidity::Invalid(INVALID_SOLUTION)
+		);
+	});
+}
+
+#[test]
+fn election_scores_are_compared() {
+	// A higher minimal stake wins, whatever the rest.
+	assert!(phragmen::is_score_better([11, 0, 100], [10, 50, 0]));
+	// Then a higher total stake.
+	assert!(phragmen::is_score_better([10, 51, 100], [10, 50, 0]));
+	// Then a more even distribution.
+	assert!(phragmen::is_score_better([10, 50, 99], [10, 50, 100]));
+	assert!(!phragmen::is_score_better([10, 50, 100], [10, 50, 100]));
+	assert!(!phragmen::is_score_better([9, 100, 0], [10, 50, 100]));
+}
+
+#[test]
+fn phragmen_elect_by_approval_works() {
+	let voters = vec![
+		(1, 10u64, vec![10, 30]),
+		(2, 20, vec![20]),
+		(3, 30, vec![30]),
+		(4, 5, vec![40]),
+		(5, 0, vec![50]),
+	];
+	// 30 has the most approval; its backers' load then weighs against 10 in favour of 20.
+	assert_eq!(phragmen::elect_by_approval(2, vec![10, 20, 30, 40, 50], voters.clone()), vec![30, 20]);
+	// With seats to spare, everyone with any approval is elected.
+	assert_eq!(phragmen::elect_by_approval(5, vec![50, 40, 30, 20, 10], voters), vec![30, 20, 10, 40]);
+}
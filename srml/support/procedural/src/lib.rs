@@ -23,6 +23,7 @@
 extern crate proc_macro;
 
 mod storage;
+mod type_info;
 
 use proc_macro::TokenStream;
 
@@ -104,3 +105,25 @@ use proc_macro::TokenStream;
 pub fn decl_storage(input: TokenStream) -> TokenStream {
 	storage::transformation::decl_storage_impl(input)
 }
+
+/// Derives `TypeInfo`, describing how the type is encoded by the `Encode` derive of
+/// `parity-codec` for the type registry of the runtime metadata.
+///
+/// ## Example
+///
+/// ```nocompile
+/// #[derive(Encode, Decode, TypeInfo)]
+/// pub struct Ledger<Balance: HasCompact> {
+/// 	#[codec(compact)]
+/// 	total: Balance,
+/// 	unlocking: Vec<Balance>,
+/// }
+/// ```
+///
+/// Fields and variants are described in declaration order; the `compact`, `encoded_as` and `skip`
+/// field attributes and the `index` variant attribute of `#[codec(..)]` are taken into account,
+/// as well as literal discriminants.
+#[proc_macro_derive(TypeInfo, attributes(codec))]
+pub fn derive_type_info(input: TokenStream) -> TokenStream {
+	type_info::derive_type_info_impl(input)
+}
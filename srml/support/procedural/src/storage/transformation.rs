@@ -135,6 +135,12 @@ pub fn decl_storage_impl(input: TokenStream) -> TokenStream {
 		&instance_opts,
 		&storage_lines,
 	);
+	let (store_types_bounds, store_types_to_metadata) = store_types_to_metadata(
+		&scrate,
+		&traitinstance,
+		&instance_opts,
+		&storage_lines,
+	);
 
 	let InstanceOpts {
 		instance,
@@ -169,6 +175,13 @@ pub fn decl_storage_impl(input: TokenStream) -> TokenStream {
 			pub fn store_metadata_name() -> &'static str {
 				#cratename_string
 			}
+			#[doc(hidden)]
+			#[allow(unused_variables)]
+			pub fn store_metadata_types(
+				registry: &mut #scrate::registry::Registry,
+			) -> #scrate::rstd::vec::Vec<#scrate::registry::StorageTypeMetadata> where #store_types_bounds {
+				#store_types_to_metadata
+			}
 		}
 
 		#extra_genesis
@@ -835,6 +848,66 @@ fn store_functions_to_metadata (
 	})
 }
 
+/// The registration of the key and value types of the storage items, along with the `TypeInfo`
+/// bounds required by the generic ones.
+fn store_types_to_metadata(
+	scrate: &TokenStream2,
+	traitinstance: &Ident,
+	instance_opts: &InstanceOpts,
+	storage_lines: &ext::Punctuated<DeclStorageLine, Token![;]>,
+) -> (TokenStream2, TokenStream2) {
+	let mut generics = vec![traitinstance.clone()];
+	generics.extend(instance_opts.instance.clone());
+
+	let mut bounds = TokenStream2::new();
+	let mut bounded = Vec::new();
+	let mut items = Vec::new();
+	for sline in storage_lines.inner.iter() {
+		let type_infos = get_type_infos(&sline.storage_type);
+		let typ = type_infos.typ;
+		let keys: Vec<TokenStream2> = match type_infos.kind {
+			DeclStorageTypeInfosKind::Simple => Vec::new(),
+			DeclStorageTypeInfosKind::Map { key_type, .. } => vec![quote!( #key_type )],
+			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, .. } =>
+				vec![quote!( #key1_type ), quote!( #key2_type )],
+		};
+
+		for ty in keys.iter().chain(Some(&typ)) {
+			let repr = ty.to_string();
+			if contains_ident(ty.clone(), &generics) && !bounded.contains(&repr) {
+				bounds.extend(quote!( #ty: #scrate::registry::TypeInfo, ));
+				bounded.push(repr);
+			}
+		}
+
+		let str_name = sline.name.to_string();
+		items.push(quote! {
+			#scrate::registry::StorageTypeMetadata {
+				name: #scrate::storage::generator::DecodeDifferent::Encode(#str_name),
+				keys: {
+					let mut key_types = #scrate::rstd::vec::Vec::new();
+					#( key_types.push(registry.register::<#keys>()); )*
+					key_types
+				},
+				value: registry.register::<#typ>(),
+			}
+		});
+	}
+
+	(bounds, quote! {
+		let mut types = #scrate::rstd::vec::Vec::new();
+		#( types.push(#items); )*
+		types
+	})
+}
+
+fn contains_ident(tokens: TokenStream2, idents: &[Ident]) -> bool {
+	tokens.into_iter().any(|token| match token {
+		proc_macro2::TokenTree::Ident(ref ident) => idents.contains(ident),
+		proc_macro2::TokenTree::Group(ref group) => contains_ident(group.stream(), idents),
+		_ => false,
+	})
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct DeclStorageTypeInfos<'a> {
@@ -0,0 +1,224 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `#[derive(TypeInfo)]` implementation.
+//!
+//! The description follows the encoding derived by `parity-codec`: the `compact`, `encoded_as`,
+//! `skip` and `index` codec attributes are taken into account.
+
+use srml_support_procedural_tools::{generate_crate_access, generate_hidden_includes};
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+
+use syn::{
+	Data, DeriveInput, Expr, Field, Fields, Ident, Lit, Meta, NestedMeta, Type, Variant,
+	spanned::Spanned,
+	parse::{Error, Result},
+	parse_macro_input, parse_quote,
+};
+use quote::quote;
+
+pub fn derive_type_info_impl(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match derive_type_info(input) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn derive_type_info(input: DeriveInput) -> Result<TokenStream2> {
+	let unique_id = format!("type_info_{}", input.ident);
+	let scrate = generate_crate_access(&unique_id, "srml-support");
+	let scrate_decl = generate_hidden_includes(&unique_id, "srml-support");
+
+	let ident = &input.ident;
+	let type_params: Vec<Ident> = input.generics.type_params().map(|p| p.ident.clone()).collect();
+	let mut field_types = Vec::new();
+
+	let description = match input.data {
+		Data::Struct(ref data) => {
+			let fields = describe_fields(&scrate, &data.fields, &mut field_types)?;
+			quote! {
+				#scrate::registry::TypeMetadata::composite(
+					concat!(module_path!(), "::", stringify!(#ident)),
+					&[ #( #fields ),* ],
+				)
+			}
+		},
+		Data::Enum(ref data) => {
+			let mut variants = Vec::new();
+			for (i, variant) in data.variants.iter().enumerate() {
+				let name = &variant.ident;
+				let index = variant_index(variant, i)?;
+				let fields = describe_fields(&scrate, &variant.fields, &mut field_types)?;
+				variants.push(quote! {
+					#scrate::registry::VariantMetadata::new(stringify!(#name), #index, &[ #( #fields ),* ])
+				});
+			}
+			quote! {
+				#scrate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::", stringify!(#ident)),
+					&[ #( #variants ),* ],
+				)
+			}
+		},
+		Data::Union(ref data) => {
+			return Err(Error::new(data.union_token.span(), "`TypeInfo` can't be derived for unions"));
+		},
+	};
+
+	// Only the bounds involving the type parameters are needed: the impl fails to compile anyway
+	// if a concrete field type doesn't implement `TypeInfo`.
+	let mut generics = input.generics.clone();
+	{
+		let where_clause = generics.make_where_clause();
+		for param in &type_params {
+			where_clause.predicates.push(parse_quote!(#param: 'static));
+		}
+		let mut bounded = Vec::new();
+		for ty in field_types {
+			let repr = quote!(#ty).to_string();
+			if contains_ident(quote!(#ty), &type_params) && !bounded.contains(&repr) {
+				where_clause.predicates.push(parse_quote!(#ty: #scrate::registry::TypeInfo));
+				bounded.push(repr);
+			}
+		}
+	}
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	Ok(quote! {
+		#scrate_decl
+
+		impl #impl_generics #scrate::registry::TypeInfo for #ident #ty_generics #where_clause {
+			#[allow(unused_variables)]
+			fn type_info(registry: &mut #scrate::registry::Registry) -> #scrate::registry::TypeMetadata {
+				#description
+			}
+		}
+	})
+}
+
+/// The descriptions of the encoded fields, collecting the types which must implement `TypeInfo`.
+fn describe_fields(scrate: &TokenStream2, fields: &Fields, field_types: &mut Vec<Type>) -> Result<Vec<TokenStream2>> {
+	let mut descriptions = Vec::new();
+	for field in fields.iter() {
+		let codec = codec_attributes(field)?;
+		if codec.iter().any(|meta| is_word(meta, "skip")) {
+			continue;
+		}
+
+		let ty = &field.ty;
+		let encoded = if let Some(lit) = codec.iter().filter_map(|meta| name_value(meta, "encoded_as")).next() {
+			let encoded_as: Type = match lit {
+				Lit::Str(ref s) => s.parse()?,
+				_ => return Err(Error::new(lit.span(), "`encoded_as` expects a type in a string")),
+			};
+			field_types.push(encoded_as.clone());
+			quote!( #encoded_as )
+		} else if codec.iter().any(|meta| is_word(meta, "compact")) {
+			field_types.push(ty.clone());
+			quote!( #scrate::codec::Compact<#ty> )
+		} else {
+			field_types.push(ty.clone());
+			quote!( #ty )
+		};
+
+		descriptions.push(match field.ident {
+			Some(ref name) => quote! {
+				#scrate::registry::FieldMetadata::named(stringify!(#name), registry.register::<#encoded>())
+			},
+			None => quote! {
+				#scrate::registry::FieldMetadata::unnamed(registry.register::<#encoded>())
+			},
+		});
+	}
+	Ok(descriptions)
+}
+
+/// The index of a variant in the encoding: its `#[codec(index = "..")]`, its discriminant or its
+/// position, in that order.
+fn variant_index(variant: &Variant, position: usize) -> Result<u8> {
+	let mut codec = Vec::new();
+	for attr in variant.attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+		codec.extend(nested_metas(attr.parse_meta()?));
+	}
+	if let Some(lit) = codec.iter().filter_map(|meta| name_value(meta, "index")).next() {
+		return match lit {
+			Lit::Str(ref s) => s.value().parse()
+				.map_err(|_| Error::new(s.span(), "`index` expects a number between 0 and 255")),
+			Lit::Int(ref i) => to_index(i.value(), i.span()),
+			_ => Err(Error::new(lit.span(), "`index` expects a number between 0 and 255")),
+		};
+	}
+
+	match variant.discriminant {
+		Some((_, Expr::Lit(ref expr))) => match expr.lit {
+			Lit::Int(ref i) => to_index(i.value(), i.span()),
+			_ => Err(Error::new(expr.span(), "Expected an integer discriminant")),
+		},
+		Some((_, ref expr)) => Err(Error::new(expr.span(), "Only literal discriminants are supported")),
+		None => to_index(position as u64, variant.span()),
+	}
+}
+
+fn to_index(value: u64, span: proc_macro2::Span) -> Result<u8> {
+	if value > u8::max_value() as u64 {
+		Err(Error::new(span, "The index of a variant must fit in a byte"))
+	} else {
+		Ok(value as u8)
+	}
+}
+
+fn codec_attributes(field: &Field) -> Result<Vec<Meta>> {
+	let mut metas = Vec::new();
+	for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+		metas.extend(nested_metas(attr.parse_meta()?));
+	}
+	Ok(metas)
+}
+
+fn nested_metas(meta: Meta) -> Vec<Meta> {
+	match meta {
+		Meta::List(list) => list.nested.into_iter().filter_map(|nested| match nested {
+			NestedMeta::Meta(meta) => Some(meta),
+			NestedMeta::Literal(_) => None,
+		}).collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn is_word(meta: &Meta, word: &str) -> bool {
+	match *meta {
+		Meta::Word(ref ident) => ident == word,
+		_ => false,
+	}
+}
+
+fn name_value<'a>(meta: &'a Meta, name: &str) -> Option<&'a Lit> {
+	match *meta {
+		Meta::NameValue(ref nv) if nv.ident == name => Some(&nv.lit),
+		_ => None,
+	}
+}
+
+fn contains_ident(tokens: TokenStream2, idents: &[Ident]) -> bool {
+	tokens.into_iter().any(|token| match token {
+		TokenTree::Ident(ref ident) => idents.contains(ident),
+		TokenTree::Group(ref group) => contains_ident(group.stream(), idents),
+		_ => false,
+	})
+}
@@ -24,8 +24,11 @@ pub use crate::rstd::result;
 pub use crate::codec::{Codec, Decode, Encode, Input, Output, HasCompact, EncodeAsRef};
 pub use srml_metadata::{
 	FunctionMetadata, DecodeDifferent, DecodeDifferentArray,
-	FunctionArgumentMetadata, OuterDispatchMetadata, OuterDispatchCall
+	FunctionArgumentMetadata, OuterDispatchMetadata, OuterDispatchCall,
+	ModuleConstantMetadata, ErrorMetadata, DefaultByte, DefaultByteGetter,
 };
+#[cfg(feature = "std")]
+use serde_derive::Serialize;
 
 /// A type that can not be instantiated.
 pub enum Never {}
@@ -34,6 +37,112 @@ pub enum Never {}
 /// or an error message.
 pub type Result = result::Result<(), &'static str>;
 
+/// Result of dispatching a call; either nothing or the error of the module which failed.
+pub type DispatchResult = result::Result<(), DispatchError>;
+
+/// The error of a dispatched call.
+///
+/// Only the indices are encoded, so that clients can look the error up in the metadata; the message
+/// is kept for the runtime itself and lost on decoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize))]
+pub struct DispatchError {
+	/// Index of the module in the runtime, if known.
+	pub module: Option<u8>,
+	/// Index of the error in the module, `0` for untyped errors.
+	pub error: u8,
+	/// Description of the error.
+	pub message: Option<&'static str>,
+}
+
+impl DispatchError {
+	/// Create an error from the error of a module function.
+	pub fn from_module_error<E: ModuleDispatchError>(error: E) -> Self {
+		DispatchError {
+			module: None,
+			error: error.as_u8(),
+			message: Some(error.as_str()),
+		}
+	}
+
+	/// Set the index of the module which failed, unless it is already set.
+	pub fn with_module(self, module: u8) -> Self {
+		DispatchError {
+			module: self.module.or(Some(module)),
+			..self
+		}
+	}
+}
+
+impl Encode for DispatchError {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.module.encode_to(dest);
+		self.error.encode_to(dest);
+	}
+}
+
+impl Decode for DispatchError {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(DispatchError {
+			module: Decode::decode(input)?,
+			error: Decode::decode(input)?,
+			message: None,
+		})
+	}
+}
+
+impl crate::registry::TypeInfo for DispatchError {
+	fn type_info(registry: &mut crate::registry::Registry) -> crate::registry::TypeMetadata {
+		use crate::registry::{TypeMetadata, FieldMetadata};
+
+		TypeMetadata::composite("srml_support::dispatch::DispatchError", &[
+			FieldMetadata::named("module", registry.register::<Option<u8>>()),
+			FieldMetadata::named("error", registry.register::<u8>()),
+		])
+	}
+}
+
+impl From<&'static str> for DispatchError {
+	fn from(message: &'static str) -> Self {
+		DispatchError::from_module_error(message)
+	}
+}
+
+impl From<DispatchError> for &'static str {
+	fn from(error: DispatchError) -> &'static str {
+		error.message.unwrap_or("Unknown error")
+	}
+}
+
+/// An error returned by module functions, see `decl_error!`.
+pub trait ModuleDispatchError {
+	/// Index of the error in the module, `0` for untyped errors.
+	fn as_u8(&self) -> u8;
+	/// Description of the error.
+	fn as_str(&self) -> &'static str;
+}
+
+impl ModuleDispatchError for &'static str {
+	fn as_u8(&self) -> u8 {
+		0
+	}
+
+	fn as_str(&self) -> &'static str {
+		*self
+	}
+}
+
+/// The metadata of the errors of a module.
+pub trait ModuleErrorMetadata {
+	fn metadata() -> &'static [ErrorMetadata];
+}
+
+impl ModuleErrorMetadata for &'static str {
+	fn metadata() -> &'static [ErrorMetadata] {
+		&[]
+	}
+}
+
 /// A lazy call (module function and argument values) that can be executed via its dispatch()
 /// method.
 pub trait Dispatchable {
@@ -42,7 +151,7 @@ pub trait Dispatchable {
 	/// identifier for the caller. The origin can be empty in the case of an inherent extrinsic.
 	type Origin;
 	type Trait;
-	fn dispatch(self, origin: Self::Origin) -> Result;
+	fn dispatch(self, origin: Self::Origin) -> DispatchResult;
 }
 
 /// Serializable version of Dispatchable.
@@ -92,6 +201,17 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 /// The `on_initialise` and `on_finalise` functions are special, since it can either take no
 /// parameters, or one parameter, which has the runtime's block number type.
 ///
+/// The `on_runtime_upgrade` function is special too: it takes no parameters and is called once,
+/// when the runtime is upgraded to a new `spec_version`. It is the place to migrate the storage
+/// of the module.
+///
+/// Constants of the module are declared with `const NAME: Type = value;` and are exposed, along with
+/// their documentation, in the metadata. The value may use the generic parameters of the module.
+///
+/// The error type of the functions is declared with `type Error = Error;`, usually an enum declared
+/// with `decl_error!`; it defaults to `&'static str`. Functions without an explicit return type
+/// return `Result<(), Error>`, and the error of a dispatched call is turned into a `DispatchError`.
+///
 /// ### Module with instances
 ///
 /// decl_module! support modules with instances with the following syntax: (DefaultInstance type is
@@ -118,6 +238,9 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
+			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -137,6 +260,9 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
+			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -150,6 +276,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event $(<$dpeg:ident $(, $dpeg_instance:ident)?>)* () = default;
@@ -163,6 +292,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -175,6 +307,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event $(<$dpeg:ident $(, $dpeg_instance:ident)?>)* (
@@ -190,6 +325,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -202,6 +340,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{}
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_finalise($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -215,6 +356,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ fn on_finalise( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -227,6 +371,9 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_initialise($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -240,6 +387,9 @@ macro_rules! decl_module {
 			{ fn on_initialise( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -252,6 +402,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn offchain_worker($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -265,6 +418,106 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ fn offchain_worker( $( $param_name : $param ),* ) { $( $impl )* } }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $($t)* ]
+			$($rest)*
+		);
+	};
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialise:tt )* }
+		{ $( $on_finalise:tt )* }
+		{ $( $offchain:tt )* }
+		{}
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		[ $($t:tt)* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_runtime_upgrade() { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialise )* }
+			{ $( $on_finalise )* }
+			{ $( $offchain )* }
+			{ fn on_runtime_upgrade() { $( $impl )* } }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $($t)* ]
+			$($rest)*
+		);
+	};
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialise:tt )* }
+		{ $( $on_finalise:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		[ $($t:tt)* ]
+		$(#[doc = $doc_attr:tt])*
+		const $name:ident: $ty:ty = $value:expr;
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, $instance: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialise )* }
+			{ $( $on_finalise )* }
+			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{
+				$( $constants )*
+				$(#[doc = $doc_attr])*
+				$name: $ty = $value;
+				{ $($instance: $instantiable)? }
+			}
+			{ $( $error_type )* }
+			[ $($t)* ]
+			$($rest)*
+		);
+	};
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialise:tt )* }
+		{ $( $on_finalise:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{}
+		[ $($t:tt)* ]
+		type Error = $error_type:ty;
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialise )* }
+			{ $( $on_finalise )* }
+			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $error_type }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -277,6 +530,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$fn_vis:vis fn $fn_name:ident(
@@ -292,6 +548,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[
 				$($t)*
 				$(#[doc = $doc_attr])*
@@ -311,6 +570,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$fn_vis:vis fn $fn_name:ident(
@@ -332,6 +594,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$fn_vis:vis fn $fn_name:ident(
@@ -353,6 +618,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$fn_vis:vis fn $fn_name:ident(
@@ -368,6 +636,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
 			[
 				$($t)*
 				$(#[doc = $doc_attr])*
@@ -387,6 +658,36 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{}
+		[ $($t:tt)* ]
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialise )* }
+			{ $( $on_finalise )* }
+			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ &'static str }
+			[ $($t)* ]
+		);
+	};
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialise:tt )* }
+		{ $( $on_finalise:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $error_type:ty }
 		[ $($t:tt)* ]
 	) => {
 		$crate::decl_module!(@imp
@@ -399,6 +700,9 @@ macro_rules! decl_module {
 			{ $( $on_initialise )* }
 			{ $( $on_finalise )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $error_type }
 		);
 	};
 
@@ -553,15 +857,87 @@ macro_rules! decl_module {
 		{}
 	};
 
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		fn on_runtime_upgrade() { $( $impl:tt )* }
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::runtime_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?>
+		{
+			fn on_runtime_upgrade() { $( $impl )* }
+		}
+	};
+
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::runtime_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?>
+		{}
+	};
+
+	(@impl_module_constants_metadata
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		$(
+			$(#[doc = $doc_attr:tt])*
+			$name:ident: $ty:ty = $value:expr;
+			{ $( $const_instance:ident: $const_instantiable:path )? }
+		)*
+	) => {
+		$crate::paste::item! {
+			$(
+				#[doc(hidden)]
+				#[allow(non_camel_case_types)]
+				pub struct [<__GetConstant $name>]<$trait_instance $(, $const_instance)?>(
+					pub $crate::rstd::marker::PhantomData<($trait_instance $(, $const_instance)?)>
+				);
+
+				impl<$trait_instance: $trait_name $(<$const_instance>, $const_instance: $const_instantiable)?>
+					$crate::dispatch::DefaultByte for [<__GetConstant $name>]<$trait_instance $(, $const_instance)?>
+				{
+					fn default_byte(&self) -> $crate::dispatch::Vec<u8> {
+						let value: $ty = $value;
+						$crate::dispatch::Encode::encode(&value)
+					}
+				}
+			)*
+
+			impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?> $module<$trait_instance $(, $instance)?> {
+				#[doc(hidden)]
+				pub fn module_constants_metadata() -> &'static [$crate::dispatch::ModuleConstantMetadata] {
+					&[
+						$(
+							$crate::dispatch::ModuleConstantMetadata {
+								name: $crate::dispatch::DecodeDifferent::Encode(stringify!($name)),
+								ty: $crate::dispatch::DecodeDifferent::Encode(stringify!($ty)),
+								value: $crate::dispatch::DecodeDifferent::Encode(
+									$crate::dispatch::DefaultByteGetter(
+										&[<__GetConstant $name>]::<$trait_instance $(, $const_instance)?>(
+											$crate::rstd::marker::PhantomData
+										)
+									)
+								),
+								documentation: $crate::dispatch::DecodeDifferent::Encode(&[ $( $doc_attr ),* ]),
+							}
+						),*
+					]
+				}
+			}
+		}
+	};
+
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		root;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident ( root $(, $param:ident : $param_ty:ty )* ) { $( $impl:tt )* }
 	) => {
 		$(#[doc = $doc_attr])*
-		$vis fn $name($( $param: $param_ty ),* ) -> $crate::dispatch::Result {
+		$vis fn $name($( $param: $param_ty ),* ) -> $crate::dispatch::result::Result<(), $error_type> {
 			{ $( $impl )* }
 			Ok(())
 		}
@@ -570,6 +946,7 @@ macro_rules! decl_module {
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		root;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident (
@@ -585,6 +962,7 @@ macro_rules! decl_module {
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident (
@@ -594,7 +972,7 @@ macro_rules! decl_module {
 		$(#[doc = $doc_attr])*
 		$vis fn $name(
 			$origin: $origin_ty $(, $param: $param_ty )*
-		) -> $crate::dispatch::Result {
+		) -> $crate::dispatch::result::Result<(), $error_type> {
 			{ $( $impl )* }
 			Ok(())
 		}
@@ -603,6 +981,7 @@ macro_rules! decl_module {
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident (
@@ -633,6 +1012,9 @@ macro_rules! decl_module {
 		{ $( $on_initialise:tt )* }
 		{ $( $on_finalise:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{ $error_type:ty }
 	) => {
 		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 		#[derive(Clone, Copy, PartialEq, Eq)]
@@ -667,6 +1049,12 @@ macro_rules! decl_module {
 			$( $offchain )*
 		}
 
+		$crate::decl_module! {
+			@impl_on_runtime_upgrade
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			$( $on_runtime_upgrade )*
+		}
+
 		$crate::decl_module! {
 			@impl_deposit_event
 			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
@@ -683,6 +1071,7 @@ macro_rules! decl_module {
 					@impl_function
 					$mod_type<$trait_instance: $trait_name $(<I>, $fn_instance: $fn_instantiable)?>;
 					$origin_type;
+					$error_type;
 					$from;
 					$(#[doc = $doc_attr])*
 					$fn_vis fn $fn_name (
@@ -787,20 +1176,55 @@ macro_rules! decl_module {
 				$crate::__impl_encode!(_dest; *self; 0; $call_type; $( fn $fn_name( $( $(#[$codec_attr on type $param])* $param_name ),* ); )*);
 			}
 		}
+
+		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::registry::TypeInfo
+			for $call_type<$trait_instance $(, $instance)?>
+		where
+			$trait_instance: 'static,
+			$( $instance: 'static, )?
+			$( $( $param: $crate::registry::TypeInfo, )* )*
+		{
+			fn type_info(_registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				// The index of a call is its position in the module, as in the encoding.
+				#[allow(non_camel_case_types, dead_code)]
+				enum CallIndex { $( $fn_name, )* }
+
+				$crate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::", stringify!($call_type)),
+					&[
+						$(
+							$crate::registry::VariantMetadata::new(
+								stringify!($fn_name),
+								CallIndex::$fn_name as u8,
+								&[
+									$(
+										$crate::registry::FieldMetadata::named(
+											stringify!($param_name),
+											$crate::__impl_register_type!(_registry; $(#[$codec_attr on type $param])* $param),
+										)
+									),*
+								],
+							)
+						),*
+					],
+				)
+			}
+		}
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::dispatch::Dispatchable
 			for $call_type<$trait_instance $(, $instance)?>
 		{
 			type Trait = $trait_instance;
 			type Origin = $origin_type;
-			fn dispatch(self, _origin: Self::Origin) -> $crate::dispatch::Result {
+			fn dispatch(self, _origin: Self::Origin) -> $crate::dispatch::DispatchResult {
 				match self {
 					$(
 						$call_type::$fn_name( $( $param_name ),* ) => {
-							$crate::decl_module!(
+							let result = $crate::decl_module!(
 								@call
 								$from
 								$mod_type<$trait_instance $(, $fn_instance)?> $fn_name _origin $system [ $( $param_name ),* ]
-							)
+							);
+							result.map_err($crate::dispatch::DispatchError::from_module_error)
 						},
 					)*
 					$call_type::__PhantomItem(_, _) => { unreachable!("__PhantomItem should never be used.") },
@@ -815,9 +1239,19 @@ macro_rules! decl_module {
 
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $mod_type<$trait_instance $(, $instance)?> {
 			#[doc(hidden)]
-			pub fn dispatch<D: $crate::dispatch::Dispatchable<Trait = $trait_instance>>(d: D, origin: D::Origin) -> $crate::dispatch::Result {
+			pub fn dispatch<D: $crate::dispatch::Dispatchable<Trait = $trait_instance>>(d: D, origin: D::Origin) -> $crate::dispatch::DispatchResult {
 				d.dispatch(origin)
 			}
+
+			#[doc(hidden)]
+			pub fn module_errors_metadata() -> &'static [$crate::dispatch::ErrorMetadata] {
+				<$error_type as $crate::dispatch::ModuleErrorMetadata>::metadata()
+			}
+		}
+		$crate::decl_module! {
+			@impl_module_constants_metadata
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			$( $constants )*
 		}
 		$crate::__dispatch_impl_metadata! {
 			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $call_type $origin_type
@@ -951,6 +1385,24 @@ macro_rules! __impl_encode {
 	};
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __impl_register_type {
+	(
+		$registry:ident;
+		#[compact on type $param:ty]
+		$ty:ty
+	) => {
+		$registry.register::<$crate::codec::Compact<$param>>()
+	};
+	(
+		$registry:ident;
+		$ty:ty
+	) => {
+		$registry.register::<$ty>()
+	};
+}
+
 pub trait IsSubType<T: Callable> {
 	fn is_aux_sub_type(&self) -> Option<&<T as Callable>::Call>;
 }
@@ -975,13 +1427,40 @@ macro_rules! impl_outer_dispatch {
 			,)*
 		}
 		$crate::__impl_outer_dispatch_common! { $call_type, $($camelcase,)* }
+		impl $crate::registry::TypeInfo for $call_type {
+			#[allow(unused_variables)]
+			fn type_info(registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				#[allow(non_camel_case_types, dead_code)]
+				enum ModuleIndex { $( $camelcase, )* }
+
+				$crate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::", stringify!($call_type)),
+					&[
+						$(
+							$crate::registry::VariantMetadata::new(
+								stringify!($camelcase),
+								ModuleIndex::$camelcase as u8,
+								&[$crate::registry::FieldMetadata::unnamed(
+									registry.register::<$crate::dispatch::CallableCallFor<$camelcase>>()
+								)],
+							)
+						),*
+					],
+				)
+			}
+		}
 		impl $crate::dispatch::Dispatchable for $call_type {
 			type Origin = $origin;
 			type Trait = $call_type;
-			fn dispatch(self, origin: $origin) -> $crate::dispatch::Result {
+			fn dispatch(self, origin: $origin) -> $crate::dispatch::DispatchResult {
+				// The index of a module is the index of its calls in the encoding of `$call_type`.
+				#[allow(non_camel_case_types)]
+				enum ModuleIndex { $( $camelcase, )* }
+
 				match self {
 					$(
-						$call_type::$camelcase(call) => call.dispatch(origin),
+						$call_type::$camelcase(call) => call.dispatch(origin)
+							.map_err(|e| e.with_module(ModuleIndex::$camelcase as u8)),
 					)*
 				}
 			}
@@ -996,6 +1475,12 @@ macro_rules! impl_outer_dispatch {
 					}
 				}
 			}
+
+			impl From<$crate::dispatch::CallableCallFor<$camelcase>> for $call_type {
+				fn from(call: $crate::dispatch::CallableCallFor<$camelcase>) -> Self {
+					$call_type::$camelcase(call)
+				}
+			}
 		)*
 	}
 }
@@ -1143,7 +1628,7 @@ macro_rules! __function_to_metadata {
 #[allow(dead_code)]
 mod tests {
 	use super::*;
-	use crate::runtime_primitives::traits::{OnInitialise, OnFinalise};
+	use crate::runtime_primitives::traits::{OnInitialise, OnFinalise, OnRuntimeUpgrade};
 
 	pub trait Trait {
 		type Origin;
@@ -1170,6 +1655,7 @@ mod tests {
 			fn on_initialise(n: T::BlockNumber) { if n.into() == 42 { panic!("on_initialise") } }
 			fn on_finalise(n: T::BlockNumber) { if n.into() == 42 { panic!("on_finalise") } }
 			fn offchain_worker() {}
+			fn on_runtime_upgrade() { panic!("on_runtime_upgrade") }
 		}
 	}
 
@@ -1253,4 +1739,10 @@ mod tests {
 	fn on_finalise_should_work() {
 		<Module<TraitImpl> as OnFinalise<u32>>::on_finalise(42);
 	}
+
+	#[test]
+	#[should_panic(expected = "on_runtime_upgrade")]
+	fn on_runtime_upgrade_should_work() {
+		<Module<TraitImpl> as OnRuntimeUpgrade>::on_runtime_upgrade();
+	}
 }
@@ -0,0 +1,176 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Macro for declaring the typed errors of a module.
+
+pub use srml_metadata::{ErrorMetadata, DecodeDifferent};
+pub use crate::dispatch::{ModuleDispatchError, ModuleErrorMetadata};
+
+/// Declare the error type of a module.
+///
+/// The enum gets an `Other(&'static str)` variant at index 0 for untyped errors, so that `ensure!`
+/// with a message and `?` on functions returning `dispatch::Result` keep working. The declared
+/// errors are indexed from 1 in the order of declaration and documented in the metadata once the
+/// enum is set as the error type of the module with `type Error = Error;` in `decl_module!`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate srml_support;
+/// decl_error! {
+///     /// Errors of this module.
+///     pub enum Error {
+///         /// The value is too low.
+///         TooLow,
+///         /// The value is too high.
+///         TooHigh,
+///     }
+/// }
+///
+/// pub trait Trait {
+///     type Origin;
+///     type BlockNumber;
+/// }
+///
+/// decl_module! {
+///     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+///         type Error = Error;
+///
+///         fn check(_origin, value: u32) {
+///             ensure!(value > 10, Error::TooLow);
+///             ensure!(value < 20, Error::TooHigh);
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! decl_error {
+	(
+		$(#[$attr:meta])*
+		pub enum $error:ident {
+			$(
+				$(#[doc = $doc_attr:tt])*
+				$name:ident
+			),*
+			$(,)?
+		}
+	) => {
+		$(#[$attr])*
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug))]
+		pub enum $error {
+			/// An untyped error.
+			Other(&'static str),
+			$(
+				$(#[doc = $doc_attr])*
+				$name
+			),*
+		}
+
+		impl $crate::error::ModuleDispatchError for $error {
+			fn as_u8(&self) -> u8 {
+				// The index of `Other` is 0, the declared errors follow.
+				#[allow(non_camel_case_types)]
+				enum Index { Other, $( $name, )* }
+
+				match *self {
+					$error::Other(_) => Index::Other as u8,
+					$(
+						$error::$name => Index::$name as u8,
+					)*
+				}
+			}
+
+			fn as_str(&self) -> &'static str {
+				match *self {
+					$error::Other(message) => message,
+					$(
+						$error::$name => stringify!($name),
+					)*
+				}
+			}
+		}
+
+		impl From<&'static str> for $error {
+			fn from(message: &'static str) -> Self {
+				$error::Other(message)
+			}
+		}
+
+		impl From<$error> for &'static str {
+			fn from(error: $error) -> &'static str {
+				$crate::error::ModuleDispatchError::as_str(&error)
+			}
+		}
+
+		impl $crate::error::ModuleErrorMetadata for $error {
+			fn metadata() -> &'static [$crate::error::ErrorMetadata] {
+				&[
+					$(
+						$crate::error::ErrorMetadata {
+							name: $crate::error::DecodeDifferent::Encode(stringify!($name)),
+							documentation: $crate::error::DecodeDifferent::Encode(&[ $( $doc_attr ),* ]),
+						}
+					),*
+				]
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::DecodeDifferent;
+	use crate::dispatch::{DispatchError, ModuleDispatchError, ModuleErrorMetadata};
+	use crate::codec::{Encode, Decode};
+
+	decl_error! {
+		pub enum Error {
+			/// First error.
+			First,
+			Second,
+		}
+	}
+
+	#[test]
+	fn errors_are_indexed_from_one() {
+		assert_eq!(Error::Other("other").as_u8(), 0);
+		assert_eq!(Error::First.as_u8(), 1);
+		assert_eq!(Error::Second.as_u8(), 2);
+		assert_eq!(Error::Second.as_str(), "Second");
+		assert_eq!(<&'static str>::from(Error::from("other")), "other");
+	}
+
+	#[test]
+	fn errors_are_documented() {
+		let metadata = Error::metadata();
+		assert_eq!(metadata.len(), 2);
+		assert_eq!(metadata[0].name, DecodeDifferent::Encode("First"));
+		assert_eq!(metadata[0].documentation, DecodeDifferent::Encode(&[" First error."][..]));
+		assert_eq!(metadata[1].name, DecodeDifferent::Encode("Second"));
+	}
+
+	#[test]
+	fn dispatch_error_encodes_indices() {
+		let error = DispatchError::from_module_error(Error::Second).with_module(3);
+		assert_eq!(error.message, Some("Second"));
+		assert_eq!(error.encode(), vec![1, 3, 2]);
+
+		let decoded = DispatchError::decode(&mut &error.encode()[..]).unwrap();
+		assert_eq!(decoded, DispatchError { message: None, ..error });
+		assert_eq!(error.with_module(4).module, Some(3));
+	}
+}
@@ -161,6 +161,14 @@ macro_rules! decl_event {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
 		}
+		impl $crate::registry::TypeInfo for Event {
+			fn type_info(_registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				$crate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::Event"),
+					$crate::__events_to_type_info!(_registry; ; 0; $( $events )* ),
+				)
+			}
+		}
 	}
 }
 
@@ -304,12 +312,57 @@ macro_rules! __decl_generic_event {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
 		}
+		impl<$( $generic_param ),* $(, $instance)?> $crate::registry::TypeInfo
+			for RawEvent<$( $generic_param ),* $(, $instance)?>
+		where
+			$( $generic_param: $crate::registry::TypeInfo, )*
+			$( $instance: 'static, )?
+		{
+			fn type_info(_registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				$crate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::RawEvent"),
+					$crate::__events_to_type_info!(
+						_registry; ; 0; $( $events )* $( PhantomData($crate::rstd::marker::PhantomData<$instance>), )?
+					),
+				)
+			}
+		}
 	};
 	(@cannot_parse $ty:ty) => {
 		compile_error!(concat!("The type `", stringify!($ty), "` can't be parsed as an unnamed one, please name it `Name = ", stringify!($ty), "`"));
 	}
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __events_to_type_info {
+	(
+		$registry:ident;
+		$( $variant:expr ),*;
+		$index:expr;
+		$( #[doc = $doc_attr:tt] )*
+		$event:ident $( ( $( $param:path ),* ) )*,
+		$( $rest:tt )*
+	) => {
+		$crate::__events_to_type_info!(
+			$registry;
+			$( $variant, )*
+			$crate::registry::VariantMetadata::new(stringify!($event), $index, &[
+				$( $( $crate::registry::FieldMetadata::unnamed($registry.register::<$param>()) ),* )*
+			]);
+			$index + 1;
+			$( $rest )*
+		)
+	};
+	(
+		$registry:ident;
+		$( $variant:expr ),*;
+		$index:expr;
+	) => {
+		&[ $( $variant ),* ]
+	};
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __events_to_metadata {
@@ -452,6 +505,30 @@ macro_rules! impl_outer_event {
 				}
 			}
 		)*
+		impl $crate::registry::TypeInfo for $name {
+			fn type_info(registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				#[allow(non_camel_case_types, dead_code)]
+				enum EventIndex { system, $( $module_name, )* }
+
+				$crate::registry::TypeMetadata::variant(
+					concat!(module_path!(), "::", stringify!($name)),
+					&[
+						$crate::registry::VariantMetadata::new("system", EventIndex::system as u8, &[
+							$crate::registry::FieldMetadata::unnamed(registry.register::<$system::Event>()),
+						]),
+						$(
+							$crate::registry::VariantMetadata::new(
+								stringify!($module_name),
+								EventIndex::$module_name as u8,
+								&[$crate::registry::FieldMetadata::unnamed(
+									registry.register::<$module_name::Event $( <$generic_param $(, $generic_instance)? > )*>()
+								)],
+							),
+						)*
+					],
+				)
+			}
+		}
 		$crate::__impl_outer_event_json_metadata!(
 			$runtime;
 			$name;
@@ -492,6 +569,10 @@ macro_rules! __impl_outer_event_json_metadata {
 			pub fn __module_events_system() -> &'static [$crate::event::EventMetadata] {
 				system::Event::metadata()
 			}
+			#[allow(dead_code)]
+			pub fn __module_event_type_system(registry: &mut $crate::registry::Registry) -> $crate::registry::TypeId {
+				registry.register::<system::Event>()
+			}
 			$(
 				#[allow(dead_code)]
 				$crate::paste::item!{
@@ -499,6 +580,14 @@ macro_rules! __impl_outer_event_json_metadata {
 						$module_name::Event $( ::<$generic_param $(, $generic_instance)? > )* ::metadata()
 					}
 				}
+				#[allow(dead_code)]
+				$crate::paste::item!{
+					pub fn [< __module_event_type_ $module_name >] (
+						registry: &mut $crate::registry::Registry,
+					) -> $crate::registry::TypeId {
+						registry.register::<$module_name::Event $( <$generic_param $(, $generic_instance)? > )*>()
+					}
+				}
 			)*
 		}
 	}
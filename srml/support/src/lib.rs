@@ -46,9 +46,12 @@ mod hashable;
 #[macro_use]
 pub mod event;
 #[macro_use]
+pub mod error;
+#[macro_use]
 mod origin;
 #[macro_use]
 pub mod metadata;
+pub mod registry;
 #[macro_use]
 mod runtime;
 #[macro_use]
@@ -59,16 +62,17 @@ pub mod traits;
 pub use self::storage::{StorageVec, StorageList, StorageValue, StorageMap, EnumerableStorageMap, StorageDoubleMap};
 pub use self::hashable::Hashable;
 pub use self::dispatch::{Parameter, Dispatchable, Callable, IsSubType};
+pub use self::registry::TypeInfo;
 pub use self::double_map::StorageDoubleMapWithHasher;
 pub use runtime_io::print;
 
 #[doc(inline)]
-pub use srml_support_procedural::decl_storage;
+pub use srml_support_procedural::{decl_storage, TypeInfo};
 
 #[macro_export]
 macro_rules! fail {
 	( $y:expr ) => {{
-		return Err($y);
+		return Err($y.into());
 	}}
 }
 
@@ -95,7 +99,7 @@ macro_rules! assert_noop {
 #[cfg(feature = "std")]
 macro_rules! assert_err {
 	( $x:expr , $y:expr ) => {
-		assert_eq!($x, Err($y));
+		assert_eq!($x, Err($y.into()));
 	}
 }
 
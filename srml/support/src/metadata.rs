@@ -16,8 +16,8 @@
 
 pub use srml_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata,
-	ModuleMetadata, RuntimeMetadataV3,
-	DefaultByteGetter, RuntimeMetadataPrefixed,
+	ModuleMetadata, RuntimeMetadataV5, ModuleConstantMetadata, ErrorMetadata,
+	DefaultByteGetter, RuntimeMetadataPrefixed, ModuleTypesMetadata,
 };
 
 /// Implements the metadata support for the given runtime and all its modules.
@@ -28,6 +28,9 @@ pub use srml_metadata::{
 /// ```
 ///
 /// In this example, just `MODULE3` implements the `Storage` trait.
+///
+/// The types of the calls, events and storage items of the modules are described in the type
+/// registry of the metadata, so they must implement `TypeInfo`.
 #[macro_export]
 macro_rules! impl_runtime_metadata {
 	(
@@ -36,9 +39,12 @@ macro_rules! impl_runtime_metadata {
 	) => {
 		impl $runtime {
 			pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
-				$crate::metadata::RuntimeMetadata::V3 (
-					$crate::metadata::RuntimeMetadataV3 {
-						modules: $crate::__runtime_modules_to_metadata!($runtime;; $( $rest )*),
+				let mut registry = $crate::registry::Registry::new();
+				let modules = $crate::__runtime_modules_to_metadata!($runtime; registry;; $( $rest )*);
+				$crate::metadata::RuntimeMetadata::V5 (
+					$crate::metadata::RuntimeMetadataV5 {
+						types: registry.into_types(),
+						modules,
 					}
 				).into()
 			}
@@ -51,27 +57,49 @@ macro_rules! impl_runtime_metadata {
 macro_rules! __runtime_modules_to_metadata {
 	(
 		$runtime: ident;
+		$registry: ident;
 		$( $metadata:expr ),*;
 		$mod:ident::$module:ident $( < $instance:ident > )? $(with)+ $($kw:ident)*,
 		$( $rest:tt )*
 	) => {
 		$crate::__runtime_modules_to_metadata!(
 			$runtime;
+			$registry;
 			$( $metadata, )* $crate::metadata::ModuleMetadata {
 				name: $crate::metadata::DecodeDifferent::Encode(stringify!($mod)),
 				prefix: $crate::__runtime_modules_to_metadata_calls_storagename!($mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
 				storage: $crate::__runtime_modules_to_metadata_calls_storage!($mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
 				calls: $crate::__runtime_modules_to_metadata_calls_call!($mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
 				event: $crate::__runtime_modules_to_metadata_calls_event!($mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
+				constants: $crate::metadata::DecodeDifferent::Encode(
+					$crate::metadata::FnEncode(
+						$mod::$module::<$runtime $(, $mod::$instance )?>::module_constants_metadata
+					)
+				),
+				errors: $crate::metadata::DecodeDifferent::Encode(
+					$crate::metadata::FnEncode(
+						$mod::$module::<$runtime $(, $mod::$instance )?>::module_errors_metadata
+					)
+				),
+				types: $crate::metadata::ModuleTypesMetadata {
+					call: $crate::__runtime_modules_to_metadata_types_call!($registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
+					event: $crate::__runtime_modules_to_metadata_types_event!($registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
+					storage: $crate::__runtime_modules_to_metadata_types_storage!($registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*),
+				},
 			};
 			$( $rest )*
 		)
 	};
 	(
 		$runtime:ident;
+		$registry:ident;
 		$( $metadata:expr ),*;
 	) => {
-		$crate::metadata::DecodeDifferent::Encode(&[ $( $metadata ),* ])
+		{
+			let mut modules = $crate::rstd::vec::Vec::new();
+			$( modules.push($metadata); )*
+			modules
+		}
 	};
 }
 
@@ -226,6 +254,118 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 	};
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_types_call {
+	// skip system
+	(
+		$registry: ident;
+		system,
+		$skip_module: ident $( <$instance:ident> )?,
+		$skip_runtime: ident,
+		with Call
+		$(with $kws:ident)*
+	) => {
+		None
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Call
+		$(with $kws:ident)*
+	) => {
+		Some($registry.register::<$mod::Call<$runtime $(, $mod::$instance )?>>())
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_types_call!( $registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)* );
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_types_event {
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Event
+		$(with $kws:ident)*
+	) => {
+		Some($crate::paste::expr!{
+			$runtime:: [< __module_event_type_ $mod $(_ $instance)?>](&mut $registry)
+		})
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_types_event!( $registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)* );
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_types_storage {
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Storage
+		$(with $kws:ident)*
+	) => {
+		$mod::$module::<$runtime $(, $mod::$instance )?>::store_metadata_types(&mut $registry)
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_types_storage!( $registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)* );
+	};
+	(
+		$registry: ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		$crate::rstd::vec::Vec::new()
+	};
+}
+
 
 #[cfg(test)]
 // Do not complain about unused `dispatch` and `dispatch_aux`.
@@ -238,6 +378,9 @@ mod tests {
 		StorageFunctionMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed
 	};
+	use crate::registry::{
+		TypeMetadata, TypeDef, PrimitiveType, FieldMetadata, VariantMetadata, StorageTypeMetadata,
+	};
 	use crate::codec::{Encode, Decode};
 
 	mod system {
@@ -293,8 +436,20 @@ mod tests {
 			}
 		);
 
+		decl_error! {
+			pub enum Error {
+				/// Hi, I am an error.
+				TestError,
+			}
+		}
+
 		decl_module! {
 			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				type Error = Error;
+
+				/// Hi, I am a constant.
+				const TestConstant: u32 = 42;
+
 				fn aux_0(_origin) -> Result { unreachable!() }
 			}
 		}
@@ -377,9 +532,25 @@ mod tests {
 			event_module2::Module with Event Storage Call,
 	);
 
-	const EXPECTED_METADATA: RuntimeMetadata = RuntimeMetadata::V3(
-		RuntimeMetadataV3 {
-		modules: DecodeDifferent::Encode(&[
+	fn expected_metadata() -> RuntimeMetadata { RuntimeMetadata::V5(
+		RuntimeMetadataV5 {
+		types: vec![
+			TypeMetadata::variant("srml_support::metadata::tests::system::Event", &[
+				VariantMetadata::new("SystemEvent", 0, &[]),
+			]),
+			TypeMetadata::variant("srml_support::metadata::tests::event_module::Call", &[
+				VariantMetadata::new("aux_0", 0, &[]),
+			]),
+			TypeMetadata::variant("srml_support::metadata::tests::event_module::RawEvent", &[
+				VariantMetadata::new("TestEvent", 0, &[FieldMetadata::unnamed(3)]),
+			]),
+			TypeMetadata::new("", TypeDef::Primitive(PrimitiveType::U32)),
+			TypeMetadata::variant("srml_support::metadata::tests::event_module2::Call", &[]),
+			TypeMetadata::variant("srml_support::metadata::tests::event_module2::RawEvent", &[
+				VariantMetadata::new("TestEvent", 0, &[FieldMetadata::unnamed(3)]),
+			]),
+		],
+		modules: vec![
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("system"),
 				prefix: DecodeDifferent::Encode(FnEncode(||"")),
@@ -394,6 +565,13 @@ mod tests {
 						}
 			 		])
 				)),
+				constants: DecodeDifferent::Encode(FnEncode(||&[])),
+				errors: DecodeDifferent::Encode(FnEncode(||&[])),
+				types: ModuleTypesMetadata {
+					call: None,
+					event: Some(0),
+					storage: vec![],
+				},
 			},
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("event_module"),
@@ -416,6 +594,33 @@ mod tests {
 						}
 			 		])
 				)),
+				constants: DecodeDifferent::Encode(
+					FnEncode(||&[
+						ModuleConstantMetadata {
+							name: DecodeDifferent::Encode("TestConstant"),
+							ty: DecodeDifferent::Encode("u32"),
+							value: DecodeDifferent::Encode(
+								DefaultByteGetter(
+									&event_module::__GetConstantTestConstant(::std::marker::PhantomData::<TestRuntime>)
+								)
+							),
+							documentation: DecodeDifferent::Encode(&[" Hi, I am a constant."]),
+						}
+					])
+				),
+				errors: DecodeDifferent::Encode(
+					FnEncode(||&[
+						ErrorMetadata {
+							name: DecodeDifferent::Encode("TestError"),
+							documentation: DecodeDifferent::Encode(&[" Hi, I am an error."]),
+						}
+					])
+				),
+				types: ModuleTypesMetadata {
+					call: Some(1),
+					event: Some(2),
+					storage: vec![],
+				},
 			},
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("event_module2"),
@@ -445,15 +650,28 @@ mod tests {
 						}
 			 		])
 				)),
+				constants: DecodeDifferent::Encode(FnEncode(||&[])),
+				errors: DecodeDifferent::Encode(FnEncode(||&[])),
+				types: ModuleTypesMetadata {
+					call: Some(4),
+					event: Some(5),
+					storage: vec![
+						StorageTypeMetadata {
+							name: DecodeDifferent::Encode("StorageMethod"),
+							keys: vec![],
+							value: 3,
+						},
+					],
+				},
 			},
-		])}
-	);
+		]}
+	)}
 
 	#[test]
 	fn runtime_metadata() {
 		let metadata_encoded = TestRuntime::metadata().encode();
 		let metadata_decoded = RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]);
-		let expected_metadata: RuntimeMetadataPrefixed = EXPECTED_METADATA.into();
+		let expected_metadata: RuntimeMetadataPrefixed = expected_metadata().into();
 
 		assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
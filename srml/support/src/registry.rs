@@ -0,0 +1,371 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The type registry of the runtime metadata.
+//!
+//! Every type used by the calls, events and storage of the modules is described structurally in
+//! the metadata and referenced by its `TypeId`, so that clients can encode and decode them without
+//! knowing the runtime. Types describe themselves by implementing `TypeInfo`, usually with
+//! `#[derive(TypeInfo)]`.
+
+use core::any::TypeId as AnyTypeId;
+use crate::rstd::prelude::*;
+use crate::rstd::collections::btree_map::BTreeMap;
+use crate::rstd::marker::PhantomData;
+use crate::codec::Compact;
+use crate::runtime_primitives::{Perbill, Permill, Perquintill, AnySignature, MultiSignature, generic};
+use substrate_primitives::{H160, H256, H512, U256, ChangesTrieConfiguration, ed25519, sr25519};
+
+pub use srml_metadata::{
+	TypeId, TypeMetadata, TypeDef, FieldMetadata, VariantMetadata, PrimitiveType,
+	ModuleTypesMetadata, StorageTypeMetadata,
+};
+
+/// A type which can describe how it is encoded.
+pub trait TypeInfo: 'static {
+	/// Describe the type, registering the types it is made of in `registry`.
+	fn type_info(registry: &mut Registry) -> TypeMetadata;
+}
+
+/// The types registered while building the metadata.
+#[derive(Default)]
+pub struct Registry {
+	ids: BTreeMap<AnyTypeId, TypeId>,
+	types: Vec<TypeMetadata>,
+}
+
+impl Registry {
+	/// Create an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register `T` and the types it is made of, returning its id.
+	///
+	/// A type is only described once; registering it again returns the same id.
+	pub fn register<T: TypeInfo + ?Sized>(&mut self) -> TypeId {
+		let any_id = AnyTypeId::of::<T>();
+		if let Some(id) = self.ids.get(&any_id) {
+			return *id;
+		}
+
+		// The id is reserved before describing the type, so that recursive types (e.g. a call
+		// taking a boxed call) refer to themselves.
+		let id = self.types.len() as TypeId;
+		self.ids.insert(any_id, id);
+		self.types.push(TypeMetadata::new("", TypeDef::Tuple(Vec::new())));
+		self.types[id as usize] = T::type_info(self);
+		id
+	}
+
+	/// The descriptions of the registered types, indexed by their id.
+	pub fn into_types(self) -> Vec<TypeMetadata> {
+		self.types
+	}
+}
+
+macro_rules! impl_primitives {
+	( $( $ty:ty => $primitive:ident, )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				fn type_info(_: &mut Registry) -> TypeMetadata {
+					TypeMetadata::new("", TypeDef::Primitive(PrimitiveType::$primitive))
+				}
+			}
+		)*
+	}
+}
+
+impl_primitives! {
+	bool => Bool,
+	char => Char,
+	u8 => U8,
+	u16 => U16,
+	u32 => U32,
+	u64 => U64,
+	u128 => U128,
+	i8 => I8,
+	i16 => I16,
+	i32 => I32,
+	i64 => I64,
+	i128 => I128,
+}
+
+#[cfg(feature = "std")]
+impl TypeInfo for String {
+	fn type_info(_: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Primitive(PrimitiveType::Str))
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Vec<T> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Sequence(registry.register::<T>()))
+	}
+}
+
+impl<K: TypeInfo, V: TypeInfo> TypeInfo for BTreeMap<K, V> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Sequence(registry.register::<(K, V)>()))
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Box<T> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		// Described as a wrapper rather than as `T` itself, which might still be being described.
+		TypeMetadata::composite("", &[FieldMetadata::unnamed(registry.register::<T>())])
+	}
+}
+
+impl<T: ?Sized + 'static> TypeInfo for PhantomData<T> {
+	fn type_info(_: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Tuple(Vec::new()))
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Compact<T> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Compact(registry.register::<T>()))
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Option<T> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::variant("Option", &[
+			VariantMetadata::new("None", 0, &[]),
+			VariantMetadata::new("Some", 1, &[FieldMetadata::unnamed(registry.register::<T>())]),
+		])
+	}
+}
+
+impl<T: TypeInfo, E: TypeInfo> TypeInfo for Result<T, E> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::variant("Result", &[
+			VariantMetadata::new("Ok", 0, &[FieldMetadata::unnamed(registry.register::<T>())]),
+			VariantMetadata::new("Err", 1, &[FieldMetadata::unnamed(registry.register::<E>())]),
+		])
+	}
+}
+
+macro_rules! impl_tuples {
+	( $first:ident, ) => {
+		impl_tuples!(@impl $first,);
+	};
+	( $first:ident, $( $rest:ident, )+ ) => {
+		impl_tuples!(@impl $first, $( $rest, )+);
+		impl_tuples!($( $rest, )+);
+	};
+	(@impl $( $name:ident, )+) => {
+		impl<$( $name: TypeInfo ),+> TypeInfo for ($( $name, )+) {
+			fn type_info(registry: &mut Registry) -> TypeMetadata {
+				TypeMetadata::new("", TypeDef::Tuple(vec![ $( registry.register::<$name>() ),+ ]))
+			}
+		}
+	};
+}
+
+impl TypeInfo for () {
+	fn type_info(_: &mut Registry) -> TypeMetadata {
+		TypeMetadata::new("", TypeDef::Tuple(Vec::new()))
+	}
+}
+
+impl_tuples!(A, B, C, D, E, F, G, H, I, J, K, L,);
+
+macro_rules! impl_arrays {
+	( $( $len:expr ),* ) => {
+		$(
+			impl<T: TypeInfo> TypeInfo for [T; $len] {
+				fn type_info(registry: &mut Registry) -> TypeMetadata {
+					TypeMetadata::new("", TypeDef::Array { len: $len, ty: registry.register::<T>() })
+				}
+			}
+		)*
+	}
+}
+
+impl_arrays!(
+	1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+	27, 28, 29, 30, 31, 32, 40, 48, 56, 64, 72, 96, 128, 160, 192, 224, 256, 384, 512, 768, 1024,
+	2048, 4096
+);
+
+macro_rules! impl_fixed_bytes {
+	( $( $ty:ty => $path:expr, $len:expr; )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				fn type_info(registry: &mut Registry) -> TypeMetadata {
+					TypeMetadata::new($path, TypeDef::Array { len: $len, ty: registry.register::<u8>() })
+				}
+			}
+		)*
+	}
+}
+
+impl_fixed_bytes! {
+	H160 => "H160", 20;
+	H256 => "H256", 32;
+	H512 => "H512", 64;
+	U256 => "U256", 32;
+}
+
+macro_rules! impl_newtypes {
+	( $( $ty:ty => $path:expr, $inner:ty; )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				fn type_info(registry: &mut Registry) -> TypeMetadata {
+					TypeMetadata::composite($path, &[FieldMetadata::unnamed(registry.register::<$inner>())])
+				}
+			}
+		)*
+	}
+}
+
+impl_newtypes! {
+	ed25519::Public => "substrate_primitives::ed25519::Public", [u8; 32];
+	ed25519::Signature => "substrate_primitives::ed25519::Signature", [u8; 64];
+	sr25519::Public => "substrate_primitives::sr25519::Public", [u8; 32];
+	sr25519::Signature => "substrate_primitives::sr25519::Signature", [u8; 64];
+	Permill => "sr_primitives::Permill", u32;
+	Perbill => "sr_primitives::Perbill", u32;
+	Perquintill => "sr_primitives::Perquintill", u64;
+	AnySignature => "sr_primitives::AnySignature", H512;
+	crate::traits::WithdrawReasons => "srml_support::traits::WithdrawReasons", i8;
+}
+
+#[cfg(feature = "std")]
+impl_newtypes! {
+	crate::runtime_primitives::testing::UintAuthorityId => "sr_primitives::testing::UintAuthorityId", u64;
+}
+
+impl TypeInfo for MultiSignature {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::variant("sr_primitives::MultiSignature", &[
+			VariantMetadata::new("Ed25519", 0, &[FieldMetadata::unnamed(registry.register::<ed25519::Signature>())]),
+			VariantMetadata::new("Sr25519", 1, &[FieldMetadata::unnamed(registry.register::<sr25519::Signature>())]),
+		])
+	}
+}
+
+impl TypeInfo for ChangesTrieConfiguration {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::composite("substrate_primitives::ChangesTrieConfiguration", &[
+			FieldMetadata::named("digest_interval", registry.register::<u64>()),
+			FieldMetadata::named("digest_levels", registry.register::<u32>()),
+		])
+	}
+}
+
+impl<Item: TypeInfo> TypeInfo for generic::Digest<Item> {
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		TypeMetadata::composite("sr_primitives::generic::Digest", &[
+			FieldMetadata::named("logs", registry.register::<Vec<Item>>()),
+		])
+	}
+}
+
+impl<Hash, AuthorityId, SealSignature> TypeInfo for generic::DigestItem<Hash, AuthorityId, SealSignature> where
+	Hash: TypeInfo,
+	AuthorityId: TypeInfo,
+	SealSignature: TypeInfo,
+{
+	fn type_info(registry: &mut Registry) -> TypeMetadata {
+		// The indices are the ones of `DigestItemType`, not the order of the variants.
+		TypeMetadata::variant("sr_primitives::generic::DigestItem", &[
+			VariantMetadata::new("Other", 0, &[FieldMetadata::unnamed(registry.register::<Vec<u8>>())]),
+			VariantMetadata::new("AuthoritiesChange", 1, &[
+				FieldMetadata::unnamed(registry.register::<Vec<AuthorityId>>()),
+			]),
+			VariantMetadata::new("ChangesTrieRoot", 2, &[FieldMetadata::unnamed(registry.register::<Hash>())]),
+			VariantMetadata::new("Seal", 3, &[
+				FieldMetadata::unnamed(registry.register::<u64>()),
+				FieldMetadata::unnamed(registry.register::<SealSignature>()),
+			]),
+		])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TypeInfo;
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Named<T> {
+		value: T,
+		#[codec(compact)]
+		amount: u64,
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Recursive {
+		Leaf,
+		Node(Box<Recursive>, Vec<u8>),
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Discriminant {
+		First = 3,
+		Second = 7,
+	}
+
+	#[test]
+	fn types_are_registered_once() {
+		let mut registry = Registry::new();
+		let named = registry.register::<Named<u32>>();
+		assert_eq!(registry.register::<Named<u32>>(), named);
+		assert_eq!(registry.register::<u32>(), 1);
+		assert_eq!(registry.register::<u64>(), 3);
+
+		let types = registry.into_types();
+		assert_eq!(types.len(), 4);
+		assert_eq!(types[named as usize], TypeMetadata::composite(
+			concat!(module_path!(), "::Named"),
+			&[FieldMetadata::named("value", 1), FieldMetadata::named("amount", 2)],
+		));
+		assert_eq!(types[2], TypeMetadata::new("", TypeDef::Compact(3)));
+	}
+
+	#[test]
+	fn recursive_types_refer_to_themselves() {
+		let mut registry = Registry::new();
+		let recursive = registry.register::<Recursive>();
+		let types = registry.into_types();
+		assert_eq!(types[recursive as usize], TypeMetadata::variant(
+			concat!(module_path!(), "::Recursive"),
+			&[
+				VariantMetadata::new("Leaf", 0, &[]),
+				VariantMetadata::new("Node", 1, &[FieldMetadata::unnamed(1), FieldMetadata::unnamed(2)]),
+			],
+		));
+		assert_eq!(types[1], TypeMetadata::composite("", &[FieldMetadata::unnamed(recursive)]));
+		assert_eq!(types[2], TypeMetadata::new("", TypeDef::Sequence(3)));
+		assert_eq!(types[3], TypeMetadata::new("", TypeDef::Primitive(PrimitiveType::U8)));
+	}
+
+	#[test]
+	fn variants_are_indexed_by_discriminant() {
+		let mut registry = Registry::new();
+		registry.register::<Discriminant>();
+		assert_eq!(registry.into_types()[0], TypeMetadata::variant(
+			concat!(module_path!(), "::Discriminant"),
+			&[VariantMetadata::new("First", 3, &[]), VariantMetadata::new("Second", 7, &[])],
+		));
+	}
+}
@@ -755,6 +755,13 @@ macro_rules! __decl_outer_log {
 				}
 			);
 		}
+
+		// `Log` is encoded as the corresponding `DigestItem`.
+		impl $crate::registry::TypeInfo for Log {
+			fn type_info(registry: &mut $crate::registry::Registry) -> $crate::registry::TypeMetadata {
+				<$crate::runtime_primitives::generic::DigestItem<$( $log_genarg ),*> as $crate::registry::TypeInfo>::type_info(registry)
+			}
+		}
 	};
 }
 
@@ -0,0 +1,204 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers to migrate storage items, meant to be used in `on_runtime_upgrade`.
+//!
+//! Typed translations of values are available through `StorageValue::translate`,
+//! `StorageMap::translate` and `EnumerableStorageMap::translate_all`. The functions of this module
+//! access items by the name of their module and storage item, e.g. to move an item which has been
+//! renamed or which belongs to a module that has been removed.
+
+use crate::rstd::prelude::*;
+use crate::codec::{Encode, Decode};
+use super::{get, get_raw, put, put_raw, kill};
+
+/// The storage key of the item `item` of the module `module`, as used by `decl_storage!` (the
+/// name after `as` in the `trait Store for Module<T: Trait> as ...` declaration).
+pub fn storage_key(module: &[u8], item: &[u8]) -> Vec<u8> {
+	let mut key = Vec::with_capacity(module.len() + 1 + item.len());
+	key.extend_from_slice(module);
+	key.push(b' ');
+	key.extend_from_slice(item);
+	key
+}
+
+/// The storage key of the entry `key` of the map `item` of the module `module`.
+pub fn storage_map_key<K: Encode>(module: &[u8], item: &[u8], key: &K) -> Vec<u8> {
+	let mut storage_key = storage_key(module, item);
+	key.encode_to(&mut storage_key);
+	storage_key
+}
+
+/// Get the value of the storage value `item` of the module `module`.
+pub fn get_storage_value<T: Decode>(module: &[u8], item: &[u8]) -> Option<T> {
+	get(&storage_key(module, item))
+}
+
+/// Take the value of the storage value `item` of the module `module`, removing it.
+pub fn take_storage_value<T: Decode>(module: &[u8], item: &[u8]) -> Option<T> {
+	let key = storage_key(module, item);
+	let value = get(&key);
+	kill(&key);
+	value
+}
+
+/// Put a value in the storage value `item` of the module `module`.
+pub fn put_storage_value<T: Encode>(module: &[u8], item: &[u8], value: &T) {
+	put(&storage_key(module, item), value)
+}
+
+/// Get the entry `key` of the map `item` of the module `module`.
+pub fn get_storage_map_value<K: Encode, T: Decode>(module: &[u8], item: &[u8], key: &K) -> Option<T> {
+	get(&storage_map_key(module, item, key))
+}
+
+/// Take the entry `key` of the map `item` of the module `module`, removing it.
+///
+/// This must not be used on linked maps, whose entries are linked to each other.
+pub fn take_storage_map_value<K: Encode, T: Decode>(module: &[u8], item: &[u8], key: &K) -> Option<T> {
+	let storage_key = storage_map_key(module, item, key);
+	let value = get(&storage_key);
+	kill(&storage_key);
+	value
+}
+
+/// Move the raw value of the storage value `from_item` of the module `from_module` to `to_item` of
+/// `to_module`. Returns whether there was a value to move.
+pub fn move_storage_value(from_module: &[u8], from_item: &[u8], to_module: &[u8], to_item: &[u8]) -> bool {
+	let from = storage_key(from_module, from_item);
+	match get_raw(&from) {
+		Some(value) => {
+			kill(&from);
+			put_raw(&storage_key(to_module, to_item), &value);
+			true
+		}
+		None => false,
+	}
+}
+
+/// Decode the value under the storage `key` as `O`, translate it with `f` and write it back.
+///
+/// Any data encoded after the value, like the linkage of linked map entries, is kept as is.
+/// Returns `Ok(None)` if there is no value and `Err` if it can't be decoded as `O`.
+pub(crate) fn translate_raw<O: Decode, T: Encode, F: FnOnce(O) -> T>(
+	key: &[u8],
+	f: F,
+) -> Result<Option<T>, ()> {
+	let raw = match get_raw(key) {
+		Some(raw) => raw,
+		None => return Ok(None),
+	};
+
+	let mut input = &raw[..];
+	let old = O::decode(&mut input).ok_or(())?;
+	let new = f(old);
+
+	let mut value = new.encode();
+	value.extend_from_slice(input);
+	put_raw(key, &value);
+
+	Ok(Some(new))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::storage::{StorageValue, StorageMap, EnumerableStorageMap};
+	use runtime_io::{with_externalities, TestExternalities};
+
+	pub trait Trait {
+		type Origin;
+		type BlockNumber;
+	}
+
+	crate::decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	crate::decl_storage! {
+		trait Store for Module<T: Trait> as Migration {
+			Value: Option<u64>;
+			Map: map u32 => Option<u64>;
+			LinkedMap: linked_map u32 => Option<u64>;
+		}
+	}
+
+	struct TraitImpl {}
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+		type BlockNumber = u32;
+	}
+
+	#[test]
+	fn value_translation_works() {
+		with_externalities(&mut TestExternalities::default(), || {
+			put_storage_value(b"Migration", b"Value", &7u32);
+			assert_eq!(<Value<TraitImpl>>::translate(|old: Option<u32>| old.map(|v| v as u64 * 2)), Ok(Some(14)));
+			assert_eq!(<Value<TraitImpl>>::get(), Some(14));
+
+			// an undecodable value is left untouched.
+			assert_eq!(<Value<TraitImpl>>::translate(|_: Option<(u64, u64)>| None), Err(()));
+			assert_eq!(<Value<TraitImpl>>::get(), Some(14));
+
+			assert_eq!(<Value<TraitImpl>>::translate(|_: Option<u64>| None), Ok(None));
+			assert!(!<Value<TraitImpl>>::exists());
+		});
+	}
+
+	#[test]
+	fn map_translation_works() {
+		with_externalities(&mut TestExternalities::default(), || {
+			put(&storage_map_key(b"Migration", b"Map", &1u32), &(1u32, 2u32));
+			assert_eq!(<Map<TraitImpl>>::translate(1u32, |(a, b): (u32, u32)| (a + b) as u64), Ok(Some(3)));
+			assert_eq!(<Map<TraitImpl>>::translate(2u32, |v: u32| v as u64), Ok(None));
+			assert_eq!(<Map<TraitImpl>>::get(1u32), Some(3));
+			assert_eq!(<Map<TraitImpl>>::get(2u32), None);
+		});
+	}
+
+	#[test]
+	fn linked_map_translation_keeps_linkage() {
+		with_externalities(&mut TestExternalities::default(), || {
+			for i in 0..3u32 {
+				<LinkedMap<TraitImpl>>::insert(i, i as u64);
+			}
+
+			assert_eq!(<LinkedMap<TraitImpl>>::translate_all(|k: &u32, v: u64| v + *k as u64 + 10), Ok(()));
+			let mut values = <LinkedMap<TraitImpl>>::enumerate().collect::<Vec<_>>();
+			values.sort();
+			assert_eq!(values, vec![(0, 10), (1, 12), (2, 14)]);
+
+			<LinkedMap<TraitImpl>>::remove(1u32);
+			let mut values = <LinkedMap<TraitImpl>>::enumerate().collect::<Vec<_>>();
+			values.sort();
+			assert_eq!(values, vec![(0, 10), (2, 14)]);
+		});
+	}
+
+	#[test]
+	fn values_can_be_moved() {
+		with_externalities(&mut TestExternalities::default(), || {
+			put_storage_value(b"Old", b"Item", &5u64);
+			assert!(move_storage_value(b"Old", b"Item", b"Migration", b"Value"));
+			assert!(!move_storage_value(b"Old", b"Item", b"Migration", b"Value"));
+			assert_eq!(get_storage_value::<u64>(b"Old", b"Item"), None);
+			assert_eq!(<Value<TraitImpl>>::get(), Some(5));
+			assert_eq!(take_storage_value::<u64>(b"Migration", b"Value"), Some(5));
+			assert_eq!(<Value<TraitImpl>>::get(), None);
+		});
+	}
+}
@@ -24,6 +24,7 @@ use crate::codec::{Codec, Encode, Decode, KeyedVec, Input};
 #[macro_use]
 pub mod generator;
 pub mod unhashed;
+pub mod migration;
 
 struct IncrementalInput<'a> {
 	key: &'a [u8],
@@ -211,6 +212,12 @@ pub trait StorageValue<T: Codec> {
 
 	/// Take a value from storage, removing it afterwards.
 	fn take() -> Self::Query;
+
+	/// Translate the value from some previous type `O` to the current type, e.g. in a migration.
+	///
+	/// `f` gets the old value, if any, and returns the new one; `None` removes the value.
+	/// Returns `Err` if the stored value can't be decoded as `O`, leaving the storage untouched.
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<Option<T>, ()>;
 }
 
 impl<T: Codec, U> StorageValue<T> for U where U: generator::StorageValue<T> {
@@ -237,6 +244,20 @@ impl<T: Codec, U> StorageValue<T> for U where U: generator::StorageValue<T> {
 	fn take() -> Self::Query {
 		U::take(&RuntimeStorage)
 	}
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<Option<T>, ()> {
+		let key = <U as generator::StorageValue<T>>::key();
+		let old = match get_raw(key) {
+			Some(raw) => Some(O::decode(&mut &raw[..]).ok_or(())?),
+			None => None,
+		};
+
+		let new = f(old);
+		match new {
+			Some(ref value) => put(key, value),
+			None => kill(key),
+		}
+		Ok(new)
+	}
 }
 
 /// A strongly-typed list in storage.
@@ -335,6 +356,13 @@ pub trait StorageMap<K: Codec, V: Codec> {
 
 	/// Take the value under a key.
 	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query;
+
+	/// Translate the value under a key from some previous type `O` to the current type, e.g. in a
+	/// migration.
+	///
+	/// Returns `Ok(None)` if there is no value under the key, and `Err` if the stored value can't
+	/// be decoded as `O`, leaving the storage untouched.
+	fn translate<KeyArg: Borrow<K>, O: Decode, F: FnOnce(O) -> V>(key: KeyArg, f: F) -> Result<Option<V>, ()>;
 }
 
 impl<K: Codec, V: Codec, U> StorageMap<K, V> for U where U: generator::StorageMap<K, V> {
@@ -371,6 +399,10 @@ impl<K: Codec, V: Codec, U> StorageMap<K, V> for U where U: generator::StorageMa
 	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query {
 		U::take(key.borrow(), &RuntimeStorage)
 	}
+
+	fn translate<KeyArg: Borrow<K>, O: Decode, F: FnOnce(O) -> V>(key: KeyArg, f: F) -> Result<Option<V>, ()> {
+		migration::translate_raw(&<U as generator::StorageMap<K, V>>::key_for(key.borrow()), f)
+	}
 }
 
 /// A storage map that can be enumerated.
@@ -383,6 +415,13 @@ pub trait EnumerableStorageMap<K: Codec, V: Codec>: StorageMap<K, V> {
 
 	/// Enumerate all elements in the map.
 	fn enumerate() -> Box<dyn Iterator<Item = (K, V)>> where K: 'static, V: 'static;
+
+	/// Translate all the values of the map from some previous type `O` to the current type, e.g. in
+	/// a migration.
+	///
+	/// Returns `Err` if a value can't be decoded as `O`, in which case the values before it have
+	/// been translated already.
+	fn translate_all<O: Decode, F: FnMut(&K, O) -> V>(f: F) -> Result<(), ()>;
 }
 
 impl<K: Codec, V: Codec, U> EnumerableStorageMap<K, V> for U where U: generator::EnumerableStorageMap<K, V> {
@@ -393,6 +432,22 @@ impl<K: Codec, V: Codec, U> EnumerableStorageMap<K, V> for U where U: generator:
 	fn enumerate() -> Box<dyn Iterator<Item = (K, V)>> where K: 'static, V: 'static {
 		<U as generator::EnumerableStorageMap<K, V>>::enumerate(&RuntimeStorage)
 	}
+
+	fn translate_all<O: Decode, F: FnMut(&K, O) -> V>(mut f: F) -> Result<(), ()> {
+		let mut next = <U as generator::EnumerableStorageMap<K, V>>::head(&RuntimeStorage);
+		while let Some(key) = next.take() {
+			let key_for = <U as generator::StorageMap<K, V>>::key_for(&key);
+			// Entries are stored along with their linkage: `(previous, next)`.
+			let raw = get_raw(&key_for).ok_or(())?;
+			let mut input = &raw[..];
+			O::decode(&mut input).ok_or(())?;
+			let (_previous, following) = <(Option<K>, Option<K>)>::decode(&mut input).ok_or(())?;
+
+			migration::translate_raw(&key_for, |old: O| f(&key, old))?;
+			next = following;
+		}
+		Ok(())
+	}
 }
 
 /// An implementation of a map with a two keys.
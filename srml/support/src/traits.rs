@@ -16,11 +16,177 @@
 
 //! Traits for SRML
 
-use crate::rstd::result;
+use crate::rstd::{result, vec::Vec};
 use crate::codec::{Codec, Encode, Decode};
 use crate::runtime_primitives::traits::{
-	MaybeSerializeDebug, SimpleArithmetic, As
+	MaybeSerializeDebug, SimpleArithmetic, As, EnsureOrigin
 };
+use substrate_primitives::u32_trait::Value as U32;
+
+/// A value which is fixed by the runtime, e.g. one of its parameters.
+pub trait Get<T> {
+	/// Return the value.
+	fn get() -> T;
+}
+
+impl<T: Default> Get<T> for () {
+	fn get() -> T {
+		T::default()
+	}
+}
+
+/// A set of values which is fixed by the runtime or some module, e.g. the members of a council.
+pub trait Contains<T: Ord> {
+	/// Return `true` if the set contains `t`.
+	fn contains(t: &T) -> bool {
+		Self::sorted_members().binary_search(t).is_ok()
+	}
+
+	/// All members of the set, in ascending order.
+	fn sorted_members() -> Vec<T>;
+}
+
+/// A handler for changes to a set of accounts, e.g. a module keeping its own copy of the members
+/// of a committee.
+pub trait ChangeMembers<AccountId: Clone + Ord> {
+	/// A number of members `incoming` just joined the set and a number `outgoing` just left it.
+	/// `new` is the full set of members afterwards, in any order.
+	fn change_members(incoming: &[AccountId], outgoing: &[AccountId], mut new: Vec<AccountId>) {
+		new.sort();
+		Self::change_members_sorted(incoming, outgoing, &new[..]);
+	}
+
+	/// As `change_members`, except that `sorted_new` is in ascending order.
+	fn change_members_sorted(incoming: &[AccountId], outgoing: &[AccountId], sorted_new: &[AccountId]);
+
+	/// Replace the members `old_members` by `new_members`, both in ascending order, working out
+	/// which members are incoming and which are outgoing.
+	fn set_members_sorted(new_members: &[AccountId], old_members: &[AccountId]) {
+		let mut incoming = Vec::new();
+		let mut outgoing = Vec::new();
+		let mut new_iter = new_members.iter().peekable();
+		let mut old_iter = old_members.iter().peekable();
+		loop {
+			match (new_iter.peek().cloned(), old_iter.peek().cloned()) {
+				(Some(new), Some(old)) if new == old => {
+					new_iter.next();
+					old_iter.next();
+				}
+				(Some(new), Some(old)) if new < old => {
+					incoming.push(new.clone());
+					new_iter.next();
+				}
+				(Some(_), Some(old)) => {
+					outgoing.push(old.clone());
+					old_iter.next();
+				}
+				(Some(_), None) => incoming.extend(new_iter.by_ref().cloned()),
+				(None, Some(_)) => outgoing.extend(old_iter.by_ref().cloned()),
+				(None, None) => break,
+			}
+		}
+		Self::change_members_sorted(&incoming[..], &outgoing[..], new_members);
+	}
+
+	/// The prime member of the set has changed. The prime, if any, is always a member.
+	fn set_prime(_prime: Option<AccountId>) {}
+}
+
+impl<AccountId: Clone + Ord> ChangeMembers<AccountId> for () {
+	fn change_members_sorted(_incoming: &[AccountId], _outgoing: &[AccountId], _sorted_new: &[AccountId]) {}
+}
+
+/// A source of on-chain randomness.
+pub trait Randomness<Output, BlockNumber> {
+	/// Get a random value specific to `subject`, along with the block number up to which it was
+	/// unpredictable.
+	///
+	/// The output is only usable as randomness for decisions that were committed to no later than
+	/// the returned block; anything decided afterwards could have been made with knowledge of it.
+	/// Distinct subjects give independent outputs from the same underlying seed.
+	fn random(subject: &[u8]) -> (Output, BlockNumber);
+
+	/// Get the underlying random seed, as `random` with an empty subject.
+	fn random_seed() -> (Output, BlockNumber) {
+		Self::random(&[][..])
+	}
+}
+
+impl<Output: Default, BlockNumber: Default> Randomness<Output, BlockNumber> for () {
+	fn random(_subject: &[u8]) -> (Output, BlockNumber) {
+		(Output::default(), BlockNumber::default())
+	}
+}
+
+/// The result of an origin check that may have passed one of two alternatives.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Either<L, R> {
+	/// The first alternative passed.
+	Left(L),
+	/// The first alternative failed and the second passed.
+	Right(R),
+}
+
+/// Origin check that passes if either `L` or `R` does, trying `L` first.
+///
+/// This allows a governance action to be available to more than one body, e.g. root or a
+/// majority of a collective.
+pub struct EnsureOneOf<L, R>(crate::rstd::marker::PhantomData<(L, R)>);
+impl<O: Clone, L: EnsureOrigin<O>, R: EnsureOrigin<O>> EnsureOrigin<O> for EnsureOneOf<L, R> {
+	type Success = Either<L::Success, R::Success>;
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		L::ensure_origin(o.clone()).map(Either::Left)
+			.or_else(|_| R::ensure_origin(o).map(Either::Right))
+	}
+}
+
+/// Origin check that passes if both `L` and `R` do, e.g. to require an absolute number of members
+/// alongside a proportion of them.
+pub struct EnsureBoth<L, R>(crate::rstd::marker::PhantomData<(L, R)>);
+impl<O: Clone, L: EnsureOrigin<O>, R: EnsureOrigin<O>> EnsureOrigin<O> for EnsureBoth<L, R> {
+	type Success = (L::Success, R::Success);
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		Ok((L::ensure_origin(o.clone())?, R::ensure_origin(o)?))
+	}
+}
+
+/// An origin that may have been condoned by a number of members of a collective.
+pub trait MembersOrigin {
+	/// The number of members that condoned this origin and the size of the collective at the
+	/// time, or `None` if it was not condoned by members of the collective.
+	fn members(&self) -> Option<(u32, u32)>;
+}
+
+/// Ensure that the origin `Inner` represents more than `N/D` of the members of its collective,
+/// returning the number of members that condoned it and the size of the collective.
+pub struct EnsureProportionMoreThan<N: U32, D: U32, Inner>(crate::rstd::marker::PhantomData<(N, D, Inner)>);
+impl<O, N: U32, D: U32, Inner: MembersOrigin> EnsureOrigin<O> for EnsureProportionMoreThan<N, D, Inner>
+	where O: Into<Option<Inner>>
+{
+	type Success = (u32, u32);
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into().as_ref().and_then(MembersOrigin::members) {
+			Some((n, m)) if n as u64 * D::VALUE as u64 > N::VALUE as u64 * m as u64 => Ok((n, m)),
+			_ => Err("bad origin: expected to be more than a proportion of members"),
+		}
+	}
+}
+
+/// Ensure that the origin `Inner` represents at least `N/D` of the members of its collective,
+/// returning the number of members that condoned it and the size of the collective.
+pub struct EnsureProportionAtLeast<N: U32, D: U32, Inner>(crate::rstd::marker::PhantomData<(N, D, Inner)>);
+impl<O, N: U32, D: U32, Inner: MembersOrigin> EnsureOrigin<O> for EnsureProportionAtLeast<N, D, Inner>
+	where O: Into<Option<Inner>>
+{
+	type Success = (u32, u32);
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into().as_ref().and_then(MembersOrigin::members) {
+			Some((n, m)) if n as u64 * D::VALUE as u64 >= N::VALUE as u64 * m as u64 => Ok((n, m)),
+			_ => Err("bad origin: expected to be at least a proportion of members"),
+		}
+	}
+}
 
 /// The account with the given id was killed.
 pub trait OnFreeBalanceZero<AccountId> {
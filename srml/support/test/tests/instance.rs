@@ -25,6 +25,7 @@ use srml_support::codec::{Encode, Decode};
 use srml_support::runtime_primitives::{generic, BuildStorage};
 use srml_support::runtime_primitives::traits::{BlakeTwo256, Block as _, Verify, Digest};
 use srml_support::Parameter;
+use srml_support::metadata::DecodeDifferent;
 use inherents::{
 	ProvideInherent, InherentData, InherentIdentifier, RuntimeString, MakeFatalError
 };
@@ -126,6 +127,9 @@ mod module1 {
 		pub struct Module<T: Trait<I>, I: InstantiableThing> for enum Call where origin: <T as system::Trait>::Origin {
 			fn deposit_event<T, I>() = default;
 
+			/// The answer, whatever the instance.
+			const Answer: u32 = 42;
+
 			fn one() {
 				Self::deposit_event(RawEvent::AnotherVariant(3));
 				Self::deposit_log(RawLog::AmountChange(3));
@@ -496,3 +500,13 @@ fn storage_with_instance_basic_operation() {
 		assert_eq!(DoubleMap::get(key1, key2), 0);
 	});
 }
+
+#[test]
+fn instance_module_constants() {
+	let constants = Module1_2::module_constants_metadata();
+	assert_eq!(constants.len(), 1);
+	assert_eq!(constants[0].name, DecodeDifferent::Encode("Answer"));
+	assert_eq!(constants[0].ty, DecodeDifferent::Encode("u32"));
+	assert_eq!(constants[0].value.encode(), 42u32.encode().encode());
+	assert_eq!(constants[0].documentation, DecodeDifferent::Encode(&[" The answer, whatever the instance."][..]));
+}
@@ -79,7 +79,9 @@ use primitives::traits::{self, CheckEqual, SimpleArithmetic, SimpleBitOps, Zero,
 	Hash, Member, MaybeDisplay, EnsureOrigin, Digest as DigestT, As, CurrentHeight, BlockNumberToHash,
 	MaybeSerializeDebugButNotDeserialize, MaybeSerializeDebug, StaticLookup};
 use substrate_primitives::storage::well_known_keys;
-use srml_support::{storage, StorageValue, StorageMap, Parameter, decl_module, decl_event, decl_storage};
+use srml_support::{storage, StorageValue, StorageMap, Parameter, decl_module, decl_event, decl_storage, TypeInfo};
+use srml_support::dispatch::{DispatchError, DispatchResult};
+use srml_support::traits::Get;
 use safe_mix::TripletMix;
 use parity_codec::{Encode, Decode};
 
@@ -170,6 +172,10 @@ pub trait Trait: 'static + Eq + Clone {
 
 	/// A piece of information which can be part of the digest (as a digest item).
 	type Log: From<Log<Self>> + Into<DigestItemOf<Self>>;
+
+	/// The `spec_version` of the runtime (see `RuntimeVersion`). When it changes, the runtime has
+	/// been upgraded and the `on_runtime_upgrade` hooks of the modules are run.
+	type SpecVersion: Get<u32>;
 }
 
 pub type DigestItemOf<T> = <<T as Trait>::Digest as traits::Digest>::Item;
@@ -188,7 +194,7 @@ decl_module! {
 }
 
 /// A phase of a block's execution.
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone, Debug))]
 pub enum Phase {
 	/// Applying an extrinsic.
@@ -198,7 +204,7 @@ pub enum Phase {
 }
 
 /// Record of an event happening.
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone, Debug))]
 pub struct EventRecord<E: Parameter + Member> {
 	/// The phase of the block it happened in.
@@ -212,8 +218,8 @@ decl_event!(
 	pub enum Event {
 		/// An extrinsic completed successfully.
 		ExtrinsicSuccess,
-		/// An extrinsic failed.
-		ExtrinsicFailed,
+		/// An extrinsic failed with the given error.
+		ExtrinsicFailed(DispatchError),
 	}
 );
 
@@ -306,6 +312,8 @@ decl_storage! {
 		Digest get(digest): T::Digest;
 		/// Events deposited for the current block.
 		Events get(events): Vec<EventRecord<T::Event>>;
+		/// The `spec_version` of the runtime which executed the last block, as of its last upgrade.
+		LastRuntimeUpgrade get(last_runtime_upgrade): u32;
 	}
 	add_extra_genesis {
 		config(changes_trie_config): Option<ChangesTrieConfiguration>;
@@ -320,6 +328,12 @@ decl_storage! {
 					well_known_keys::CHANGES_TRIE_CONFIG.to_vec(),
 					changes_trie_config.encode());
 			}
+
+			// the genesis runtime must not run the migrations of its own storage.
+			let spec_version = T::SpecVersion::get();
+			if spec_version != 0 {
+				storage.insert(twox_128(<LastRuntimeUpgrade<T>>::key()).to_vec(), spec_version.encode());
+			}
 		});
 	}
 }
@@ -391,6 +405,19 @@ impl<T: Trait> Module<T> {
 		<Events<T>>::kill();
 	}
 
+	/// Note the `spec_version` of the runtime executing the current block. Returns `true` if the
+	/// runtime has been upgraded since the last block, in which case the storage migrations must
+	/// be run.
+	pub fn note_runtime_version() -> bool {
+		let spec_version = T::SpecVersion::get();
+		if Self::last_runtime_upgrade() == spec_version {
+			return false
+		}
+
+		<LastRuntimeUpgrade<T>>::put(spec_version);
+		true
+	}
+
 	/// Remove temporary "environment" entries in storage.
 	pub fn finalise() -> T::Header {
 		<RandomSeed<T>>::kill();
@@ -489,10 +516,10 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// To be called immediately after an extrinsic has been applied.
-	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, encoded_len: u32) {
+	pub fn note_applied_extrinsic(r: &DispatchResult, encoded_len: u32) {
 		Self::deposit_event(match r {
 			Ok(_) => Event::ExtrinsicSuccess,
-			Err(_) => Event::ExtrinsicFailed,
+			Err(e) => Event::ExtrinsicFailed(*e),
 		}.into());
 
 		let next_extrinsic_index = Self::extrinsic_index().unwrap_or_default() + 1u32;
@@ -575,13 +602,14 @@ mod tests {
 		type Header = Header;
 		type Event = u16;
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 
 	impl From<Event> for u16 {
 		fn from(e: Event) -> u16 {
 			match e {
 				Event::ExtrinsicSuccess => 100,
-				Event::ExtrinsicFailed => 101,
+				Event::ExtrinsicFailed(_) => 101,
 			}
 		}
 	}
@@ -604,7 +632,7 @@ mod tests {
 			System::initialise(&2, &[0u8; 32].into(), &[0u8; 32].into());
 			System::deposit_event(42u16);
 			System::note_applied_extrinsic(&Ok(()), 0);
-			System::note_applied_extrinsic(&Err(""), 0);
+			System::note_applied_extrinsic(&Err("".into()), 0);
 			System::note_finished_extrinsics();
 			System::deposit_event(3u16);
 			System::finalise();
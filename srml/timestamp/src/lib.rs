@@ -347,6 +347,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl Trait for Test {
 		type Moment = u64;
@@ -15,15 +15,25 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! The Treasury: Keeps account of the taxed cash and handles its deployment.
+//!
+//! Besides spending proposals, the treasury pays out two lighter kinds of reward:
+//!
+//! - Tips: anyone may report a reason worth tipping someone for, placing a deposit. Members of the
+//!   `Tippers` set then declare the amount they think is due. Once half of them have done so, a
+//!   countdown starts, after which anyone may close the tip and the median amount is paid, part of
+//!   it going to the finder.
+//! - Bounties: a proposed bounty that is approved is funded from the pot at the next spend period.
+//!   A curator is then assigned for a fee and, once they award the bounty to a beneficiary, it can
+//!   be claimed after a delay.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(feature = "std")]
 use serde_derive::{Serialize, Deserialize};
 use rstd::prelude::*;
-use srml_support::{StorageValue, StorageMap, decl_module, decl_storage, decl_event, ensure};
-use srml_support::traits::{Currency, ReservableCurrency, OnDilution, OnUnbalanced, Imbalance};
-use runtime_primitives::{Permill, traits::{Zero, EnsureOrigin, StaticLookup}};
+use srml_support::{StorageValue, StorageMap, decl_module, decl_storage, decl_event, ensure, TypeInfo};
+use srml_support::traits::{Currency, ReservableCurrency, OnDilution, OnUnbalanced, Imbalance, Contains};
+use runtime_primitives::{Permill, traits::{Zero, As, Hash, Saturating, EnsureOrigin, StaticLookup}};
 use parity_codec::{Encode, Decode};
 use system::ensure_signed;
 
@@ -49,9 +59,13 @@ pub trait Trait: system::Trait {
 
 	/// Handler for the unbalanced decrease when slashing for a rejected proposal.
 	type ProposalRejection: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The set of accounts which may declare tip amounts.
+	type Tippers: Contains<Self::AccountId>;
 }
 
 type ProposalIndex = u32;
+type BountyIndex = u32;
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
@@ -117,6 +131,234 @@ decl_module! {
 			<Approvals<T>>::mutate(|v| v.push(proposal_id));
 		}
 
+		/// Report something worth tipping `who` for, giving the `reason` as a UTF-8 string, e.g. a
+		/// URL. A deposit proportional to the length of the reason is reserved and returned once
+		/// the tip is closed or retracted.
+		fn report_awesome(origin, reason: Vec<u8>, who: <T::Lookup as StaticLookup>::Source) {
+			let finder = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			ensure!(reason.len() <= Self::maximum_reason_length() as usize, "reason too long");
+			let reason_hash = T::Hashing::hash(&reason[..]);
+			ensure!(!<Reasons<T>>::exists(&reason_hash), "tip reason already known");
+			let hash = T::Hashing::hash_of(&(&reason_hash, &who));
+			ensure!(!<Tips<T>>::exists(&hash), "tip already exists");
+
+			let deposit = Self::tip_report_deposit_base()
+				+ Self::data_deposit_per_byte() * BalanceOf::<T>::sa(reason.len() as u64);
+			T::Currency::reserve(&finder, deposit)
+				.map_err(|_| "Finder's balance too low")?;
+
+			<Reasons<T>>::insert(&reason_hash, reason);
+			let tip = OpenTip { reason: reason_hash, who, finder: Some((finder, deposit)), closes: None, tips: vec![] };
+			<Tips<T>>::insert(&hash, tip);
+			Self::deposit_event(RawEvent::NewTip(hash));
+		}
+
+		/// Retract a tip reported with `report_awesome`, returning the finder's deposit. Only the
+		/// finder may do this.
+		fn retract_tip(origin, hash: T::Hash) {
+			let who = ensure_signed(origin)?;
+			let tip = Self::tips(&hash).ok_or("No tip with that hash")?;
+			let (finder, deposit) = tip.finder.ok_or("Tip has no finder")?;
+			ensure!(finder == who, "Only the finder can retract a tip");
+
+			<Reasons<T>>::remove(&tip.reason);
+			<Tips<T>>::remove(&hash);
+			let _ = T::Currency::unreserve(&finder, deposit);
+			Self::deposit_event(RawEvent::TipRetracted(hash));
+		}
+
+		/// Open a tip for `who` as one of the tippers, declaring `tip_value` as the amount due. No
+		/// deposit is taken and no finder's fee is paid.
+		fn tip_new(
+			origin,
+			reason: Vec<u8>,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] tip_value: BalanceOf<T>
+		) {
+			let tipper = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(T::Tippers::contains(&tipper), "Not a tipper");
+			ensure!(reason.len() <= Self::maximum_reason_length() as usize, "reason too long");
+
+			let reason_hash = T::Hashing::hash(&reason[..]);
+			ensure!(!<Reasons<T>>::exists(&reason_hash), "tip reason already known");
+			let hash = T::Hashing::hash_of(&(&reason_hash, &who));
+
+			<Reasons<T>>::insert(&reason_hash, reason);
+			Self::deposit_event(RawEvent::NewTip(hash.clone()));
+			let mut tip = OpenTip { reason: reason_hash, who, finder: None, closes: None, tips: vec![] };
+			if Self::insert_tip_and_check_closing(&mut tip, tipper, tip_value) {
+				Self::deposit_event(RawEvent::TipClosing(hash.clone()));
+			}
+			<Tips<T>>::insert(&hash, tip);
+		}
+
+		/// Declare `tip_value` as the amount due for an open tip, replacing any earlier declaration
+		/// by the same tipper. Once half of the tippers have declared, the tip closes after the tip
+		/// countdown.
+		fn tip(origin, hash: T::Hash, #[compact] tip_value: BalanceOf<T>) {
+			let tipper = ensure_signed(origin)?;
+			ensure!(T::Tippers::contains(&tipper), "Not a tipper");
+
+			let mut tip = Self::tips(&hash).ok_or("No tip with that hash")?;
+			if Self::insert_tip_and_check_closing(&mut tip, tipper, tip_value) {
+				Self::deposit_event(RawEvent::TipClosing(hash.clone()));
+			}
+			<Tips<T>>::insert(&hash, tip);
+		}
+
+		/// Close a tip whose countdown has ended, paying out the median of the amounts declared by
+		/// those who are still tippers.
+		fn close_tip(origin, hash: T::Hash) {
+			ensure_signed(origin)?;
+
+			let tip = Self::tips(&hash).ok_or("No tip with that hash")?;
+			let closes = tip.closes.ok_or("Tip is not yet closing")?;
+			ensure!(<system::Module<T>>::block_number() >= closes, "Tip countdown has not ended");
+
+			<Reasons<T>>::remove(&tip.reason);
+			<Tips<T>>::remove(&hash);
+			Self::payout_tip(hash, tip);
+		}
+
+		/// Propose a bounty of `value` for the work described by `description`. A deposit
+		/// proportional to the length of the description is reserved; it is returned once the
+		/// bounty is funded and slashed if the bounty is rejected.
+		fn propose_bounty(origin, #[compact] value: BalanceOf<T>, description: Vec<u8>) {
+			let proposer = ensure_signed(origin)?;
+
+			ensure!(description.len() <= Self::maximum_reason_length() as usize, "reason too long");
+			ensure!(!value.is_zero(), "Bounty value cannot be zero");
+
+			let bond = Self::bounty_deposit_base()
+				+ Self::data_deposit_per_byte() * BalanceOf::<T>::sa(description.len() as u64);
+			T::Currency::reserve(&proposer, bond)
+				.map_err(|_| "Proposer's balance too low")?;
+
+			let index = Self::bounty_count();
+			<BountyCount<T>>::put(index + 1);
+			<Bounties<T>>::insert(index, Bounty {
+				proposer,
+				value,
+				fee: Zero::zero(),
+				bond,
+				status: BountyStatus::Proposed,
+			});
+			<BountyDescriptions<T>>::insert(index, description);
+
+			Self::deposit_event(RawEvent::BountyProposed(index));
+		}
+
+		/// Approve a bounty. It will be funded from the pot at a later spend period.
+		fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			let mut bounty = Self::bounties(bounty_id).ok_or("No bounty at that index")?;
+			ensure!(bounty.status == BountyStatus::Proposed, "Bounty is not awaiting approval");
+
+			bounty.status = BountyStatus::Approved;
+			<Bounties<T>>::insert(bounty_id, bounty);
+			<BountyApprovals<T>>::mutate(|v| v.push(bounty_id));
+		}
+
+		/// Assign a curator to a funded bounty, who is paid `fee` out of its value once it is
+		/// claimed. Any previous curator is replaced.
+		fn assign_curator(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[compact] fee: BalanceOf<T>
+		) {
+			T::ApproveOrigin::ensure_origin(origin)?;
+			let curator = T::Lookup::lookup(curator)?;
+
+			let mut bounty = Self::bounties(bounty_id).ok_or("No bounty at that index")?;
+			match bounty.status {
+				BountyStatus::Funded | BountyStatus::CuratorAssigned { .. } => {},
+				_ => return Err("Bounty is not funded"),
+			}
+			ensure!(fee < bounty.value, "Curator fee must be less than the bounty value");
+
+			bounty.fee = fee;
+			bounty.status = BountyStatus::CuratorAssigned { curator: curator.clone() };
+			<Bounties<T>>::insert(bounty_id, bounty);
+
+			Self::deposit_event(RawEvent::BountyCuratorAssigned(bounty_id, curator));
+		}
+
+		/// Award a bounty to `beneficiary`. Only the curator may do this. The bounty can be
+		/// claimed once the payout delay has passed.
+		fn award_bounty(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source
+		) {
+			let who = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let mut bounty = Self::bounties(bounty_id).ok_or("No bounty at that index")?;
+			let curator = match bounty.status {
+				BountyStatus::CuratorAssigned { curator } => curator,
+				_ => return Err("Bounty has no curator"),
+			};
+			ensure!(curator == who, "Only the curator can award a bounty");
+
+			let unlock_at = <system::Module<T>>::block_number() + Self::bounty_payout_delay();
+			bounty.status = BountyStatus::PendingPayout { curator, beneficiary: beneficiary.clone(), unlock_at };
+			<Bounties<T>>::insert(bounty_id, bounty);
+
+			Self::deposit_event(RawEvent::BountyAwarded(bounty_id, beneficiary));
+		}
+
+		/// Pay out an awarded bounty whose payout delay has passed: the curator receives their fee
+		/// and the beneficiary the rest.
+		fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			ensure_signed(origin)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or("No bounty at that index")?;
+			let (curator, beneficiary, unlock_at) = match bounty.status {
+				BountyStatus::PendingPayout { curator, beneficiary, unlock_at } => (curator, beneficiary, unlock_at),
+				_ => return Err("Bounty has not been awarded"),
+			};
+			ensure!(<system::Module<T>>::block_number() >= unlock_at, "Bounty payout is still locked");
+
+			<Bounties<T>>::remove(bounty_id);
+			<BountyDescriptions<T>>::remove(bounty_id);
+
+			let payout = bounty.value - bounty.fee;
+			let mut imbalance = T::Currency::deposit_creating(&curator, bounty.fee);
+			imbalance.subsume(T::Currency::deposit_creating(&beneficiary, payout));
+			T::MintedForSpending::on_unbalanced(imbalance);
+
+			Self::deposit_event(RawEvent::BountyClaimed(bounty_id, payout, beneficiary));
+		}
+
+		/// Close a bounty. A proposed bounty is rejected and its deposit slashed. The value of a
+		/// funded bounty that has not yet been awarded is returned to the pot.
+		fn close_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or("No bounty at that index")?;
+			match bounty.status {
+				BountyStatus::Proposed => {
+					let imbalance = T::Currency::slash_reserved(&bounty.proposer, bounty.bond).0;
+					T::ProposalRejection::on_unbalanced(imbalance);
+					Self::deposit_event(RawEvent::BountyRejected(bounty_id, bounty.bond));
+				}
+				BountyStatus::Funded | BountyStatus::CuratorAssigned { .. } => {
+					<Pot<T>>::mutate(|pot| *pot += bounty.value);
+					Self::deposit_event(RawEvent::BountyCanceled(bounty_id));
+				}
+				BountyStatus::Approved => return Err("Bounty is awaiting funding"),
+				BountyStatus::PendingPayout { .. } => return Err("Bounty is pending payout"),
+			}
+
+			<Bounties<T>>::remove(bounty_id);
+			<BountyDescriptions<T>>::remove(bounty_id);
+		}
+
 		fn on_finalise(n: T::BlockNumber) {
 			// Check to see if we should spend some funds!
 			if (n % Self::spend_period()).is_zero() {
@@ -128,7 +370,7 @@ decl_module! {
 
 /// A spending proposal.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
 pub struct Proposal<AccountId, Balance> {
 	proposer: AccountId,
 	value: Balance,
@@ -136,6 +378,50 @@ pub struct Proposal<AccountId, Balance> {
 	bond: Balance,
 }
 
+/// An open tip: someone to be tipped, and the amounts declared by the tippers so far.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct OpenTip<AccountId, Balance, BlockNumber, Hash> {
+	/// The hash of the reason for the tip.
+	reason: Hash,
+	/// The account to be tipped.
+	who: AccountId,
+	/// The account who reported the tip and the deposit they placed, unless it was opened by a
+	/// tipper.
+	finder: Option<(AccountId, Balance)>,
+	/// The block at which the tip may be closed, once enough tippers have declared.
+	closes: Option<BlockNumber>,
+	/// The amounts declared by each tipper, sorted by tipper.
+	tips: Vec<(AccountId, Balance)>,
+}
+
+/// The progress of a bounty.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub enum BountyStatus<AccountId, BlockNumber> {
+	/// Proposed and waiting for approval.
+	Proposed,
+	/// Approved and waiting to be funded at the next spend period.
+	Approved,
+	/// Funded and waiting for a curator.
+	Funded,
+	/// Being curated by `curator`.
+	CuratorAssigned { curator: AccountId },
+	/// Awarded to `beneficiary` and claimable from `unlock_at`.
+	PendingPayout { curator: AccountId, beneficiary: AccountId, unlock_at: BlockNumber },
+}
+
+/// A bounty.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct Bounty<AccountId, Balance, BlockNumber> {
+	proposer: AccountId,
+	value: Balance,
+	fee: Balance,
+	bond: Balance,
+	status: BountyStatus<AccountId, BlockNumber>,
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Treasury {
 		// Config...
@@ -153,6 +439,28 @@ decl_storage! {
 		/// Percentage of spare funds (if any) that are burnt per spend period.
 		Burn get(burn) config(): Permill;
 
+		/// Maximum length of the reason for a tip or the description of a bounty.
+		MaximumReasonLength get(maximum_reason_length) config(): u32 = 16384;
+
+		/// Amount of funds that should be placed in a deposit for each byte of a tip reason or
+		/// bounty description.
+		DataDepositPerByte get(data_deposit_per_byte) config(): BalanceOf<T>;
+
+		/// Minimum amount of funds that should be placed in a deposit for reporting a tip.
+		TipReportDepositBase get(tip_report_deposit_base) config(): BalanceOf<T>;
+
+		/// Period for which a tip remains open once half of the tippers have declared.
+		TipCountdown get(tip_countdown) config(): T::BlockNumber;
+
+		/// Proportion of a tip that goes to the finder who reported it.
+		TipFindersFee get(tip_finders_fee) config(): Permill;
+
+		/// Minimum amount of funds that should be placed in a deposit for proposing a bounty.
+		BountyDepositBase get(bounty_deposit_base) config(): BalanceOf<T>;
+
+		/// Period after a bounty is awarded before it can be claimed.
+		BountyPayoutDelay get(bounty_payout_delay) config(): T::BlockNumber;
+
 		// State...
 
 		/// Total funds available to this module for spending.
@@ -166,6 +474,24 @@ decl_storage! {
 
 		/// Proposal indices that have been approved but not yet awarded.
 		Approvals get(approvals): Vec<ProposalIndex>;
+
+		/// Open tips, keyed by the hash of the reason and the account to be tipped.
+		Tips get(tips): map T::Hash => Option<OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>>;
+
+		/// The reasons of open tips, keyed by their hash.
+		Reasons get(reasons): map T::Hash => Option<Vec<u8>>;
+
+		/// Number of bounties that have been proposed.
+		BountyCount get(bounty_count): BountyIndex;
+
+		/// Bounties that have been proposed and not yet claimed, rejected or canceled.
+		Bounties get(bounties): map BountyIndex => Option<Bounty<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+
+		/// The descriptions of bounties.
+		BountyDescriptions get(bounty_descriptions): map BountyIndex => Option<Vec<u8>>;
+
+		/// Bounty indices that have been approved but not yet funded.
+		BountyApprovals get(bounty_approvals): Vec<BountyIndex>;
 	}
 }
 
@@ -173,7 +499,8 @@ decl_event!(
 	pub enum Event<T>
 	where
 		Balance = BalanceOf<T>,
-		<T as system::Trait>::AccountId
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::Hash
 	{
 		/// New proposal.
 		Proposed(ProposalIndex),
@@ -185,6 +512,28 @@ decl_event!(
 		Burnt(Balance),
 		/// Spending has finished; this is the amount that rolls over until next spend.
 		Rollover(Balance),
+		/// A new tip has been opened.
+		NewTip(Hash),
+		/// A tip has enough declarations and will close after the countdown.
+		TipClosing(Hash),
+		/// A tip has been closed, paying the given amount to the given account.
+		TipClosed(Hash, AccountId, Balance),
+		/// A tip has been retracted by its finder.
+		TipRetracted(Hash),
+		/// New bounty proposal.
+		BountyProposed(BountyIndex),
+		/// A bounty proposal was rejected and the given bond slashed.
+		BountyRejected(BountyIndex, Balance),
+		/// A bounty has been funded and is waiting for a curator.
+		BountyBecameActive(BountyIndex),
+		/// A curator has been assigned to a bounty.
+		BountyCuratorAssigned(BountyIndex, AccountId),
+		/// A bounty has been awarded to a beneficiary.
+		BountyAwarded(BountyIndex, AccountId),
+		/// A bounty has been claimed, paying the given amount to the beneficiary.
+		BountyClaimed(BountyIndex, Balance, AccountId),
+		/// A bounty has been canceled and its value returned to the pot.
+		BountyCanceled(BountyIndex),
 	}
 );
 
@@ -196,6 +545,61 @@ impl<T: Trait> Module<T> {
 		Self::proposal_bond_minimum().max(Self::proposal_bond() * value)
 	}
 
+	/// Record `tipper`'s declaration of `tip_value` for `tip`, and start its countdown if enough
+	/// tippers have now declared. Returns `true` if the countdown was started.
+	fn insert_tip_and_check_closing(
+		tip: &mut OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+		tipper: T::AccountId,
+		tip_value: BalanceOf<T>,
+	) -> bool {
+		match tip.tips.binary_search_by_key(&&tipper, |t| &t.0) {
+			Ok(pos) => tip.tips[pos] = (tipper, tip_value),
+			Err(pos) => tip.tips.insert(pos, (tipper, tip_value)),
+		}
+		Self::retain_active_tips(&mut tip.tips);
+		let threshold = (T::Tippers::sorted_members().len() + 1) / 2;
+		if tip.tips.len() >= threshold && tip.closes.is_none() {
+			tip.closes = Some(<system::Module<T>>::block_number() + Self::tip_countdown());
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Drop the declarations of those who are no longer tippers.
+	fn retain_active_tips(tips: &mut Vec<(T::AccountId, BalanceOf<T>)>) {
+		let members = T::Tippers::sorted_members();
+		tips.retain(|(tipper, _)| members.binary_search(tipper).is_ok());
+	}
+
+	/// Pay out a closed tip: the median of the declared amounts, capped by the pot, of which the
+	/// finder's fee goes to the finder unless they are the one tipped.
+	fn payout_tip(hash: T::Hash, tip: OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>) {
+		let mut tips = tip.tips;
+		Self::retain_active_tips(&mut tips);
+		tips.sort_by_key(|&(_, value)| value);
+		let median = tips.get(tips.len() / 2).map(|&(_, value)| value).unwrap_or_else(Zero::zero);
+
+		let pot = Self::pot();
+		let payout = median.min(pot);
+		<Pot<T>>::put(pot - payout);
+
+		let mut imbalance = <PositiveImbalanceOf<T>>::zero();
+		let mut tip_payout = payout;
+		if let Some((finder, deposit)) = tip.finder {
+			let _ = T::Currency::unreserve(&finder, deposit);
+			if finder != tip.who {
+				let finders_fee = Self::tip_finders_fee() * payout;
+				tip_payout = payout.saturating_sub(finders_fee);
+				imbalance.subsume(T::Currency::deposit_creating(&finder, finders_fee));
+			}
+		}
+		imbalance.subsume(T::Currency::deposit_creating(&tip.who, tip_payout));
+		T::MintedForSpending::on_unbalanced(imbalance);
+
+		Self::deposit_event(RawEvent::TipClosed(hash, tip.who, payout));
+	}
+
 	// Spend some money!
 	fn spend_funds() {
 		let mut budget_remaining = Self::pot();
@@ -229,6 +633,30 @@ impl<T: Trait> Module<T> {
 			});
 		});
 
+		<BountyApprovals<T>>::mutate(|v| {
+			v.retain(|&index| {
+				// Should always be true, but shouldn't panic if false or we're screwed.
+				if let Some(mut bounty) = Self::bounties(index) {
+					if bounty.value <= budget_remaining {
+						budget_remaining -= bounty.value;
+
+						// return their deposit; the value is held for the bounty until it is claimed.
+						let _ = T::Currency::unreserve(&bounty.proposer, bounty.bond);
+						bounty.status = BountyStatus::Funded;
+						<Bounties<T>>::insert(index, bounty);
+
+						Self::deposit_event(RawEvent::BountyBecameActive(index));
+						false
+					} else {
+						missed_any = true;
+						true
+					}
+				} else {
+					false
+				}
+			});
+		});
+
 		T::MintedForSpending::on_unbalanced(imbalance);
 
 		if !missed_any {
@@ -285,6 +713,7 @@ mod tests {
 		type Header = Header;
 		type Event = ();
 		type Log = DigestItem;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -302,7 +731,15 @@ mod tests {
 		type Event = ();
 		type MintedForSpending = ();
 		type ProposalRejection = ();
+		type Tippers = TenToFourteen;
+	}
+	pub struct TenToFourteen;
+	impl Contains<u64> for TenToFourteen {
+		fn sorted_members() -> Vec<u64> {
+			vec![10, 11, 12, 13, 14]
+		}
 	}
+	type System = system::Module<Test>;
 	type Balances = balances::Module<Test>;
 	type Treasury = Module<Test>;
 
@@ -322,6 +759,13 @@ mod tests {
 			proposal_bond_minimum: 1,
 			spend_period: 2,
 			burn: Permill::from_percent(50),
+			maximum_reason_length: 16384,
+			data_deposit_per_byte: 1,
+			tip_report_deposit_base: 1,
+			tip_countdown: 1,
+			tip_finders_fee: Permill::from_percent(20),
+			bounty_deposit_base: 1,
+			bounty_payout_delay: 3,
 		}.build_storage().unwrap().0);
 		t.into()
 	}
@@ -477,4 +921,144 @@ mod tests {
 			assert_eq!(Treasury::pot(), 25);
 		});
 	}
+
+	fn tip_hash() -> H256 {
+		BlakeTwo256::hash_of(&(BlakeTwo256::hash(&b"awesome.dot"[..]), 3u64))
+	}
+
+	#[test]
+	fn report_awesome_and_tip_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			Treasury::on_dilution(100, 100);
+
+			assert_ok!(Treasury::report_awesome(Origin::signed(0), b"awesome.dot".to_vec(), 3));
+			assert_eq!(Balances::reserved_balance(&0), 12);
+			assert_eq!(Balances::free_balance(&0), 88);
+			assert_noop!(
+				Treasury::report_awesome(Origin::signed(1), b"awesome.dot".to_vec(), 3),
+				"tip reason already known"
+			);
+
+			let h = tip_hash();
+			assert_noop!(Treasury::tip(Origin::signed(9), h.clone(), 10), "Not a tipper");
+			assert_ok!(Treasury::tip(Origin::signed(10), h.clone(), 10));
+			assert_ok!(Treasury::tip(Origin::signed(11), h.clone(), 10));
+			assert_noop!(Treasury::close_tip(Origin::signed(100), h.clone()), "Tip is not yet closing");
+			assert_ok!(Treasury::tip(Origin::signed(12), h.clone(), 10));
+			assert_noop!(Treasury::close_tip(Origin::signed(100), h.clone()), "Tip countdown has not ended");
+
+			System::set_block_number(2);
+			assert_ok!(Treasury::close_tip(Origin::signed(100), h.clone()));
+			assert_eq!(Balances::reserved_balance(&0), 0);
+			assert_eq!(Balances::free_balance(&0), 102);
+			assert_eq!(Balances::free_balance(&3), 8);
+			assert_eq!(Treasury::pot(), 90);
+			assert_eq!(Treasury::tips(&h), None);
+			assert_eq!(Treasury::reasons(&BlakeTwo256::hash(&b"awesome.dot"[..])), None);
+		});
+	}
+
+	#[test]
+	fn tip_new_pays_the_median_of_current_tippers() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			Treasury::on_dilution(100, 100);
+
+			assert_noop!(Treasury::tip_new(Origin::signed(0), b"awesome.dot".to_vec(), 3, 10), "Not a tipper");
+			assert_noop!(Treasury::tip_new(Origin::signed(10), vec![0; 16385], 3, 10), "reason too long");
+			assert_ok!(Treasury::tip_new(Origin::signed(10), b"awesome.dot".to_vec(), 3, 10));
+			assert_noop!(
+				Treasury::tip_new(Origin::signed(11), b"awesome.dot".to_vec(), 3, 10),
+				"tip reason already known"
+			);
+
+			let h = tip_hash();
+			assert_ok!(Treasury::tip(Origin::signed(11), h.clone(), 20));
+			assert_ok!(Treasury::tip(Origin::signed(12), h.clone(), 30));
+			// declarations made during the countdown still count.
+			assert_ok!(Treasury::tip(Origin::signed(13), h.clone(), 40));
+			// and a tipper may change their mind.
+			assert_ok!(Treasury::tip(Origin::signed(10), h.clone(), 50));
+
+			System::set_block_number(2);
+			assert_ok!(Treasury::close_tip(Origin::signed(100), h.clone()));
+			assert_eq!(Balances::free_balance(&3), 40);
+			assert_eq!(Treasury::pot(), 60);
+		});
+	}
+
+	#[test]
+	fn retract_tip_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Treasury::report_awesome(Origin::signed(0), b"awesome.dot".to_vec(), 3));
+			let h = tip_hash();
+			assert_noop!(Treasury::retract_tip(Origin::signed(1), h.clone()), "Only the finder can retract a tip");
+			assert_ok!(Treasury::retract_tip(Origin::signed(0), h.clone()));
+			assert_eq!(Balances::reserved_balance(&0), 0);
+			assert_eq!(Balances::free_balance(&0), 100);
+			assert_noop!(Treasury::retract_tip(Origin::signed(0), h.clone()), "No tip with that hash");
+
+			assert_ok!(Treasury::tip_new(Origin::signed(10), b"awesome.dot".to_vec(), 3, 10));
+			assert_noop!(Treasury::retract_tip(Origin::signed(10), h.clone()), "Tip has no finder");
+		});
+	}
+
+	#[test]
+	fn bounty_is_funded_curated_and_claimed() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			Treasury::on_dilution(100, 100);
+
+			assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+			assert_eq!(Balances::reserved_balance(&0), 6);
+			assert_ok!(Treasury::approve_bounty(Origin::ROOT, 0));
+			assert_noop!(Treasury::approve_bounty(Origin::ROOT, 0), "Bounty is not awaiting approval");
+			assert_noop!(Treasury::assign_curator(Origin::ROOT, 0, 4, 4), "Bounty is not funded");
+
+			<Treasury as OnFinalise<u64>>::on_finalise(2);
+			assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Funded);
+			assert_eq!(Balances::reserved_balance(&0), 0);
+			assert_eq!(Treasury::pot(), 45);
+
+			assert_noop!(
+				Treasury::assign_curator(Origin::ROOT, 0, 4, 10),
+				"Curator fee must be less than the bounty value"
+			);
+			assert_ok!(Treasury::assign_curator(Origin::ROOT, 0, 4, 4));
+			assert_noop!(Treasury::award_bounty(Origin::signed(3), 0, 3), "Only the curator can award a bounty");
+			assert_ok!(Treasury::award_bounty(Origin::signed(4), 0, 3));
+
+			System::set_block_number(3);
+			assert_noop!(Treasury::claim_bounty(Origin::signed(1), 0), "Bounty payout is still locked");
+			System::set_block_number(4);
+			assert_ok!(Treasury::claim_bounty(Origin::signed(1), 0));
+			assert_eq!(Balances::free_balance(&4), 4);
+			assert_eq!(Balances::free_balance(&3), 6);
+			assert_eq!(Treasury::bounties(0), None);
+			assert_eq!(Treasury::bounty_descriptions(0), None);
+		});
+	}
+
+	#[test]
+	fn close_bounty_works() {
+		with_externalities(&mut new_test_ext(), || {
+			Treasury::on_dilution(100, 100);
+
+			assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+			assert_ok!(Treasury::close_bounty(Origin::ROOT, 0));
+			assert_eq!(Balances::reserved_balance(&0), 0);
+			assert_eq!(Balances::free_balance(&0), 94);
+			assert_noop!(Treasury::close_bounty(Origin::ROOT, 0), "No bounty at that index");
+
+			assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+			assert_ok!(Treasury::approve_bounty(Origin::ROOT, 1));
+			assert_noop!(Treasury::close_bounty(Origin::ROOT, 1), "Bounty is awaiting funding");
+
+			<Treasury as OnFinalise<u64>>::on_finalise(2);
+			assert_eq!(Treasury::pot(), 45);
+			assert_ok!(Treasury::close_bounty(Origin::ROOT, 1));
+			assert_eq!(Treasury::pot(), 55);
+		});
+	}
 }